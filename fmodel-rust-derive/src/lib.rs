@@ -0,0 +1,362 @@
+//! # Derive macros for the `fmodel-rust` library
+//!
+//! - `#[derive(Identifier)]` implements the `fmodel_rust::Identifier` trait
+//! - `#[derive(EventName)]` implements the `fmodel_rust::EventName` trait
+//! - `#[derive(DeciderInfo)]`, `#[derive(ViewInfo)]` and `#[derive(SagaInfo)]` implement the `fmodel_rust::{DeciderInfo, ViewInfo, SagaInfo}` traits
+//!
+//! Enable the `derive` feature of the `fmodel-rust` crate to use them via `fmodel_rust::{Identifier, EventName, DeciderInfo, ViewInfo, SagaInfo}`.
+//!
+//! ## Identifier
+//!
+//! On structs, the field marked with `#[id]` is the identifier.
+//! On enums, every variant is either marked with `#[id]` on one of its fields, or it has a single field that implements `Identifier` itself.
+//!
+//! ```
+//! use fmodel_rust::Identifier;
+//! use fmodel_rust_derive::Identifier;
+//!
+//! #[derive(Identifier)]
+//! pub struct CreateOrderCommand {
+//!     #[id]
+//!     pub order_id: u32,
+//!     pub customer_name: String,
+//! }
+//!
+//! #[derive(Identifier)]
+//! pub enum OrderCommand {
+//!     Create(CreateOrderCommand),
+//!     Cancel {
+//!         #[id]
+//!         order_id: u32,
+//!     },
+//! }
+//!
+//! let command = OrderCommand::Cancel { order_id: 1 };
+//! assert_eq!(command.identifier(), "1");
+//! ```
+//!
+//! ## EventName
+//!
+//! On enums, the name of the variant is the event name. On structs, the name of the struct is the event name.
+//! Use `#[event_name = "..."]` to override the name, and keep it stable across the renames in the code.
+//!
+//! ```
+//! use fmodel_rust::EventName;
+//! use fmodel_rust_derive::EventName;
+//!
+//! #[derive(EventName)]
+//! pub enum OrderEvent {
+//!     Created(u32),
+//!     #[event_name = "OrderCancelled"]
+//!     Cancelled(u32),
+//! }
+//!
+//! assert_eq!(OrderEvent::Created(1).event_name(), "Created");
+//! assert_eq!(OrderEvent::Cancelled(1).event_name(), "OrderCancelled");
+//! ```
+
+//!
+//! ## DeciderInfo, ViewInfo, SagaInfo
+//!
+//! Derived on the command (decider), event (view) or action result (saga) type.
+//! On enums, the names of the variants are the handled message types. On structs, the name of the struct is the handled message type.
+//! Use `#[decider(name = "...", version = ...)]` (`#[view(...)]`, `#[saga(...)]`) to set the name (the name of the type by default) and the version (`1` by default).
+//!
+//! ```
+//! use fmodel_rust::DeciderInfo;
+//! use fmodel_rust_derive::DeciderInfo;
+//!
+//! #[derive(DeciderInfo)]
+//! #[decider(name = "Order", version = 2)]
+//! pub enum OrderCommand {
+//!     Create(u32),
+//!     Cancel(u32),
+//! }
+//!
+//! assert_eq!(OrderCommand::decider_name(), "Order");
+//! assert_eq!(OrderCommand::decider_version(), 2);
+//! assert_eq!(OrderCommand::handled_commands(), ["Create", "Cancel"]);
+//! ```
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::spanned::Spanned;
+use syn::{parse_macro_input, Attribute, Data, DeriveInput, Expr, ExprLit, Fields, Lit, Meta};
+
+/// Derives the `fmodel_rust::Identifier` trait.
+#[proc_macro_derive(Identifier, attributes(id))]
+pub fn derive_identifier(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_identifier(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Derives the `fmodel_rust::EventName` trait.
+#[proc_macro_derive(EventName, attributes(event_name))]
+pub fn derive_event_name(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_event_name(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Derives the `fmodel_rust::DeciderInfo` trait.
+#[proc_macro_derive(DeciderInfo, attributes(decider))]
+pub fn derive_decider_info(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_info(
+        &input,
+        "decider",
+        quote!(::fmodel_rust::DeciderInfo),
+        [
+            format_ident!("decider_name"),
+            format_ident!("decider_version"),
+            format_ident!("handled_commands"),
+        ],
+    )
+    .unwrap_or_else(syn::Error::into_compile_error)
+    .into()
+}
+
+/// Derives the `fmodel_rust::ViewInfo` trait.
+#[proc_macro_derive(ViewInfo, attributes(view))]
+pub fn derive_view_info(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_info(
+        &input,
+        "view",
+        quote!(::fmodel_rust::ViewInfo),
+        [
+            format_ident!("view_name"),
+            format_ident!("view_version"),
+            format_ident!("handled_events"),
+        ],
+    )
+    .unwrap_or_else(syn::Error::into_compile_error)
+    .into()
+}
+
+/// Derives the `fmodel_rust::SagaInfo` trait.
+#[proc_macro_derive(SagaInfo, attributes(saga))]
+pub fn derive_saga_info(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_info(
+        &input,
+        "saga",
+        quote!(::fmodel_rust::SagaInfo),
+        [
+            format_ident!("saga_name"),
+            format_ident!("saga_version"),
+            format_ident!("handled_action_results"),
+        ],
+    )
+    .unwrap_or_else(syn::Error::into_compile_error)
+    .into()
+}
+
+fn expand_identifier(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let body = match &input.data {
+        Data::Struct(data) => {
+            let (index, field) = id_field(&data.fields).ok_or_else(|| {
+                syn::Error::new(
+                    name.span(),
+                    "`#[derive(Identifier)]` requires a field marked with `#[id]`",
+                )
+            })?;
+            let member = match &field.ident {
+                Some(ident) => quote!(#ident),
+                None => {
+                    let index = syn::Index::from(index);
+                    quote!(#index)
+                }
+            };
+            quote!(::fmodel_rust::__private::ToString::to_string(&self.#member))
+        }
+        Data::Enum(data) => {
+            let mut arms = Vec::with_capacity(data.variants.len());
+            for variant in &data.variants {
+                let variant_name = &variant.ident;
+                let bindings = (0..variant.fields.len())
+                    .map(|index| format_ident!("__field{}", index))
+                    .collect::<Vec<_>>();
+                let pattern = match &variant.fields {
+                    Fields::Named(fields) => {
+                        let names = fields.named.iter().map(|field| &field.ident);
+                        quote!(Self::#variant_name { #(#names: #bindings),* })
+                    }
+                    Fields::Unnamed(_) => quote!(Self::#variant_name(#(#bindings),*)),
+                    Fields::Unit => {
+                        return Err(syn::Error::new(
+                            variant.span(),
+                            "`#[derive(Identifier)]` does not support unit variants",
+                        ))
+                    }
+                };
+                let expression = match id_field(&variant.fields) {
+                    Some((index, _)) => {
+                        let binding = &bindings[index];
+                        quote!(::fmodel_rust::__private::ToString::to_string(#binding))
+                    }
+                    None if bindings.len() == 1 => {
+                        let binding = &bindings[0];
+                        quote!(::fmodel_rust::Identifier::identifier(#binding))
+                    }
+                    None => {
+                        return Err(syn::Error::new(
+                            variant.span(),
+                            "`#[derive(Identifier)]` requires a single field, or a field marked with `#[id]`",
+                        ))
+                    }
+                };
+                arms.push(quote!(#[allow(unused_variables)] #pattern => #expression,));
+            }
+            quote!(match self { #(#arms)* })
+        }
+        Data::Union(_) => {
+            return Err(syn::Error::new(
+                name.span(),
+                "`#[derive(Identifier)]` does not support unions",
+            ))
+        }
+    };
+    Ok(quote! {
+        impl #impl_generics ::fmodel_rust::Identifier for #name #ty_generics #where_clause {
+            fn identifier(&self) -> ::fmodel_rust::__private::String {
+                #body
+            }
+        }
+    })
+}
+
+fn expand_event_name(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let body = match &input.data {
+        Data::Struct(_) => {
+            let event_name = event_name(&input.attrs)?.unwrap_or_else(|| name.to_string());
+            quote!(#event_name)
+        }
+        Data::Enum(data) => {
+            let mut arms = Vec::with_capacity(data.variants.len());
+            for variant in &data.variants {
+                let variant_name = &variant.ident;
+                let event_name =
+                    event_name(&variant.attrs)?.unwrap_or_else(|| variant_name.to_string());
+                let pattern = match &variant.fields {
+                    Fields::Named(_) => quote!(Self::#variant_name { .. }),
+                    Fields::Unnamed(_) => quote!(Self::#variant_name(..)),
+                    Fields::Unit => quote!(Self::#variant_name),
+                };
+                arms.push(quote!(#pattern => #event_name,));
+            }
+            quote!(match self { #(#arms)* })
+        }
+        Data::Union(_) => {
+            return Err(syn::Error::new(
+                name.span(),
+                "`#[derive(EventName)]` does not support unions",
+            ))
+        }
+    };
+    Ok(quote! {
+        impl #impl_generics ::fmodel_rust::EventName for #name #ty_generics #where_clause {
+            fn event_name(&self) -> &'static str {
+                #body
+            }
+        }
+    })
+}
+
+fn expand_info(
+    input: &DeriveInput,
+    attribute: &str,
+    trait_path: TokenStream2,
+    [name_fn, version_fn, handled_fn]: [syn::Ident; 3],
+) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let mut info_name = name.to_string();
+    let mut info_version = 1u32;
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident(attribute))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("name") {
+                info_name = meta.value()?.parse::<syn::LitStr>()?.value();
+                Ok(())
+            } else if meta.path.is_ident("version") {
+                info_version = meta.value()?.parse::<syn::LitInt>()?.base10_parse()?;
+                Ok(())
+            } else {
+                Err(meta.error(format!(
+                    "expected `#[{}(name = \"...\", version = ...)]`",
+                    attribute
+                )))
+            }
+        })?;
+    }
+    let handled = match &input.data {
+        Data::Struct(_) => vec![name.to_string()],
+        Data::Enum(data) => data
+            .variants
+            .iter()
+            .map(|variant| variant.ident.to_string())
+            .collect(),
+        Data::Union(_) => {
+            return Err(syn::Error::new(
+                name.span(),
+                "the info derive macros do not support unions",
+            ))
+        }
+    };
+    Ok(quote! {
+        impl #impl_generics #trait_path for #name #ty_generics #where_clause {
+            fn #name_fn() -> &'static str {
+                #info_name
+            }
+            fn #version_fn() -> u32 {
+                #info_version
+            }
+            fn #handled_fn() -> &'static [&'static str] {
+                &[#(#handled),*]
+            }
+        }
+    })
+}
+
+/// Finds the field marked with `#[id]`, together with its position.
+fn id_field(fields: &Fields) -> Option<(usize, &syn::Field)> {
+    fields
+        .iter()
+        .enumerate()
+        .find(|(_, field)| field.attrs.iter().any(|attr| attr.path().is_ident("id")))
+}
+
+/// Reads the `#[event_name = "..."]` attribute, if present.
+fn event_name(attrs: &[Attribute]) -> syn::Result<Option<String>> {
+    for attr in attrs {
+        if !attr.path().is_ident("event_name") {
+            continue;
+        }
+        if let Meta::NameValue(meta) = &attr.meta {
+            if let Expr::Lit(ExprLit {
+                lit: Lit::Str(value),
+                ..
+            }) = &meta.value
+            {
+                return Ok(Some(value.value()));
+            }
+        }
+        return Err(syn::Error::new(
+            attr.span(),
+            "expected `#[event_name = \"...\"]`",
+        ));
+    }
+    Ok(None)
+}
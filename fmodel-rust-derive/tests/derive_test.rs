@@ -0,0 +1,122 @@
+use fmodel_rust::{
+    DeciderInfo as _, EventName as _, Identifier as _, SagaInfo as _, ViewInfo as _,
+};
+use fmodel_rust_derive::{DeciderInfo, EventName, Identifier, SagaInfo, ViewInfo};
+
+#[derive(Identifier)]
+pub struct CreateOrderCommand {
+    #[id]
+    pub order_id: u32,
+    pub customer_name: String,
+}
+
+#[derive(Identifier)]
+pub struct CancelOrderCommand(#[id] pub u32);
+
+#[derive(Identifier, DeciderInfo)]
+#[decider(name = "Order", version = 2)]
+pub enum OrderCommand {
+    Create(CreateOrderCommand),
+    Cancel(CancelOrderCommand),
+    Update {
+        #[id]
+        order_id: u32,
+        new_items: Vec<String>,
+    },
+}
+
+#[derive(Identifier, EventName, ViewInfo, SagaInfo)]
+#[view(name = "OrderView")]
+pub enum OrderEvent {
+    Created {
+        #[id]
+        order_id: u32,
+        customer_name: String,
+    },
+    #[event_name = "OrderCancelled"]
+    Cancelled(#[id] u32),
+}
+
+#[derive(EventName)]
+pub struct OrderUpdatedEvent {
+    pub order_id: u32,
+}
+
+#[derive(EventName)]
+#[event_name = "ShipmentCreated"]
+pub struct ShipmentCreatedEvent {
+    pub shipment_id: u32,
+}
+
+#[test]
+fn identifier_test() {
+    let create = CreateOrderCommand {
+        order_id: 1,
+        customer_name: "John Doe".to_string(),
+    };
+    assert_eq!(create.identifier(), "1");
+    assert_eq!(CancelOrderCommand(2).identifier(), "2");
+    assert_eq!(OrderCommand::Create(create).identifier(), "1");
+    assert_eq!(
+        OrderCommand::Cancel(CancelOrderCommand(2)).identifier(),
+        "2"
+    );
+    assert_eq!(
+        OrderCommand::Update {
+            order_id: 3,
+            new_items: vec!["Item 1".to_string()],
+        }
+        .identifier(),
+        "3"
+    );
+    assert_eq!(
+        OrderEvent::Created {
+            order_id: 4,
+            customer_name: "John Doe".to_string(),
+        }
+        .identifier(),
+        "4"
+    );
+    assert_eq!(OrderEvent::Cancelled(5).identifier(), "5");
+}
+
+#[test]
+fn event_name_test() {
+    assert_eq!(
+        OrderEvent::Created {
+            order_id: 1,
+            customer_name: "John Doe".to_string(),
+        }
+        .event_name(),
+        "Created"
+    );
+    assert_eq!(OrderEvent::Cancelled(1).event_name(), "OrderCancelled");
+    assert_eq!(
+        OrderUpdatedEvent { order_id: 1 }.event_name(),
+        "OrderUpdatedEvent"
+    );
+    assert_eq!(
+        ShipmentCreatedEvent { shipment_id: 1 }.event_name(),
+        "ShipmentCreated"
+    );
+}
+
+#[test]
+fn info_test() {
+    assert_eq!(OrderCommand::decider_name(), "Order");
+    assert_eq!(OrderCommand::decider_version(), 2);
+    assert_eq!(
+        OrderCommand::handled_commands(),
+        ["Create", "Cancel", "Update"]
+    );
+
+    assert_eq!(OrderEvent::view_name(), "OrderView");
+    assert_eq!(OrderEvent::view_version(), 1);
+    assert_eq!(OrderEvent::handled_events(), ["Created", "Cancelled"]);
+
+    assert_eq!(OrderEvent::saga_name(), "OrderEvent");
+    assert_eq!(
+        OrderEvent::handled_action_results(),
+        ["Created", "Cancelled"]
+    );
+}
@@ -1,9 +1,13 @@
+use std::collections::HashMap;
+use std::fmt::{self, Debug, Display, Formatter};
 use std::future::Future;
+use std::hash::{BuildHasher, RandomState};
 use std::marker::PhantomData;
+use std::time::Duration;
 
-use crate::decider::{Decider, EventComputation, StateComputation};
+use crate::decider::{CurrentStateComputation, Decider, EventComputation, StateComputation};
 use crate::saga::{ActionComputation, Saga};
-use crate::Identifier;
+use crate::{Identifier, MaybeSend, MaybeSync};
 
 /// Event Repository trait
 ///
@@ -15,24 +19,318 @@ use crate::Identifier;
 /// - `Error` - Error
 pub trait EventRepository<C, E, Version, Error> {
     /// Fetches current events, based on the command.
-    /// Desugared `async fn fetch_events(&self, command: &C) -> Result<Vec<(E, Version)>, Error>;` to a normal `fn` that returns `impl Future`, and adds bound `Send`.
+    /// Desugared `async fn fetch_events(&self, command: &C) -> Result<Vec<(E, Version)>, Error>;` to a normal `fn` that returns `impl Future`, and adds bound `MaybeSend` (`Send` with the default `send` feature).
     /// You can freely move between the `async fn` and `-> impl Future` spelling in your traits and impls. This is true even when one form has a Send bound.
     fn fetch_events(
         &self,
         command: &C,
-    ) -> impl Future<Output = Result<Vec<(E, Version)>, Error>> + Send;
+    ) -> impl Future<Output = Result<Vec<(E, Version)>, Error>> + MaybeSend;
     /// Saves events.
-    /// Desugared `async fn save(&self, events: &[E], latest_version: &Option<Version>) -> Result<Vec<(E, Version)>, Error>;` to a normal `fn` that returns `impl Future`, and adds bound `Send`
+    /// Desugared `async fn save(&self, events: &[E], latest_version: &Option<Version>) -> Result<Vec<(E, Version)>, Error>;` to a normal `fn` that returns `impl Future`, and adds bound `MaybeSend` (`Send` with the default `send` feature)
     /// You can freely move between the `async fn` and `-> impl Future` spelling in your traits and impls. This is true even when one form has a Send bound.
-    fn save(&self, events: &[E]) -> impl Future<Output = Result<Vec<(E, Version)>, Error>> + Send;
+    fn save(
+        &self,
+        events: &[E],
+    ) -> impl Future<Output = Result<Vec<(E, Version)>, Error>> + MaybeSend;
 
     /// Version provider. It is used to provide the version/sequence of the stream to wich this event belongs to. Optimistic locking is useing this version to check if the event is already saved.
-    /// Desugared `async fn version_provider(&self, event: &E) -> Result<Option<Version>, Error>;` to a normal `fn` that returns `impl Future`, and adds bound `Send`
+    /// Desugared `async fn version_provider(&self, event: &E) -> Result<Option<Version>, Error>;` to a normal `fn` that returns `impl Future`, and adds bound `MaybeSend` (`Send` with the default `send` feature)
     /// You can freely move between the `async fn` and `-> impl Future` spelling in your traits and impls. This is true even when one form has a Send bound.
     fn version_provider(
         &self,
         event: &E,
-    ) -> impl Future<Output = Result<Option<Version>, Error>> + Send;
+    ) -> impl Future<Output = Result<Option<Version>, Error>> + MaybeSend;
+}
+
+/// Event Repository trait with metadata
+///
+/// Extends the [EventRepository] with the ability to save the events together with the metadata (correlation id, causation id, tenant id, user info, ...), without polluting the domain types.
+///
+/// Generic parameters:
+///
+/// - `C` - Command
+/// - `E` - Event
+/// - `M` - Metadata
+/// - `Version` - Version/Offset/Sequence number
+/// - `Error` - Error
+pub trait EventRepositoryWithMetadata<C, E, M, Version, Error>:
+    EventRepository<C, E, Version, Error>
+{
+    /// Saves events together with the metadata.
+    /// Desugared `async fn save_with_metadata(&self, events: &[(E, M)]) -> Result<Vec<(E, Version)>, Error>;` to a normal `fn` that returns `impl Future`, and adds bound `MaybeSend` (`Send` with the default `send` feature)
+    /// You can freely move between the `async fn` and `-> impl Future` spelling in your traits and impls. This is true even when one form has a Send bound.
+    fn save_with_metadata(
+        &self,
+        events: &[(E, M)],
+    ) -> impl Future<Output = Result<Vec<(E, Version)>, Error>> + MaybeSend;
+}
+
+/// Event Repository trait with context
+///
+/// Extends the [EventRepository] with the ability to fetch and save the events within the context (tenant id, partition, ...), so the multi-tenant systems can partition the event streams without wrapping every repository.
+///
+/// Generic parameters:
+///
+/// - `C` - Command
+/// - `E` - Event
+/// - `Ctx` - Context (tenant id, ...)
+/// - `Version` - Version/Offset/Sequence number
+/// - `Error` - Error
+pub trait EventRepositoryWithContext<C, E, Ctx, Version, Error>:
+    EventRepository<C, E, Version, Error>
+{
+    /// Fetches current events within the context, based on the command.
+    /// Desugared `async fn fetch_events_with_context(&self, command: &C, context: &Ctx) -> Result<Vec<(E, Version)>, Error>;` to a normal `fn` that returns `impl Future`, and adds bound `MaybeSend` (`Send` with the default `send` feature)
+    /// You can freely move between the `async fn` and `-> impl Future` spelling in your traits and impls. This is true even when one form has a Send bound.
+    fn fetch_events_with_context(
+        &self,
+        command: &C,
+        context: &Ctx,
+    ) -> impl Future<Output = Result<Vec<(E, Version)>, Error>> + MaybeSend;
+    /// Saves events within the context.
+    /// Desugared `async fn save_with_context(&self, events: &[E], context: &Ctx) -> Result<Vec<(E, Version)>, Error>;` to a normal `fn` that returns `impl Future`, and adds bound `MaybeSend` (`Send` with the default `send` feature)
+    /// You can freely move between the `async fn` and `-> impl Future` spelling in your traits and impls. This is true even when one form has a Send bound.
+    fn save_with_context(
+        &self,
+        events: &[E],
+        context: &Ctx,
+    ) -> impl Future<Output = Result<Vec<(E, Version)>, Error>> + MaybeSend;
+}
+
+/// Event Repository trait with outbox
+///
+/// Extends the [EventRepository] with the ability to save the events and to record them in the outbox, in a single transaction.
+/// The events in the outbox are published to the message bus afterwards (see `outbox::OutboxProcessor`), so the saved events are published reliably, even if the process crashes right after the save.
+///
+/// Generic parameters:
+///
+/// - `C` - Command
+/// - `E` - Event
+/// - `Version` - Version/Offset/Sequence number
+/// - `Error` - Error
+pub trait EventRepositoryWithOutbox<C, E, Version, Error>:
+    EventRepository<C, E, Version, Error>
+{
+    /// Saves events, and records them in the outbox, atomically.
+    /// Desugared `async fn save_and_outbox(&self, events: &[E]) -> Result<Vec<(E, Version)>, Error>;` to a normal `fn` that returns `impl Future`, and adds bound `MaybeSend` (`Send` with the default `send` feature)
+    /// You can freely move between the `async fn` and `-> impl Future` spelling in your traits and impls. This is true even when one form has a Send bound.
+    fn save_and_outbox(
+        &self,
+        events: &[E],
+    ) -> impl Future<Output = Result<Vec<(E, Version)>, Error>> + MaybeSend;
+}
+
+/// Event Repository trait with optimistic locking
+///
+/// Extends the [EventRepository] with the ability to save the events only if the stream was not modified since it was fetched.
+/// The implementation should fail with the [ConcurrencyError] (converted into the `Error`) if the latest version of the stream does not match the expected version.
+///
+/// Generic parameters:
+///
+/// - `C` - Command
+/// - `E` - Event
+/// - `Version` - Version/Offset/Sequence number
+/// - `Error` - Error
+pub trait EventRepositoryWithOptimisticLocking<C, E, Version, Error>:
+    EventRepository<C, E, Version, Error>
+{
+    /// Saves events, if the latest version of the stream matches the expected version.
+    /// Desugared `async fn save_with_expected_version(&self, events: &[E], expected_version: &Option<Version>) -> Result<Vec<(E, Version)>, Error>;` to a normal `fn` that returns `impl Future`, and adds bound `MaybeSend` (`Send` with the default `send` feature)
+    /// You can freely move between the `async fn` and `-> impl Future` spelling in your traits and impls. This is true even when one form has a Send bound.
+    fn save_with_expected_version(
+        &self,
+        events: &[E],
+        expected_version: &Option<Version>,
+    ) -> impl Future<Output = Result<Vec<(E, Version)>, Error>> + MaybeSend;
+}
+
+/// Concurrency error - the stream/state was modified concurrently, after it was fetched.
+///
+/// Generic parameters:
+///
+/// - `Version` - Version/Offset/Sequence number
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConcurrencyError<Version> {
+    /// The version the command was handled against
+    pub expected_version: Option<Version>,
+    /// The actual, latest version in the repository
+    pub actual_version: Option<Version>,
+}
+
+impl<Version: Debug> Display for ConcurrencyError<Version> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Concurrency error: expected version {:?}, actual version {:?}",
+            self.expected_version, self.actual_version
+        )
+    }
+}
+
+impl<Version: Debug> std::error::Error for ConcurrencyError<Version> {}
+
+/// Optimistic Locking Error trait
+///
+/// It is used by the aggregates to recognize the errors that are caused by the concurrent modification, and to retry the command handling.
+pub trait OptimisticLockingError {
+    /// Returns `true` if the error is caused by the concurrent modification.
+    fn is_concurrency_error(&self) -> bool;
+}
+
+impl<Version> OptimisticLockingError for ConcurrencyError<Version> {
+    fn is_concurrency_error(&self) -> bool {
+        true
+    }
+}
+
+/// Resolution of the conflict between the new events of the command and the events that were saved concurrently.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Resolution<E> {
+    /// The events commute: save these (merged) events on top of the concurrent ones, without handling the command again
+    Merge(Vec<E>),
+    /// Handle the command again, against the current stream (including the concurrent events)
+    Retry,
+    /// Give up, and fail with the concurrency error
+    Reject,
+}
+
+/// Conflict Resolver trait
+///
+/// It is used by the aggregates to resolve the concurrent modification of the stream, given the new events of the command (mine) and the events that were saved concurrently (theirs).
+/// It is implemented for the functions `Fn(&[E], &[E]) -> Resolution<E>`.
+///
+/// Generic parameters:
+///
+/// - `E` - Event
+pub trait ConflictResolver<E> {
+    /// Resolves the conflict between my new events and their concurrently saved events.
+    fn resolve(&self, mine: &[E], theirs: &[E]) -> Resolution<E>;
+}
+
+impl<E, F> ConflictResolver<E> for F
+where
+    F: Fn(&[E], &[E]) -> Resolution<E>,
+{
+    fn resolve(&self, mine: &[E], theirs: &[E]) -> Resolution<E> {
+        self(mine, theirs)
+    }
+}
+
+/// Delay between the attempts of the [RetryPolicy].
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum RetryDelay {
+    /// The next attempt is made immediately
+    #[default]
+    None,
+    /// The same delay before every next attempt
+    Fixed(Duration),
+    /// The delay is doubled for every next attempt, up to the `max` delay.
+    /// With the `jitter`, the random delay between zero and the computed delay is used, so the clients that failed together are not retrying together.
+    Exponential {
+        /// The delay before the second attempt
+        initial: Duration,
+        /// The upper bound of the delay
+        max: Duration,
+        /// Randomize the delay (full jitter)
+        jitter: bool,
+    },
+}
+
+/// Retry policy.
+///
+/// It is used for the command handling that failed because of the concurrent modification (see `handle_with_retry`), and for the repository/publisher calls that failed transiently (see [crate::retry::Retrying]).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryPolicy {
+    /// The maximum number of attempts to handle the command, including the first one
+    pub max_attempts: usize,
+    /// The delay between the attempts
+    pub delay: RetryDelay,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 3,
+            delay: RetryDelay::None,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Creates a new instance of [RetryPolicy], without the delay between the attempts.
+    pub fn new(max_attempts: usize) -> Self {
+        RetryPolicy {
+            max_attempts,
+            delay: RetryDelay::None,
+        }
+    }
+
+    /// Creates a new instance of [RetryPolicy], with the fixed delay between the attempts.
+    pub fn fixed(max_attempts: usize, delay: Duration) -> Self {
+        RetryPolicy {
+            max_attempts,
+            delay: RetryDelay::Fixed(delay),
+        }
+    }
+
+    /// Creates a new instance of [RetryPolicy], with the exponential backoff between the attempts.
+    pub fn exponential(
+        max_attempts: usize,
+        initial: Duration,
+        max: Duration,
+        jitter: bool,
+    ) -> Self {
+        RetryPolicy {
+            max_attempts,
+            delay: RetryDelay::Exponential {
+                initial,
+                max,
+                jitter,
+            },
+        }
+    }
+
+    /// The delay after the failed `attempt` (starting from `1`).
+    pub fn delay(&self, attempt: usize) -> Duration {
+        match self.delay {
+            RetryDelay::None => Duration::ZERO,
+            RetryDelay::Fixed(delay) => delay,
+            RetryDelay::Exponential {
+                initial,
+                max,
+                jitter,
+            } => {
+                let exponent = attempt.saturating_sub(1).min(31) as u32;
+                let delay = initial.saturating_mul(2u32.pow(exponent)).min(max);
+                if jitter {
+                    // `RandomState` is randomly seeded, which is good enough for the jitter
+                    let random = RandomState::new().hash_one(attempt);
+                    delay.mul_f64(random as f64 / u64::MAX as f64)
+                } else {
+                    delay
+                }
+            }
+        }
+    }
+}
+
+/// Lock Repository trait
+///
+/// It is used by the aggregates to serialize the command handling per aggregate/stream (pessimistic locking), for example, by the advisory locks of the database.
+///
+/// Generic parameters:
+///
+/// - `C` - Command
+/// - `Error` - Error
+pub trait LockRepository<C, Error> {
+    /// Acquires the lock of the aggregate/stream the command belongs to, waiting for the lock to be released by the other process if needed.
+    /// Desugared `async fn acquire_lock(&self, command: &C) -> Result<(), Error>;` to a normal `fn` that returns `impl Future`, and adds bound `MaybeSend` (`Send` with the default `send` feature)
+    /// You can freely move between the `async fn` and `-> impl Future` spelling in your traits and impls. This is true even when one form has a Send bound.
+    fn acquire_lock(&self, command: &C) -> impl Future<Output = Result<(), Error>> + MaybeSend;
+    /// Releases the lock of the aggregate/stream the command belongs to.
+    /// Desugared `async fn release_lock(&self, command: &C) -> Result<(), Error>;` to a normal `fn` that returns `impl Future`, and adds bound `MaybeSend` (`Send` with the default `send` feature)
+    /// You can freely move between the `async fn` and `-> impl Future` spelling in your traits and impls. This is true even when one form has a Send bound.
+    fn release_lock(&self, command: &C) -> impl Future<Output = Result<(), Error>> + MaybeSend;
 }
 
 /// Event Sourced Aggregate.
@@ -74,13 +372,13 @@ where
 impl<C, S, E, Repository, Decider, Version, Error> EventRepository<C, E, Version, Error>
     for EventSourcedAggregate<C, S, E, Repository, Decider, Version, Error>
 where
-    Repository: EventRepository<C, E, Version, Error> + Sync,
-    Decider: EventComputation<C, S, E, Error> + Sync,
-    C: Sync,
-    S: Sync,
-    E: Sync,
-    Version: Sync,
-    Error: Sync,
+    Repository: EventRepository<C, E, Version, Error> + MaybeSync,
+    Decider: EventComputation<C, S, E, Error> + MaybeSync,
+    C: MaybeSync,
+    S: MaybeSync,
+    E: MaybeSync,
+    Version: MaybeSync,
+    Error: MaybeSync,
 {
     /// Fetches current events, based on the command.
     async fn fetch_events(&self, command: &C) -> Result<Vec<(E, Version)>, Error> {
@@ -99,13 +397,13 @@ where
 impl<C, S, E, Repository, Decider, Version, Error>
     EventSourcedAggregate<C, S, E, Repository, Decider, Version, Error>
 where
-    Repository: EventRepository<C, E, Version, Error> + Sync,
-    Decider: EventComputation<C, S, E, Error> + Sync,
-    C: Sync,
-    S: Sync,
-    E: Sync,
-    Version: Sync,
-    Error: Sync,
+    Repository: EventRepository<C, E, Version, Error> + MaybeSync,
+    Decider: EventComputation<C, S, E, Error> + MaybeSync,
+    C: MaybeSync,
+    S: MaybeSync,
+    E: MaybeSync,
+    Version: MaybeSync,
+    Error: MaybeSync,
 {
     /// Creates a new instance of [EventSourcedAggregate].
     pub fn new(repository: Repository, decider: Decider) -> Self {
@@ -126,6 +424,529 @@ where
         let saved_events = self.save(&new_events).await?;
         Ok(saved_events)
     }
+    /// Handles the batch of commands by fetching the events from the repository once per decider/stream identifier, computing new events for each command sequentially (evolving the events in memory), and saving all the new events to the repository in a single call.
+    pub async fn handle_all(&self, commands: &[C]) -> Result<Vec<(E, Version)>, Error>
+    where
+        C: Identifier,
+        E: Clone,
+    {
+        let mut current_events: HashMap<String, Vec<E>> = HashMap::new();
+        let mut new_events: Vec<E> = vec![];
+        for command in commands {
+            let id = command.identifier();
+            if !current_events.contains_key(&id) {
+                let events = self.fetch_events(command).await?;
+                current_events.insert(
+                    id.clone(),
+                    events.into_iter().map(|(event, _)| event).collect(),
+                );
+            }
+            let stream_events = current_events.entry(id).or_default();
+            let events = self.compute_new_events(stream_events, command)?;
+            stream_events.extend_from_slice(&events);
+            new_events.extend(events);
+        }
+        if new_events.is_empty() {
+            return Ok(vec![]);
+        }
+        self.save(&new_events).await
+    }
+    /// Fetches the current state, by folding the events of the stream to which the query (command) belongs to, without handling the command.
+    pub async fn fetch_current_state(&self, query: &C) -> Result<S, Error>
+    where
+        Decider: CurrentStateComputation<S, E>,
+    {
+        let current_events: Vec<E> = self
+            .fetch_events(query)
+            .await?
+            .into_iter()
+            .map(|(event, _)| event)
+            .collect();
+        Ok(self.decider.compute_current_state(&current_events))
+    }
+    /// Handles the command by fetching the events from the repository, replaying only the events that are relevant to the command, computing new events based on the relevant events and the command, and saving the new events to the repository.
+    /// It is used with the combined deciders, so the sub-decider that is handling the command replays only the events it can evolve (for example, `Sum::is_same_variant`), instead of all the events of the stream.
+    pub async fn handle_with_filter<F>(
+        &self,
+        command: &C,
+        relevant: &F,
+    ) -> Result<Vec<(E, Version)>, Error>
+    where
+        F: Fn(&C, &E) -> bool + MaybeSync,
+    {
+        let events: Vec<(E, Version)> = self.fetch_events(command).await?;
+        let current_events: Vec<E> = events
+            .into_iter()
+            .map(|(event, _)| event)
+            .filter(|event| relevant(command, event))
+            .collect();
+        let new_events = self.compute_new_events(&current_events, command)?;
+        let saved_events = self.save(&new_events).await?;
+        Ok(saved_events)
+    }
+    /// Handles the command with metadata by fetching the events from the repository, computing new events based on the current events and the command, and saving the new events together with the metadata to the repository.
+    pub async fn handle_with_metadata<M>(
+        &self,
+        command: &C,
+        metadata: &M,
+    ) -> Result<Vec<(E, Version)>, Error>
+    where
+        Repository: EventRepositoryWithMetadata<C, E, M, Version, Error>,
+        M: Clone + MaybeSync,
+    {
+        let events: Vec<(E, Version)> = self.fetch_events(command).await?;
+        let mut current_events: Vec<E> = vec![];
+        for (event, _) in events {
+            current_events.push(event);
+        }
+        let new_events = self
+            .compute_new_events(&current_events, command)?
+            .into_iter()
+            .map(|event| (event, metadata.clone()))
+            .collect::<Vec<(E, M)>>();
+        let saved_events = self.repository.save_with_metadata(&new_events).await?;
+        Ok(saved_events)
+    }
+    /// Handles the command within the context (tenant id, ...) by fetching the events from the repository, computing new events based on the current events and the command, and saving the new events to the repository, within the same context.
+    pub async fn handle_with_context<Ctx>(
+        &self,
+        command: &C,
+        context: &Ctx,
+    ) -> Result<Vec<(E, Version)>, Error>
+    where
+        Repository: EventRepositoryWithContext<C, E, Ctx, Version, Error>,
+        Ctx: MaybeSync,
+    {
+        let events: Vec<(E, Version)> = self
+            .repository
+            .fetch_events_with_context(command, context)
+            .await?;
+        let mut current_events: Vec<E> = vec![];
+        for (event, _) in events {
+            current_events.push(event);
+        }
+        let new_events = self.compute_new_events(&current_events, command)?;
+        let saved_events = self
+            .repository
+            .save_with_context(&new_events, context)
+            .await?;
+        Ok(saved_events)
+    }
+    /// Handles the command by fetching the events from the repository, computing new events based on the current events and the command, and saving the new events to the repository and to the outbox, atomically.
+    pub async fn handle_with_outbox(&self, command: &C) -> Result<Vec<(E, Version)>, Error>
+    where
+        Repository: EventRepositoryWithOutbox<C, E, Version, Error>,
+    {
+        let events: Vec<(E, Version)> = self.fetch_events(command).await?;
+        let mut current_events: Vec<E> = vec![];
+        for (event, _) in events {
+            current_events.push(event);
+        }
+        let new_events = self.compute_new_events(&current_events, command)?;
+        let saved_events = self.repository.save_and_outbox(&new_events).await?;
+        Ok(saved_events)
+    }
+    /// Handles the command with pessimistic locking: the lock of the stream is acquired before the command is handled, and released afterwards (even if the handling fails).
+    pub async fn handle_with_lock<Lock>(
+        &self,
+        command: &C,
+        lock_repository: &Lock,
+    ) -> Result<Vec<(E, Version)>, Error>
+    where
+        Lock: LockRepository<C, Error> + MaybeSync,
+    {
+        lock_repository.acquire_lock(command).await?;
+        let result = self.handle(command).await;
+        let released = lock_repository.release_lock(command).await;
+        let saved_events = result?;
+        released?;
+        Ok(saved_events)
+    }
+    /// Handles the command with optimistic locking by fetching the events from the repository, computing new events based on the current events and the command, and saving the new events to the repository if the stream was not modified in the meantime.
+    /// The command handling is retried, according to the retry policy, if the events could not be saved because of the concurrent modification.
+    /// The `sleep` is awaited for the delay of the retry policy before every retry.
+    pub async fn handle_with_retry<F, Fut>(
+        &self,
+        command: &C,
+        retry_policy: &RetryPolicy,
+        sleep: &F,
+    ) -> Result<Vec<(E, Version)>, Error>
+    where
+        Repository: EventRepositoryWithOptimisticLocking<C, E, Version, Error>,
+        Error: OptimisticLockingError,
+        F: Fn(Duration) -> Fut,
+        Fut: Future<Output = ()>,
+    {
+        let mut attempt = 1;
+        loop {
+            let (current_events, expected_version) = self.fetch_versioned(command).await?;
+            let new_events = self.compute_new_events(&current_events, command)?;
+            match self
+                .repository
+                .save_with_expected_version(&new_events, &expected_version)
+                .await
+            {
+                Err(error)
+                    if error.is_concurrency_error() && attempt < retry_policy.max_attempts =>
+                {
+                    sleep(retry_policy.delay(attempt)).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+    /// Handles the command with optimistic locking, like [EventSourcedAggregate::handle_with_retry], but the concurrent modification is resolved by the [ConflictResolver].
+    /// The resolver is given the new events of the command and the events that were saved concurrently, and it merges them (saving the merged events without handling the command again), retries the command, or rejects it.
+    /// The number of the save attempts is limited by the retry policy, and the `sleep` is awaited for the delay of the retry policy before every attempt to resolve the conflict.
+    pub async fn handle_with_conflict_resolution<Resolver, F, Fut>(
+        &self,
+        command: &C,
+        resolver: &Resolver,
+        retry_policy: &RetryPolicy,
+        sleep: &F,
+    ) -> Result<Vec<(E, Version)>, Error>
+    where
+        Repository: EventRepositoryWithOptimisticLocking<C, E, Version, Error>,
+        Resolver: ConflictResolver<E> + MaybeSync,
+        Error: OptimisticLockingError,
+        F: Fn(Duration) -> Fut,
+        Fut: Future<Output = ()>,
+    {
+        let (mut current_events, mut expected_version) = self.fetch_versioned(command).await?;
+        let mut new_events = self.compute_new_events(&current_events, command)?;
+        let mut attempt = 1;
+        loop {
+            match self
+                .repository
+                .save_with_expected_version(&new_events, &expected_version)
+                .await
+            {
+                Err(error)
+                    if error.is_concurrency_error() && attempt < retry_policy.max_attempts =>
+                {
+                    sleep(retry_policy.delay(attempt)).await;
+                    attempt += 1;
+                    let (events, version) = self.fetch_versioned(command).await?;
+                    let theirs = &events[current_events.len().min(events.len())..];
+                    new_events = match resolver.resolve(&new_events, theirs) {
+                        Resolution::Merge(merged_events) => merged_events,
+                        Resolution::Retry => self.compute_new_events(&events, command)?,
+                        Resolution::Reject => return Err(error),
+                    };
+                    current_events = events;
+                    expected_version = version;
+                }
+                result => return result,
+            }
+        }
+    }
+    /// Fetches the events of the stream, together with the latest version of the stream.
+    async fn fetch_versioned(&self, command: &C) -> Result<(Vec<E>, Option<Version>), Error> {
+        let mut current_events: Vec<E> = vec![];
+        let mut latest_version: Option<Version> = None;
+        for (event, version) in self.fetch_events(command).await? {
+            current_events.push(event);
+            latest_version = Some(version);
+        }
+        Ok((current_events, latest_version))
+    }
+}
+
+/// Event Repository trait that is able to fetch the tail of the event stream
+///
+/// Extends the [EventRepository] with the ability to fetch only the events that happened after the given version/snapshot, so the aggregate does not need to replay the whole stream.
+///
+/// Generic parameters:
+///
+/// - `C` - Command
+/// - `E` - Event
+/// - `Version` - Version/Offset/Sequence number
+/// - `Error` - Error
+pub trait EventTailRepository<C, E, Version, Error>: EventRepository<C, E, Version, Error> {
+    /// Fetches current events that happened after the given version, based on the command.
+    /// Desugared `async fn fetch_events_after(&self, command: &C, version: &Version) -> Result<Vec<(E, Version)>, Error>;` to a normal `fn` that returns `impl Future`, and adds bound `MaybeSend` (`Send` with the default `send` feature)
+    /// You can freely move between the `async fn` and `-> impl Future` spelling in your traits and impls. This is true even when one form has a Send bound.
+    fn fetch_events_after(
+        &self,
+        command: &C,
+        version: &Version,
+    ) -> impl Future<Output = Result<Vec<(E, Version)>, Error>> + MaybeSend;
+}
+
+/// Event Repository With Archiving trait
+///
+/// It is used to archive (move to the cold storage) or to truncate (delete permanently) the events of the stream that are included in the latest snapshot, keeping the long-lived streams short.
+/// The versions of the archived/truncated events are not reused by the new events of the stream.
+///
+/// Generic parameters:
+///
+/// - `C` - Command
+/// - `E` - Event
+/// - `Version` - Version/Offset/Sequence number
+/// - `Error` - Error
+pub trait EventRepositoryWithArchiving<C, E, Version, Error>:
+    EventTailRepository<C, E, Version, Error>
+{
+    /// Archives the events of the stream, based on the command, up to and including the given version, returning the archived events.
+    /// Desugared `async fn archive_events_until(&self, command: &C, version: &Version) -> Result<Vec<(E, Version)>, Error>;` to a normal `fn` that returns `impl Future`, and adds bound `MaybeSend` (`Send` with the default `send` feature)
+    /// You can freely move between the `async fn` and `-> impl Future` spelling in your traits and impls. This is true even when one form has a Send bound.
+    fn archive_events_until(
+        &self,
+        command: &C,
+        version: &Version,
+    ) -> impl Future<Output = Result<Vec<(E, Version)>, Error>> + MaybeSend;
+    /// Truncates (deletes permanently) the events of the stream, based on the command, up to and including the given version, returning the number of the deleted events.
+    /// Desugared `async fn truncate_events_until(&self, command: &C, version: &Version) -> Result<usize, Error>;` to a normal `fn` that returns `impl Future`, and adds bound `MaybeSend` (`Send` with the default `send` feature)
+    /// You can freely move between the `async fn` and `-> impl Future` spelling in your traits and impls. This is true even when one form has a Send bound.
+    fn truncate_events_until(
+        &self,
+        command: &C,
+        version: &Version,
+    ) -> impl Future<Output = Result<usize, Error>> + MaybeSend;
+}
+
+/// The error signaling that the stream can not be archived/truncated, because there is no snapshot of its state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MissingSnapshot;
+
+impl Display for MissingSnapshot {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "No snapshot exists for the stream")
+    }
+}
+
+impl std::error::Error for MissingSnapshot {}
+
+/// Snapshot Repository trait
+///
+/// It is used to fetch and save the snapshots of the event-sourced state, together with the version of the latest event that is included in the snapshot.
+///
+/// Generic parameters:
+///
+/// - `C` - Command
+/// - `S` - State
+/// - `Version` - Version/Offset/Sequence number of the latest event included in the snapshot
+/// - `Error` - Error
+pub trait SnapshotRepository<C, S, Version, Error> {
+    /// Fetches the latest snapshot, based on the command.
+    /// Desugared `async fn fetch_snapshot(&self, command: &C) -> Result<Option<(S, Version)>, Error>;` to a normal `fn` that returns `impl Future`, and adds bound `MaybeSend` (`Send` with the default `send` feature)
+    /// You can freely move between the `async fn` and `-> impl Future` spelling in your traits and impls. This is true even when one form has a Send bound.
+    fn fetch_snapshot(
+        &self,
+        command: &C,
+    ) -> impl Future<Output = Result<Option<(S, Version)>, Error>> + MaybeSend;
+    /// Saves the snapshot.
+    /// Desugared `async fn save_snapshot(&self, state: &S, version: &Version) -> Result<(S, Version), Error>;` to a normal `fn` that returns `impl Future`, and adds bound `MaybeSend` (`Send` with the default `send` feature)
+    /// You can freely move between the `async fn` and `-> impl Future` spelling in your traits and impls. This is true even when one form has a Send bound.
+    fn save_snapshot(
+        &self,
+        state: &S,
+        version: &Version,
+    ) -> impl Future<Output = Result<(S, Version), Error>> + MaybeSend;
+}
+
+/// Snapshotting Event Sourced Aggregate.
+///
+/// It is using a [Decider] to compute new events based on the latest snapshot of the state, the events that happened after it, and the command.
+/// It is using a [EventTailRepository] to fetch the tail of the events and to save the new events.
+/// It is using a [SnapshotRepository] to fetch the latest snapshot, and to save the new snapshot once the number of events since the latest snapshot reaches the `snapshot_threshold`.
+///
+/// Generic parameters:
+///
+/// - `C` - Command
+/// - `S` - State
+/// - `E` - Event
+/// - `Repository` - Event repository
+/// - `Snapshots` - Snapshot repository
+/// - `Version` - Version/Offset/Sequence number
+/// - `Error` - Error
+pub struct EventSourcedSnapshottingAggregate<'a, C, S, E, Repository, Snapshots, Version, Error>
+where
+    Repository: EventTailRepository<C, E, Version, Error>,
+    Snapshots: SnapshotRepository<C, S, Version, Error>,
+{
+    repository: Repository,
+    snapshot_repository: Snapshots,
+    decider: Decider<'a, C, S, E, Error>,
+    snapshot_threshold: usize,
+    _marker: PhantomData<(C, S, E, Version, Error)>,
+}
+
+impl<C, S, E, Repository, Snapshots, Version, Error> EventRepository<C, E, Version, Error>
+    for EventSourcedSnapshottingAggregate<'_, C, S, E, Repository, Snapshots, Version, Error>
+where
+    Repository: EventTailRepository<C, E, Version, Error> + MaybeSync,
+    Snapshots: SnapshotRepository<C, S, Version, Error> + MaybeSync,
+    C: MaybeSync,
+    S: MaybeSync,
+    E: MaybeSync,
+    Version: MaybeSync,
+    Error: MaybeSync,
+{
+    /// Fetches current events, based on the command.
+    fn fetch_events(
+        &self,
+        command: &C,
+    ) -> impl Future<Output = Result<Vec<(E, Version)>, Error>> + MaybeSend {
+        self.repository.fetch_events(command)
+    }
+    /// Saves events.
+    fn save(
+        &self,
+        events: &[E],
+    ) -> impl Future<Output = Result<Vec<(E, Version)>, Error>> + MaybeSend {
+        self.repository.save(events)
+    }
+    /// Version provider. It is used to provide the version/sequence of the event. Optimistic locking is useing this version to check if the event is already saved.
+    fn version_provider(
+        &self,
+        event: &E,
+    ) -> impl Future<Output = Result<Option<Version>, Error>> + MaybeSend {
+        self.repository.version_provider(event)
+    }
+}
+
+impl<C, S, E, Repository, Snapshots, Version, Error> SnapshotRepository<C, S, Version, Error>
+    for EventSourcedSnapshottingAggregate<'_, C, S, E, Repository, Snapshots, Version, Error>
+where
+    Repository: EventTailRepository<C, E, Version, Error> + MaybeSync,
+    Snapshots: SnapshotRepository<C, S, Version, Error> + MaybeSync,
+    C: MaybeSync,
+    S: MaybeSync,
+    E: MaybeSync,
+    Version: MaybeSync,
+    Error: MaybeSync,
+{
+    /// Fetches the latest snapshot, based on the command.
+    fn fetch_snapshot(
+        &self,
+        command: &C,
+    ) -> impl Future<Output = Result<Option<(S, Version)>, Error>> + MaybeSend {
+        self.snapshot_repository.fetch_snapshot(command)
+    }
+    /// Saves the snapshot.
+    fn save_snapshot(
+        &self,
+        state: &S,
+        version: &Version,
+    ) -> impl Future<Output = Result<(S, Version), Error>> + MaybeSend {
+        self.snapshot_repository.save_snapshot(state, version)
+    }
+}
+
+impl<'a, C, S, E, Repository, Snapshots, Version, Error>
+    EventSourcedSnapshottingAggregate<'a, C, S, E, Repository, Snapshots, Version, Error>
+where
+    Repository: EventTailRepository<C, E, Version, Error> + MaybeSync,
+    Snapshots: SnapshotRepository<C, S, Version, Error> + MaybeSync,
+    C: MaybeSync,
+    S: MaybeSync,
+    E: MaybeSync,
+    Version: MaybeSync,
+    Error: MaybeSync,
+{
+    /// Creates a new instance of [EventSourcedSnapshottingAggregate].
+    /// A new snapshot is saved once the number of events since the latest snapshot reaches the `snapshot_threshold`.
+    pub fn new(
+        repository: Repository,
+        snapshot_repository: Snapshots,
+        decider: Decider<'a, C, S, E, Error>,
+        snapshot_threshold: usize,
+    ) -> Self {
+        EventSourcedSnapshottingAggregate {
+            repository,
+            snapshot_repository,
+            decider,
+            snapshot_threshold,
+            _marker: PhantomData,
+        }
+    }
+    /// Handles the command by fetching the latest snapshot and the events that happened after it from the repositories, computing new events based on the current state and the command, and saving the new events to the repository.
+    /// The new snapshot is saved if the number of events since the latest snapshot reaches the `snapshot_threshold`.
+    /// The snapshot is the optimization only: the snapshot that fails to be saved does not fail the command, and the events are replayed from the previous snapshot instead.
+    pub async fn handle(&self, command: &C) -> Result<Vec<(E, Version)>, Error> {
+        let (current_state, replayed) = self.replay(command).await?;
+        let new_events = (self.decider.decide)(command, &current_state)?;
+        let saved_events = self.repository.save(&new_events).await?;
+        if replayed + saved_events.len() >= self.snapshot_threshold {
+            if let Some((_, version)) = saved_events.last() {
+                let new_state = saved_events
+                    .iter()
+                    .fold(current_state, |state, (event, _)| {
+                        (self.decider.evolve)(&state, event)
+                    });
+                let _ = self
+                    .snapshot_repository
+                    .save_snapshot(&new_state, version)
+                    .await;
+            }
+        }
+        Ok(saved_events)
+    }
+    /// Fetches the current state, from the latest snapshot and the events that happened after it, without handling the command.
+    pub async fn fetch_current_state(&self, query: &C) -> Result<S, Error> {
+        self.replay(query)
+            .await
+            .map(|(current_state, _)| current_state)
+    }
+    /// Replays the events that happened after the latest snapshot onto it, returning the current state and the number of the replayed events.
+    async fn replay(&self, command: &C) -> Result<(S, usize), Error> {
+        let (snapshot_state, events) =
+            match self.snapshot_repository.fetch_snapshot(command).await? {
+                None => (
+                    (self.decider.initial_state)(),
+                    self.repository.fetch_events(command).await?,
+                ),
+                Some((state, version)) => (
+                    state,
+                    self.repository
+                        .fetch_events_after(command, &version)
+                        .await?,
+                ),
+            };
+        let current_state = events.iter().fold(snapshot_state, |state, (event, _)| {
+            (self.decider.evolve)(&state, event)
+        });
+        Ok((current_state, events.len()))
+    }
+}
+
+impl<C, S, E, Repository, Snapshots, Version, Error>
+    EventSourcedSnapshottingAggregate<'_, C, S, E, Repository, Snapshots, Version, Error>
+where
+    Repository: EventRepositoryWithArchiving<C, E, Version, Error> + MaybeSync,
+    Snapshots: SnapshotRepository<C, S, Version, Error> + MaybeSync,
+    C: MaybeSync,
+    S: MaybeSync,
+    E: MaybeSync,
+    Version: MaybeSync,
+    Error: From<MissingSnapshot> + MaybeSync,
+{
+    /// Archives the events of the stream, based on the command, that are included in the latest snapshot, returning the archived events.
+    /// It fails with the [MissingSnapshot] error if there is no snapshot, so the state can always be restored.
+    pub async fn archive(&self, command: &C) -> Result<Vec<(E, Version)>, Error> {
+        match self.snapshot_repository.fetch_snapshot(command).await? {
+            None => Err(MissingSnapshot.into()),
+            Some((_, version)) => {
+                self.repository
+                    .archive_events_until(command, &version)
+                    .await
+            }
+        }
+    }
+    /// Truncates (deletes permanently) the events of the stream, based on the command, that are included in the latest snapshot, returning the number of the deleted events.
+    /// It fails with the [MissingSnapshot] error if there is no snapshot, so the state can always be restored.
+    pub async fn truncate(&self, command: &C) -> Result<usize, Error> {
+        match self.snapshot_repository.fetch_snapshot(command).await? {
+            None => Err(MissingSnapshot.into()),
+            Some((_, version)) => {
+                self.repository
+                    .truncate_events_until(command, &version)
+                    .await
+            }
+        }
+    }
 }
 
 /// State Repository trait
@@ -138,20 +959,79 @@ where
 /// - `Error` - Error
 pub trait StateRepository<C, S, Version, Error> {
     /// Fetches current state, based on the command.
-    /// Desugared `async fn fetch_state(&self, command: &C) -> Result<Option<(S, Version)>, Error>;` to a normal `fn` that returns `impl Future` and adds bound `Send`
+    /// Desugared `async fn fetch_state(&self, command: &C) -> Result<Option<(S, Version)>, Error>;` to a normal `fn` that returns `impl Future` and adds bound `MaybeSend` (`Send` with the default `send` feature)
     /// You can freely move between the `async fn` and `-> impl Future` spelling in your traits and impls. This is true even when one form has a Send bound.
     fn fetch_state(
         &self,
         command: &C,
-    ) -> impl Future<Output = Result<Option<(S, Version)>, Error>> + Send;
+    ) -> impl Future<Output = Result<Option<(S, Version)>, Error>> + MaybeSend;
     /// Saves state.
-    /// Desugared `async fn save(&self, state: &S, version: &Option<Version>) -> Result<(S, Version), Error>;` to a normal `fn` that returns `impl Future` and adds bound `Send`
+    /// The implementation should fail with the [ConcurrencyError] (converted into the `Error`) if the latest version of the state does not match the given version (optimistic locking).
+    /// Desugared `async fn save(&self, state: &S, version: &Option<Version>) -> Result<(S, Version), Error>;` to a normal `fn` that returns `impl Future` and adds bound `MaybeSend` (`Send` with the default `send` feature)
     /// You can freely move between the `async fn` and `-> impl Future` spelling in your traits and impls. This is true even when one form has a Send bound.
     fn save(
         &self,
         state: &S,
         version: &Option<Version>,
-    ) -> impl Future<Output = Result<(S, Version), Error>> + Send;
+    ) -> impl Future<Output = Result<(S, Version), Error>> + MaybeSend;
+}
+
+/// State Repository trait with metadata
+///
+/// Extends the [StateRepository] with the ability to save the state together with the metadata (correlation id, causation id, tenant id, user info, ...), without polluting the domain types.
+///
+/// Generic parameters:
+///
+/// - `C` - Command
+/// - `S` - State
+/// - `M` - Metadata
+/// - `Version` - Version
+/// - `Error` - Error
+pub trait StateRepositoryWithMetadata<C, S, M, Version, Error>:
+    StateRepository<C, S, Version, Error>
+{
+    /// Saves state together with the metadata.
+    /// Desugared `async fn save_with_metadata(&self, state: &S, version: &Option<Version>, metadata: &M) -> Result<(S, Version), Error>;` to a normal `fn` that returns `impl Future` and adds bound `MaybeSend` (`Send` with the default `send` feature)
+    /// You can freely move between the `async fn` and `-> impl Future` spelling in your traits and impls. This is true even when one form has a Send bound.
+    fn save_with_metadata(
+        &self,
+        state: &S,
+        version: &Option<Version>,
+        metadata: &M,
+    ) -> impl Future<Output = Result<(S, Version), Error>> + MaybeSend;
+}
+
+/// State Repository trait with context
+///
+/// Extends the [StateRepository] with the ability to fetch and save the state within the context (tenant id, partition, ...), so the multi-tenant systems can partition the state without wrapping every repository.
+///
+/// Generic parameters:
+///
+/// - `C` - Command
+/// - `S` - State
+/// - `Ctx` - Context (tenant id, ...)
+/// - `Version` - Version
+/// - `Error` - Error
+pub trait StateRepositoryWithContext<C, S, Ctx, Version, Error>:
+    StateRepository<C, S, Version, Error>
+{
+    /// Fetches current state within the context, based on the command.
+    /// Desugared `async fn fetch_state_with_context(&self, command: &C, context: &Ctx) -> Result<Option<(S, Version)>, Error>;` to a normal `fn` that returns `impl Future` and adds bound `MaybeSend` (`Send` with the default `send` feature)
+    /// You can freely move between the `async fn` and `-> impl Future` spelling in your traits and impls. This is true even when one form has a Send bound.
+    fn fetch_state_with_context(
+        &self,
+        command: &C,
+        context: &Ctx,
+    ) -> impl Future<Output = Result<Option<(S, Version)>, Error>> + MaybeSend;
+    /// Saves state within the context.
+    /// Desugared `async fn save_with_context(&self, state: &S, version: &Option<Version>, context: &Ctx) -> Result<(S, Version), Error>;` to a normal `fn` that returns `impl Future` and adds bound `MaybeSend` (`Send` with the default `send` feature)
+    /// You can freely move between the `async fn` and `-> impl Future` spelling in your traits and impls. This is true even when one form has a Send bound.
+    fn save_with_context(
+        &self,
+        state: &S,
+        version: &Option<Version>,
+        context: &Ctx,
+    ) -> impl Future<Output = Result<(S, Version), Error>> + MaybeSend;
 }
 
 /// State Stored Aggregate.
@@ -193,13 +1073,13 @@ where
 impl<C, S, E, Repository, Decider, Version, Error> StateRepository<C, S, Version, Error>
     for StateStoredAggregate<C, S, E, Repository, Decider, Version, Error>
 where
-    Repository: StateRepository<C, S, Version, Error> + Sync,
-    Decider: StateComputation<C, S, E, Error> + Sync,
-    C: Sync,
-    S: Sync,
-    E: Sync,
-    Version: Sync,
-    Error: Sync,
+    Repository: StateRepository<C, S, Version, Error> + MaybeSync,
+    Decider: StateComputation<C, S, E, Error> + MaybeSync,
+    C: MaybeSync,
+    S: MaybeSync,
+    E: MaybeSync,
+    Version: MaybeSync,
+    Error: MaybeSync,
 {
     /// Fetches current state, based on the command.
     async fn fetch_state(&self, command: &C) -> Result<Option<(S, Version)>, Error> {
@@ -214,13 +1094,13 @@ where
 impl<C, S, E, Repository, Decider, Version, Error>
     StateStoredAggregate<C, S, E, Repository, Decider, Version, Error>
 where
-    Repository: StateRepository<C, S, Version, Error> + Sync,
-    Decider: StateComputation<C, S, E, Error> + Sync,
-    C: Sync,
-    S: Sync,
-    E: Sync,
-    Version: Sync,
-    Error: Sync,
+    Repository: StateRepository<C, S, Version, Error> + MaybeSync,
+    Decider: StateComputation<C, S, E, Error> + MaybeSync,
+    C: MaybeSync,
+    S: MaybeSync,
+    E: MaybeSync,
+    Version: MaybeSync,
+    Error: MaybeSync,
 {
     /// Creates a new instance of [StateStoredAggregate].
     pub fn new(repository: Repository, decider: Decider) -> Self {
@@ -246,6 +1126,135 @@ where
             }
         }
     }
+    /// Handles the batch of commands by fetching the state from the repository once per decider identifier, computing new state for each command sequentially (evolving the state in memory), and saving the final state of each decider to the repository.
+    /// The saved states are returned in the order in which the deciders first appear in the batch.
+    pub async fn handle_all(&self, commands: &[C]) -> Result<Vec<(S, Version)>, Error>
+    where
+        C: Identifier,
+    {
+        let mut ids: Vec<String> = vec![];
+        let mut states: HashMap<String, (Option<S>, Option<Version>)> = HashMap::new();
+        for command in commands {
+            let id = command.identifier();
+            let (state, version) = match states.remove(&id) {
+                Some(state_version) => state_version,
+                None => {
+                    ids.push(id.clone());
+                    match self.fetch_state(command).await? {
+                        Some((state, version)) => (Some(state), Some(version)),
+                        None => (None, None),
+                    }
+                }
+            };
+            let new_state = self.compute_new_state(state, command)?;
+            states.insert(id, (Some(new_state), version));
+        }
+        let mut saved_states: Vec<(S, Version)> = vec![];
+        for id in ids {
+            if let Some((Some(state), version)) = states.remove(&id) {
+                saved_states.push(self.save(&state, &version).await?);
+            }
+        }
+        Ok(saved_states)
+    }
+    /// Handles the command with metadata by fetching the state from the repository, computing new state based on the current state and the command, and saving the new state together with the metadata to the repository.
+    pub async fn handle_with_metadata<M>(
+        &self,
+        command: &C,
+        metadata: &M,
+    ) -> Result<(S, Version), Error>
+    where
+        Repository: StateRepositoryWithMetadata<C, S, M, Version, Error>,
+        M: MaybeSync,
+    {
+        let state_version = self.fetch_state(command).await?;
+        match state_version {
+            None => {
+                let new_state = self.compute_new_state(None, command)?;
+                let saved_state = self
+                    .repository
+                    .save_with_metadata(&new_state, &None, metadata)
+                    .await?;
+                Ok(saved_state)
+            }
+            Some((state, version)) => {
+                let new_state = self.compute_new_state(Some(state), command)?;
+                let saved_state = self
+                    .repository
+                    .save_with_metadata(&new_state, &Some(version), metadata)
+                    .await?;
+                Ok(saved_state)
+            }
+        }
+    }
+    /// Handles the command within the context (tenant id, ...) by fetching the state from the repository, computing new state based on the current state and the command, and saving the new state to the repository, within the same context.
+    pub async fn handle_with_context<Ctx>(
+        &self,
+        command: &C,
+        context: &Ctx,
+    ) -> Result<(S, Version), Error>
+    where
+        Repository: StateRepositoryWithContext<C, S, Ctx, Version, Error>,
+        Ctx: MaybeSync,
+    {
+        let (state, version) = match self
+            .repository
+            .fetch_state_with_context(command, context)
+            .await?
+        {
+            None => (None, None),
+            Some((state, version)) => (Some(state), Some(version)),
+        };
+        let new_state = self.compute_new_state(state, command)?;
+        let saved_state = self
+            .repository
+            .save_with_context(&new_state, &version, context)
+            .await?;
+        Ok(saved_state)
+    }
+    /// Handles the command with pessimistic locking: the lock of the state is acquired before the command is handled, and released afterwards (even if the handling fails).
+    pub async fn handle_with_lock<Lock>(
+        &self,
+        command: &C,
+        lock_repository: &Lock,
+    ) -> Result<(S, Version), Error>
+    where
+        Lock: LockRepository<C, Error> + MaybeSync,
+    {
+        lock_repository.acquire_lock(command).await?;
+        let result = self.handle(command).await;
+        let released = lock_repository.release_lock(command).await;
+        let saved_state = result?;
+        released?;
+        Ok(saved_state)
+    }
+    /// Handles the command with optimistic locking by fetching the state from the repository, computing new state based on the current state and the command, and saving the new state to the repository if the state was not modified in the meantime.
+    /// The command handling is retried, according to the retry policy, if the state could not be saved because of the concurrent modification.
+    /// The `sleep` is awaited for the delay of the retry policy before every retry.
+    pub async fn handle_with_retry<F, Fut>(
+        &self,
+        command: &C,
+        retry_policy: &RetryPolicy,
+        sleep: &F,
+    ) -> Result<(S, Version), Error>
+    where
+        Error: OptimisticLockingError,
+        F: Fn(Duration) -> Fut,
+        Fut: Future<Output = ()>,
+    {
+        let mut attempt = 1;
+        loop {
+            match self.handle(command).await {
+                Err(error)
+                    if error.is_concurrency_error() && attempt < retry_policy.max_attempts =>
+                {
+                    sleep(retry_policy.delay(attempt)).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
 }
 
 /// Orchestrating Event Sourced Aggregate.
@@ -265,49 +1274,58 @@ where
 {
     repository: Repository,
     decider: Decider<'a, C, S, E, Error>,
-    saga: Saga<'a, E, C>,
+    saga: Saga<'a, E, C, Error>,
     _marker: PhantomData<(C, S, E, Version, Error)>,
 }
 
 impl<C, S, E, Repository, Version, Error> EventRepository<C, E, Version, Error>
     for EventSourcedOrchestratingAggregate<'_, C, S, E, Repository, Version, Error>
 where
-    Repository: EventRepository<C, E, Version, Error> + Sync,
-    C: Sync,
-    S: Sync,
-    E: Sync,
-    Version: Sync,
-    Error: Sync,
+    Repository: EventRepository<C, E, Version, Error> + MaybeSync,
+    C: MaybeSync,
+    S: MaybeSync,
+    E: MaybeSync,
+    Version: MaybeSync,
+    Error: MaybeSync,
 {
     /// Fetches current events, based on the command.
-    async fn fetch_events(&self, command: &C) -> Result<Vec<(E, Version)>, Error> {
-        self.repository.fetch_events(command).await
+    fn fetch_events(
+        &self,
+        command: &C,
+    ) -> impl Future<Output = Result<Vec<(E, Version)>, Error>> + MaybeSend {
+        self.repository.fetch_events(command)
     }
     /// Saves events.
-    async fn save(&self, events: &[E]) -> Result<Vec<(E, Version)>, Error> {
-        self.repository.save(events).await
+    fn save(
+        &self,
+        events: &[E],
+    ) -> impl Future<Output = Result<Vec<(E, Version)>, Error>> + MaybeSend {
+        self.repository.save(events)
     }
     /// Version provider. It is used to provide the version/sequence of the event. Optimistic locking is useing this version to check if the event is already saved.
-    async fn version_provider(&self, event: &E) -> Result<Option<Version>, Error> {
-        self.repository.version_provider(event).await
+    fn version_provider(
+        &self,
+        event: &E,
+    ) -> impl Future<Output = Result<Option<Version>, Error>> + MaybeSend {
+        self.repository.version_provider(event)
     }
 }
 
 impl<'a, C, S, E, Repository, Version, Error>
     EventSourcedOrchestratingAggregate<'a, C, S, E, Repository, Version, Error>
 where
-    Repository: EventRepository<C, E, Version, Error> + Sync,
-    C: Sync,
-    S: Sync,
-    E: Sync + Clone,
-    Version: Sync,
-    Error: Sync,
+    Repository: EventRepository<C, E, Version, Error> + MaybeSync,
+    C: MaybeSync,
+    S: MaybeSync,
+    E: MaybeSync + Clone,
+    Version: MaybeSync,
+    Error: MaybeSync,
 {
     /// Creates a new instance of [EventSourcedAggregate].
     pub fn new(
         repository: Repository,
         decider: Decider<'a, C, S, E, Error>,
-        saga: Saga<'a, E, C>,
+        saga: Saga<'a, E, C, Error>,
     ) -> Self {
         EventSourcedOrchestratingAggregate {
             repository,
@@ -346,18 +1364,14 @@ where
         E: Identifier,
         C: Identifier,
     {
-        let current_state: S = current_events
-            .iter()
-            .fold((self.decider.initial_state)(), |state, event| {
-                (self.decider.evolve)(&state, event)
-            });
+        let current_state: S = self.decider.fold_to_state(current_events);
 
         let initial_events = (self.decider.decide)(command, &current_state)?;
 
-        let commands: Vec<C> = initial_events
-            .iter()
-            .flat_map(|event: &E| self.saga.compute_new_actions(event))
-            .collect();
+        let mut commands: Vec<C> = vec![];
+        for event in initial_events.iter() {
+            commands.extend(self.saga.compute_new_actions(event)?);
+        }
 
         // Collect all events including recursively computed new events.
         let mut all_events = initial_events.clone();
@@ -409,7 +1423,7 @@ where
 {
     repository: Repository,
     decider: Decider<'a, C, S, E, Error>,
-    saga: Saga<'a, E, C>,
+    saga: Saga<'a, E, C, Error>,
     _marker: PhantomData<(C, S, E, Version, Error)>,
 }
 
@@ -427,10 +1441,10 @@ where
         let mut new_state = events.iter().fold(effective_current_state, |state, event| {
             (self.decider.evolve)(&state, event)
         });
-        let commands = events
-            .iter()
-            .flat_map(|event: &E| self.saga.compute_new_actions(event))
-            .collect::<Vec<C>>();
+        let mut commands: Vec<C> = vec![];
+        for event in events.iter() {
+            commands.extend(self.saga.compute_new_actions(event)?);
+        }
         for action in commands {
             new_state = self.compute_new_state(Some(new_state.clone()), &action)?;
         }
@@ -441,38 +1455,45 @@ where
 impl<C, S, E, Repository, Version, Error> StateRepository<C, S, Version, Error>
     for StateStoredOrchestratingAggregate<'_, C, S, E, Repository, Version, Error>
 where
-    Repository: StateRepository<C, S, Version, Error> + Sync,
-    C: Sync,
-    S: Sync,
-    E: Sync,
-    Version: Sync,
-    Error: Sync,
+    Repository: StateRepository<C, S, Version, Error> + MaybeSync,
+    C: MaybeSync,
+    S: MaybeSync,
+    E: MaybeSync,
+    Version: MaybeSync,
+    Error: MaybeSync,
 {
     /// Fetches current state, based on the command.
-    async fn fetch_state(&self, command: &C) -> Result<Option<(S, Version)>, Error> {
-        self.repository.fetch_state(command).await
+    fn fetch_state(
+        &self,
+        command: &C,
+    ) -> impl Future<Output = Result<Option<(S, Version)>, Error>> + MaybeSend {
+        self.repository.fetch_state(command)
     }
     /// Saves state.
-    async fn save(&self, state: &S, version: &Option<Version>) -> Result<(S, Version), Error> {
-        self.repository.save(state, version).await
+    fn save(
+        &self,
+        state: &S,
+        version: &Option<Version>,
+    ) -> impl Future<Output = Result<(S, Version), Error>> + MaybeSend {
+        self.repository.save(state, version)
     }
 }
 
 impl<'a, C, S, E, Repository, Version, Error>
     StateStoredOrchestratingAggregate<'a, C, S, E, Repository, Version, Error>
 where
-    Repository: StateRepository<C, S, Version, Error> + Sync,
-    C: Sync,
-    S: Sync + Clone,
-    E: Sync,
-    Version: Sync,
-    Error: Sync,
+    Repository: StateRepository<C, S, Version, Error> + MaybeSync,
+    C: MaybeSync,
+    S: MaybeSync + Clone,
+    E: MaybeSync,
+    Version: MaybeSync,
+    Error: MaybeSync,
 {
     /// Creates a new instance of [StateStoredAggregate].
     pub fn new(
         repository: Repository,
         decider: Decider<'a, C, S, E, Error>,
-        saga: Saga<'a, E, C>,
+        saga: Saga<'a, E, C, Error>,
     ) -> Self {
         StateStoredOrchestratingAggregate {
             repository,
@@ -0,0 +1,310 @@
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::marker::PhantomData;
+use std::sync::{Arc, Mutex};
+
+use crate::aggregate::{
+    EventRepository, EventRepositoryWithOptimisticLocking, EventTailRepository,
+};
+use crate::decider::Decider;
+use crate::{Identifier, MaybeSend, MaybeSync};
+
+/// State Cache trait
+///
+/// It is used to cache the event-sourced state, together with the version of the latest event that is included in it, keyed by the [Identifier] of the command/stream.
+///
+/// Generic parameters:
+///
+/// - `S` - State
+/// - `Version` - Version/Offset/Sequence number of the latest event included in the state
+/// - `Error` - Error
+pub trait StateCache<S, Version, Error> {
+    /// Gets the cached state and its version.
+    /// Desugared `async fn get(&self, id: &str) -> Result<Option<(S, Version)>, Error>;` to a normal `fn` that returns `impl Future`, and adds bound `MaybeSend` (`Send` with the default `send` feature)
+    /// You can freely move between the `async fn` and `-> impl Future` spelling in your traits and impls. This is true even when one form has a Send bound.
+    fn get(
+        &self,
+        id: &str,
+    ) -> impl Future<Output = Result<Option<(S, Version)>, Error>> + MaybeSend;
+    /// Puts the state and its version into the cache, replacing the previous one.
+    /// Desugared `async fn put(&self, id: &str, state: &S, version: &Version) -> Result<(), Error>;` to a normal `fn` that returns `impl Future`, and adds bound `MaybeSend` (`Send` with the default `send` feature)
+    /// You can freely move between the `async fn` and `-> impl Future` spelling in your traits and impls. This is true even when one form has a Send bound.
+    fn put(
+        &self,
+        id: &str,
+        state: &S,
+        version: &Version,
+    ) -> impl Future<Output = Result<(), Error>> + MaybeSend;
+    /// Invalidates (removes) the cached state.
+    /// Desugared `async fn invalidate(&self, id: &str) -> Result<(), Error>;` to a normal `fn` that returns `impl Future`, and adds bound `MaybeSend` (`Send` with the default `send` feature)
+    /// You can freely move between the `async fn` and `-> impl Future` spelling in your traits and impls. This is true even when one form has a Send bound.
+    fn invalidate(&self, id: &str) -> impl Future<Output = Result<(), Error>> + MaybeSend;
+}
+
+/// Shares the cache among many aggregates (and the tasks/threads they run on).
+impl<S, Version, Error, T> StateCache<S, Version, Error> for Arc<T>
+where
+    T: StateCache<S, Version, Error> + MaybeSend + MaybeSync,
+{
+    fn get(
+        &self,
+        id: &str,
+    ) -> impl Future<Output = Result<Option<(S, Version)>, Error>> + MaybeSend {
+        self.as_ref().get(id)
+    }
+
+    fn put(
+        &self,
+        id: &str,
+        state: &S,
+        version: &Version,
+    ) -> impl Future<Output = Result<(), Error>> + MaybeSend {
+        self.as_ref().put(id, state, version)
+    }
+
+    fn invalidate(&self, id: &str) -> impl Future<Output = Result<(), Error>> + MaybeSend {
+        self.as_ref().invalidate(id)
+    }
+}
+
+/// In-memory, thread-safe, least recently used (LRU) [StateCache] implementation.
+///
+/// The least recently used state is evicted once the number of the cached states exceeds the `capacity`.
+///
+/// Generic parameters:
+///
+/// - `S` - State
+/// - `Version` - Version/Offset/Sequence number
+pub struct LruStateCache<S, Version> {
+    capacity: usize,
+    entries: Mutex<LruEntries<S, Version>>,
+}
+
+/// The cached entries, and their keys from the least to the most recently used.
+struct LruEntries<S, Version> {
+    states: HashMap<String, (S, Version)>,
+    usage: VecDeque<String>,
+}
+
+impl<S, Version> LruEntries<S, Version> {
+    fn touch(&mut self, id: &str) {
+        if let Some(position) = self.usage.iter().position(|key| key == id) {
+            self.usage.remove(position);
+        }
+        self.usage.push_back(id.to_string());
+    }
+}
+
+impl<S, Version> LruStateCache<S, Version> {
+    /// Creates a new, empty instance of [LruStateCache], holding at most `capacity` states.
+    pub fn new(capacity: usize) -> Self {
+        LruStateCache {
+            capacity,
+            entries: Mutex::new(LruEntries {
+                states: HashMap::new(),
+                usage: VecDeque::new(),
+            }),
+        }
+    }
+
+    /// Returns the number of the cached states.
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().states.len()
+    }
+
+    /// Returns `true` if no state is cached.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<S, Version, Error> StateCache<S, Version, Error> for LruStateCache<S, Version>
+where
+    S: Clone + MaybeSend + MaybeSync,
+    Version: Clone + MaybeSend + MaybeSync,
+{
+    async fn get(&self, id: &str) -> Result<Option<(S, Version)>, Error> {
+        let mut entries = self.entries.lock().unwrap();
+        let cached = entries.states.get(id).cloned();
+        if cached.is_some() {
+            entries.touch(id);
+        }
+        Ok(cached)
+    }
+
+    async fn put(&self, id: &str, state: &S, version: &Version) -> Result<(), Error> {
+        let mut entries = self.entries.lock().unwrap();
+        entries
+            .states
+            .insert(id.to_string(), (state.clone(), version.clone()));
+        entries.touch(id);
+        while entries.states.len() > self.capacity {
+            match entries.usage.pop_front() {
+                Some(evicted) => entries.states.remove(&evicted),
+                None => break,
+            };
+        }
+        Ok(())
+    }
+
+    async fn invalidate(&self, id: &str) -> Result<(), Error> {
+        let mut entries = self.entries.lock().unwrap();
+        entries.states.remove(id);
+        entries.usage.retain(|key| key != id);
+        Ok(())
+    }
+}
+
+/// Caching Event Sourced Aggregate.
+///
+/// It is using a [Decider] to compute new events based on the cached state, the events that happened after it, and the command.
+/// It is using a [EventTailRepository] to fetch the tail of the events since the cached version (or all the events, on the cache miss), and the [EventRepositoryWithOptimisticLocking] to save the new events.
+/// It is using a [StateCache] to serve the state, and to cache the new state after the command is handled.
+///
+/// The cached state is never trusted blindly: the events that happened after its version (saved by the other processes, for example) are always replayed onto it.
+/// The new events are saved only if no other events were saved after the replayed ones, so the new state that is cached is exactly the state at the version of the latest saved event.
+///
+/// Generic parameters:
+///
+/// - `C` - Command
+/// - `S` - State
+/// - `E` - Event
+/// - `Repository` - Event repository
+/// - `Cache` - State cache
+/// - `Version` - Version/Offset/Sequence number
+/// - `Error` - Error
+pub struct EventSourcedCachingAggregate<'a, C, S, E, Repository, Cache, Version, Error>
+where
+    Repository: EventTailRepository<C, E, Version, Error>,
+    Cache: StateCache<S, Version, Error>,
+{
+    repository: Repository,
+    cache: Cache,
+    decider: Decider<'a, C, S, E, Error>,
+    _marker: PhantomData<(C, S, E, Version, Error)>,
+}
+
+impl<C, S, E, Repository, Cache, Version, Error> EventRepository<C, E, Version, Error>
+    for EventSourcedCachingAggregate<'_, C, S, E, Repository, Cache, Version, Error>
+where
+    Repository: EventTailRepository<C, E, Version, Error> + MaybeSync,
+    Cache: StateCache<S, Version, Error> + MaybeSync,
+    C: MaybeSync,
+    S: MaybeSync,
+    E: MaybeSync,
+    Version: MaybeSync,
+    Error: MaybeSync,
+{
+    /// Fetches current events, based on the command.
+    fn fetch_events(
+        &self,
+        command: &C,
+    ) -> impl Future<Output = Result<Vec<(E, Version)>, Error>> + MaybeSend {
+        self.repository.fetch_events(command)
+    }
+    /// Saves events.
+    fn save(
+        &self,
+        events: &[E],
+    ) -> impl Future<Output = Result<Vec<(E, Version)>, Error>> + MaybeSend {
+        self.repository.save(events)
+    }
+    /// Version provider. It is used to provide the version/sequence of the event. Optimistic locking is useing this version to check if the event is already saved.
+    fn version_provider(
+        &self,
+        event: &E,
+    ) -> impl Future<Output = Result<Option<Version>, Error>> + MaybeSend {
+        self.repository.version_provider(event)
+    }
+}
+
+impl<'a, C, S, E, Repository, Cache, Version, Error>
+    EventSourcedCachingAggregate<'a, C, S, E, Repository, Cache, Version, Error>
+where
+    Repository: EventTailRepository<C, E, Version, Error>
+        + EventRepositoryWithOptimisticLocking<C, E, Version, Error>
+        + MaybeSync,
+    Cache: StateCache<S, Version, Error> + MaybeSync,
+    C: Identifier + MaybeSync,
+    S: MaybeSync,
+    E: MaybeSync,
+    Version: Clone + MaybeSync,
+    Error: MaybeSync,
+{
+    /// Creates a new instance of [EventSourcedCachingAggregate].
+    pub fn new(repository: Repository, cache: Cache, decider: Decider<'a, C, S, E, Error>) -> Self {
+        EventSourcedCachingAggregate {
+            repository,
+            cache,
+            decider,
+            _marker: PhantomData,
+        }
+    }
+    /// Handles the command by fetching the cached state and the events that happened after it, computing new events based on the current state and the command, and saving the new events to the repository.
+    /// The new events are saved with the version of the latest replayed event as the expected version, so the command fails (and the cached state is invalidated) if the stream was modified in the meantime.
+    /// The new state is cached, together with the version of the latest saved event.
+    /// The cache is best-effort: the state that fails to be cached is invalidated, and the saved events are returned anyway.
+    pub async fn handle(&self, command: &C) -> Result<Vec<(E, Version)>, Error> {
+        let id = command.identifier();
+        let (current_state, current_version) = self.replay(&id, command).await?;
+        let new_events = (self.decider.decide)(command, &current_state)?;
+        let saved_events = match self
+            .repository
+            .save_with_expected_version(&new_events, &current_version)
+            .await
+        {
+            Ok(saved_events) => saved_events,
+            Err(error) => {
+                let _ = self.cache.invalidate(&id).await;
+                return Err(error);
+            }
+        };
+        let new_state = saved_events
+            .iter()
+            .fold(current_state, |state, (event, _)| {
+                (self.decider.evolve)(&state, event)
+            });
+        let new_version = saved_events
+            .last()
+            .map(|(_, version)| version.clone())
+            .or(current_version);
+        if let Some(version) = new_version {
+            if self.cache.put(&id, &new_state, &version).await.is_err() {
+                let _ = self.cache.invalidate(&id).await;
+            }
+        }
+        Ok(saved_events)
+    }
+    /// Fetches the current state, from the cached state and the events that happened after it, without handling the command.
+    pub async fn fetch_current_state(&self, query: &C) -> Result<S, Error> {
+        self.replay(&query.identifier(), query)
+            .await
+            .map(|(current_state, _)| current_state)
+    }
+    /// Replays the events that happened after the cached state onto it, returning the current state and the version of the latest event included in it.
+    /// All the events are replayed onto the initial state on the cache miss, or if the cache fails to be read.
+    async fn replay(&self, id: &str, command: &C) -> Result<(S, Option<Version>), Error> {
+        let (cached_state, cached_version, events) = match self.cache.get(id).await {
+            Ok(Some((state, version))) => {
+                let events = self
+                    .repository
+                    .fetch_events_after(command, &version)
+                    .await?;
+                (state, Some(version), events)
+            }
+            Ok(None) | Err(_) => (
+                (self.decider.initial_state)(),
+                None,
+                self.repository.fetch_events(command).await?,
+            ),
+        };
+        let current_version = events
+            .last()
+            .map(|(_, version)| version.clone())
+            .or(cached_version);
+        let current_state = events.iter().fold(cached_state, |state, (event, _)| {
+            (self.decider.evolve)(&state, event)
+        });
+        Ok((current_state, current_version))
+    }
+}
@@ -0,0 +1,152 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use crate::saga::ActionComputation;
+
+/// The recorded reaction of the saga: the action result (of the named type/variant) produced the action (of the named type/variant).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reaction {
+    /// The name of the action result
+    pub action_result: String,
+    /// The name of the produced action
+    pub action: String,
+}
+
+/// [Choreography] is the introspection of the sagas, recording which action results/events produce which actions/commands, and rendering them as the Mermaid or Graphviz diagram of the event flow.
+///
+/// The sagas are opaque functions, so the reactions are recorded by running the sagas over the sample action results (a recorded run).
+/// Record all the sagas of the system (and the representative sample of every action result variant) to get the whole choreography.
+/// Only the successful reactions are recorded; the action results the saga is not reacting to (or failing on) are the diagram nodes without the edges.
+///
+/// ## Example
+///
+/// ```
+/// use fmodel_rust::choreography::Choreography;
+/// use fmodel_rust::saga::Saga;
+///
+/// let saga: Saga<u32, String> = Saga {
+///     react: Box::new(|order_id| Ok(vec![format!("Ship order {}", order_id)])),
+/// };
+/// let choreography = Choreography::new().record(
+///     &saga,
+///     &[1],
+///     &|_| "OrderCreated".to_string(),
+///     &|_| "CreateShipment".to_string(),
+/// );
+/// assert_eq!(
+///     choreography.to_mermaid(),
+///     "flowchart LR\n    n0[\"OrderCreated\"]\n    n1[\"CreateShipment\"]\n    n0 --> n1\n"
+/// );
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Choreography {
+    nodes: Vec<String>,
+    reactions: Vec<Reaction>,
+}
+
+impl Choreography {
+    /// Creates a new, empty instance of [Choreography].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the reactions of the saga to the sample action results, naming the action results and the actions by the given functions (for example, by the enum variant).
+    /// The same reaction is recorded once.
+    pub fn record<AR, A, Error, Saga, F1, F2>(
+        mut self,
+        saga: &Saga,
+        action_results: &[AR],
+        action_result_name: &F1,
+        action_name: &F2,
+    ) -> Self
+    where
+        Saga: ActionComputation<AR, A, Error>,
+        F1: Fn(&AR) -> String,
+        F2: Fn(&A) -> String,
+    {
+        for action_result in action_results {
+            let action_result_name = action_result_name(action_result);
+            self.add_node(&action_result_name);
+            let actions = saga.compute_new_actions(action_result).unwrap_or_default();
+            for action in actions {
+                let action_name = action_name(&action);
+                self.add_node(&action_name);
+                let reaction = Reaction {
+                    action_result: action_result_name.clone(),
+                    action: action_name,
+                };
+                if !self.reactions.contains(&reaction) {
+                    self.reactions.push(reaction);
+                }
+            }
+        }
+        self
+    }
+
+    /// Returns the recorded reactions, in the order of the recording.
+    pub fn reactions(&self) -> &[Reaction] {
+        &self.reactions
+    }
+
+    /// Renders the choreography as the Mermaid flowchart.
+    pub fn to_mermaid(&self) -> String {
+        let mut diagram = String::from("flowchart LR\n");
+        for (index, node) in self.nodes.iter().enumerate() {
+            diagram.push_str(&format!("    n{}[\"{}\"]\n", index, escape_mermaid(node)));
+        }
+        for (from, to) in self.edges() {
+            diagram.push_str(&format!("    n{} --> n{}\n", from, to));
+        }
+        diagram
+    }
+
+    /// Renders the choreography as the Graphviz (DOT) directed graph.
+    pub fn to_graphviz(&self) -> String {
+        let mut diagram = String::from("digraph {\n    rankdir=LR;\n");
+        for (index, node) in self.nodes.iter().enumerate() {
+            diagram.push_str(&format!(
+                "    n{} [label=\"{}\"];\n",
+                index,
+                escape_graphviz(node)
+            ));
+        }
+        for (from, to) in self.edges() {
+            diagram.push_str(&format!("    n{} -> n{};\n", from, to));
+        }
+        diagram.push_str("}\n");
+        diagram
+    }
+
+    fn add_node(&mut self, name: &str) {
+        if !self.nodes.iter().any(|node| node == name) {
+            self.nodes.push(name.to_string());
+        }
+    }
+
+    fn node_index(&self, name: &str) -> usize {
+        self.nodes
+            .iter()
+            .position(|node| node == name)
+            .expect("every recorded reaction has its nodes")
+    }
+
+    fn edges(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.reactions.iter().map(|reaction| {
+            (
+                self.node_index(&reaction.action_result),
+                self.node_index(&reaction.action),
+            )
+        })
+    }
+}
+
+/// Escapes the double quotes of the Mermaid label, as the entity code (Mermaid does not support the backslash escapes).
+pub(crate) fn escape_mermaid(label: &str) -> String {
+    label.replace('"', "#quot;")
+}
+
+/// Escapes the double quotes of the Graphviz label.
+fn escape_graphviz(label: &str) -> String {
+    label.replace('"', "\\\"")
+}
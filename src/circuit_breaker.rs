@@ -0,0 +1,275 @@
+use std::collections::VecDeque;
+use std::fmt::{self, Display, Formatter};
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::aggregate::{EventRepository, StateRepository};
+use crate::metrics::{
+    MetricsRecorder, CIRCUIT_BREAKER_CLOSED, CIRCUIT_BREAKER_HALF_OPENED, CIRCUIT_BREAKER_OPENED,
+};
+use crate::retry::TransientError;
+use crate::saga_manager::ActionPublisher;
+use crate::{MaybeSend, MaybeSync};
+
+/// The state of the circuit breaker.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    /// The calls are passed to the decorated component, and their outcomes are recorded.
+    Closed,
+    /// The calls are rejected with the [CircuitOpen] error, without reaching the decorated component.
+    Open,
+    /// The open duration elapsed: the single probing call is passed to the decorated component again, and its outcome closes (success) or reopens (failure) the circuit. The other calls are rejected while the probing call is in flight.
+    HalfOpen,
+}
+
+/// Circuit breaker policy.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CircuitBreakerPolicy {
+    /// The number of the latest calls the failure rate is computed over
+    pub window_size: usize,
+    /// The minimum number of the recorded calls before the failure rate is considered
+    pub minimum_calls: usize,
+    /// The failure rate (`0.0` - `1.0`) at which the circuit is opened
+    pub failure_rate_threshold: f64,
+    /// The duration the circuit stays open before it is half-opened
+    pub open_duration: Duration,
+}
+
+impl Default for CircuitBreakerPolicy {
+    fn default() -> Self {
+        CircuitBreakerPolicy {
+            window_size: 20,
+            minimum_calls: 10,
+            failure_rate_threshold: 0.5,
+            open_duration: Duration::from_secs(30),
+        }
+    }
+}
+
+/// Circuit open error - the call is rejected, as the circuit breaker is open.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CircuitOpen {
+    /// The remaining duration until the circuit is half-opened (zero, if the circuit is half-open and the probing call is in flight)
+    pub retry_after: Duration,
+}
+
+impl Display for CircuitOpen {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Circuit open: the call is rejected, retry after {:?}",
+            self.retry_after
+        )
+    }
+}
+
+impl std::error::Error for CircuitOpen {}
+
+/// The state of the circuit, the outcomes (`true` for the failure) of the latest calls, and whether the probing call of the half-open circuit is in flight.
+struct Circuit {
+    state: CircuitState,
+    opened_at: Instant,
+    outcomes: VecDeque<bool>,
+    probing: bool,
+}
+
+/// Releases the probe of the half-open circuit, if the probing call is cancelled before its outcome is recorded.
+struct ProbeGuard<'a> {
+    circuit: Option<&'a Mutex<Circuit>>,
+}
+
+impl Drop for ProbeGuard<'_> {
+    fn drop(&mut self) {
+        if let Some(circuit) = self.circuit {
+            circuit.lock().unwrap().probing = false;
+        }
+    }
+}
+
+/// Circuit breaker decorator.
+///
+/// It decorates the repository ([EventRepository], [StateRepository]) or the publisher ([ActionPublisher]), and stops calling it once the rate of the calls that failed with the [TransientError] reaches the threshold of the [CircuitBreakerPolicy].
+/// While the circuit is open, the calls fail fast with the [CircuitOpen] error, so the outage of the broker/database is not cascading into the aggregates and saga managers waiting on it.
+/// The non-transient errors (the domain errors, for example) are not counted as the failures.
+///
+/// The changes of the circuit state are counted by the [MetricsRecorder] ([CIRCUIT_BREAKER_OPENED], [CIRCUIT_BREAKER_HALF_OPENED] and [CIRCUIT_BREAKER_CLOSED]).
+///
+/// Generic parameters:
+///
+/// - `T` - Decorated repository/publisher
+/// - `Recorder` - Metrics recorder
+pub struct CircuitBreaker<T, Recorder> {
+    inner: T,
+    policy: CircuitBreakerPolicy,
+    recorder: Recorder,
+    circuit: Mutex<Circuit>,
+}
+
+impl<T, Recorder> CircuitBreaker<T, Recorder>
+where
+    Recorder: MetricsRecorder,
+{
+    /// Creates a new instance of [CircuitBreaker], with the closed circuit.
+    pub fn new(inner: T, policy: CircuitBreakerPolicy, recorder: Recorder) -> Self {
+        CircuitBreaker {
+            inner,
+            policy,
+            recorder,
+            circuit: Mutex::new(Circuit {
+                state: CircuitState::Closed,
+                opened_at: Instant::now(),
+                outcomes: VecDeque::new(),
+                probing: false,
+            }),
+        }
+    }
+
+    /// Returns the current state of the circuit.
+    pub fn state(&self) -> CircuitState {
+        self.circuit.lock().unwrap().state
+    }
+
+    /// Runs the `operation` if the circuit permits it, and records its outcome.
+    async fn call<R, Error, F, Fut>(&self, operation: F) -> Result<R, Error>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<R, Error>>,
+        Error: TransientError + From<CircuitOpen>,
+    {
+        let probe = self.permit()?;
+        let mut guard = ProbeGuard {
+            circuit: probe.then_some(&self.circuit),
+        };
+        let result = operation().await;
+        guard.circuit = None;
+        let failed = matches!(&result, Err(error) if error.is_transient());
+        self.record(failed, probe);
+        result
+    }
+
+    /// Permits the call, returning `true` if it is the probing call of the half-open circuit.
+    /// The circuit is half-opened once the open duration elapsed, and only one probing call is permitted at a time.
+    fn permit(&self) -> Result<bool, CircuitOpen> {
+        let mut circuit = self.circuit.lock().unwrap();
+        match circuit.state {
+            CircuitState::Closed => Ok(false),
+            CircuitState::HalfOpen if circuit.probing => Err(CircuitOpen {
+                retry_after: Duration::ZERO,
+            }),
+            CircuitState::HalfOpen => {
+                circuit.probing = true;
+                Ok(true)
+            }
+            CircuitState::Open => {
+                let elapsed = circuit.opened_at.elapsed();
+                if elapsed < self.policy.open_duration {
+                    return Err(CircuitOpen {
+                        retry_after: self.policy.open_duration - elapsed,
+                    });
+                }
+                circuit.state = CircuitState::HalfOpen;
+                circuit.probing = true;
+                self.recorder
+                    .increment_counter(CIRCUIT_BREAKER_HALF_OPENED, 1);
+                Ok(true)
+            }
+        }
+    }
+
+    /// Records the outcome of the call, and transitions the circuit accordingly.
+    fn record(&self, failed: bool, probe: bool) {
+        let mut circuit = self.circuit.lock().unwrap();
+        if probe {
+            circuit.probing = false;
+        }
+        match (circuit.state, failed) {
+            (CircuitState::HalfOpen, false) if probe => {
+                circuit.state = CircuitState::Closed;
+                circuit.outcomes.clear();
+                self.recorder.increment_counter(CIRCUIT_BREAKER_CLOSED, 1);
+            }
+            (CircuitState::HalfOpen, true) if probe => self.open(&mut circuit),
+            // The late outcome of the call that was permitted before the circuit half-opened
+            (CircuitState::HalfOpen, _) => {}
+            (CircuitState::Closed, _) => {
+                circuit.outcomes.push_back(failed);
+                while circuit.outcomes.len() > self.policy.window_size {
+                    circuit.outcomes.pop_front();
+                }
+                let calls = circuit.outcomes.len();
+                let failures = circuit.outcomes.iter().filter(|failed| **failed).count();
+                if calls >= self.policy.minimum_calls
+                    && failures as f64 >= self.policy.failure_rate_threshold * calls as f64
+                {
+                    self.open(&mut circuit);
+                }
+            }
+            // The late outcome of the call that was permitted before the circuit opened
+            (CircuitState::Open, _) => {}
+        }
+    }
+
+    /// Opens the circuit.
+    fn open(&self, circuit: &mut Circuit) {
+        circuit.state = CircuitState::Open;
+        circuit.opened_at = Instant::now();
+        circuit.outcomes.clear();
+        self.recorder.increment_counter(CIRCUIT_BREAKER_OPENED, 1);
+    }
+}
+
+impl<C, E, Version, Error, Repository, Recorder> EventRepository<C, E, Version, Error>
+    for CircuitBreaker<Repository, Recorder>
+where
+    Repository: EventRepository<C, E, Version, Error> + MaybeSync,
+    Recorder: MetricsRecorder + MaybeSend + MaybeSync,
+    C: MaybeSync,
+    E: MaybeSync,
+    Version: MaybeSync,
+    Error: TransientError + From<CircuitOpen>,
+{
+    async fn fetch_events(&self, command: &C) -> Result<Vec<(E, Version)>, Error> {
+        self.call(|| self.inner.fetch_events(command)).await
+    }
+
+    async fn save(&self, events: &[E]) -> Result<Vec<(E, Version)>, Error> {
+        self.call(|| self.inner.save(events)).await
+    }
+
+    async fn version_provider(&self, event: &E) -> Result<Option<Version>, Error> {
+        self.call(|| self.inner.version_provider(event)).await
+    }
+}
+
+impl<C, S, Version, Error, Repository, Recorder> StateRepository<C, S, Version, Error>
+    for CircuitBreaker<Repository, Recorder>
+where
+    Repository: StateRepository<C, S, Version, Error> + MaybeSync,
+    Recorder: MetricsRecorder + MaybeSend + MaybeSync,
+    C: MaybeSync,
+    S: MaybeSync,
+    Version: MaybeSync,
+    Error: TransientError + From<CircuitOpen>,
+{
+    async fn fetch_state(&self, command: &C) -> Result<Option<(S, Version)>, Error> {
+        self.call(|| self.inner.fetch_state(command)).await
+    }
+
+    async fn save(&self, state: &S, version: &Option<Version>) -> Result<(S, Version), Error> {
+        self.call(|| self.inner.save(state, version)).await
+    }
+}
+
+impl<A, Error, Publisher, Recorder> ActionPublisher<A, Error>
+    for CircuitBreaker<Publisher, Recorder>
+where
+    Publisher: ActionPublisher<A, Error> + MaybeSync,
+    Recorder: MetricsRecorder + MaybeSend + MaybeSync,
+    A: MaybeSync,
+    Error: TransientError + From<CircuitOpen>,
+{
+    async fn publish(&self, action: &[A]) -> Result<Vec<A>, Error> {
+        self.call(|| self.inner.publish(action)).await
+    }
+}
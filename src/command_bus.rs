@@ -0,0 +1,156 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use crate::aggregate::{
+    EventRepository, EventSourcedAggregate, StateRepository, StateStoredAggregate,
+};
+use crate::decider::{EventComputation, StateComputation};
+use crate::{MaybeSend, MaybeSync, Variant};
+
+/// Handles the command, producing the output (for example, the saved events or the saved state).
+/// It is implemented by the [EventSourcedAggregate] and the [StateStoredAggregate], and by the [CommandBus] itself, so the buses can be nested.
+pub trait CommandHandler<C, Output, Error> {
+    /// Handles the command.
+    /// Desugared `async fn handle_command(&self, command: &C) -> Result<Output, Error>;` to a normal `fn` that returns `impl Future`, and adds bound `MaybeSend` (`Send` with the default `send` feature).
+    /// You can freely move between the `async fn` and `-> impl Future` spelling in your traits and impls. This is true even when one form has a Send bound.
+    fn handle_command(
+        &self,
+        command: &C,
+    ) -> impl Future<Output = Result<Output, Error>> + MaybeSend;
+}
+
+impl<C, S, E, Repository, Decider, Version, Error> CommandHandler<C, Vec<(E, Version)>, Error>
+    for EventSourcedAggregate<C, S, E, Repository, Decider, Version, Error>
+where
+    Repository: EventRepository<C, E, Version, Error> + MaybeSync,
+    Decider: EventComputation<C, S, E, Error> + MaybeSync,
+    C: MaybeSync,
+    S: MaybeSync,
+    E: MaybeSend + MaybeSync,
+    Version: MaybeSend + MaybeSync,
+    Error: MaybeSync,
+{
+    async fn handle_command(&self, command: &C) -> Result<Vec<(E, Version)>, Error> {
+        self.handle(command).await
+    }
+}
+
+impl<C, S, E, Repository, Decider, Version, Error> CommandHandler<C, (S, Version), Error>
+    for StateStoredAggregate<C, S, E, Repository, Decider, Version, Error>
+where
+    Repository: StateRepository<C, S, Version, Error> + MaybeSync,
+    Decider: StateComputation<C, S, E, Error> + MaybeSync,
+    C: MaybeSync,
+    S: MaybeSend + MaybeSync,
+    E: MaybeSync,
+    Version: MaybeSend + MaybeSync,
+    Error: MaybeSync,
+{
+    async fn handle_command(&self, command: &C) -> Result<(S, Version), Error> {
+        self.handle(command).await
+    }
+}
+
+/// The error signaling that no handler is registered for the command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnhandledCommand;
+
+impl Display for UnhandledCommand {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "No handler is registered for the command")
+    }
+}
+
+impl Error for UnhandledCommand {}
+
+/// The handling of the command by the registered handler
+#[cfg(feature = "send")]
+type CommandHandling<'a, Output, Error> =
+    Pin<Box<dyn Future<Output = Result<Output, Error>> + Send + 'a>>;
+/// The handling of the command by the registered handler
+#[cfg(not(feature = "send"))]
+type CommandHandling<'a, Output, Error> = Pin<Box<dyn Future<Output = Result<Output, Error>> + 'a>>;
+
+/// The registered command handler, returning `None` if it is not handling the command
+#[cfg(feature = "send")]
+type RegisteredCommandHandler<'a, C, Output, Error> =
+    Box<dyn Fn(&C) -> Option<CommandHandling<'a, Output, Error>> + 'a + Send + Sync>;
+/// The registered command handler, returning `None` if it is not handling the command
+#[cfg(not(feature = "send"))]
+type RegisteredCommandHandler<'a, C, Output, Error> =
+    Box<dyn Fn(&C) -> Option<CommandHandling<'a, Output, Error>> + 'a>;
+
+/// [CommandBus] is routing the heterogeneous commands to the aggregates (or any other [CommandHandler]) that handle them.
+///
+/// The handlers are registered per variant of the command (via [Variant]), and the command is dispatched to the first registered handler of its variant.
+/// The output of every handler is mapped into the unified `Output` of the bus (for example, the `Sum` of the saved events). It is avoiding the hand-written dispatcher matches over many aggregates.
+///
+/// The variant of the command is cloned into the handling future, so the future is not borrowing the command.
+/// Dispatching the command that no handler is registered for fails with the [UnhandledCommand] error.
+///
+/// Generic parameters:
+///
+/// - `C` - Command
+/// - `Output` - Unified output of the handlers
+/// - `Error` - Error
+pub struct CommandBus<'a, C: 'a, Output: 'a, Error: 'a> {
+    handlers: Vec<RegisteredCommandHandler<'a, C, Output, Error>>,
+}
+
+impl<C, Output, Error> Default for CommandBus<'_, C, Output, Error> {
+    fn default() -> Self {
+        CommandBus { handlers: vec![] }
+    }
+}
+
+impl<'a, C, Output, Error> CommandBus<'a, C, Output, Error> {
+    /// Creates a new, empty instance of [CommandBus].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers the handler of the `V` variant of the command, mapping its output `O` into the unified `Output` of the bus.
+    pub fn register<V, O, H, F>(mut self, handler: H, f: &'a F) -> Self
+    where
+        V: Variant<C> + Clone + MaybeSend + MaybeSync + 'a,
+        H: CommandHandler<V, O, Error> + MaybeSend + MaybeSync + 'a,
+        F: Fn(O) -> Output + MaybeSend + MaybeSync,
+        O: 'a,
+        Error: 'a,
+    {
+        let handler = Arc::new(handler);
+        self.handlers.push(Box::new(move |command: &C| {
+            V::from_variant(command).map(|variant| {
+                let variant = variant.clone();
+                let handler = Arc::clone(&handler);
+                Box::pin(async move { handler.handle_command(&variant).await.map(f) })
+                    as CommandHandling<'a, Output, Error>
+            })
+        }));
+        self
+    }
+
+    /// Dispatches the command to the first registered handler of its variant.
+    pub async fn dispatch(&self, command: &C) -> Result<Output, Error>
+    where
+        Error: From<UnhandledCommand>,
+    {
+        match self.handlers.iter().find_map(|handler| handler(command)) {
+            Some(handling) => handling.await,
+            None => Err(UnhandledCommand.into()),
+        }
+    }
+}
+
+impl<C, Output, Error> CommandHandler<C, Output, Error> for CommandBus<'_, C, Output, Error>
+where
+    C: MaybeSync,
+    Error: From<UnhandledCommand>,
+{
+    async fn handle_command(&self, command: &C) -> Result<Output, Error> {
+        self.dispatch(command).await
+    }
+}
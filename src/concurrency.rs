@@ -0,0 +1,48 @@
+use std::collections::HashMap;
+use std::future::{poll_fn, Future};
+use std::hash::Hash;
+use std::pin::Pin;
+use std::task::Poll;
+
+/// Splits the items into (at most) `workers` partitions by the key, so the items with the same key end up in the same partition, in the original order.
+/// The keys are assigned to the partitions round-robin, in the order of their first appearance, so the distinct keys are spread evenly.
+pub(crate) fn partition_by_key<T, K, F>(items: Vec<T>, workers: usize, key: F) -> Vec<Vec<T>>
+where
+    K: Eq + Hash,
+    F: Fn(&T) -> K,
+{
+    let workers = workers.max(1);
+    let mut partitions: Vec<Vec<T>> = (0..workers).map(|_| vec![]).collect();
+    let mut assigned: HashMap<K, usize> = HashMap::new();
+    for item in items {
+        let next = assigned.len() % workers;
+        let partition = *assigned.entry(key(&item)).or_insert(next);
+        partitions[partition].push(item);
+    }
+    partitions.retain(|partition| !partition.is_empty());
+    partitions
+}
+
+/// Runs the futures concurrently (on the current task), returning their outputs in the original order.
+pub(crate) async fn join_all<F: Future>(futures: Vec<F>) -> Vec<F::Output> {
+    let mut futures: Vec<Pin<Box<F>>> = futures.into_iter().map(Box::pin).collect();
+    let mut outputs: Vec<Option<F::Output>> = futures.iter().map(|_| None).collect();
+    poll_fn(|cx| {
+        let mut pending = false;
+        for (future, output) in futures.iter_mut().zip(outputs.iter_mut()) {
+            if output.is_none() {
+                match future.as_mut().poll(cx) {
+                    Poll::Ready(value) => *output = Some(value),
+                    Poll::Pending => pending = true,
+                }
+            }
+        }
+        if pending {
+            Poll::Pending
+        } else {
+            Poll::Ready(())
+        }
+    })
+    .await;
+    outputs.into_iter().flatten().collect()
+}
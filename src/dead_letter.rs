@@ -0,0 +1,47 @@
+use std::future::Future;
+
+use crate::MaybeSend;
+
+/// The message that could not be processed, together with the error.
+///
+/// The message is the event that the view fails to apply (see `materialized_view::DeadLetteringMaterializedView`), or the actions that the saga manager fails to publish (see `saga_manager::UnpublishedActions`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeadLetter<M, Error> {
+    /// The message that could not be processed
+    pub message: M,
+    /// The error of the processing
+    pub error: Error,
+}
+
+/// Dead Letter Repository trait
+///
+/// It is used to park the messages that could not be processed (after the retries, see [crate::retry::Retrying]), so they are not lost, and the processing can continue with the next message.
+/// The parked dead letters are fetched without removing them, and they are removed only once they are acknowledged (after they are processed again, or parked again), so a failure in between does not lose them.
+///
+/// Generic parameters:
+///
+/// - `M` - Message
+/// - `Error` - Error
+pub trait DeadLetterRepository<M, Error> {
+    /// Parks the dead letter.
+    /// Desugared `async fn park(&self, dead_letter: DeadLetter<M, Error>) -> Result<(), Error>;` to a normal `fn` that returns `impl Future`, and adds bound `MaybeSend` (`Send` with the default `send` feature).
+    /// You can freely move between the `async fn` and `-> impl Future` spelling in your traits and impls. This is true even when one form has a Send bound.
+    fn park(
+        &self,
+        dead_letter: DeadLetter<M, Error>,
+    ) -> impl Future<Output = Result<(), Error>> + MaybeSend;
+    /// Fetches (at most `limit`) parked dead letters, in the order they are parked, without removing them.
+    /// Desugared `async fn fetch_dead_letters(&self, limit: usize) -> Result<Vec<DeadLetter<M, Error>>, Error>;` to a normal `fn` that returns `impl Future`, and adds bound `MaybeSend` (`Send` with the default `send` feature).
+    /// You can freely move between the `async fn` and `-> impl Future` spelling in your traits and impls. This is true even when one form has a Send bound.
+    fn fetch_dead_letters(
+        &self,
+        limit: usize,
+    ) -> impl Future<Output = Result<Vec<DeadLetter<M, Error>>, Error>> + MaybeSend;
+    /// Acknowledges (removes) the fetched dead letter, so it is not fetched again.
+    /// Desugared `async fn acknowledge(&self, dead_letter: &DeadLetter<M, Error>) -> Result<(), Error>;` to a normal `fn` that returns `impl Future`, and adds bound `MaybeSend` (`Send` with the default `send` feature).
+    /// You can freely move between the `async fn` and `-> impl Future` spelling in your traits and impls. This is true even when one form has a Send bound.
+    fn acknowledge(
+        &self,
+        dead_letter: &DeadLetter<M, Error>,
+    ) -> impl Future<Output = Result<(), Error>> + MaybeSend;
+}
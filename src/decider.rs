@@ -1,4 +1,15 @@
-use crate::{DecideFunction, EvolveFunction, InitialStateFunction, Sum};
+use alloc::borrow::ToOwned;
+use alloc::boxed::Box;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::sync::OnceLock;
+
+use crate::view::View;
+use crate::{
+    DecideFunction, EvolveFunction, EvolveMutFunction, InitialStateFunction, MaybeSend, MaybeSync,
+    Sum, Sum3, Sum4,
+};
 
 /// [Decider] represents the main decision-making algorithm.
 /// It has three generic parameters `C`/`Command`, `S`/`State`, `E`/`Event` , representing the type of the values that Decider may contain or use.
@@ -157,13 +168,63 @@ pub struct Decider<'a, C: 'a, S: 'a, E: 'a, Error: 'a = ()> {
     pub initial_state: InitialStateFunction<'a, S>,
 }
 
+/// [Decision] is the outcome of the decision: either the events are accepted, or the command is rejected with the typed reason.
+///
+/// It codifies the "validation result" pattern: the rejection is not the (error) event, so it is never persisted into the event stream.
+/// The aggregates translate the rejection into the error, without saving anything (see [Decider::decide_or_fail]).
+#[derive(Debug, Clone, PartialEq)]
+pub enum Decision<E, R> {
+    /// The command is accepted, producing the events
+    Accepted(Vec<E>),
+    /// The command is rejected, with the reason
+    Rejected(R),
+}
+
+impl<E, R> Decision<E, R> {
+    /// Returns `true` if the command is rejected.
+    pub fn is_rejected(&self) -> bool {
+        matches!(self, Decision::Rejected(_))
+    }
+
+    /// Converts the decision into the result of the `decide` function.
+    pub fn into_result(self) -> Result<Vec<E>, R> {
+        match self {
+            Decision::Accepted(events) => Ok(events),
+            Decision::Rejected(reason) => Err(reason),
+        }
+    }
+}
+
+impl<E, R> From<Decision<E, R>> for Result<Vec<E>, R> {
+    fn from(decision: Decision<E, R>) -> Self {
+        decision.into_result()
+    }
+}
+
 impl<'a, C, S, E, Error> Decider<'a, C, S, E, Error> {
+    /// Creates a new instance of [Decider] out of the `decide` function that is deciding the [Decision]: either the accepted events, or the typed rejection of the command.
+    /// The rejection is the error of the decider, so the aggregates fail the command without persisting anything.
+    pub fn decide_or_fail<F>(
+        decide: F,
+        evolve: EvolveFunction<'a, S, E>,
+        initial_state: InitialStateFunction<'a, S>,
+    ) -> Self
+    where
+        F: Fn(&C, &S) -> Decision<E, Error> + 'a + MaybeSend + MaybeSync,
+    {
+        Decider {
+            decide: Box::new(move |c: &C, s: &S| decide(c, s).into_result()),
+            evolve,
+            initial_state,
+        }
+    }
+
     /// Maps the Decider over the S/State type parameter.
     /// Creates a new instance of [Decider]`<C, S2, E, Error>`.
     pub fn map_state<S2, F1, F2>(self, f1: &'a F1, f2: &'a F2) -> Decider<'a, C, S2, E, Error>
     where
-        F1: Fn(&S2) -> S + Send + Sync,
-        F2: Fn(&S) -> S2 + Send + Sync,
+        F1: Fn(&S2) -> S + MaybeSend + MaybeSync,
+        F2: Fn(&S) -> S2 + MaybeSend + MaybeSync,
     {
         let new_decide = Box::new(move |c: &C, s2: &S2| {
             let s = f1(s2);
@@ -188,8 +249,8 @@ impl<'a, C, S, E, Error> Decider<'a, C, S, E, Error> {
     /// Creates a new instance of [Decider]`<C, S, E2, Error>`.
     pub fn map_event<E2, F1, F2>(self, f1: &'a F1, f2: &'a F2) -> Decider<'a, C, S, E2, Error>
     where
-        F1: Fn(&E2) -> E + Send + Sync,
-        F2: Fn(&E) -> E2 + Send + Sync,
+        F1: Fn(&E2) -> E + MaybeSend + MaybeSync,
+        F2: Fn(&E) -> E2 + MaybeSend + MaybeSync,
     {
         let new_decide = Box::new(move |c: &C, s: &S| {
             (self.decide)(c, s).map(|result| result.into_iter().map(|e: E| f2(&e)).collect())
@@ -213,7 +274,7 @@ impl<'a, C, S, E, Error> Decider<'a, C, S, E, Error> {
     /// Creates a new instance of [Decider]`<C2, S, E, Error>`.
     pub fn map_command<C2, F>(self, f: &'a F) -> Decider<'a, C2, S, E, Error>
     where
-        F: Fn(&C2) -> C + Send + Sync,
+        F: Fn(&C2) -> C + MaybeSend + MaybeSync,
     {
         let new_decide = Box::new(move |c2: &C2, s: &S| {
             let c = f(c2);
@@ -235,7 +296,7 @@ impl<'a, C, S, E, Error> Decider<'a, C, S, E, Error> {
     /// Creates a new instance of [Decider]`<C, S, E, Error2>`.
     pub fn map_error<Error2, F>(self, f: &'a F) -> Decider<'a, C, S, E, Error2>
     where
-        F: Fn(&Error) -> Error2 + Send + Sync,
+        F: Fn(&Error) -> Error2 + MaybeSend + MaybeSync,
     {
         let new_decide = Box::new(move |c: &C, s: &S| (self.decide)(c, s).map_err(|e| f(&e)));
 
@@ -309,6 +370,339 @@ impl<'a, C, S, E, Error> Decider<'a, C, S, E, Error> {
             initial_state: new_initial_state,
         }
     }
+
+    /// Combines two deciders into one bigger decider, with the discriminated union of the states
+    /// Creates a new instance of a Decider by combining two deciders of type `C`, `S`, `E` and `C2`, `S2`, `E2` into a new decider of type `Sum<C, C2>`, `Sum<S, S2>`, `Sum<E, E2>`
+    /// Similar to `combine`, but the state is carrying only the state of the decider the stream belongs to, which is common when every stream/instance is handled by exactly one of the deciders (sharding).
+    /// The state of the other decider (including the initial state, which is the initial state of the first decider) is treated as the initial state of the decider that is handling the command/event.
+    #[allow(clippy::type_complexity)]
+    pub fn combine_via_sum<C2, S2, E2>(
+        self,
+        decider2: Decider<'a, C2, S2, E2, Error>,
+    ) -> Decider<'a, Sum<C, C2>, Sum<S, S2>, Sum<E, E2>, Error> {
+        let decider = Arc::new(self);
+        let decider2 = Arc::new(decider2);
+        let (decide_decider, decide_decider2) = (decider.clone(), decider2.clone());
+        let (evolve_decider, evolve_decider2) = (decider.clone(), decider2.clone());
+
+        let new_decide = Box::new(move |c: &Sum<C, C2>, s: &Sum<S, S2>| match c {
+            Sum::First(c) => {
+                let events = match s {
+                    Sum::First(s1) => (decide_decider.decide)(c, s1),
+                    Sum::Second(_) => (decide_decider.decide)(c, &(decide_decider.initial_state)()),
+                };
+                events.map(|result| result.into_iter().map(Sum::First).collect())
+            }
+            Sum::Second(c) => {
+                let events = match s {
+                    Sum::Second(s2) => (decide_decider2.decide)(c, s2),
+                    Sum::First(_) => {
+                        (decide_decider2.decide)(c, &(decide_decider2.initial_state)())
+                    }
+                };
+                events.map(|result| result.into_iter().map(Sum::Second).collect())
+            }
+        });
+
+        let new_evolve = Box::new(move |s: &Sum<S, S2>, e: &Sum<E, E2>| match e {
+            Sum::First(e) => match s {
+                Sum::First(s1) => Sum::First((evolve_decider.evolve)(s1, e)),
+                Sum::Second(_) => Sum::First((evolve_decider.evolve)(
+                    &(evolve_decider.initial_state)(),
+                    e,
+                )),
+            },
+            Sum::Second(e) => match s {
+                Sum::Second(s2) => Sum::Second((evolve_decider2.evolve)(s2, e)),
+                Sum::First(_) => Sum::Second((evolve_decider2.evolve)(
+                    &(evolve_decider2.initial_state)(),
+                    e,
+                )),
+            },
+        });
+
+        let new_initial_state = Box::new(move || Sum::First((decider.initial_state)()));
+
+        Decider {
+            decide: new_decide,
+            evolve: new_evolve,
+            initial_state: new_initial_state,
+        }
+    }
+
+    /// Merges two deciders into one bigger decider
+    /// Creates a new instance of a Decider by merging two deciders of type `C`, `S`, `E` and `C2`, `S`, `E2` into a new decider of type `Sum<C, C2>`, `S`, `Sum<E, E2>`
+    /// Similar to `combine`, but the state type is the same/shared for both deciders, which is common when splitting a large aggregate into focused deciders over one state.
+    /// The initial state of the first decider is used as the initial state of the new decider.
+    pub fn merge<C2, E2>(
+        self,
+        decider2: Decider<'a, C2, S, E2, Error>,
+    ) -> Decider<'a, Sum<C, C2>, S, Sum<E, E2>, Error> {
+        let new_decide = Box::new(move |c: &Sum<C, C2>, s: &S| match c {
+            Sum::First(c) => {
+                let events = (self.decide)(c, s);
+                events.map(|result| result.into_iter().map(|e: E| Sum::First(e)).collect())
+            }
+            Sum::Second(c) => {
+                let events = (decider2.decide)(c, s);
+                events.map(|result| result.into_iter().map(|e: E2| Sum::Second(e)).collect())
+            }
+        });
+
+        let new_evolve = Box::new(move |s: &S, e: &Sum<E, E2>| match e {
+            Sum::First(e) => (self.evolve)(s, e),
+            Sum::Second(e) => (decider2.evolve)(s, e),
+        });
+
+        let new_initial_state = Box::new(move || (self.initial_state)());
+
+        Decider {
+            decide: new_decide,
+            evolve: new_evolve,
+            initial_state: new_initial_state,
+        }
+    }
+
+    /// Combines three deciders into one bigger decider
+    /// Creates a new instance of a Decider by combining three deciders of type `C`, `S`, `E`, `C2`, `S2`, `E2` and `C3`, `S3`, `E3` into a new decider of type `Sum3<C, C2, C3>`, `(S, S2, S3)`, `Sum3<E, E2, E3>`
+    /// It is avoiding the deeply nested `Sum<Sum<C, C2>, C3>` types that `combine` would produce.
+    #[allow(clippy::type_complexity)]
+    pub fn combine3<C2, S2, E2, C3, S3, E3>(
+        self,
+        decider2: Decider<'a, C2, S2, E2, Error>,
+        decider3: Decider<'a, C3, S3, E3, Error>,
+    ) -> Decider<'a, Sum3<C, C2, C3>, (S, S2, S3), Sum3<E, E2, E3>, Error>
+    where
+        S: Clone,
+        S2: Clone,
+        S3: Clone,
+    {
+        let new_decide = Box::new(move |c: &Sum3<C, C2, C3>, s: &(S, S2, S3)| match c {
+            Sum3::First(c) => {
+                (self.decide)(c, &s.0).map(|result| result.into_iter().map(Sum3::First).collect())
+            }
+            Sum3::Second(c) => (decider2.decide)(c, &s.1)
+                .map(|result| result.into_iter().map(Sum3::Second).collect()),
+            Sum3::Third(c) => (decider3.decide)(c, &s.2)
+                .map(|result| result.into_iter().map(Sum3::Third).collect()),
+        });
+
+        let new_evolve = Box::new(move |s: &(S, S2, S3), e: &Sum3<E, E2, E3>| match e {
+            Sum3::First(e) => ((self.evolve)(&s.0, e), s.1.to_owned(), s.2.to_owned()),
+            Sum3::Second(e) => (s.0.to_owned(), (decider2.evolve)(&s.1, e), s.2.to_owned()),
+            Sum3::Third(e) => (s.0.to_owned(), s.1.to_owned(), (decider3.evolve)(&s.2, e)),
+        });
+
+        let new_initial_state = Box::new(move || {
+            (
+                (self.initial_state)(),
+                (decider2.initial_state)(),
+                (decider3.initial_state)(),
+            )
+        });
+
+        Decider {
+            decide: new_decide,
+            evolve: new_evolve,
+            initial_state: new_initial_state,
+        }
+    }
+
+    /// Combines four deciders into one bigger decider
+    /// Creates a new instance of a Decider by combining four deciders into a new decider of type `Sum4<C, C2, C3, C4>`, `(S, S2, S3, S4)`, `Sum4<E, E2, E3, E4>`
+    /// It is avoiding the deeply nested `Sum<Sum<Sum<C, C2>, C3>, C4>` types that `combine` would produce.
+    #[allow(clippy::type_complexity)]
+    pub fn combine4<C2, S2, E2, C3, S3, E3, C4, S4, E4>(
+        self,
+        decider2: Decider<'a, C2, S2, E2, Error>,
+        decider3: Decider<'a, C3, S3, E3, Error>,
+        decider4: Decider<'a, C4, S4, E4, Error>,
+    ) -> Decider<'a, Sum4<C, C2, C3, C4>, (S, S2, S3, S4), Sum4<E, E2, E3, E4>, Error>
+    where
+        S: Clone,
+        S2: Clone,
+        S3: Clone,
+        S4: Clone,
+    {
+        let new_decide = Box::new(
+            move |c: &Sum4<C, C2, C3, C4>, s: &(S, S2, S3, S4)| match c {
+                Sum4::First(c) => (self.decide)(c, &s.0)
+                    .map(|result| result.into_iter().map(Sum4::First).collect()),
+                Sum4::Second(c) => (decider2.decide)(c, &s.1)
+                    .map(|result| result.into_iter().map(Sum4::Second).collect()),
+                Sum4::Third(c) => (decider3.decide)(c, &s.2)
+                    .map(|result| result.into_iter().map(Sum4::Third).collect()),
+                Sum4::Fourth(c) => (decider4.decide)(c, &s.3)
+                    .map(|result| result.into_iter().map(Sum4::Fourth).collect()),
+            },
+        );
+
+        let new_evolve = Box::new(
+            move |s: &(S, S2, S3, S4), e: &Sum4<E, E2, E3, E4>| match e {
+                Sum4::First(e) => (
+                    (self.evolve)(&s.0, e),
+                    s.1.to_owned(),
+                    s.2.to_owned(),
+                    s.3.to_owned(),
+                ),
+                Sum4::Second(e) => (
+                    s.0.to_owned(),
+                    (decider2.evolve)(&s.1, e),
+                    s.2.to_owned(),
+                    s.3.to_owned(),
+                ),
+                Sum4::Third(e) => (
+                    s.0.to_owned(),
+                    s.1.to_owned(),
+                    (decider3.evolve)(&s.2, e),
+                    s.3.to_owned(),
+                ),
+                Sum4::Fourth(e) => (
+                    s.0.to_owned(),
+                    s.1.to_owned(),
+                    s.2.to_owned(),
+                    (decider4.evolve)(&s.3, e),
+                ),
+            },
+        );
+
+        let new_initial_state = Box::new(move || {
+            (
+                (self.initial_state)(),
+                (decider2.initial_state)(),
+                (decider3.initial_state)(),
+                (decider4.initial_state)(),
+            )
+        });
+
+        Decider {
+            decide: new_decide,
+            evolve: new_evolve,
+            initial_state: new_initial_state,
+        }
+    }
+
+    /// Caches the initial state of the Decider (enabled by the `std` feature).
+    /// Creates a new instance of [Decider]`<C, S, E, Error>` that computes the initial state lazily, at most once, and clones it afterwards.
+    /// It is useful when the initial state is expensive to construct, as it is (re)computed for every command that is handled by the aggregate.
+    #[cfg(feature = "std")]
+    pub fn cache_initial_state(self) -> Decider<'a, C, S, E, Error>
+    where
+        S: Clone + MaybeSend + MaybeSync,
+    {
+        let initial_state = self.initial_state;
+        let cached_initial_state: OnceLock<S> = OnceLock::new();
+        let new_initial_state =
+            Box::new(move || cached_initial_state.get_or_init(&initial_state).clone());
+
+        Decider {
+            decide: self.decide,
+            evolve: self.evolve,
+            initial_state: new_initial_state,
+        }
+    }
+
+    /// Chains the next decision step to the `decide` function of the Decider.
+    /// Creates a new instance of [Decider]`<C, S, E, Error>` whose `decide` function is running the current `decide`, evolving the state with the produced events, and running the `next` step on the evolved state. The events of both steps are returned.
+    /// It is useful for the multi-step decisions within one command, without emitting the synthetic intermediate commands.
+    pub fn chain_decide<F>(self, next: F) -> Decider<'a, C, S, E, Error>
+    where
+        F: Fn(&C, &S) -> Result<Vec<E>, Error> + MaybeSend + MaybeSync + 'a,
+    {
+        let decider = Arc::new(self);
+        let (decide_decider, evolve_decider) = (decider.clone(), decider.clone());
+
+        let new_decide = Box::new(move |c: &C, s: &S| {
+            let mut events = (decide_decider.decide)(c, s)?;
+            let evolved_state = events.iter().fold(None, |state: Option<S>, event| {
+                Some((decide_decider.evolve)(state.as_ref().unwrap_or(s), event))
+            });
+            events.extend(next(c, evolved_state.as_ref().unwrap_or(s))?);
+            Ok(events)
+        });
+
+        let new_evolve = Box::new(move |s: &S, e: &E| (evolve_decider.evolve)(s, e));
+
+        let new_initial_state = Box::new(move || (decider.initial_state)());
+
+        Decider {
+            decide: new_decide,
+            evolve: new_evolve,
+            initial_state: new_initial_state,
+        }
+    }
+
+    /// Folds the events into the state, starting from the initial state.
+    /// It is the left fold of the `evolve` function over the events, independent of the command handling.
+    pub fn fold_to_state(&self, events: &[E]) -> S {
+        events.iter().fold((self.initial_state)(), |state, event| {
+            (self.evolve)(&state, event)
+        })
+    }
+
+    /// Derives the [View] from the Decider.
+    /// Creates a new instance of [View]`<S, E>` that is reusing the `evolve` and `initial_state` functions of the Decider, and drops the `decide` function.
+    /// It is useful when the read model is the same fold of the events as the write model (see `From`).
+    pub fn to_view(self) -> View<'a, S, E> {
+        View {
+            evolve: self.evolve,
+            initial_state: self.initial_state,
+        }
+    }
+}
+
+impl<'a, C, S, E, Error> From<Decider<'a, C, S, E, Error>> for View<'a, S, E> {
+    fn from(decider: Decider<'a, C, S, E, Error>) -> Self {
+        decider.to_view()
+    }
+}
+
+/// [FoldingDecider] is the variant of the [Decider] that evolves the state in place (`evolve_mut: Fn(&mut S, &E)`), instead of producing the new state per event.
+/// It is cheaper to replay the long event streams into the large states, as the state is not cloned/rebuilt on every event.
+/// Every [Decider] can be converted into the [FoldingDecider] (see `From`), and it can be used by the aggregates wherever the [Decider] is used.
+///
+/// ## Example
+/// ```
+/// use fmodel_rust::decider::{EventComputation, FoldingDecider, StateComputation};
+///
+/// let decider: FoldingDecider<u32, Vec<u32>, u32> = FoldingDecider {
+///     decide: Box::new(|command, _state| Ok(vec![*command])),
+///     evolve_mut: Box::new(|state, event| state.push(*event)),
+///     initial_state: Box::new(Vec::new),
+/// };
+/// assert_eq!(decider.fold_to_state(&[1, 2]), vec![1, 2]);
+/// assert_eq!(decider.compute_new_events(&[1, 2], &3), Ok(vec![3]));
+/// assert_eq!(decider.compute_new_state(Some(vec![1, 2]), &3), Ok(vec![1, 2, 3]));
+/// ```
+pub struct FoldingDecider<'a, C: 'a, S: 'a, E: 'a, Error: 'a = ()> {
+    /// The `decide` function is used to decide which events to produce based on the command and the current state.
+    pub decide: DecideFunction<'a, C, S, E, Error>,
+    /// The `evolve_mut` function is used to evolve the current state in place, based on the event.
+    pub evolve_mut: EvolveMutFunction<'a, S, E>,
+    /// The `initial_state` function is used to produce the initial state of the decider.
+    pub initial_state: InitialStateFunction<'a, S>,
+}
+
+impl<C, S, E, Error> FoldingDecider<'_, C, S, E, Error> {
+    /// Folds the events into the state, evolving the single (initial) state in place.
+    pub fn fold_to_state(&self, events: &[E]) -> S {
+        let mut state = (self.initial_state)();
+        events
+            .iter()
+            .for_each(|event| (self.evolve_mut)(&mut state, event));
+        state
+    }
+}
+
+impl<'a, C, S, E, Error> From<Decider<'a, C, S, E, Error>> for FoldingDecider<'a, C, S, E, Error> {
+    fn from(decider: Decider<'a, C, S, E, Error>) -> Self {
+        let evolve = decider.evolve;
+        FoldingDecider {
+            decide: decider.decide,
+            evolve_mut: Box::new(move |s: &mut S, e: &E| *s = evolve(s, e)),
+            initial_state: decider.initial_state,
+        }
+    }
 }
 
 /// Formalizes the `Event Computation` algorithm / event sourced system for the `decider` to handle commands based on the current events, and produce new events.
@@ -323,18 +717,31 @@ pub trait StateComputation<C, S, E, Error = ()> {
     fn compute_new_state(&self, current_state: Option<S>, command: &C) -> Result<S, Error>;
 }
 
+/// Formalizes the `Current State Computation` algorithm for the `decider` to compute the current state based on the current events, without handling the command.
+pub trait CurrentStateComputation<S, E> {
+    /// Computes the current state based on the current events.
+    fn compute_current_state(&self, current_events: &[E]) -> S;
+}
+
 impl<C, S, E, Error> EventComputation<C, S, E, Error> for Decider<'_, C, S, E, Error> {
     /// Computes new events based on the current events and the command.
     fn compute_new_events(&self, current_events: &[E], command: &C) -> Result<Vec<E>, Error> {
-        let current_state: S = current_events
-            .iter()
-            .fold((self.initial_state)(), |state, event| {
-                (self.evolve)(&state, event)
-            });
+        let current_state: S = self.fold_to_state(current_events);
         (self.decide)(command, &current_state)
     }
 }
 
+/// Shares the `decider` (for example, the `'static` one) among many aggregates/tasks.
+impl<C, S, E, Error, D> EventComputation<C, S, E, Error> for Arc<D>
+where
+    D: EventComputation<C, S, E, Error>,
+{
+    /// Computes new events based on the current events and the command.
+    fn compute_new_events(&self, current_events: &[E], command: &C) -> Result<Vec<E>, Error> {
+        (**self).compute_new_events(current_events, command)
+    }
+}
+
 impl<C, S, E, Error> StateComputation<C, S, E, Error> for Decider<'_, C, S, E, Error> {
     /// Computes new state based on the current state and the command.
     fn compute_new_state(&self, current_state: Option<S>, command: &C) -> Result<S, Error> {
@@ -349,3 +756,59 @@ impl<C, S, E, Error> StateComputation<C, S, E, Error> for Decider<'_, C, S, E, E
         })
     }
 }
+
+impl<C, S, E, Error> EventComputation<C, S, E, Error> for FoldingDecider<'_, C, S, E, Error> {
+    /// Computes new events based on the current events and the command.
+    fn compute_new_events(&self, current_events: &[E], command: &C) -> Result<Vec<E>, Error> {
+        let current_state: S = self.fold_to_state(current_events);
+        (self.decide)(command, &current_state)
+    }
+}
+
+impl<C, S, E, Error> StateComputation<C, S, E, Error> for FoldingDecider<'_, C, S, E, Error> {
+    /// Computes new state based on the current state and the command.
+    fn compute_new_state(&self, current_state: Option<S>, command: &C) -> Result<S, Error> {
+        let mut effective_current_state = current_state.unwrap_or_else(|| (self.initial_state)());
+        let events = (self.decide)(command, &effective_current_state)?;
+        events
+            .iter()
+            .for_each(|event| (self.evolve_mut)(&mut effective_current_state, event));
+        Ok(effective_current_state)
+    }
+}
+
+impl<C, S, E, Error> CurrentStateComputation<S, E> for Decider<'_, C, S, E, Error> {
+    /// Computes the current state based on the current events.
+    fn compute_current_state(&self, current_events: &[E]) -> S {
+        self.fold_to_state(current_events)
+    }
+}
+
+impl<C, S, E, Error> CurrentStateComputation<S, E> for FoldingDecider<'_, C, S, E, Error> {
+    /// Computes the current state based on the current events.
+    fn compute_current_state(&self, current_events: &[E]) -> S {
+        self.fold_to_state(current_events)
+    }
+}
+
+/// Shares the `decider` (for example, the `'static` one) among many aggregates/tasks.
+impl<S, E, D> CurrentStateComputation<S, E> for Arc<D>
+where
+    D: CurrentStateComputation<S, E>,
+{
+    /// Computes the current state based on the current events.
+    fn compute_current_state(&self, current_events: &[E]) -> S {
+        (**self).compute_current_state(current_events)
+    }
+}
+
+/// Shares the `decider` (for example, the `'static` one) among many aggregates/tasks.
+impl<C, S, E, Error, D> StateComputation<C, S, E, Error> for Arc<D>
+where
+    D: StateComputation<C, S, E, Error>,
+{
+    /// Computes new state based on the current state and the command.
+    fn compute_new_state(&self, current_state: Option<S>, command: &C) -> Result<S, Error> {
+        (**self).compute_new_state(current_state, command)
+    }
+}
@@ -0,0 +1,157 @@
+use alloc::boxed::Box;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::decider::Decider;
+use crate::{InitialStateFunction, MaybeSend, MaybeSync, Variant};
+
+/// The handler of the command, returning `None` if it is not responsible for the command
+#[cfg(feature = "send")]
+type CommandHandler<'a, C, S, E, Error> =
+    Box<dyn Fn(&C, &S) -> Option<Result<Vec<E>, Error>> + 'a + Send + Sync>;
+/// The handler of the command, returning `None` if it is not responsible for the command
+#[cfg(not(feature = "send"))]
+type CommandHandler<'a, C, S, E, Error> = Box<dyn Fn(&C, &S) -> Option<Result<Vec<E>, Error>> + 'a>;
+/// The handler of the event, returning `None` if it is not responsible for the event
+#[cfg(feature = "send")]
+type EventHandler<'a, S, E> = Box<dyn Fn(&S, &E) -> Option<S> + 'a + Send + Sync>;
+/// The handler of the event, returning `None` if it is not responsible for the event
+#[cfg(not(feature = "send"))]
+type EventHandler<'a, S, E> = Box<dyn Fn(&S, &E) -> Option<S> + 'a>;
+
+/// Fluent builder of the [Decider].
+///
+/// The command and event handlers are registered one by one (per variant, via [Variant], or for all the commands/events), instead of the big `match` statements in the `decide`/`evolve` functions.
+/// The first registered handler that is responsible for the command/event is used.
+/// The command that no handler is responsible for produces no events, and the event that no handler is responsible for leaves the state as it is.
+///
+/// Generic parameters:
+///
+/// - `C` - Command
+/// - `S` - State
+/// - `E` - Event
+/// - `Error` - Error
+///
+/// ## Example
+///
+/// ```
+/// use fmodel_rust::decider::Decider;
+/// use fmodel_rust::decider_builder::DeciderBuilder;
+/// use fmodel_rust::Variant;
+///
+/// struct CreateOrderCommand {
+///     order_id: u32,
+/// }
+///
+/// enum OrderCommand {
+///     Create(CreateOrderCommand),
+/// }
+///
+/// impl Variant<OrderCommand> for CreateOrderCommand {
+///     fn from_variant(value: &OrderCommand) -> Option<&Self> {
+///         match value {
+///             OrderCommand::Create(cmd) => Some(cmd),
+///         }
+///     }
+/// }
+///
+/// let decider: Decider<OrderCommand, u32, u32> = DeciderBuilder::new(|| 0)
+///     .when(|command: &CreateOrderCommand, _state: &u32| Ok(vec![command.order_id]))
+///     .on_event(|_state: &u32, event: &u32| *event)
+///     .build();
+///
+/// let events = (decider.decide)(&OrderCommand::Create(CreateOrderCommand { order_id: 1 }), &0);
+/// assert_eq!(events, Ok(vec![1]));
+/// ```
+pub struct DeciderBuilder<'a, C: 'a, S: 'a, E: 'a, Error: 'a = ()> {
+    command_handlers: Vec<CommandHandler<'a, C, S, E, Error>>,
+    event_handlers: Vec<EventHandler<'a, S, E>>,
+    initial_state: InitialStateFunction<'a, S>,
+}
+
+impl<'a, C, S, E, Error> DeciderBuilder<'a, C, S, E, Error> {
+    /// Creates a new instance of [DeciderBuilder], with the initial state function.
+    pub fn new<F>(initial_state: F) -> Self
+    where
+        F: Fn() -> S + 'a + MaybeSend + MaybeSync,
+    {
+        DeciderBuilder {
+            command_handlers: vec![],
+            event_handlers: vec![],
+            initial_state: Box::new(initial_state),
+        }
+    }
+
+    /// Registers the handler of the commands of the `V` variant.
+    pub fn when<V, F>(mut self, handler: F) -> Self
+    where
+        V: Variant<C>,
+        F: Fn(&V, &S) -> Result<Vec<E>, Error> + 'a + MaybeSend + MaybeSync,
+    {
+        self.command_handlers.push(Box::new(move |c: &C, s: &S| {
+            V::from_variant(c).map(|v| handler(v, s))
+        }));
+        self
+    }
+
+    /// Registers the handler of all the commands (that are not handled by the previously registered handlers).
+    pub fn on_command<F>(mut self, handler: F) -> Self
+    where
+        F: Fn(&C, &S) -> Result<Vec<E>, Error> + 'a + MaybeSend + MaybeSync,
+    {
+        self.command_handlers
+            .push(Box::new(move |c: &C, s: &S| Some(handler(c, s))));
+        self
+    }
+
+    /// Registers the handler of the events of the `V` variant.
+    pub fn evolve_when<V, F>(mut self, handler: F) -> Self
+    where
+        V: Variant<E>,
+        F: Fn(&S, &V) -> S + 'a + MaybeSend + MaybeSync,
+    {
+        self.event_handlers.push(Box::new(move |s: &S, e: &E| {
+            V::from_variant(e).map(|v| handler(s, v))
+        }));
+        self
+    }
+
+    /// Registers the handler of all the events (that are not handled by the previously registered handlers).
+    pub fn on_event<F>(mut self, handler: F) -> Self
+    where
+        F: Fn(&S, &E) -> S + 'a + MaybeSend + MaybeSync,
+    {
+        self.event_handlers
+            .push(Box::new(move |s: &S, e: &E| Some(handler(s, e))));
+        self
+    }
+
+    /// Builds the [Decider] out of the registered handlers.
+    pub fn build(self) -> Decider<'a, C, S, E, Error>
+    where
+        S: Clone,
+    {
+        let command_handlers = self.command_handlers;
+        let event_handlers = self.event_handlers;
+
+        let new_decide = Box::new(move |c: &C, s: &S| {
+            command_handlers
+                .iter()
+                .find_map(|handler| handler(c, s))
+                .unwrap_or_else(|| Ok(vec![]))
+        });
+
+        let new_evolve = Box::new(move |s: &S, e: &E| {
+            event_handlers
+                .iter()
+                .find_map(|handler| handler(s, e))
+                .unwrap_or_else(|| s.clone())
+        });
+
+        Decider {
+            decide: new_decide,
+            evolve: new_evolve,
+            initial_state: self.initial_state,
+        }
+    }
+}
@@ -0,0 +1,136 @@
+use std::future::Future;
+
+use crate::aggregate::{EventRepository, EventSourcedAggregate};
+use crate::decider::EventComputation;
+use crate::{MaybeSend, MaybeSync};
+
+/// Aggregate Decorator trait
+///
+/// It is used to apply the cross-cutting concerns (authorization, validation, auditing, ...) around the command handling, without embedding them in the deciders.
+///
+/// Generic parameters:
+///
+/// - `C` - Command
+/// - `E` - Event
+/// - `Version` - Version/Offset/Sequence number
+/// - `M` - Metadata (user info, tenant id, ...)
+/// - `Error` - Error
+pub trait AggregateDecorator<C, E, Version, M, Error> {
+    /// Runs before the command is handled. The command is rejected (not handled) if it fails.
+    /// Desugared `async fn before_handle(&self, command: &C, metadata: &M) -> Result<(), Error>;` to a normal `fn` that returns `impl Future`, and adds bound `MaybeSend` (`Send` with the default `send` feature)
+    /// You can freely move between the `async fn` and `-> impl Future` spelling in your traits and impls. This is true even when one form has a Send bound.
+    fn before_handle(
+        &self,
+        command: &C,
+        metadata: &M,
+    ) -> impl Future<Output = Result<(), Error>> + MaybeSend;
+    /// Runs after the command is handled, with the saved events.
+    /// Desugared `async fn after_handle(&self, events: &[(E, Version)]);` to a normal `fn` that returns `impl Future`, and adds bound `MaybeSend` (`Send` with the default `send` feature)
+    /// You can freely move between the `async fn` and `-> impl Future` spelling in your traits and impls. This is true even when one form has a Send bound.
+    fn after_handle(&self, events: &[(E, Version)]) -> impl Future<Output = ()> + MaybeSend;
+}
+
+/// Chains two decorators: the `before_handle` and `after_handle` of the first decorator run before the ones of the second decorator.
+impl<C, E, Version, M, Error, D1, D2> AggregateDecorator<C, E, Version, M, Error> for (D1, D2)
+where
+    D1: AggregateDecorator<C, E, Version, M, Error> + MaybeSync,
+    D2: AggregateDecorator<C, E, Version, M, Error> + MaybeSync,
+    C: MaybeSync,
+    E: MaybeSync,
+    Version: MaybeSync,
+    M: MaybeSync,
+{
+    async fn before_handle(&self, command: &C, metadata: &M) -> Result<(), Error> {
+        self.0.before_handle(command, metadata).await?;
+        self.1.before_handle(command, metadata).await
+    }
+
+    async fn after_handle(&self, events: &[(E, Version)]) {
+        self.0.after_handle(events).await;
+        self.1.after_handle(events).await;
+    }
+}
+
+/// Authorization decorator.
+///
+/// It rejects the commands that are failing the policy function, for example, the commands of the users that lack the role/permission (carried in the metadata).
+///
+/// Generic parameters:
+///
+/// - `Policy` - Policy function `Fn(&C, &M) -> Result<(), Error>`
+pub struct Authorization<Policy> {
+    policy: Policy,
+}
+
+impl<Policy> Authorization<Policy> {
+    /// Creates a new instance of [Authorization].
+    pub fn new(policy: Policy) -> Self {
+        Authorization { policy }
+    }
+}
+
+impl<C, E, Version, M, Error, Policy> AggregateDecorator<C, E, Version, M, Error>
+    for Authorization<Policy>
+where
+    Policy: Fn(&C, &M) -> Result<(), Error> + MaybeSync,
+    C: MaybeSync,
+    E: MaybeSync,
+    Version: MaybeSync,
+    M: MaybeSync,
+{
+    async fn before_handle(&self, command: &C, metadata: &M) -> Result<(), Error> {
+        (self.policy)(command, metadata)
+    }
+
+    async fn after_handle(&self, _events: &[(E, Version)]) {}
+}
+
+/// Decorated Aggregate.
+///
+/// It decorates the [EventSourcedAggregate] with the [AggregateDecorator].
+///
+/// Generic parameters:
+///
+/// - `Aggregate` - Aggregate
+/// - `Decorator` - Aggregate decorator
+pub struct DecoratedAggregate<Aggregate, Decorator> {
+    aggregate: Aggregate,
+    decorator: Decorator,
+}
+
+impl<Aggregate, Decorator> DecoratedAggregate<Aggregate, Decorator> {
+    /// Creates a new instance of [DecoratedAggregate].
+    pub fn new(aggregate: Aggregate, decorator: Decorator) -> Self {
+        DecoratedAggregate {
+            aggregate,
+            decorator,
+        }
+    }
+}
+
+impl<C, S, E, Repository, Decider, Version, Error, Decorator>
+    DecoratedAggregate<
+        EventSourcedAggregate<C, S, E, Repository, Decider, Version, Error>,
+        Decorator,
+    >
+where
+    Repository: EventRepository<C, E, Version, Error> + MaybeSync,
+    Decider: EventComputation<C, S, E, Error> + MaybeSync,
+    C: MaybeSync,
+    S: MaybeSync,
+    E: MaybeSync,
+    Version: MaybeSync,
+    Error: MaybeSync,
+{
+    /// Handles the command by the aggregate, running the decorator before and after the handling.
+    pub async fn handle<M>(&self, command: &C, metadata: &M) -> Result<Vec<(E, Version)>, Error>
+    where
+        Decorator: AggregateDecorator<C, E, Version, M, Error> + MaybeSync,
+        M: MaybeSync,
+    {
+        self.decorator.before_handle(command, metadata).await?;
+        let saved_events = self.aggregate.handle(command).await?;
+        self.decorator.after_handle(&saved_events).await;
+        Ok(saved_events)
+    }
+}
@@ -0,0 +1,200 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::future::Future;
+
+use crate::aggregate::{
+    EventRepository, EventSourcedAggregate, StateRepository, StateStoredAggregate,
+};
+use crate::decider::{EventComputation, StateComputation};
+use crate::saga_manager::{ActionEnvelope, ActionPublisher, ActionPublisherWithIdempotency};
+use crate::{MaybeSend, MaybeSync};
+
+/// Provide the idempotency key of the command.
+/// It is used to recognize the command that is already processed, for example, the `command_id` or the `request_id`.
+pub trait IdempotencyKey {
+    /// Returns the idempotency key of the command
+    fn idempotency_key(&self) -> String;
+}
+
+/// Deduplication Store trait
+///
+/// It is used to store the results of the processed commands, keyed by the [IdempotencyKey] of the command.
+///
+/// Generic parameters:
+///
+/// - `R` - Result of the command handling
+/// - `Error` - Error
+pub trait DeduplicationStore<R, Error> {
+    /// Fetches the result of the already processed command, based on the idempotency key.
+    /// Desugared `async fn fetch_result(&self, key: &str) -> Result<Option<R>, Error>;` to a normal `fn` that returns `impl Future`, and adds bound `MaybeSend` (`Send` with the default `send` feature)
+    /// You can freely move between the `async fn` and `-> impl Future` spelling in your traits and impls. This is true even when one form has a Send bound.
+    fn fetch_result(&self, key: &str)
+        -> impl Future<Output = Result<Option<R>, Error>> + MaybeSend;
+    /// Saves the result of the processed command, based on the idempotency key.
+    /// Desugared `async fn save_result(&self, key: &str, result: &R) -> Result<(), Error>;` to a normal `fn` that returns `impl Future`, and adds bound `MaybeSend` (`Send` with the default `send` feature)
+    /// You can freely move between the `async fn` and `-> impl Future` spelling in your traits and impls. This is true even when one form has a Send bound.
+    fn save_result(
+        &self,
+        key: &str,
+        result: &R,
+    ) -> impl Future<Output = Result<(), Error>> + MaybeSend;
+}
+
+/// Command Deduplication.
+///
+/// It decorates the aggregate ([EventSourcedAggregate] or [StateStoredAggregate]), and consults the [DeduplicationStore] before the command is handled.
+/// If the command with the same [IdempotencyKey] is already processed, the stored result is replayed, and the aggregate is not invoked again.
+/// Otherwise, the command is handled by the aggregate, and the successful result is stored.
+///
+/// The fetch/handle/save sequence is not atomic. Use a store with a unique constraint on the key (or a transactional store shared with the repository) if two concurrent deliveries of the same command are possible.
+///
+/// Generic parameters:
+///
+/// - `Aggregate` - Aggregate
+/// - `Store` - Deduplication store
+pub struct CommandDeduplication<Aggregate, Store> {
+    aggregate: Aggregate,
+    store: Store,
+}
+
+impl<Aggregate, Store> CommandDeduplication<Aggregate, Store> {
+    /// Creates a new instance of [CommandDeduplication].
+    pub fn new(aggregate: Aggregate, store: Store) -> Self {
+        CommandDeduplication { aggregate, store }
+    }
+}
+
+impl<C, S, E, Repository, Decider, Version, Error, Store>
+    CommandDeduplication<EventSourcedAggregate<C, S, E, Repository, Decider, Version, Error>, Store>
+where
+    Repository: EventRepository<C, E, Version, Error> + MaybeSync,
+    Decider: EventComputation<C, S, E, Error> + MaybeSync,
+    Store: DeduplicationStore<Vec<(E, Version)>, Error> + MaybeSync,
+    C: IdempotencyKey + MaybeSync,
+    S: MaybeSync,
+    E: MaybeSync,
+    Version: MaybeSync,
+    Error: MaybeSync,
+{
+    /// Handles the command, unless the command with the same idempotency key is already processed. In that case, the stored result is replayed.
+    pub async fn handle(&self, command: &C) -> Result<Vec<(E, Version)>, Error> {
+        let key = command.idempotency_key();
+        if let Some(result) = self.store.fetch_result(&key).await? {
+            return Ok(result);
+        }
+        let result = self.aggregate.handle(command).await?;
+        self.store.save_result(&key, &result).await?;
+        Ok(result)
+    }
+}
+
+impl<C, S, E, Repository, Decider, Version, Error, Store>
+    CommandDeduplication<StateStoredAggregate<C, S, E, Repository, Decider, Version, Error>, Store>
+where
+    Repository: StateRepository<C, S, Version, Error> + MaybeSync,
+    Decider: StateComputation<C, S, E, Error> + MaybeSync,
+    Store: DeduplicationStore<(S, Version), Error> + MaybeSync,
+    C: IdempotencyKey + MaybeSync,
+    S: MaybeSync,
+    E: MaybeSync,
+    Version: MaybeSync,
+    Error: MaybeSync,
+{
+    /// Handles the command, unless the command with the same idempotency key is already processed. In that case, the stored result is replayed.
+    pub async fn handle(&self, command: &C) -> Result<(S, Version), Error> {
+        let key = command.idempotency_key();
+        if let Some(result) = self.store.fetch_result(&key).await? {
+            return Ok(result);
+        }
+        let result = self.aggregate.handle(command).await?;
+        self.store.save_result(&key, &result).await?;
+        Ok(result)
+    }
+}
+
+/// The error signaling that the actions are published without the idempotency keys, so they can not be deduplicated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MissingIdempotencyKey;
+
+impl Display for MissingIdempotencyKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "The actions are published without the idempotency keys, so they can not be deduplicated"
+        )
+    }
+}
+
+impl Error for MissingIdempotencyKey {}
+
+/// Idempotent Action Publisher.
+///
+/// It decorates the [ActionPublisher], and consults the [DeduplicationStore] before the [ActionEnvelope] is published.
+/// If the action with the same idempotency key is already published, the stored action is replayed, and it is not published again.
+/// Otherwise, the action is published, and stored once it is successfully published.
+///
+/// The actions are deduplicated by `SagaManager::handle_idempotent` only. Publishing the actions without the idempotency keys (via the plain [ActionPublisher::publish], for example by `SagaManager::handle`) fails with the [MissingIdempotencyKey] error, instead of publishing them without the deduplication.
+///
+/// The same non-atomicity as for the [CommandDeduplication] applies.
+///
+/// Generic parameters:
+///
+/// - `Publisher` - Action publisher
+/// - `Store` - Deduplication store
+pub struct IdempotentActionPublisher<Publisher, Store> {
+    publisher: Publisher,
+    store: Store,
+}
+
+impl<Publisher, Store> IdempotentActionPublisher<Publisher, Store> {
+    /// Creates a new instance of [IdempotentActionPublisher].
+    pub fn new(publisher: Publisher, store: Store) -> Self {
+        IdempotentActionPublisher { publisher, store }
+    }
+}
+
+impl<A, E, Publisher, Store> ActionPublisher<A, E> for IdempotentActionPublisher<Publisher, Store>
+where
+    Publisher: ActionPublisher<A, E> + MaybeSync,
+    Store: MaybeSync,
+    A: MaybeSync,
+    E: From<MissingIdempotencyKey>,
+{
+    /// Fails with the [MissingIdempotencyKey] error, as the actions can not be deduplicated without the idempotency keys. Nothing is published.
+    async fn publish(&self, action: &[A]) -> Result<Vec<A>, E> {
+        if action.is_empty() {
+            Ok(vec![])
+        } else {
+            Err(MissingIdempotencyKey.into())
+        }
+    }
+}
+
+impl<A, E, Publisher, Store> ActionPublisherWithIdempotency<A, E>
+    for IdempotentActionPublisher<Publisher, Store>
+where
+    Publisher: ActionPublisher<A, E> + MaybeSync,
+    Store: DeduplicationStore<A, E> + MaybeSync,
+    A: Clone + MaybeSend + MaybeSync,
+    E: From<MissingIdempotencyKey> + MaybeSend,
+{
+    /// Publishes the actions/commands that are not published yet, replaying the already published ones.
+    async fn publish_idempotent(&self, action: &[ActionEnvelope<A>]) -> Result<Vec<A>, E> {
+        let mut published_actions = Vec::with_capacity(action.len());
+        for envelope in action {
+            if let Some(published) = self.store.fetch_result(&envelope.idempotency_key).await? {
+                published_actions.push(published);
+                continue;
+            }
+            let published = self
+                .publisher
+                .publish(std::slice::from_ref(&envelope.action))
+                .await?;
+            self.store
+                .save_result(&envelope.idempotency_key, &envelope.action)
+                .await?;
+            published_actions.extend(published);
+        }
+        Ok(published_actions)
+    }
+}
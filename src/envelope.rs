@@ -0,0 +1,122 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::EventName;
+
+/// Event Envelope.
+///
+/// It wraps the domain event with the information that is needed to store it, and to read it back: the event type name, the version of the stream, the timestamp and the metadata.
+/// It derives [Serialize] and [Deserialize], so it can be stored with any `serde` data format (JSON, CBOR, MessagePack, ...), while the domain event stays free of the infrastructure concerns.
+///
+/// Generic parameters:
+///
+/// - `E` - Event
+/// - `Version` - Version/Offset/Sequence number
+/// - `M` - Metadata
+///
+/// ## Example
+///
+/// ```
+/// use fmodel_rust::envelope::EventEnvelope;
+/// use fmodel_rust::EventName;
+///
+/// #[derive(Debug, Clone, PartialEq)]
+/// pub enum OrderEvent {
+///     Created(u32),
+///     Cancelled(u32),
+/// }
+///
+/// impl EventName for OrderEvent {
+///     fn event_name(&self) -> &'static str {
+///         match self {
+///             OrderEvent::Created(_) => "OrderCreated",
+///             OrderEvent::Cancelled(_) => "OrderCancelled",
+///         }
+///     }
+/// }
+///
+/// let envelope = EventEnvelope::new(OrderEvent::Created(1), 0, ());
+/// assert_eq!(envelope.event_type, "OrderCreated");
+/// assert_eq!(envelope.into_event(), OrderEvent::Created(1));
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EventEnvelope<E, Version, M = ()> {
+    /// The domain event / payload
+    pub event: E,
+    /// The stable name of the event type
+    pub event_type: String,
+    /// The version of the stream, after the event is appended
+    pub version: Version,
+    /// The time of the event, in milliseconds since the UNIX epoch
+    pub timestamp: u128,
+    /// Infrastructure metadata (correlation id, causation id, tenant id, ...)
+    pub metadata: M,
+}
+
+impl<E, Version, M> EventEnvelope<E, Version, M>
+where
+    E: EventName,
+{
+    /// Creates a new instance of [EventEnvelope], timestamped with the current time.
+    pub fn new(event: E, version: Version, metadata: M) -> Self {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_millis())
+            .unwrap_or_default();
+        EventEnvelope {
+            event_type: event.event_name().to_string(),
+            event,
+            version,
+            timestamp,
+            metadata,
+        }
+    }
+}
+
+impl<E, Version, M> EventEnvelope<E, Version, M> {
+    /// Unwraps the domain event.
+    pub fn into_event(self) -> E {
+        self.event
+    }
+
+    /// Maps the envelope over the E/Event type parameter, keeping the event type, version, timestamp and metadata.
+    /// Creates a new instance of [EventEnvelope]`<E2, Version, M>`.
+    pub fn map_event<E2, F>(self, f: &F) -> EventEnvelope<E2, Version, M>
+    where
+        F: Fn(E) -> E2,
+    {
+        EventEnvelope {
+            event: f(self.event),
+            event_type: self.event_type,
+            version: self.version,
+            timestamp: self.timestamp,
+            metadata: self.metadata,
+        }
+    }
+}
+
+/// Wraps the versioned events, as returned by the `EventRepository`, into the envelopes with the same metadata.
+pub fn wrap_events<E, Version, M>(
+    events: Vec<(E, Version)>,
+    metadata: &M,
+) -> Vec<EventEnvelope<E, Version, M>>
+where
+    E: EventName,
+    M: Clone,
+{
+    events
+        .into_iter()
+        .map(|(event, version)| EventEnvelope::new(event, version, metadata.clone()))
+        .collect()
+}
+
+/// Unwraps the envelopes into the versioned events, as expected by the `EventRepository`.
+pub fn unwrap_events<E, Version, M>(
+    envelopes: Vec<EventEnvelope<E, Version, M>>,
+) -> Vec<(E, Version)> {
+    envelopes
+        .into_iter()
+        .map(|envelope| (envelope.event, envelope.version))
+        .collect()
+}
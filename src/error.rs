@@ -0,0 +1,80 @@
+use std::fmt::{self, Debug, Display, Formatter};
+
+use crate::aggregate::{ConcurrencyError, OptimisticLockingError};
+
+/// Error type hierarchy of the application layer.
+///
+/// The aggregates, materialized views and saga managers are generic over the `Error`, and this is the ready-made error you can use for all of them, so the failures can be matched by category.
+/// The domain errors (produced by the `decider`/`saga`) are carried in the `Domain` variant. Use `map_error` on the `decider`/`saga` to lift them.
+///
+/// Generic parameters:
+///
+/// - `DomainError` - Domain error
+///
+/// ## Example
+///
+/// ```
+/// use fmodel_rust::decider::Decider;
+/// use fmodel_rust::error::Error;
+///
+/// fn decider<'a>() -> Decider<'a, u32, u32, u32, String> {
+///     Decider {
+///         decide: Box::new(|command, state| {
+///             if command > state {
+///                 Ok(vec![*command])
+///             } else {
+///                 Err("The command is outdated".to_string())
+///             }
+///         }),
+///         evolve: Box::new(|_state, event| *event),
+///         initial_state: Box::new(|| 0),
+///     }
+/// }
+///
+/// let decider: Decider<u32, u32, u32, Error> = decider().map_error(&|e: &String| Error::Domain(e.to_owned()));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error<DomainError = String> {
+    /// The domain error, produced by the `decider`/`saga`
+    Domain(DomainError),
+    /// Fetching the events failed
+    FetchEvents(String),
+    /// Saving the events failed
+    SaveEvents(String),
+    /// Fetching the state failed
+    FetchState(String),
+    /// Saving the state failed
+    SaveState(String),
+    /// Publishing the actions failed
+    PublishAction(String),
+    /// The stream/state was modified concurrently
+    Concurrency(String),
+}
+
+impl<DomainError: Display> Display for Error<DomainError> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Domain(error) => write!(f, "Domain error: {}", error),
+            Error::FetchEvents(error) => write!(f, "Failed to fetch events: {}", error),
+            Error::SaveEvents(error) => write!(f, "Failed to save events: {}", error),
+            Error::FetchState(error) => write!(f, "Failed to fetch state: {}", error),
+            Error::SaveState(error) => write!(f, "Failed to save state: {}", error),
+            Error::PublishAction(error) => write!(f, "Failed to publish action: {}", error),
+            Error::Concurrency(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl<DomainError: Debug + Display> std::error::Error for Error<DomainError> {}
+
+impl<DomainError, Version: Debug> From<ConcurrencyError<Version>> for Error<DomainError> {
+    fn from(error: ConcurrencyError<Version>) -> Self {
+        Error::Concurrency(error.to_string())
+    }
+}
+
+impl<DomainError> OptimisticLockingError for Error<DomainError> {
+    fn is_concurrency_error(&self) -> bool {
+        matches!(self, Error::Concurrency(_))
+    }
+}
@@ -0,0 +1,126 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use crate::outbox::EventPublisher;
+use crate::subscription::EventHandler;
+use crate::{MaybeSend, MaybeSync, Variant};
+
+/// The handling of the event by the subscriber
+#[cfg(feature = "send")]
+type EventHandling<'a, Error> = Pin<Box<dyn Future<Output = Result<(), Error>> + Send + 'a>>;
+/// The handling of the event by the subscriber
+#[cfg(not(feature = "send"))]
+type EventHandling<'a, Error> = Pin<Box<dyn Future<Output = Result<(), Error>> + 'a>>;
+
+/// The subscriber of the event bus, returning `None` if it is not interested in the event
+#[cfg(feature = "send")]
+type RegisteredEventHandler<'a, E, Error> =
+    Box<dyn Fn(&E) -> Option<EventHandling<'a, Error>> + 'a + Send + Sync>;
+/// The subscriber of the event bus, returning `None` if it is not interested in the event
+#[cfg(not(feature = "send"))]
+type RegisteredEventHandler<'a, E, Error> =
+    Box<dyn Fn(&E) -> Option<EventHandling<'a, Error>> + 'a>;
+
+/// [EventBus] is the in-process bus, delivering the events to the subscribers that are interested in them.
+///
+/// The subscribers (materialized views, saga managers, or any other [EventHandler]s and closures) subscribe per variant of the event (via [Variant]), or to all the events.
+/// The events are delivered in order, and every event is delivered to the interested subscribers in the order of the subscription, failing on the first subscriber that fails.
+/// The bus is the [EventPublisher], so the aggregates publish the saved events to it via `outbox::EventPublishingAggregate`. It is the [EventHandler] as well, so it can be fed by the `subscription::Subscriber`.
+///
+/// The variant of the event is cloned into the handling future, so the future is not borrowing the event.
+///
+/// Generic parameters:
+///
+/// - `E` - Event
+/// - `Error` - Error
+pub struct EventBus<'a, E: 'a, Error: 'a = ()> {
+    handlers: Vec<RegisteredEventHandler<'a, E, Error>>,
+}
+
+impl<E, Error> Default for EventBus<'_, E, Error> {
+    fn default() -> Self {
+        EventBus { handlers: vec![] }
+    }
+}
+
+impl<'a, E, Error> EventBus<'a, E, Error> {
+    /// Creates a new, empty instance of [EventBus].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Subscribes the handler to the events of the `V` variant.
+    pub fn subscribe<V, H>(self, handler: H) -> Self
+    where
+        V: Variant<E> + Clone + MaybeSend + MaybeSync + 'a,
+        H: EventHandler<V, Error> + MaybeSend + MaybeSync + 'a,
+    {
+        let handler = Arc::new(handler);
+        self.subscribe_fn(move |event: V| {
+            let handler = Arc::clone(&handler);
+            async move { handler.handle_event(&event).await }
+        })
+    }
+
+    /// Subscribes the handler to all the events.
+    pub fn subscribe_all<H>(mut self, handler: H) -> Self
+    where
+        E: Clone + MaybeSend + MaybeSync + 'a,
+        H: EventHandler<E, Error> + MaybeSend + MaybeSync + 'a,
+    {
+        let handler = Arc::new(handler);
+        self.handlers.push(Box::new(move |event: &E| {
+            let event = event.clone();
+            let handler = Arc::clone(&handler);
+            Some(Box::pin(async move { handler.handle_event(&event).await })
+                as EventHandling<'a, Error>)
+        }));
+        self
+    }
+
+    /// Subscribes the closure to the events of the `V` variant.
+    pub fn subscribe_fn<V, F, Fut>(mut self, f: F) -> Self
+    where
+        V: Variant<E> + Clone + 'a,
+        F: Fn(V) -> Fut + MaybeSend + MaybeSync + 'a,
+        Fut: Future<Output = Result<(), Error>> + MaybeSend + 'a,
+    {
+        self.handlers.push(Box::new(move |event: &E| {
+            V::from_variant(event)
+                .map(|variant| Box::pin(f(variant.clone())) as EventHandling<'a, Error>)
+        }));
+        self
+    }
+
+    /// Delivers the event to all the interested subscribers.
+    pub async fn dispatch(&self, event: &E) -> Result<(), Error> {
+        for handling in self.handlers.iter().filter_map(|handler| handler(event)) {
+            handling.await?;
+        }
+        Ok(())
+    }
+}
+
+impl<E, Error> EventHandler<E, Error> for EventBus<'_, E, Error>
+where
+    E: MaybeSync,
+{
+    async fn handle_event(&self, event: &E) -> Result<(), Error> {
+        self.dispatch(event).await
+    }
+}
+
+impl<E, Version, Error> EventPublisher<E, Version, Error> for EventBus<'_, E, Error>
+where
+    E: Clone + MaybeSync,
+    Version: Clone + MaybeSync,
+{
+    /// Delivers the saved events, in order, to the subscribers.
+    async fn publish(&self, events: &[(E, Version)]) -> Result<Vec<(E, Version)>, Error> {
+        for (event, _) in events {
+            self.dispatch(event).await?;
+        }
+        Ok(events.to_vec())
+    }
+}
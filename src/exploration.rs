@@ -0,0 +1,168 @@
+//! The runtime exploration of the state machine that the `evolve` function of the decider (or view) implements.
+//!
+//! The states are explored breadth-first from the initial state, by evolving every reached state with the sample events that the generator produces for it.
+//! The explored [StateMachine] is the state-transition table, that is rendered as the Mermaid state diagram for the documentation, and that detects the states the events never lead to.
+//!
+//! ```
+//! use fmodel_rust::decider::Decider;
+//! use fmodel_rust::exploration::StateMachine;
+//!
+//! fn decider<'a>() -> Decider<'a, (), &'static str, &'static str> {
+//!     Decider {
+//!         decide: Box::new(|_command, _state| Ok(vec![])),
+//!         evolve: Box::new(|state, event| match (*state, *event) {
+//!             ("Created", "Paid") => "Paid",
+//!             ("Paid", "Shipped") => "Shipped",
+//!             (state, _) => state,
+//!         }),
+//!         initial_state: Box::new(|| "Created"),
+//!     }
+//! }
+//!
+//! let machine = StateMachine::from_decider(&decider(), &|_state| vec!["Paid", "Shipped"], 10);
+//! assert_eq!(machine.states(), &["Created", "Paid", "Shipped"]);
+//! assert_eq!(machine.unreachable_states(&["Paid", "Cancelled"]), vec![&"Cancelled"]);
+//! ```
+
+use crate::decider::Decider;
+use crate::view::View;
+
+/// The transition of the state machine: the `event` evolves the state at the index `from` into the state at the index `to` (see [StateMachine::states]).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Transition<E> {
+    /// The index of the evolved state
+    pub from: usize,
+    /// The event
+    pub event: E,
+    /// The index of the new state
+    pub to: usize,
+}
+
+/// The state machine, explored by sampling the `evolve` function.
+///
+/// Generic parameters:
+///
+/// - `S` - State
+/// - `E` - Event
+#[derive(Debug, Clone, PartialEq)]
+pub struct StateMachine<S, E> {
+    states: Vec<S>,
+    transitions: Vec<Transition<E>>,
+    complete: bool,
+}
+
+impl<S, E> StateMachine<S, E>
+where
+    S: PartialEq,
+{
+    /// Explores the state machine of the `evolve` function, starting from the `initial_state`.
+    /// The `events` generator produces the sample events for the given state, and at most `max_states` states are explored.
+    pub fn explore<F, G>(initial_state: S, evolve: &F, events: &G, max_states: usize) -> Self
+    where
+        F: Fn(&S, &E) -> S,
+        G: Fn(&S) -> Vec<E>,
+    {
+        let mut states = vec![initial_state];
+        let mut transitions = Vec::new();
+        let mut complete = true;
+        let mut from = 0;
+        while from < states.len() {
+            for event in events(&states[from]) {
+                let new_state = evolve(&states[from], &event);
+                let to = match states.iter().position(|state| *state == new_state) {
+                    Some(to) => to,
+                    None if states.len() < max_states => {
+                        states.push(new_state);
+                        states.len() - 1
+                    }
+                    None => {
+                        complete = false;
+                        continue;
+                    }
+                };
+                transitions.push(Transition { from, event, to });
+            }
+            from += 1;
+        }
+        StateMachine {
+            states,
+            transitions,
+            complete,
+        }
+    }
+
+    /// Explores the state machine of the `evolve` function of the [Decider], starting from its initial state.
+    pub fn from_decider<C, Error, G>(
+        decider: &Decider<C, S, E, Error>,
+        events: &G,
+        max_states: usize,
+    ) -> Self
+    where
+        G: Fn(&S) -> Vec<E>,
+    {
+        Self::explore(
+            (decider.initial_state)(),
+            &|state: &S, event: &E| (decider.evolve)(state, event),
+            events,
+            max_states,
+        )
+    }
+
+    /// Explores the state machine of the `evolve` function of the [View], starting from its initial state.
+    pub fn from_view<G>(view: &View<S, E>, events: &G, max_states: usize) -> Self
+    where
+        G: Fn(&S) -> Vec<E>,
+    {
+        Self::explore(
+            (view.initial_state)(),
+            &|state: &S, event: &E| (view.evolve)(state, event),
+            events,
+            max_states,
+        )
+    }
+
+    /// Returns the reached states, in the order of the exploration. The initial state is the first one.
+    pub fn states(&self) -> &[S] {
+        &self.states
+    }
+
+    /// Returns the state-transition table.
+    pub fn transitions(&self) -> &[Transition<E>] {
+        &self.transitions
+    }
+
+    /// Returns `true` if all the reachable states are explored, `false` if the exploration stopped at `max_states`.
+    pub fn is_complete(&self) -> bool {
+        self.complete
+    }
+
+    /// Returns the `expected` states that are not reached by the sampled events.
+    pub fn unreachable_states<'s>(&self, expected: &'s [S]) -> Vec<&'s S> {
+        expected
+            .iter()
+            .filter(|state| !self.states.contains(state))
+            .collect()
+    }
+
+    /// Renders the state machine as the Mermaid state diagram, labeling the states and the events by the given functions.
+    pub fn to_mermaid<FS, FE>(&self, state_label: &FS, event_label: &FE) -> String
+    where
+        FS: Fn(&S) -> String,
+        FE: Fn(&E) -> String,
+    {
+        let mut diagram = String::from("stateDiagram-v2\n");
+        for (index, state) in self.states.iter().enumerate() {
+            diagram.push_str(&format!("    s{} : {}\n", index, state_label(state)));
+        }
+        diagram.push_str("    [*] --> s0\n");
+        for transition in &self.transitions {
+            diagram.push_str(&format!(
+                "    s{} --> s{} : {}\n",
+                transition.from,
+                transition.to,
+                event_label(&transition.event)
+            ));
+        }
+        diagram
+    }
+}
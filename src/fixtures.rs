@@ -0,0 +1,85 @@
+use std::fmt::{self, Debug, Display, Formatter};
+use std::fs;
+use std::path::Path;
+
+use crate::view::ViewStateComputation;
+
+/// Fixture error - the fixture file could not be read or parsed.
+///
+/// Generic parameters:
+///
+/// - `ParseError` - Error of the parse function
+#[derive(Debug)]
+pub enum FixtureError<ParseError> {
+    /// Reading the file failed
+    Io(std::io::Error),
+    /// Parsing the file (or the `line` of the NDJSON file, starting from `1`) failed
+    Parse {
+        /// The line of the NDJSON file, `None` for the JSON file
+        line: Option<usize>,
+        /// The error of the parse function
+        error: ParseError,
+    },
+}
+
+impl<ParseError: Display> Display for FixtureError<ParseError> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            FixtureError::Io(error) => write!(f, "Failed to read the fixture: {}", error),
+            FixtureError::Parse {
+                line: Some(line),
+                error,
+            } => write!(f, "Failed to parse the fixture at line {}: {}", line, error),
+            FixtureError::Parse { line: None, error } => {
+                write!(f, "Failed to parse the fixture: {}", error)
+            }
+        }
+    }
+}
+
+impl<ParseError: Debug + Display> std::error::Error for FixtureError<ParseError> {}
+
+/// Reads the events from the JSON file, containing the array of the events.
+/// The `parse` function is the deserializer of your choice, for example, `serde_json::from_str::<Vec<E>>`.
+pub fn read_json<E, ParseError, F>(
+    path: impl AsRef<Path>,
+    parse: F,
+) -> Result<Vec<E>, FixtureError<ParseError>>
+where
+    F: Fn(&str) -> Result<Vec<E>, ParseError>,
+{
+    let content = fs::read_to_string(path).map_err(FixtureError::Io)?;
+    parse(&content).map_err(|error| FixtureError::Parse { line: None, error })
+}
+
+/// Reads the events from the NDJSON file, containing one event per line. The blank lines are skipped.
+/// The `parse` function is the deserializer of your choice, for example, `serde_json::from_str::<E>`.
+pub fn read_ndjson<E, ParseError, F>(
+    path: impl AsRef<Path>,
+    parse: F,
+) -> Result<Vec<E>, FixtureError<ParseError>>
+where
+    F: Fn(&str) -> Result<E, ParseError>,
+{
+    let content = fs::read_to_string(path).map_err(FixtureError::Io)?;
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| {
+            parse(line).map_err(|error| FixtureError::Parse {
+                line: Some(index + 1),
+                error,
+            })
+        })
+        .collect()
+}
+
+/// Folds the events into the state of the `view`, starting from the initial state.
+/// Use the `fold_to_state` of the `decider` to fold the events into the state of the `decider`.
+pub fn fold_view<S, E, V>(view: &V, events: &[E]) -> S
+where
+    V: ViewStateComputation<E, S>,
+{
+    view.compute_new_state(None, &events.iter().collect::<Vec<&E>>())
+}
@@ -0,0 +1,366 @@
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use crate::aggregate::{
+    ConcurrencyError, EventRepository, EventRepositoryWithArchiving,
+    EventRepositoryWithOptimisticLocking, EventTailRepository, SnapshotRepository, StateRepository,
+};
+use crate::materialized_view::{ViewStateRepository, ViewStateRepositoryWithDeletion};
+use crate::{Identifier, MaybeSend, MaybeSync};
+
+/// In-memory, thread-safe [EventRepository] implementation.
+///
+/// It is a reference implementation of the [EventRepository] contract, and it is intended to be used in tests.
+/// The events are partitioned into streams by the [Identifier] of the command/event, and versioned per stream, starting from `0`.
+/// The archived events are moved to the in-memory archive, and the truncated events are dropped.
+///
+/// Generic parameters:
+///
+/// - `E` - Event
+pub struct InMemoryEventRepository<E> {
+    events: RwLock<Vec<(E, u64)>>,
+    archive: RwLock<Vec<(E, u64)>>,
+    removed_versions: RwLock<HashMap<String, u64>>,
+}
+
+impl<E> Default for InMemoryEventRepository<E> {
+    fn default() -> Self {
+        InMemoryEventRepository {
+            events: RwLock::new(Vec::new()),
+            archive: RwLock::new(Vec::new()),
+            removed_versions: RwLock::new(HashMap::new()),
+        }
+    }
+}
+
+impl<E> InMemoryEventRepository<E> {
+    /// Creates a new, empty instance of [InMemoryEventRepository].
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<E> InMemoryEventRepository<E>
+where
+    E: Identifier + Clone,
+{
+    /// Returns the archived events of all the streams, in the order they are archived.
+    pub fn archived_events(&self) -> Vec<(E, u64)> {
+        self.archive.read().unwrap().clone()
+    }
+
+    fn latest_version(&self, stored_events: &[(E, u64)], id: &str) -> Option<u64> {
+        stored_events
+            .iter()
+            .filter(|(event, _)| event.identifier() == id)
+            .map(|(_, version)| *version)
+            .next_back()
+            .or_else(|| self.removed_versions.read().unwrap().get(id).copied())
+    }
+
+    fn append(&self, stored_events: &mut Vec<(E, u64)>, events: &[E]) -> Vec<(E, u64)> {
+        let mut saved_events = Vec::with_capacity(events.len());
+        for event in events {
+            let version = self
+                .latest_version(stored_events, &event.identifier())
+                .map_or(0, |version| version + 1);
+            stored_events.push((event.clone(), version));
+            saved_events.push((event.clone(), version));
+        }
+        saved_events
+    }
+
+    /// Removes the events of the stream up to and including the version, remembering the latest removed version of the stream.
+    fn remove_until(&self, id: &str, version: u64) -> Vec<(E, u64)> {
+        let mut stored_events = self.events.write().unwrap();
+        let (removed, kept): (Vec<_>, Vec<_>) = stored_events
+            .drain(..)
+            .partition(|(event, v)| event.identifier() == id && *v <= version);
+        *stored_events = kept;
+        if let Some((_, latest)) = removed.last() {
+            self.removed_versions
+                .write()
+                .unwrap()
+                .insert(id.to_string(), *latest);
+        }
+        removed
+    }
+}
+
+impl<C, E, Error> EventRepository<C, E, u64, Error> for InMemoryEventRepository<E>
+where
+    C: Identifier + MaybeSync,
+    E: Identifier + Clone + MaybeSend + MaybeSync,
+{
+    /// Fetches current events of the stream to which the command belongs to.
+    async fn fetch_events(&self, command: &C) -> Result<Vec<(E, u64)>, Error> {
+        let id = command.identifier();
+        Ok(self
+            .events
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|(event, _)| event.identifier() == id)
+            .cloned()
+            .collect())
+    }
+
+    /// Saves events, incrementing the version of the stream to which each event belongs to.
+    async fn save(&self, events: &[E]) -> Result<Vec<(E, u64)>, Error> {
+        Ok(self.append(&mut self.events.write().unwrap(), events))
+    }
+
+    /// Provides the latest version of the stream to which the event belongs to.
+    async fn version_provider(&self, event: &E) -> Result<Option<u64>, Error> {
+        Ok(self.latest_version(&self.events.read().unwrap(), &event.identifier()))
+    }
+}
+
+impl<C, E, Error> EventRepositoryWithOptimisticLocking<C, E, u64, Error>
+    for InMemoryEventRepository<E>
+where
+    C: Identifier + MaybeSync,
+    E: Identifier + Clone + MaybeSend + MaybeSync,
+    Error: From<ConcurrencyError<u64>>,
+{
+    /// Saves events, if the latest version of the stream to which the first event belongs to matches the expected version.
+    async fn save_with_expected_version(
+        &self,
+        events: &[E],
+        expected_version: &Option<u64>,
+    ) -> Result<Vec<(E, u64)>, Error> {
+        let mut stored_events = self.events.write().unwrap();
+        if let Some(event) = events.first() {
+            let actual_version = self.latest_version(&stored_events, &event.identifier());
+            if actual_version != *expected_version {
+                return Err(ConcurrencyError {
+                    expected_version: *expected_version,
+                    actual_version,
+                }
+                .into());
+            }
+        }
+        Ok(self.append(&mut stored_events, events))
+    }
+}
+
+impl<C, E, Error> EventTailRepository<C, E, u64, Error> for InMemoryEventRepository<E>
+where
+    C: Identifier + MaybeSync,
+    E: Identifier + Clone + MaybeSend + MaybeSync,
+{
+    /// Fetches current events of the stream to which the command belongs to, that happened after the given version.
+    async fn fetch_events_after(&self, command: &C, version: &u64) -> Result<Vec<(E, u64)>, Error> {
+        let id = command.identifier();
+        Ok(self
+            .events
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|(event, v)| event.identifier() == id && v > version)
+            .cloned()
+            .collect())
+    }
+}
+
+impl<C, E, Error> EventRepositoryWithArchiving<C, E, u64, Error> for InMemoryEventRepository<E>
+where
+    C: Identifier + MaybeSync,
+    E: Identifier + Clone + MaybeSend + MaybeSync,
+{
+    /// Moves the events of the stream to which the command belongs to, up to and including the given version, to the archive.
+    async fn archive_events_until(
+        &self,
+        command: &C,
+        version: &u64,
+    ) -> Result<Vec<(E, u64)>, Error> {
+        let archived = self.remove_until(&command.identifier(), *version);
+        self.archive
+            .write()
+            .unwrap()
+            .extend(archived.iter().cloned());
+        Ok(archived)
+    }
+
+    /// Drops the events of the stream to which the command belongs to, up to and including the given version.
+    async fn truncate_events_until(&self, command: &C, version: &u64) -> Result<usize, Error> {
+        Ok(self.remove_until(&command.identifier(), *version).len())
+    }
+}
+
+/// In-memory, thread-safe [StateRepository] implementation.
+///
+/// It is a reference implementation of the [StateRepository] contract, and it is intended to be used in tests.
+/// The state is keyed by the [Identifier] of the command/state, and versioned, starting from `0`.
+///
+/// Generic parameters:
+///
+/// - `S` - State
+pub struct InMemoryStateRepository<S> {
+    states: RwLock<HashMap<String, (S, u64)>>,
+}
+
+impl<S> Default for InMemoryStateRepository<S> {
+    fn default() -> Self {
+        InMemoryStateRepository {
+            states: RwLock::new(HashMap::new()),
+        }
+    }
+}
+
+impl<S> InMemoryStateRepository<S> {
+    /// Creates a new, empty instance of [InMemoryStateRepository].
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<C, S, Error> StateRepository<C, S, u64, Error> for InMemoryStateRepository<S>
+where
+    C: Identifier + MaybeSync,
+    S: Identifier + Clone + MaybeSend + MaybeSync,
+    Error: From<ConcurrencyError<u64>>,
+{
+    /// Fetches current state, based on the identifier of the command.
+    async fn fetch_state(&self, command: &C) -> Result<Option<(S, u64)>, Error> {
+        Ok(self
+            .states
+            .read()
+            .unwrap()
+            .get(&command.identifier())
+            .cloned())
+    }
+
+    /// Saves state, incrementing the version, if the latest version of the state matches the given version.
+    async fn save(&self, state: &S, version: &Option<u64>) -> Result<(S, u64), Error> {
+        let mut states = self.states.write().unwrap();
+        let id = state.identifier();
+        let actual_version = states.get(&id).map(|(_, version)| *version);
+        if actual_version != *version {
+            return Err(ConcurrencyError {
+                expected_version: *version,
+                actual_version,
+            }
+            .into());
+        }
+        let new_version = version.map_or(0, |version| version + 1);
+        states.insert(id, (state.clone(), new_version));
+        Ok((state.clone(), new_version))
+    }
+}
+
+/// In-memory, thread-safe [ViewStateRepository] implementation.
+///
+/// It is a reference implementation of the [ViewStateRepository] contract, and it is intended to be used in tests.
+/// The state is keyed by the [Identifier] of the event/state.
+///
+/// Generic parameters:
+///
+/// - `S` - State
+pub struct InMemoryViewStateRepository<S> {
+    states: RwLock<HashMap<String, S>>,
+}
+
+impl<S> Default for InMemoryViewStateRepository<S> {
+    fn default() -> Self {
+        InMemoryViewStateRepository {
+            states: RwLock::new(HashMap::new()),
+        }
+    }
+}
+
+impl<S> InMemoryViewStateRepository<S> {
+    /// Creates a new, empty instance of [InMemoryViewStateRepository].
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<E, S, Error> ViewStateRepository<E, S, Error> for InMemoryViewStateRepository<S>
+where
+    E: Identifier + MaybeSync,
+    S: Identifier + Clone + MaybeSend + MaybeSync,
+{
+    /// Fetches current state, based on the identifier of the event.
+    async fn fetch_state(&self, event: &E) -> Result<Option<S>, Error> {
+        Ok(self
+            .states
+            .read()
+            .unwrap()
+            .get(&event.identifier())
+            .cloned())
+    }
+
+    /// Saves the new state.
+    async fn save(&self, state: &S) -> Result<S, Error> {
+        self.states
+            .write()
+            .unwrap()
+            .insert(state.identifier(), state.clone());
+        Ok(state.clone())
+    }
+}
+
+impl<E, S, Error> ViewStateRepositoryWithDeletion<E, S, Error> for InMemoryViewStateRepository<S>
+where
+    E: Identifier + MaybeSync,
+    S: Identifier + Clone + MaybeSend + MaybeSync,
+{
+    /// Deletes the state.
+    async fn delete_state(&self, state: &S) -> Result<(), Error> {
+        self.states.write().unwrap().remove(&state.identifier());
+        Ok(())
+    }
+}
+
+/// In-memory, thread-safe [SnapshotRepository] implementation.
+///
+/// It is a reference implementation of the [SnapshotRepository] contract, and it is intended to be used in tests.
+/// The latest snapshot is keyed by the [Identifier] of the command/state.
+///
+/// Generic parameters:
+///
+/// - `S` - State
+pub struct InMemorySnapshotRepository<S> {
+    snapshots: RwLock<HashMap<String, (S, u64)>>,
+}
+
+impl<S> Default for InMemorySnapshotRepository<S> {
+    fn default() -> Self {
+        InMemorySnapshotRepository {
+            snapshots: RwLock::new(HashMap::new()),
+        }
+    }
+}
+
+impl<S> InMemorySnapshotRepository<S> {
+    /// Creates a new, empty instance of [InMemorySnapshotRepository].
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<C, S, Error> SnapshotRepository<C, S, u64, Error> for InMemorySnapshotRepository<S>
+where
+    C: Identifier + MaybeSync,
+    S: Identifier + Clone + MaybeSend + MaybeSync,
+{
+    /// Fetches the latest snapshot, based on the identifier of the command.
+    async fn fetch_snapshot(&self, command: &C) -> Result<Option<(S, u64)>, Error> {
+        Ok(self
+            .snapshots
+            .read()
+            .unwrap()
+            .get(&command.identifier())
+            .cloned())
+    }
+
+    /// Saves the snapshot, replacing the previous one.
+    async fn save_snapshot(&self, state: &S, version: &u64) -> Result<(S, u64), Error> {
+        self.snapshots
+            .write()
+            .unwrap()
+            .insert(state.identifier(), (state.clone(), *version));
+        Ok((state.clone(), *version))
+    }
+}
@@ -0,0 +1,254 @@
+//! The algebraic laws of the deciders and sagas (and their combinators).
+//!
+//! The laws are plain predicates over the given inputs, so they can be checked by any property-based testing framework (for example, `proptest` or `quickcheck`), by generating the inputs and asserting the predicates.
+//!
+//! ```
+//! use fmodel_rust::decider::Decider;
+//! use fmodel_rust::laws;
+//!
+//! fn decider<'a>() -> Decider<'a, u32, u32, u32> {
+//!     Decider {
+//!         decide: Box::new(|command, state| Ok(vec![*command + *state])),
+//!         evolve: Box::new(|state, event| *state + *event),
+//!         initial_state: Box::new(|| 0),
+//!     }
+//! }
+//!
+//! // In real life, the inputs are generated by the property-based testing framework
+//! for events in [vec![], vec![1], vec![1, 2, 3]] {
+//!     assert!(laws::decide_is_deterministic(&decider(), &1, &2));
+//!     assert!(laws::fold_is_deterministic(&decider(), &events));
+//!     assert!(laws::state_converges(&decider(), &events, &4));
+//! }
+//! ```
+
+use crate::decider::{Decider, EventComputation, StateComputation};
+use crate::saga::Saga;
+use crate::{MaybeSend, MaybeSync, Sum, Sum3};
+
+/// The `decide` function is pure: deciding the same command on the same state produces the same events (or error).
+pub fn decide_is_deterministic<C, S, E, Error>(
+    decider: &Decider<C, S, E, Error>,
+    command: &C,
+    state: &S,
+) -> bool
+where
+    E: PartialEq,
+    Error: PartialEq,
+{
+    (decider.decide)(command, state) == (decider.decide)(command, state)
+}
+
+/// The folding of the events is pure: folding the same events produces the same state.
+pub fn fold_is_deterministic<C, S, E, Error>(
+    decider: &Decider<C, S, E, Error>,
+    events: &[E],
+) -> bool
+where
+    S: PartialEq,
+{
+    decider.fold_to_state(events) == decider.fold_to_state(events)
+}
+
+/// The state converges: evolving the folded state by the event equals folding the events followed by the event.
+/// `evolve(fold(events), event) == fold(events ++ [event])`
+pub fn state_converges<C, S, E, Error>(
+    decider: &Decider<C, S, E, Error>,
+    events: &[E],
+    event: &E,
+) -> bool
+where
+    S: PartialEq,
+    E: Clone,
+{
+    let evolved = (decider.evolve)(&decider.fold_to_state(events), event);
+    let all_events = events
+        .iter()
+        .cloned()
+        .chain(std::iter::once(event.clone()))
+        .collect::<Vec<E>>();
+    evolved == decider.fold_to_state(&all_events)
+}
+
+/// The event-sourced and the state-stored computation agree: the state computed from the current state equals the state folded from the current events and the new events.
+pub fn computations_agree<C, S, E, Error>(
+    decider: &Decider<C, S, E, Error>,
+    events: &[E],
+    command: &C,
+) -> bool
+where
+    S: PartialEq,
+    E: Clone,
+    Error: PartialEq,
+{
+    let new_state = decider.compute_new_state(Some(decider.fold_to_state(events)), command);
+    let new_events = decider.compute_new_events(events, command);
+    let folded_state = new_events.map(|new_events| {
+        let all_events = events.iter().cloned().chain(new_events).collect::<Vec<E>>();
+        decider.fold_to_state(&all_events)
+    });
+    new_state == folded_state
+}
+
+/// The combined decider round-trips: it produces the same events as the decider that the command belongs to, given the events of that decider.
+/// The deciders are provided by the factories, as the combination consumes them.
+pub fn combine_round_trips<'a, C, S, E, C2, S2, E2, Error>(
+    decider: impl Fn() -> Decider<'a, C, S, E, Error>,
+    decider2: impl Fn() -> Decider<'a, C2, S2, E2, Error>,
+    events: &[Sum<E, E2>],
+    command: &Sum<C, C2>,
+) -> bool
+where
+    C: 'a,
+    S: Clone + 'a,
+    E: Clone + PartialEq + 'a,
+    C2: 'a,
+    S2: Clone + 'a,
+    E2: Clone + PartialEq + 'a,
+    Error: PartialEq + 'a,
+{
+    let combined = decider().combine(decider2());
+    let combined_events = combined.compute_new_events(events, command);
+    match command {
+        Sum::First(command) => {
+            let events = events
+                .iter()
+                .filter_map(|event| match event {
+                    Sum::First(event) => Some(event.clone()),
+                    Sum::Second(_) => None,
+                })
+                .collect::<Vec<E>>();
+            let expected = decider()
+                .compute_new_events(&events, command)
+                .map(|events| events.into_iter().map(Sum::First).collect::<Vec<_>>());
+            combined_events == expected
+        }
+        Sum::Second(command) => {
+            let events = events
+                .iter()
+                .filter_map(|event| match event {
+                    Sum::First(_) => None,
+                    Sum::Second(event) => Some(event.clone()),
+                })
+                .collect::<Vec<E2>>();
+            let expected = decider2()
+                .compute_new_events(&events, command)
+                .map(|events| events.into_iter().map(Sum::Second).collect::<Vec<_>>());
+            combined_events == expected
+        }
+    }
+}
+
+/// Clones the value. It is the identity function of the mapping laws.
+fn identity<T: Clone>(value: &T) -> T {
+    value.clone()
+}
+
+/// Mapping the actions of the saga by the identity function does not change the saga.
+/// The saga is provided by the factory, as the mapping consumes it.
+pub fn saga_map_action_identity<'a, AR, A, Error>(
+    saga: impl Fn() -> Saga<'a, AR, A, Error>,
+    action_result: &AR,
+) -> bool
+where
+    AR: 'a,
+    A: Clone + PartialEq + MaybeSend + MaybeSync + 'a,
+    Error: PartialEq + 'a,
+{
+    let mapped = saga().map_action(&identity::<A>);
+    (mapped.react)(action_result) == (saga().react)(action_result)
+}
+
+/// Mapping the action results of the saga by the identity function does not change the saga.
+/// The saga is provided by the factory, as the mapping consumes it.
+pub fn saga_map_action_result_identity<'a, AR, A, Error>(
+    saga: impl Fn() -> Saga<'a, AR, A, Error>,
+    action_result: &AR,
+) -> bool
+where
+    AR: Clone + MaybeSend + MaybeSync + 'a,
+    A: PartialEq + 'a,
+    Error: PartialEq + 'a,
+{
+    let mapped = saga().map_action_result(&identity::<AR>);
+    (mapped.react)(action_result) == (saga().react)(action_result)
+}
+
+/// The merged saga produces the actions of the first saga, followed by the actions of the second saga.
+/// The sagas are provided by the factories, as the merging consumes them.
+pub fn saga_merge_concatenates<'a, AR, A, A2, Error>(
+    saga: impl Fn() -> Saga<'a, AR, A, Error>,
+    saga2: impl Fn() -> Saga<'a, AR, A2, Error>,
+    action_result: &AR,
+) -> bool
+where
+    AR: 'a,
+    A: PartialEq + 'a,
+    A2: PartialEq + 'a,
+    Error: PartialEq + 'a,
+{
+    let merged = (saga().merge(saga2()).react)(action_result);
+    let concatenated = (saga().react)(action_result).and_then(|actions| {
+        (saga2().react)(action_result).map(|actions2| {
+            actions
+                .into_iter()
+                .map(Sum::Second)
+                .chain(actions2.into_iter().map(Sum::First))
+                .collect::<Vec<_>>()
+        })
+    });
+    merged == concatenated
+}
+
+/// The combination of the sagas is associative, up to the re-association of the `Sum` types.
+/// `(saga.combine(saga2)).combine(saga3)` reacts the same as `saga.combine(saga2.combine(saga3))`.
+/// The sagas are provided by the factories, as the combination consumes them.
+#[allow(clippy::type_complexity)]
+pub fn saga_combine_is_associative<'a, AR, A, AR2, A2, AR3, A3, Error>(
+    saga: impl Fn() -> Saga<'a, AR, A, Error>,
+    saga2: impl Fn() -> Saga<'a, AR2, A2, Error>,
+    saga3: impl Fn() -> Saga<'a, AR3, A3, Error>,
+    action_result: &Sum3<AR, AR2, AR3>,
+) -> bool
+where
+    AR: Clone + 'a,
+    A: PartialEq + 'a,
+    AR2: Clone + 'a,
+    A2: PartialEq + 'a,
+    AR3: Clone + 'a,
+    A3: PartialEq + 'a,
+    Error: PartialEq + 'a,
+{
+    let (left_ar, right_ar) = match action_result {
+        Sum3::First(ar) => (Sum::First(Sum::First(ar.clone())), Sum::First(ar.clone())),
+        Sum3::Second(ar2) => (
+            Sum::First(Sum::Second(ar2.clone())),
+            Sum::Second(Sum::First(ar2.clone())),
+        ),
+        Sum3::Third(ar3) => (
+            Sum::Second(ar3.clone()),
+            Sum::Second(Sum::Second(ar3.clone())),
+        ),
+    };
+    let left = (saga().combine(saga2()).combine(saga3()).react)(&left_ar).map(|actions| {
+        actions
+            .into_iter()
+            .map(|action| match action {
+                Sum::First(a3) => Sum3::Third(a3),
+                Sum::Second(Sum::First(a2)) => Sum3::Second(a2),
+                Sum::Second(Sum::Second(a)) => Sum3::First(a),
+            })
+            .collect::<Vec<_>>()
+    });
+    let right = (saga().combine(saga2().combine(saga3())).react)(&right_ar).map(|actions| {
+        actions
+            .into_iter()
+            .map(|action| match action {
+                Sum::First(Sum::First(a3)) => Sum3::Third(a3),
+                Sum::First(Sum::Second(a2)) => Sum3::Second(a2),
+                Sum::Second(a) => Sum3::First(a),
+            })
+            .collect::<Vec<_>>()
+    });
+    left == right
+}
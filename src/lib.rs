@@ -1,4 +1,5 @@
 #![deny(missing_docs)]
+#![cfg_attr(not(feature = "std"), no_std)]
 //! # FModel Rust
 //!
 //! When you’re developing an information system to automate the activities of the business, you are modeling the business.
@@ -117,9 +118,9 @@
 //! - `A` - Action/Command
 //!
 //! ```rust
-//! pub type ReactFunction<'a, AR, A> = Box<dyn Fn(&AR) -> Vec<A> + 'a + Send + Sync>;
-//! pub struct Saga<'a, AR: 'a, A: 'a> {
-//!     pub react: ReactFunction<'a, AR, A>,
+//! pub type ReactFunction<'a, AR, A, Error> = Box<dyn Fn(&AR) -> Result<Vec<A>, Error> + 'a + Send + Sync>;
+//! pub struct Saga<'a, AR: 'a, A: 'a, Error: 'a = ()> {
+//!     pub react: ReactFunction<'a, AR, A, Error>,
 //! }
 //! ```
 //!
@@ -132,6 +133,27 @@
 //! It is using a [saga::Saga] to react to the action result and to publish the new actions.
 //! It is using an [saga_manager::ActionPublisher] to publish the new actions.
 //!
+//! ## Process Manager
+//!
+//! [process_manager::ProcessManager] is the orchestrating (stateful) variant of the `Saga`.
+//! It reacts to the action result (`AR`) based on the current state (`S`) of the process, and evolves the state of the process, so long-running business processes can track their progress.
+//!
+//! - `AR` - Action Result/Event
+//! - `S` - State
+//! - `A` - Action/Command
+//!
+//! ```rust
+//! pub type ProcessReactFunction<'a, AR, S, A, Error> = Box<dyn Fn(&S, &AR) -> Result<Vec<A>, Error> + 'a + Send + Sync>;
+//! pub type EvolveFunction<'a, S, E> = Box<dyn Fn(&S, &E) -> S + 'a + Send + Sync>;
+//! pub type InitialStateFunction<'a, S> = Box<dyn Fn() -> S + 'a + Send + Sync>;
+//!
+//! pub struct ProcessManager<'a, AR: 'a, S: 'a, A: 'a, Error: 'a = ()> {
+//!     pub react: ProcessReactFunction<'a, AR, S, A, Error>,
+//!     pub evolve: EvolveFunction<'a, S, AR>,
+//!     pub initial_state: InitialStateFunction<'a, S>,
+//! }
+//! ```
+//!
 //! ## Clear separation between data and behaviour
 //!
 //!```rust
@@ -283,6 +305,22 @@
 //! }
 //! ```
 //!
+//! ## Async runtime
+//!
+//! The library is not tied to any async runtime. The components that wait (the retries, the polling loops, ...) take the `sleep` function of your runtime (`Fn(Duration) -> impl Future<Output = ()>`, for example `tokio::time::sleep`) as an argument, instead of sleeping by themselves.
+//!
+//! ## WebAssembly
+//!
+//! The function aliases ([DecideFunction], [EvolveFunction], [ReactFunction], ...) are `Send + Sync` by default, so the deciders, views and sagas can be shared among the threads/tasks.
+//! The futures of the repositories and publishers are `Send` by default as well (see [MaybeSend] and [MaybeSync]), so the aggregates, materialized views and saga managers can be spawned on the multi-threaded runtimes.
+//! Disable the default `send` feature (`default-features = false, features = ["std"]`) to drop these bounds, so the functions can capture the non-thread-safe data, and the repositories/publishers can await the non-`Send` futures (for example, the `JsFuture` in the browser, `wasm32-unknown-unknown`).
+//!
+//! ## no_std
+//!
+//! The pure decision logic ([decider], [decider_builder], [view], [saga], [process_manager], [choreography] and the [upcaster::Upcaster]) only needs the `alloc` crate, so it can run on the embedded devices.
+//! Disable the default `std` feature (`default-features = false`, optionally with the `send` feature) to build the crate as `#![no_std]`.
+//! The application and infrastructure modules (the aggregates, materialized views, saga managers, repositories, ...) stay server-only, and are enabled by the `std` feature.
+//!
 //! ## Examples
 //!
 //! - [Restaurant Demo - with Postgres](https://github.com/fraktalio/fmodel-rust-demo)
@@ -307,30 +345,218 @@
 //! ---
 //! Created with `love` by [Fraktalio](https://!fraktalio.com/)
 
+extern crate alloc;
+
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::future::Future;
+use core::pin::Pin;
+
 use serde::{Deserialize, Serialize};
 
-/// Aggregate module - belongs to the `Application` layer - composes pure logic and effects (fetching, storing)
+/// Derive macros for the [Identifier], [EventName], [DeciderInfo], [ViewInfo] and [SagaInfo] traits (enabled by the `derive` feature)
+#[cfg(feature = "derive")]
+pub use fmodel_rust_derive::{DeciderInfo, EventName, Identifier, SagaInfo, ViewInfo};
+
+/// The `alloc` items the code generated by the derive macros refers to, so it compiles in the `no_std` crates as well
+#[doc(hidden)]
+pub mod __private {
+    pub use alloc::string::{String, ToString};
+}
+
+/// Aggregate module - belongs to the `Application` layer - composes pure logic and effects (fetching, storing) (enabled by the `std` feature)
+#[cfg(feature = "std")]
 pub mod aggregate;
+/// Cache module - belongs to the `Application` layer - caches the event-sourced state, replaying only the tail of the events since the cached version (enabled by the `std` feature)
+#[cfg(feature = "std")]
+pub mod cache;
+/// Choreography module - belongs to the `Domain` layer - records the reactions of the sagas, and renders the event flow as the Mermaid/Graphviz diagram
+pub mod choreography;
+/// Circuit Breaker module - belongs to the `Application` layer - stops calling the failing repositories/publishers, until they recover (enabled by the `std` feature)
+#[cfg(feature = "std")]
+pub mod circuit_breaker;
+/// Command Bus module - belongs to the `Application` layer - routes the heterogeneous commands to the aggregates that handle them (enabled by the `std` feature)
+#[cfg(feature = "std")]
+pub mod command_bus;
+/// Concurrency helpers - partitioning the work by the key, and running it concurrently (enabled by the `std` feature)
+#[cfg(feature = "std")]
+mod concurrency;
+/// Dead Letter module - belongs to the `Application` layer - parks the messages that could not be processed, so they are not lost, and can be replayed (enabled by the `std` feature)
+#[cfg(feature = "std")]
+pub mod dead_letter;
 /// Decider module - belongs to the `Domain` layer - pure decision making component - pure logic
 pub mod decider;
-/// Materialized View module - belongs to the `Application` layer - composes pure event handling algorithm and effects (fetching, storing)
+/// Decider Builder module - belongs to the `Domain` layer - fluent builder of the deciders, with the handlers registered per command/event variant
+pub mod decider_builder;
+/// Decorator module - belongs to the `Application` layer - applies the cross-cutting concerns (authorization, auditing, ...) around the aggregates (enabled by the `std` feature)
+#[cfg(feature = "std")]
+pub mod decorator;
+/// Deduplication module - belongs to the `Application` layer - decorates the aggregates with the idempotent command handling (enabled by the `std` feature)
+#[cfg(feature = "std")]
+pub mod deduplication;
+/// Envelope module - belongs to the `Infrastructure` layer - serializable wrapper of the events, carrying the event type, version, timestamp and metadata (enabled by the `std` feature)
+#[cfg(feature = "std")]
+pub mod envelope;
+/// Error module - belongs to the `Application` layer - the ready-made error type hierarchy of the aggregates, materialized views and saga managers (enabled by the `std` feature)
+#[cfg(feature = "std")]
+pub mod error;
+/// Event Bus module - belongs to the `Application` layer - in-process delivery of the events to the subscribed views, sagas and handlers (enabled by the `std` feature)
+#[cfg(feature = "std")]
+pub mod event_bus;
+/// Exploration module - belongs to the `Domain` layer - explores the state machine of the `evolve` function, for the documentation and the detection of the unreachable states (enabled by the `test-utils` feature)
+#[cfg(feature = "test-utils")]
+pub mod exploration;
+/// Fixtures module - belongs to the `Infrastructure` layer - reads the event fixtures from the JSON/NDJSON files, for the golden-file tests of the views and deciders (enabled by the `test-utils` feature)
+#[cfg(feature = "test-utils")]
+pub mod fixtures;
+/// In-memory module - belongs to the `Infrastructure` layer - reference, in-memory implementations of the repositories (enabled by the `test-utils` feature)
+#[cfg(feature = "test-utils")]
+pub mod in_memory;
+/// Laws module - the algebraic laws of the deciders and sagas, to be checked by the property-based tests of your domain (enabled by the `test-utils` feature)
+#[cfg(feature = "test-utils")]
+pub mod laws;
+/// Materialized View module - belongs to the `Application` layer - composes pure event handling algorithm and effects (fetching, storing) (enabled by the `std` feature)
+#[cfg(feature = "std")]
 pub mod materialized_view;
+/// Metrics module - belongs to the `Application` layer - pluggable metrics of the aggregates, sagas and repositories (enabled by the `std` feature)
+#[cfg(feature = "std")]
+pub mod metrics;
+/// Outbox module - belongs to the `Application` layer - publishes the saved events to the message bus (enabled by the `std` feature)
+#[cfg(feature = "std")]
+pub mod outbox;
+/// Process Manager module - belongs to the `Domain` layer - pure, stateful mapper of action results/events into new actions/commands
+pub mod process_manager;
+/// Replay module - belongs to the `Application` layer - reconstructs the state as of the point in time (version or timestamp), and compares it to the current state (enabled by the `std` feature)
+#[cfg(feature = "std")]
+pub mod replay;
+/// Retry module - belongs to the `Application` layer - retries the repository/publisher calls that failed transiently (enabled by the `std` feature)
+#[cfg(feature = "std")]
+pub mod retry;
 /// Saga module - belongs to the `Domain` layer - pure mapper of action results/events into new actions/commands
 pub mod saga;
-/// Saga Manager module - belongs to the `Application` layer - composes pure saga and effects (publishing)
+/// Saga Manager module - belongs to the `Application` layer - composes pure saga and effects (publishing) (enabled by the `std` feature)
+#[cfg(feature = "std")]
 pub mod saga_manager;
+/// Scheduler module - belongs to the `Application` layer - publishes the delayed actions of the sagas (timeouts/deadlines)
+pub mod scheduler;
+/// Shredding module - belongs to the `Infrastructure` layer - encrypts the personal data of the events per data subject, for the crypto-shredding (right to erasure) (enabled by the `std` feature)
+#[cfg(feature = "std")]
+pub mod shredding;
+/// Snapshot module - belongs to the `Application` layer - the serialization contract (wire format) of the state snapshots (enabled by the `std` feature)
+#[cfg(feature = "std")]
+pub mod snapshot;
+/// Specification module - provides the `Given-When-Then` test specification DSL for the deciders, views and sagas (enabled by the `test-utils` feature)
+#[cfg(feature = "test-utils")]
+pub mod specification;
+/// Subscription module - belongs to the `Application` layer - feeds the views and the sagas from the event stream, with the checkpoint persistence (enabled by the `std` feature)
+#[cfg(feature = "std")]
+pub mod subscription;
+/// Upcaster module - belongs to the `Infrastructure` layer - migrates the old persisted events to the new schema, on read
+pub mod upcaster;
 /// View module - belongs to the `Domain` layer - pure event handling algorithm
 pub mod view;
+/// Visualization module - renders the composed application (deciders, views, sagas and their message types) as the Mermaid/PlantUML diagrams (enabled by the `visualization` feature)
+#[cfg(feature = "visualization")]
+pub mod visualization;
+
+/// The [MaybeSend] marker is `Send` with the default `send` feature, and it is implemented by every type without it.
+/// It is the bound of the futures returned by the repositories/publishers, and of the functions captured by the combinators.
+#[cfg(feature = "send")]
+pub trait MaybeSend: Send {}
+#[cfg(feature = "send")]
+impl<T: Send + ?Sized> MaybeSend for T {}
+/// The [MaybeSend] marker is `Send` with the default `send` feature, and it is implemented by every type without it.
+/// It is the bound of the futures returned by the repositories/publishers, and of the functions captured by the combinators.
+#[cfg(not(feature = "send"))]
+pub trait MaybeSend {}
+#[cfg(not(feature = "send"))]
+impl<T: ?Sized> MaybeSend for T {}
+/// The [MaybeSync] marker is `Sync` with the default `send` feature, and it is implemented by every type without it.
+/// It is the bound of the components (and their repositories, deciders, ...) that are borrowed by the returned futures.
+#[cfg(feature = "send")]
+pub trait MaybeSync: Sync {}
+#[cfg(feature = "send")]
+impl<T: Sync + ?Sized> MaybeSync for T {}
+/// The [MaybeSync] marker is `Sync` with the default `send` feature, and it is implemented by every type without it.
+/// It is the bound of the components (and their repositories, deciders, ...) that are borrowed by the returned futures.
+#[cfg(not(feature = "send"))]
+pub trait MaybeSync {}
+#[cfg(not(feature = "send"))]
+impl<T: ?Sized> MaybeSync for T {}
 
 /// The [DecideFunction] function is used to decide which events to produce based on the command and the current state.
+#[cfg(feature = "send")]
 pub type DecideFunction<'a, C, S, E, Error> =
     Box<dyn Fn(&C, &S) -> Result<Vec<E>, Error> + 'a + Send + Sync>;
+/// The [DecideFunction] function is used to decide which events to produce based on the command and the current state.
+#[cfg(not(feature = "send"))]
+pub type DecideFunction<'a, C, S, E, Error> = Box<dyn Fn(&C, &S) -> Result<Vec<E>, Error> + 'a>;
 /// The [EvolveFunction] function is used to evolve the state based on the current state and the event.
+#[cfg(feature = "send")]
 pub type EvolveFunction<'a, S, E> = Box<dyn Fn(&S, &E) -> S + 'a + Send + Sync>;
+/// The [EvolveFunction] function is used to evolve the state based on the current state and the event.
+#[cfg(not(feature = "send"))]
+pub type EvolveFunction<'a, S, E> = Box<dyn Fn(&S, &E) -> S + 'a>;
+/// The [EvolveMutFunction] function is used to evolve the state in place based on the event, avoiding the state clone per event.
+#[cfg(feature = "send")]
+pub type EvolveMutFunction<'a, S, E> = Box<dyn Fn(&mut S, &E) + 'a + Send + Sync>;
+/// The [EvolveMutFunction] function is used to evolve the state in place based on the event, avoiding the state clone per event.
+#[cfg(not(feature = "send"))]
+pub type EvolveMutFunction<'a, S, E> = Box<dyn Fn(&mut S, &E) + 'a>;
 /// The [InitialStateFunction] function is used to produce the initial state.
+#[cfg(feature = "send")]
 pub type InitialStateFunction<'a, S> = Box<dyn Fn() -> S + 'a + Send + Sync>;
+/// The [InitialStateFunction] function is used to produce the initial state.
+#[cfg(not(feature = "send"))]
+pub type InitialStateFunction<'a, S> = Box<dyn Fn() -> S + 'a>;
+/// The [ReactFunction] function is used to decide what actions/A to execute next based on the action result/AR.
+#[cfg(feature = "send")]
+pub type ReactFunction<'a, AR, A, Error> =
+    Box<dyn Fn(&AR) -> Result<Vec<A>, Error> + 'a + Send + Sync>;
 /// The [ReactFunction] function is used to decide what actions/A to execute next based on the action result/AR.
-pub type ReactFunction<'a, AR, A> = Box<dyn Fn(&AR) -> Vec<A> + 'a + Send + Sync>;
+#[cfg(not(feature = "send"))]
+pub type ReactFunction<'a, AR, A, Error> = Box<dyn Fn(&AR) -> Result<Vec<A>, Error> + 'a>;
+/// The [OwnedReactFunction] function is used to decide what actions/A to execute next based on the owned action result/AR, so the data can be moved out of it, instead of cloned.
+#[cfg(feature = "send")]
+pub type OwnedReactFunction<'a, AR, A, Error> =
+    Box<dyn Fn(AR) -> Result<Vec<A>, Error> + 'a + Send + Sync>;
+/// The [OwnedReactFunction] function is used to decide what actions/A to execute next based on the owned action result/AR, so the data can be moved out of it, instead of cloned.
+#[cfg(not(feature = "send"))]
+pub type OwnedReactFunction<'a, AR, A, Error> = Box<dyn Fn(AR) -> Result<Vec<A>, Error> + 'a>;
+/// The [AsyncReactFunction] function is used to decide what actions/A to execute next based on the action result/AR, asynchronously.
+#[cfg(feature = "send")]
+pub type AsyncReactFunction<'a, AR, A, Error> = Box<
+    dyn Fn(&AR) -> Pin<Box<dyn Future<Output = Result<Vec<A>, Error>> + Send + 'a>>
+        + 'a
+        + Send
+        + Sync,
+>;
+/// The [AsyncReactFunction] function is used to decide what actions/A to execute next based on the action result/AR, asynchronously.
+#[cfg(not(feature = "send"))]
+pub type AsyncReactFunction<'a, AR, A, Error> =
+    Box<dyn Fn(&AR) -> Pin<Box<dyn Future<Output = Result<Vec<A>, Error>> + 'a>> + 'a>;
+/// The [ProcessReactFunction] function is used to decide what actions/A to execute next based on the current state/S of the process and the action result/AR.
+#[cfg(feature = "send")]
+pub type ProcessReactFunction<'a, AR, S, A, Error> =
+    Box<dyn Fn(&S, &AR) -> Result<Vec<A>, Error> + 'a + Send + Sync>;
+/// The [ProcessReactFunction] function is used to decide what actions/A to execute next based on the current state/S of the process and the action result/AR.
+#[cfg(not(feature = "send"))]
+pub type ProcessReactFunction<'a, AR, S, A, Error> =
+    Box<dyn Fn(&S, &AR) -> Result<Vec<A>, Error> + 'a>;
+/// The [TryEvolveFunction] function is used to evolve the state based on the current state and the event, failing if the event can not be applied.
+#[cfg(feature = "send")]
+pub type TryEvolveFunction<'a, S, E, Error> =
+    Box<dyn Fn(&S, &E) -> Result<S, Error> + 'a + Send + Sync>;
+/// The [TryEvolveFunction] function is used to evolve the state based on the current state and the event, failing if the event can not be applied.
+#[cfg(not(feature = "send"))]
+pub type TryEvolveFunction<'a, S, E, Error> = Box<dyn Fn(&S, &E) -> Result<S, Error> + 'a>;
+/// The [UpcastFunction] function is used to migrate the old event to the new event.
+#[cfg(feature = "send")]
+pub type UpcastFunction<'a, Old, New> = Box<dyn Fn(&Old) -> New + 'a + Send + Sync>;
+/// The [UpcastFunction] function is used to migrate the old event to the new event.
+#[cfg(not(feature = "send"))]
+pub type UpcastFunction<'a, Old, New> = Box<dyn Fn(&Old) -> New + 'a>;
 
 /// Define the generic Combined/Sum Enum
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
@@ -341,13 +567,226 @@ pub enum Sum<A, B> {
     Second(B),
 }
 
+impl<A, B> Sum<A, B> {
+    /// Returns `true` if the other sum is of the same variant (both are `First`, or both are `Second`).
+    /// It is used to match the commands of the combined decider to the events of the same sub-decider (see `aggregate::EventSourcedAggregate::handle_with_filter`).
+    pub fn is_same_variant<C, D>(&self, other: &Sum<C, D>) -> bool {
+        matches!(
+            (self, other),
+            (Sum::First(_), Sum::First(_)) | (Sum::Second(_), Sum::Second(_))
+        )
+    }
+    /// Maps the first variant, leaving the second variant untouched.
+    pub fn map_first<A2, F>(self, f: F) -> Sum<A2, B>
+    where
+        F: FnOnce(A) -> A2,
+    {
+        match self {
+            Sum::First(a) => Sum::First(f(a)),
+            Sum::Second(b) => Sum::Second(b),
+        }
+    }
+
+    /// Maps the second variant, leaving the first variant untouched.
+    pub fn map_second<B2, F>(self, f: F) -> Sum<A, B2>
+    where
+        F: FnOnce(B) -> B2,
+    {
+        match self {
+            Sum::First(a) => Sum::First(a),
+            Sum::Second(b) => Sum::Second(f(b)),
+        }
+    }
+
+    /// Swaps the variants.
+    pub fn swap(self) -> Sum<B, A> {
+        match self {
+            Sum::First(a) => Sum::Second(a),
+            Sum::Second(b) => Sum::First(b),
+        }
+    }
+
+    /// Returns the value of the first variant, or `None`.
+    pub fn into_first(self) -> Option<A> {
+        match self {
+            Sum::First(a) => Some(a),
+            Sum::Second(_) => None,
+        }
+    }
+
+    /// Returns the value of the second variant, or `None`.
+    pub fn into_second(self) -> Option<B> {
+        match self {
+            Sum::First(_) => None,
+            Sum::Second(b) => Some(b),
+        }
+    }
+
+    /// Returns the reference to the value of the first variant, or `None`.
+    pub fn as_first(&self) -> Option<&A> {
+        match self {
+            Sum::First(a) => Some(a),
+            Sum::Second(_) => None,
+        }
+    }
+
+    /// Returns the reference to the value of the second variant, or `None`.
+    pub fn as_second(&self) -> Option<&B> {
+        match self {
+            Sum::First(_) => None,
+            Sum::Second(b) => Some(b),
+        }
+    }
+}
+
+impl<A> Sum<A, A> {
+    /// Returns the value of either variant.
+    pub fn into_inner(self) -> A {
+        match self {
+            Sum::First(a) | Sum::Second(a) => a,
+        }
+    }
+}
+
+/// The first variant is `Ok`, and the second variant is `Err`.
+impl<A, B> From<Result<A, B>> for Sum<A, B> {
+    fn from(result: Result<A, B>) -> Self {
+        match result {
+            Ok(a) => Sum::First(a),
+            Err(b) => Sum::Second(b),
+        }
+    }
+}
+
+/// The first variant is `Ok`, and the second variant is `Err`.
+impl<A, B> From<Sum<A, B>> for Result<A, B> {
+    fn from(sum: Sum<A, B>) -> Self {
+        match sum {
+            Sum::First(a) => Ok(a),
+            Sum::Second(b) => Err(b),
+        }
+    }
+}
+
+/// Define the generic Combined/Sum Enum of three variants
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub enum Sum3<A, B, C> {
+    /// First variant
+    First(A),
+    /// Second variant
+    Second(B),
+    /// Third variant
+    Third(C),
+}
+
+/// Define the generic Combined/Sum Enum of four variants
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub enum Sum4<A, B, C, D> {
+    /// First variant
+    First(A),
+    /// Second variant
+    Second(B),
+    /// Third variant
+    Third(C),
+    /// Fourth variant
+    Fourth(D),
+}
+
+/// Define the generic Combined/Sum Enum of five variants
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub enum Sum5<A, B, C, D, E> {
+    /// First variant
+    First(A),
+    /// Second variant
+    Second(B),
+    /// Third variant
+    Third(C),
+    /// Fourth variant
+    Fourth(D),
+    /// Fifth variant
+    Fifth(E),
+}
+
 /// Identify the state/command/event.
 /// It is used to identify the concept to what the state/command/event belongs to. For example, the `order_id` or `restaurant_id`.
+/// The repositories (for example, the `in_memory` ones) and the aggregates/views use it to derive the stream/key of the command, event and state.
 pub trait Identifier {
     /// Returns the identifier of the state/command/event
     fn identifier(&self) -> String;
 }
 
+/// Name the event.
+/// It is used to provide the stable name of the event type, for example, to (de)serialize the event from/to the event store.
+pub trait EventName {
+    /// Returns the name of the event
+    fn event_name(&self) -> &'static str;
+}
+
+/// Describe the decider.
+/// It is used by the repositories and the observability hooks to tag the persisted events/snapshots and the metrics with the decider that produced them.
+/// Implement it on the command type of the decider (manually, or via `#[derive(DeciderInfo)]`), or on the marker type.
+pub trait DeciderInfo {
+    /// Returns the stable name of the decider
+    fn decider_name() -> &'static str;
+    /// Returns the version of the decider
+    fn decider_version() -> u32;
+    /// Returns the names of the handled command types
+    fn handled_commands() -> &'static [&'static str];
+}
+
+/// Describe the view.
+/// It is used by the repositories and the observability hooks to tag the persisted state and the metrics with the view that produced them.
+/// Implement it on the event type of the view (manually, or via `#[derive(ViewInfo)]`), or on the marker type.
+pub trait ViewInfo {
+    /// Returns the stable name of the view
+    fn view_name() -> &'static str;
+    /// Returns the version of the view
+    fn view_version() -> u32;
+    /// Returns the names of the handled event types
+    fn handled_events() -> &'static [&'static str];
+}
+
+/// Describe the saga.
+/// It is used by the publishers and the observability hooks to tag the published actions and the metrics with the saga that produced them.
+/// Implement it on the action result type of the saga (manually, or via `#[derive(SagaInfo)]`), or on the marker type.
+pub trait SagaInfo {
+    /// Returns the stable name of the saga
+    fn saga_name() -> &'static str;
+    /// Returns the version of the saga
+    fn saga_version() -> u32;
+    /// Returns the names of the handled action result types
+    fn handled_action_results() -> &'static [&'static str];
+}
+
+/// Provides the access to the variant of the (command/event) enum.
+/// It is used to register the handlers per variant (see `decider_builder::DeciderBuilder` and `saga::SagaRegistry`), instead of matching the whole enum.
+///
+/// ## Example
+///
+/// ```
+/// use fmodel_rust::Variant;
+///
+/// struct CreateOrderCommand {
+///     order_id: u32,
+/// }
+///
+/// enum OrderCommand {
+///     Create(CreateOrderCommand),
+/// }
+///
+/// impl Variant<OrderCommand> for CreateOrderCommand {
+///     fn from_variant(value: &OrderCommand) -> Option<&Self> {
+///         match value {
+///             OrderCommand::Create(cmd) => Some(cmd),
+///         }
+///     }
+/// }
+/// ```
+pub trait Variant<T> {
+    /// Returns the variant, if the value is of this variant.
+    fn from_variant(value: &T) -> Option<&Self>;
+}
+
 impl<A, B> Identifier for Sum<A, B>
 where
     A: Identifier,
@@ -360,3 +799,54 @@ where
         }
     }
 }
+
+impl<A, B, C> Identifier for Sum3<A, B, C>
+where
+    A: Identifier,
+    B: Identifier,
+    C: Identifier,
+{
+    fn identifier(&self) -> String {
+        match self {
+            Sum3::First(a) => a.identifier(),
+            Sum3::Second(b) => b.identifier(),
+            Sum3::Third(c) => c.identifier(),
+        }
+    }
+}
+
+impl<A, B, C, D> Identifier for Sum4<A, B, C, D>
+where
+    A: Identifier,
+    B: Identifier,
+    C: Identifier,
+    D: Identifier,
+{
+    fn identifier(&self) -> String {
+        match self {
+            Sum4::First(a) => a.identifier(),
+            Sum4::Second(b) => b.identifier(),
+            Sum4::Third(c) => c.identifier(),
+            Sum4::Fourth(d) => d.identifier(),
+        }
+    }
+}
+
+impl<A, B, C, D, E> Identifier for Sum5<A, B, C, D, E>
+where
+    A: Identifier,
+    B: Identifier,
+    C: Identifier,
+    D: Identifier,
+    E: Identifier,
+{
+    fn identifier(&self) -> String {
+        match self {
+            Sum5::First(a) => a.identifier(),
+            Sum5::Second(b) => b.identifier(),
+            Sum5::Third(c) => c.identifier(),
+            Sum5::Fourth(d) => d.identifier(),
+            Sum5::Fifth(e) => e.identifier(),
+        }
+    }
+}
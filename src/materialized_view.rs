@@ -1,7 +1,10 @@
 use std::future::Future;
 use std::marker::PhantomData;
 
-use crate::view::ViewStateComputation;
+use crate::concurrency::{join_all, partition_by_key};
+use crate::dead_letter::{DeadLetter, DeadLetterRepository};
+use crate::view::{FallibleViewStateComputation, ViewStateComputation};
+use crate::{Identifier, MaybeSend, MaybeSync};
 
 /// View State Repository trait
 ///
@@ -12,13 +15,92 @@ use crate::view::ViewStateComputation;
 /// - `Error` - Error
 pub trait ViewStateRepository<E, S, Error> {
     /// Fetches current state, based on the event.
-    /// Desugared `async fn fetch_state(&self, event: &E) -> Result<Option<S>, Error>;` to a normal `fn` that returns `impl Future`, and adds bound `Send`.
+    /// Desugared `async fn fetch_state(&self, event: &E) -> Result<Option<S>, Error>;` to a normal `fn` that returns `impl Future`, and adds bound `MaybeSend` (`Send` with the default `send` feature).
     /// You can freely move between the `async fn` and `-> impl Future` spelling in your traits and impls. This is true even when one form has a Send bound.
-    fn fetch_state(&self, event: &E) -> impl Future<Output = Result<Option<S>, Error>> + Send;
+    fn fetch_state(&self, event: &E) -> impl Future<Output = Result<Option<S>, Error>> + MaybeSend;
     /// Saves the new state.
-    /// Desugared `async fn save(&self, state: &S) -> Result<S, Error>;` to a normal `fn` that returns `impl Future`, and adds bound `Send`.
+    /// Desugared `async fn save(&self, state: &S) -> Result<S, Error>;` to a normal `fn` that returns `impl Future`, and adds bound `MaybeSend` (`Send` with the default `send` feature).
     /// You can freely move between the `async fn` and `-> impl Future` spelling in your traits and impls. This is true even when one form has a Send bound.
-    fn save(&self, state: &S) -> impl Future<Output = Result<S, Error>> + Send;
+    fn save(&self, state: &S) -> impl Future<Output = Result<S, Error>> + MaybeSend;
+}
+
+/// View State Repository trait with metadata
+///
+/// Extends the [ViewStateRepository] with the ability to save the state together with the metadata (correlation id, causation id, tenant id, user info, ...), without polluting the domain types.
+///
+/// Generic parameters:
+///
+/// - `E` - Event
+/// - `S` - State
+/// - `M` - Metadata
+/// - `Error` - Error
+pub trait ViewStateRepositoryWithMetadata<E, S, M, Error>:
+    ViewStateRepository<E, S, Error>
+{
+    /// Saves the new state together with the metadata.
+    /// Desugared `async fn save_with_metadata(&self, state: &S, metadata: &M) -> Result<S, Error>;` to a normal `fn` that returns `impl Future`, and adds bound `MaybeSend` (`Send` with the default `send` feature).
+    /// You can freely move between the `async fn` and `-> impl Future` spelling in your traits and impls. This is true even when one form has a Send bound.
+    fn save_with_metadata(
+        &self,
+        state: &S,
+        metadata: &M,
+    ) -> impl Future<Output = Result<S, Error>> + MaybeSend;
+}
+
+/// View State Repository trait with context
+///
+/// Extends the [ViewStateRepository] with the ability to fetch and save the state within the context (tenant id, partition, ...), so the multi-tenant systems can partition the read models without wrapping every repository.
+///
+/// Generic parameters:
+///
+/// - `E` - Event
+/// - `S` - State
+/// - `Ctx` - Context (tenant id, ...)
+/// - `Error` - Error
+pub trait ViewStateRepositoryWithContext<E, S, Ctx, Error>:
+    ViewStateRepository<E, S, Error>
+{
+    /// Fetches current state within the context, based on the event.
+    /// Desugared `async fn fetch_state_with_context(&self, event: &E, context: &Ctx) -> Result<Option<S>, Error>;` to a normal `fn` that returns `impl Future`, and adds bound `MaybeSend` (`Send` with the default `send` feature).
+    /// You can freely move between the `async fn` and `-> impl Future` spelling in your traits and impls. This is true even when one form has a Send bound.
+    fn fetch_state_with_context(
+        &self,
+        event: &E,
+        context: &Ctx,
+    ) -> impl Future<Output = Result<Option<S>, Error>> + MaybeSend;
+    /// Saves the new state within the context.
+    /// Desugared `async fn save_with_context(&self, state: &S, context: &Ctx) -> Result<S, Error>;` to a normal `fn` that returns `impl Future`, and adds bound `MaybeSend` (`Send` with the default `send` feature).
+    /// You can freely move between the `async fn` and `-> impl Future` spelling in your traits and impls. This is true even when one form has a Send bound.
+    fn save_with_context(
+        &self,
+        state: &S,
+        context: &Ctx,
+    ) -> impl Future<Output = Result<S, Error>> + MaybeSend;
+}
+
+/// View State Repository trait with deletion
+///
+/// Extends the [ViewStateRepository] with the ability to delete the state (purge semantics, GDPR erasure, ...), instead of saving the tombstone state.
+///
+/// Generic parameters:
+///
+/// - `E` - Event
+/// - `S` - State
+/// - `Error` - Error
+pub trait ViewStateRepositoryWithDeletion<E, S, Error>: ViewStateRepository<E, S, Error> {
+    /// Deletes the state.
+    /// Desugared `async fn delete_state(&self, state: &S) -> Result<(), Error>;` to a normal `fn` that returns `impl Future`, and adds bound `MaybeSend` (`Send` with the default `send` feature).
+    /// You can freely move between the `async fn` and `-> impl Future` spelling in your traits and impls. This is true even when one form has a Send bound.
+    fn delete_state(&self, state: &S) -> impl Future<Output = Result<(), Error>> + MaybeSend;
+}
+
+/// The outcome of handling the event by the [MaterializedView]: the state is either updated, or deleted.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ViewOutcome<S> {
+    /// The new state is saved
+    Updated(S),
+    /// The state is deleted
+    Deleted,
 }
 
 /// Materialized View.
@@ -58,11 +140,11 @@ where
 impl<S, E, Repository, View, Error> ViewStateRepository<E, S, Error>
     for MaterializedView<S, E, Repository, View, Error>
 where
-    Repository: ViewStateRepository<E, S, Error> + Sync,
-    View: ViewStateComputation<E, S> + Sync,
-    E: Sync,
-    S: Sync,
-    Error: Sync,
+    Repository: ViewStateRepository<E, S, Error> + MaybeSync,
+    View: ViewStateComputation<E, S> + MaybeSync,
+    E: MaybeSync,
+    S: MaybeSync,
+    Error: MaybeSync,
 {
     /// Fetches current state, based on the event.
     async fn fetch_state(&self, event: &E) -> Result<Option<S>, Error> {
@@ -77,11 +159,11 @@ where
 
 impl<S, E, Repository, View, Error> MaterializedView<S, E, Repository, View, Error>
 where
-    Repository: ViewStateRepository<E, S, Error> + Sync,
-    View: ViewStateComputation<E, S> + Sync,
-    E: Sync,
-    S: Sync,
-    Error: Sync,
+    Repository: ViewStateRepository<E, S, Error> + MaybeSync,
+    View: ViewStateComputation<E, S> + MaybeSync,
+    E: MaybeSync,
+    S: MaybeSync,
+    Error: MaybeSync,
 {
     /// Creates a new instance of [MaterializedView].
     pub fn new(repository: Repository, view: View) -> Self {
@@ -98,4 +180,281 @@ where
         let saved_state = self.save(&new_state).await?;
         Ok(saved_state)
     }
+    /// Handles the event with metadata by fetching the state from the repository, computing new state based on the current state and the event, and saving the new state together with the metadata to the repository.
+    pub async fn handle_with_metadata<M>(&self, event: &E, metadata: &M) -> Result<S, Error>
+    where
+        Repository: ViewStateRepositoryWithMetadata<E, S, M, Error>,
+        M: MaybeSync,
+    {
+        let state = self.fetch_state(event).await?;
+        let new_state = self.compute_new_state(state, &[event]);
+        let saved_state = self
+            .repository
+            .save_with_metadata(&new_state, metadata)
+            .await?;
+        Ok(saved_state)
+    }
+    /// Handles the event by fetching the state from the repository, computing new state based on the current state and the event, and either deleting the state (if `deleted` holds for the event and the new state), or saving the new state to the repository.
+    pub async fn handle_with_deletion<F>(
+        &self,
+        event: &E,
+        deleted: &F,
+    ) -> Result<ViewOutcome<S>, Error>
+    where
+        Repository: ViewStateRepositoryWithDeletion<E, S, Error>,
+        F: Fn(&E, &S) -> bool + MaybeSync,
+    {
+        let state = self.fetch_state(event).await?;
+        let new_state = self.compute_new_state(state, &[event]);
+        if deleted(event, &new_state) {
+            self.repository.delete_state(&new_state).await?;
+            Ok(ViewOutcome::Deleted)
+        } else {
+            let saved_state = self.save(&new_state).await?;
+            Ok(ViewOutcome::Updated(saved_state))
+        }
+    }
+    /// Handles the event within the context (tenant id, ...) by fetching the state from the repository, computing new state based on the current state and the event, and saving the new state to the repository, within the same context.
+    pub async fn handle_with_context<Ctx>(&self, event: &E, context: &Ctx) -> Result<S, Error>
+    where
+        Repository: ViewStateRepositoryWithContext<E, S, Ctx, Error>,
+        Ctx: MaybeSync,
+    {
+        let state = self
+            .repository
+            .fetch_state_with_context(event, context)
+            .await?;
+        let new_state = self.compute_new_state(state, &[event]);
+        let saved_state = self
+            .repository
+            .save_with_context(&new_state, context)
+            .await?;
+        Ok(saved_state)
+    }
+}
+
+/// Dead Lettering Materialized View.
+///
+/// It is using a `FallibleView` / [FallibleViewStateComputation] to compute new state based on the current state and the event.
+/// It is using a [ViewStateRepository] to fetch the current state and to save the new state.
+/// The event that the view fails to apply is parked via [DeadLetterRepository], the state is left as it is, and the processing can continue with the next event.
+///
+/// Generic parameters:
+///
+/// - `S` - State
+/// - `E` - Event
+/// - `Repository` - View State repository
+/// - `View` - Fallible View
+/// - `DeadLetters` - Dead letter repository
+/// - `Error` - Error
+pub struct DeadLetteringMaterializedView<S, E, Repository, View, DeadLetters, Error>
+where
+    Repository: ViewStateRepository<E, S, Error>,
+    View: FallibleViewStateComputation<E, S, Error>,
+    DeadLetters: DeadLetterRepository<E, Error>,
+{
+    repository: Repository,
+    view: View,
+    dead_letter_repository: DeadLetters,
+    _marker: PhantomData<(S, E, Error)>,
+}
+
+impl<S, E, Repository, View, DeadLetters, Error>
+    DeadLetteringMaterializedView<S, E, Repository, View, DeadLetters, Error>
+where
+    Repository: ViewStateRepository<E, S, Error> + MaybeSync,
+    View: FallibleViewStateComputation<E, S, Error> + MaybeSync,
+    DeadLetters: DeadLetterRepository<E, Error> + MaybeSync,
+    E: Clone + MaybeSync,
+    S: MaybeSync,
+    Error: MaybeSync,
+{
+    /// Creates a new instance of [DeadLetteringMaterializedView].
+    pub fn new(repository: Repository, view: View, dead_letter_repository: DeadLetters) -> Self {
+        DeadLetteringMaterializedView {
+            repository,
+            view,
+            dead_letter_repository,
+            _marker: PhantomData,
+        }
+    }
+    /// Handles the event by fetching the state from the repository, computing new state based on the current state and the event, and saving the new state to the repository.
+    /// Returns `None` if the event could not be applied, and it is parked as the dead letter.
+    pub async fn handle(&self, event: &E) -> Result<Option<S>, Error> {
+        let state = self.repository.fetch_state(event).await?;
+        match self.view.try_compute_new_state(state, &[event]) {
+            Ok(new_state) => Ok(Some(self.repository.save(&new_state).await?)),
+            Err(error) => {
+                self.dead_letter_repository
+                    .park(DeadLetter {
+                        message: event.clone(),
+                        error,
+                    })
+                    .await?;
+                Ok(None)
+            }
+        }
+    }
+}
+
+/// Checkpoint Repository trait
+///
+/// It is used to store the position (offset/sequence number) of the last event that is projected into the view, so the projection can be resumed/rebuilt from there.
+///
+/// Generic parameters:
+///
+/// - `Checkpoint` - Checkpoint/Offset/Sequence number
+/// - `Error` - Error
+pub trait CheckpointRepository<Checkpoint, Error> {
+    /// Fetches the last saved checkpoint.
+    /// Desugared `async fn fetch_checkpoint(&self) -> Result<Option<Checkpoint>, Error>;` to a normal `fn` that returns `impl Future`, and adds bound `MaybeSend` (`Send` with the default `send` feature).
+    /// You can freely move between the `async fn` and `-> impl Future` spelling in your traits and impls. This is true even when one form has a Send bound.
+    fn fetch_checkpoint(
+        &self,
+    ) -> impl Future<Output = Result<Option<Checkpoint>, Error>> + MaybeSend;
+    /// Saves the checkpoint.
+    /// Desugared `async fn save_checkpoint(&self, checkpoint: &Checkpoint) -> Result<(), Error>;` to a normal `fn` that returns `impl Future`, and adds bound `MaybeSend` (`Send` with the default `send` feature).
+    /// You can freely move between the `async fn` and `-> impl Future` spelling in your traits and impls. This is true even when one form has a Send bound.
+    fn save_checkpoint(
+        &self,
+        checkpoint: &Checkpoint,
+    ) -> impl Future<Output = Result<(), Error>> + MaybeSend;
+}
+
+/// Projection Rebuilder.
+///
+/// It replays the (historical) events through the [MaterializedView], in order to (re)build the read model, for example, after the schema of the view has changed.
+/// The events that are already projected (according to the [CheckpointRepository]) are skipped, and the checkpoint is saved after every `batch_size` events, and at the end of the replay.
+///
+/// Generic parameters:
+///
+/// - `S` - State
+/// - `E` - Event
+/// - `Repository` - View State repository
+/// - `View` - View
+/// - `Checkpoints` - Checkpoint repository
+/// - `Checkpoint` - Checkpoint/Offset/Sequence number
+/// - `Error` - Error
+pub struct ProjectionRebuilder<S, E, Repository, View, Checkpoints, Checkpoint, Error>
+where
+    Repository: ViewStateRepository<E, S, Error>,
+    View: ViewStateComputation<E, S>,
+    Checkpoints: CheckpointRepository<Checkpoint, Error>,
+{
+    materialized_view: MaterializedView<S, E, Repository, View, Error>,
+    checkpoint_repository: Checkpoints,
+    batch_size: usize,
+    _marker: PhantomData<Checkpoint>,
+}
+
+impl<S, E, Repository, View, Checkpoints, Checkpoint, Error>
+    ProjectionRebuilder<S, E, Repository, View, Checkpoints, Checkpoint, Error>
+where
+    Repository: ViewStateRepository<E, S, Error> + MaybeSync,
+    View: ViewStateComputation<E, S> + MaybeSync,
+    Checkpoints: CheckpointRepository<Checkpoint, Error> + MaybeSync,
+    Checkpoint: PartialOrd + MaybeSync,
+    E: MaybeSync,
+    S: MaybeSync,
+    Error: MaybeSync,
+{
+    /// Creates a new instance of [ProjectionRebuilder].
+    /// The `batch_size` is the number of events that are projected between two checkpoints (`0` is treated as `1`).
+    pub fn new(
+        materialized_view: MaterializedView<S, E, Repository, View, Error>,
+        checkpoint_repository: Checkpoints,
+        batch_size: usize,
+    ) -> Self {
+        ProjectionRebuilder {
+            materialized_view,
+            checkpoint_repository,
+            batch_size: batch_size.max(1),
+            _marker: PhantomData,
+        }
+    }
+    /// Replays the events (ordered by the checkpoint) through the materialized view, skipping the events that are already projected.
+    /// Returns the last saved checkpoint.
+    pub async fn rebuild<I>(&self, events: I) -> Result<Option<Checkpoint>, Error>
+    where
+        I: IntoIterator<Item = (E, Checkpoint)>,
+    {
+        let mut checkpoint = self.checkpoint_repository.fetch_checkpoint().await?;
+        let mut pending: Option<Checkpoint> = None;
+        let mut projected = 0;
+        for (event, position) in events {
+            if checkpoint
+                .as_ref()
+                .is_some_and(|checkpoint| position <= *checkpoint)
+            {
+                continue;
+            }
+            self.materialized_view.handle(&event).await?;
+            pending = Some(position);
+            projected += 1;
+            if projected % self.batch_size == 0 {
+                if let Some(position) = pending.take() {
+                    self.checkpoint_repository
+                        .save_checkpoint(&position)
+                        .await?;
+                    checkpoint = Some(position);
+                }
+            }
+        }
+        if let Some(position) = pending {
+            self.checkpoint_repository
+                .save_checkpoint(&position)
+                .await?;
+            checkpoint = Some(position);
+        }
+        Ok(checkpoint)
+    }
+    /// Replays the events (ordered by the checkpoint) through the materialized view, skipping the events that are already projected.
+    /// The events of the batch are partitioned by the identifier into (at most) `workers` partitions, and the partitions are projected concurrently, preserving the order of the events per identifier.
+    /// The checkpoint is saved once the whole batch is projected. Returns the last saved checkpoint.
+    pub async fn rebuild_partitioned<I>(
+        &self,
+        events: I,
+        workers: usize,
+    ) -> Result<Option<Checkpoint>, Error>
+    where
+        I: IntoIterator<Item = (E, Checkpoint)>,
+        E: Identifier,
+    {
+        let checkpoint = self.checkpoint_repository.fetch_checkpoint().await?;
+        let mut saved: Option<Checkpoint> = None;
+        let mut events = events.into_iter().filter(|(_, position)| {
+            checkpoint
+                .as_ref()
+                .is_none_or(|checkpoint| position > checkpoint)
+        });
+        loop {
+            let (batch, positions): (Vec<E>, Vec<Checkpoint>) =
+                events.by_ref().take(self.batch_size).unzip();
+            let Some(position) = positions.into_iter().last() else {
+                break;
+            };
+            let partitions = partition_by_key(batch.iter().collect(), workers, |event: &&E| {
+                event.identifier()
+            });
+            join_all(
+                partitions
+                    .into_iter()
+                    .map(|partition| async move {
+                        for event in partition {
+                            self.materialized_view.handle(event).await?;
+                        }
+                        Ok(())
+                    })
+                    .collect(),
+            )
+            .await
+            .into_iter()
+            .collect::<Result<Vec<()>, Error>>()?;
+            self.checkpoint_repository
+                .save_checkpoint(&position)
+                .await?;
+            saved = Some(position);
+        }
+        Ok(saved.or(checkpoint))
+    }
 }
@@ -0,0 +1,192 @@
+use std::sync::Arc;
+use std::time::Instant;
+
+use crate::aggregate::{
+    EventRepository, EventSourcedAggregate, StateRepository, StateStoredAggregate,
+};
+use crate::decider::{EventComputation, StateComputation};
+use crate::saga::ActionComputation;
+use crate::MaybeSync;
+
+/// The number of the handled commands - counter
+pub const COMMANDS_HANDLED: &str = "fmodel_commands_handled_total";
+/// The number of the commands that failed to be handled - counter
+pub const COMMANDS_FAILED: &str = "fmodel_commands_failed_total";
+/// The number of the produced (and saved) events - counter
+pub const EVENTS_PRODUCED: &str = "fmodel_events_produced_total";
+/// The number of the actions emitted by the saga - counter
+pub const SAGA_ACTIONS_EMITTED: &str = "fmodel_saga_actions_emitted_total";
+/// The duration of the command handling, in seconds - histogram
+pub const HANDLE_DURATION: &str = "fmodel_handle_duration_seconds";
+/// The number of the events replayed per load of the event stream - histogram
+pub const EVENTS_REPLAYED: &str = "fmodel_events_replayed";
+/// The number of the times the circuit breaker opened - counter
+pub const CIRCUIT_BREAKER_OPENED: &str = "fmodel_circuit_breaker_opened_total";
+/// The number of the times the circuit breaker half-opened - counter
+pub const CIRCUIT_BREAKER_HALF_OPENED: &str = "fmodel_circuit_breaker_half_opened_total";
+/// The number of the times the circuit breaker closed - counter
+pub const CIRCUIT_BREAKER_CLOSED: &str = "fmodel_circuit_breaker_closed_total";
+
+/// Metrics Recorder trait
+///
+/// It is a pluggable sink for the metrics of the core operations. Implement it to forward the metrics to your metrics backend (Prometheus, OpenTelemetry, StatsD, ...).
+pub trait MetricsRecorder {
+    /// Increments the counter with the given name by the value.
+    fn increment_counter(&self, name: &'static str, value: u64);
+    /// Records the value into the histogram with the given name.
+    fn record_histogram(&self, name: &'static str, value: f64);
+}
+
+/// Shares the `recorder` among many metered components.
+impl<R> MetricsRecorder for Arc<R>
+where
+    R: MetricsRecorder,
+{
+    fn increment_counter(&self, name: &'static str, value: u64) {
+        (**self).increment_counter(name, value)
+    }
+    fn record_histogram(&self, name: &'static str, value: f64) {
+        (**self).record_histogram(name, value)
+    }
+}
+
+/// Borrows the `recorder`, that is owned elsewhere.
+impl<R> MetricsRecorder for &R
+where
+    R: MetricsRecorder,
+{
+    fn increment_counter(&self, name: &'static str, value: u64) {
+        (**self).increment_counter(name, value)
+    }
+    fn record_histogram(&self, name: &'static str, value: f64) {
+        (**self).record_histogram(name, value)
+    }
+}
+
+/// Metered component.
+///
+/// It decorates the component and records the metrics of its operations into the [MetricsRecorder]:
+///
+/// - the aggregate ([EventSourcedAggregate] or [StateStoredAggregate]) - [COMMANDS_HANDLED], [COMMANDS_FAILED], [EVENTS_PRODUCED] and [HANDLE_DURATION]
+/// - the saga ([ActionComputation]) - [SAGA_ACTIONS_EMITTED]
+/// - the event repository ([EventRepository]) - [EVENTS_REPLAYED]
+///
+/// Generic parameters:
+///
+/// - `T` - Component (aggregate, saga, event repository)
+/// - `Recorder` - Metrics recorder
+pub struct Metered<T, Recorder> {
+    inner: T,
+    recorder: Recorder,
+}
+
+impl<T, Recorder> Metered<T, Recorder> {
+    /// Creates a new instance of [Metered].
+    pub fn new(inner: T, recorder: Recorder) -> Self {
+        Metered { inner, recorder }
+    }
+}
+
+impl<T, Recorder> Metered<T, Recorder>
+where
+    Recorder: MetricsRecorder,
+{
+    /// Records the metrics of the handled command.
+    fn record_handled<R, Error>(&self, result: &Result<R, Error>, events: u64, started: Instant) {
+        self.recorder.increment_counter(COMMANDS_HANDLED, 1);
+        if result.is_err() {
+            self.recorder.increment_counter(COMMANDS_FAILED, 1);
+        }
+        self.recorder.increment_counter(EVENTS_PRODUCED, events);
+        self.recorder
+            .record_histogram(HANDLE_DURATION, started.elapsed().as_secs_f64());
+    }
+}
+
+impl<C, S, E, Repository, Decider, Version, Error, Recorder>
+    Metered<EventSourcedAggregate<C, S, E, Repository, Decider, Version, Error>, Recorder>
+where
+    Repository: EventRepository<C, E, Version, Error> + MaybeSync,
+    Decider: EventComputation<C, S, E, Error> + MaybeSync,
+    Recorder: MetricsRecorder + MaybeSync,
+    C: MaybeSync,
+    S: MaybeSync,
+    E: MaybeSync,
+    Version: MaybeSync,
+    Error: MaybeSync,
+{
+    /// Handles the command by the aggregate, and records the metrics.
+    pub async fn handle(&self, command: &C) -> Result<Vec<(E, Version)>, Error> {
+        let started = Instant::now();
+        let result = self.inner.handle(command).await;
+        let events = result.as_ref().map_or(0, |events| events.len() as u64);
+        self.record_handled(&result, events, started);
+        result
+    }
+}
+
+impl<C, S, E, Repository, Decider, Version, Error, Recorder>
+    Metered<StateStoredAggregate<C, S, E, Repository, Decider, Version, Error>, Recorder>
+where
+    Repository: StateRepository<C, S, Version, Error> + MaybeSync,
+    Decider: StateComputation<C, S, E, Error> + MaybeSync,
+    Recorder: MetricsRecorder + MaybeSync,
+    C: MaybeSync,
+    S: MaybeSync,
+    E: MaybeSync,
+    Version: MaybeSync,
+    Error: MaybeSync,
+{
+    /// Handles the command by the aggregate, and records the metrics.
+    pub async fn handle(&self, command: &C) -> Result<(S, Version), Error> {
+        let started = Instant::now();
+        let result = self.inner.handle(command).await;
+        self.record_handled(&result, 0, started);
+        result
+    }
+}
+
+impl<AR, A, Error, T, Recorder> ActionComputation<AR, A, Error> for Metered<T, Recorder>
+where
+    T: ActionComputation<AR, A, Error>,
+    Recorder: MetricsRecorder,
+{
+    /// Computes new commands/actions based on the event/action_result, and records the number of the emitted actions.
+    fn compute_new_actions(&self, event: &AR) -> Result<Vec<A>, Error> {
+        let result = self.inner.compute_new_actions(event);
+        if let Ok(actions) = &result {
+            self.recorder
+                .increment_counter(SAGA_ACTIONS_EMITTED, actions.len() as u64);
+        }
+        result
+    }
+}
+
+impl<C, E, Version, Error, T, Recorder> EventRepository<C, E, Version, Error>
+    for Metered<T, Recorder>
+where
+    T: EventRepository<C, E, Version, Error> + MaybeSync,
+    Recorder: MetricsRecorder + MaybeSync,
+    C: MaybeSync,
+    E: MaybeSync,
+    Version: MaybeSync,
+    Error: MaybeSync,
+{
+    /// Fetches current events, based on the command, and records the number of the replayed events.
+    async fn fetch_events(&self, command: &C) -> Result<Vec<(E, Version)>, Error> {
+        let result = self.inner.fetch_events(command).await;
+        if let Ok(events) = &result {
+            self.recorder
+                .record_histogram(EVENTS_REPLAYED, events.len() as f64);
+        }
+        result
+    }
+    /// Saves events.
+    async fn save(&self, events: &[E]) -> Result<Vec<(E, Version)>, Error> {
+        self.inner.save(events).await
+    }
+    /// Version provider.
+    async fn version_provider(&self, event: &E) -> Result<Option<Version>, Error> {
+        self.inner.version_provider(event).await
+    }
+}
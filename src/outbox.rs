@@ -0,0 +1,230 @@
+use std::future::Future;
+use std::marker::PhantomData;
+use std::time::Duration;
+
+use crate::aggregate::{EventRepository, EventSourcedAggregate};
+use crate::decider::EventComputation;
+use crate::{MaybeSend, MaybeSync};
+
+/// Publishes the saved events to some external system (message bus).
+///
+/// Generic parameters:
+///
+/// - `E` - Event
+/// - `Version` - Version/Offset/Sequence number
+/// - `Error` - Error
+pub trait EventPublisher<E, Version, Error> {
+    /// Publishes the saved events to some external system, returning either the events that are successfully published or error.
+    /// Desugared `async fn publish(&self, events: &[(E, Version)]) -> Result<Vec<(E, Version)>, Error>;` to a normal `fn` that returns `impl Future`, and adds bound `MaybeSend` (`Send` with the default `send` feature).
+    /// You can freely move between the `async fn` and `-> impl Future` spelling in your traits and impls. This is true even when one form has a Send bound.
+    fn publish(
+        &self,
+        events: &[(E, Version)],
+    ) -> impl Future<Output = Result<Vec<(E, Version)>, Error>> + MaybeSend;
+}
+
+/// Event Publishing Aggregate.
+///
+/// It decorates the [EventSourcedAggregate], and publishes the saved events via [EventPublisher], right after the command is handled.
+/// The events are saved even if the publishing fails (the error is returned), so the publishing is not atomic. Use the repository with the outbox (`aggregate::EventRepositoryWithOutbox`) when the events must be published reliably.
+///
+/// Generic parameters:
+///
+/// - `Aggregate` - Aggregate
+/// - `Publisher` - Event publisher
+pub struct EventPublishingAggregate<Aggregate, Publisher> {
+    aggregate: Aggregate,
+    publisher: Publisher,
+}
+
+impl<Aggregate, Publisher> EventPublishingAggregate<Aggregate, Publisher> {
+    /// Creates a new instance of [EventPublishingAggregate].
+    pub fn new(aggregate: Aggregate, publisher: Publisher) -> Self {
+        EventPublishingAggregate {
+            aggregate,
+            publisher,
+        }
+    }
+}
+
+impl<C, S, E, Repository, Decider, Version, Error, Publisher>
+    EventPublishingAggregate<
+        EventSourcedAggregate<C, S, E, Repository, Decider, Version, Error>,
+        Publisher,
+    >
+where
+    Repository: EventRepository<C, E, Version, Error> + MaybeSync,
+    Decider: EventComputation<C, S, E, Error> + MaybeSync,
+    Publisher: EventPublisher<E, Version, Error> + MaybeSync,
+    C: MaybeSync,
+    S: MaybeSync,
+    E: MaybeSync,
+    Version: MaybeSync,
+    Error: MaybeSync,
+{
+    /// Handles the command by the aggregate, and publishes the saved events.
+    pub async fn handle(&self, command: &C) -> Result<Vec<(E, Version)>, Error> {
+        let saved_events = self.aggregate.handle(command).await?;
+        self.publisher.publish(&saved_events).await?;
+        Ok(saved_events)
+    }
+}
+
+/// Outbox Repository trait
+///
+/// It is used to fetch the events that are recorded in the outbox (see `aggregate::EventRepositoryWithOutbox`), but not published yet, and to mark them as dispatched once they are published.
+///
+/// Generic parameters:
+///
+/// - `E` - Event
+/// - `Version` - Version/Offset/Sequence number
+/// - `Error` - Error
+pub trait OutboxRepository<E, Version, Error> {
+    /// Fetches (at most `limit`) events from the outbox that are not dispatched yet, in the order they were recorded.
+    /// Desugared `async fn fetch_pending(&self, limit: usize) -> Result<Vec<(E, Version)>, Error>;` to a normal `fn` that returns `impl Future`, and adds bound `MaybeSend` (`Send` with the default `send` feature)
+    /// You can freely move between the `async fn` and `-> impl Future` spelling in your traits and impls. This is true even when one form has a Send bound.
+    fn fetch_pending(
+        &self,
+        limit: usize,
+    ) -> impl Future<Output = Result<Vec<(E, Version)>, Error>> + MaybeSend;
+    /// Marks the events as dispatched, so they are not fetched again.
+    /// Desugared `async fn mark_dispatched(&self, events: &[(E, Version)]) -> Result<(), Error>;` to a normal `fn` that returns `impl Future`, and adds bound `MaybeSend` (`Send` with the default `send` feature)
+    /// You can freely move between the `async fn` and `-> impl Future` spelling in your traits and impls. This is true even when one form has a Send bound.
+    fn mark_dispatched(
+        &self,
+        events: &[(E, Version)],
+    ) -> impl Future<Output = Result<(), Error>> + MaybeSend;
+}
+
+/// Exponential backoff policy of the outbox processing.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Backoff {
+    /// The maximum number of attempts to process the batch, including the first one
+    pub max_attempts: usize,
+    /// The delay before the second attempt. It is doubled for every next attempt
+    pub initial_delay: Duration,
+    /// The upper bound of the delay
+    pub max_delay: Duration,
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Backoff {
+            max_attempts: 5,
+            initial_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(10),
+        }
+    }
+}
+
+impl Backoff {
+    /// Creates a new instance of [Backoff].
+    pub fn new(max_attempts: usize, initial_delay: Duration, max_delay: Duration) -> Self {
+        Backoff {
+            max_attempts,
+            initial_delay,
+            max_delay,
+        }
+    }
+
+    /// The delay after the failed `attempt` (starting from `1`).
+    pub fn delay(&self, attempt: usize) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(31) as u32;
+        self.initial_delay
+            .saturating_mul(2u32.pow(exponent))
+            .min(self.max_delay)
+    }
+}
+
+/// Outbox Processor.
+///
+/// It polls the [OutboxRepository] for the pending events, publishes them via [EventPublisher], and marks them as dispatched.
+/// The failed batch is retried according to the [Backoff]. The events are published at least once: the batch that is published, but not marked as dispatched, is published again.
+///
+/// The backoff and the polling sleep with the injected `sleep` function (see [Async runtime](crate#async-runtime)).
+///
+/// Generic parameters:
+///
+/// - `E` - Event
+/// - `Version` - Version/Offset/Sequence number
+/// - `Error` - Error
+/// - `Outbox` - Outbox repository
+/// - `Publisher` - Event publisher
+pub struct OutboxProcessor<E, Version, Error, Outbox, Publisher>
+where
+    Outbox: OutboxRepository<E, Version, Error>,
+    Publisher: EventPublisher<E, Version, Error>,
+{
+    outbox: Outbox,
+    publisher: Publisher,
+    batch_size: usize,
+    backoff: Backoff,
+    _marker: PhantomData<(E, Version, Error)>,
+}
+
+impl<E, Version, Error, Outbox, Publisher> OutboxProcessor<E, Version, Error, Outbox, Publisher>
+where
+    Outbox: OutboxRepository<E, Version, Error> + MaybeSync,
+    Publisher: EventPublisher<E, Version, Error> + MaybeSync,
+    E: MaybeSync,
+    Version: MaybeSync,
+    Error: MaybeSync,
+{
+    /// Creates a new instance of [OutboxProcessor].
+    pub fn new(outbox: Outbox, publisher: Publisher, batch_size: usize, backoff: Backoff) -> Self {
+        OutboxProcessor {
+            outbox,
+            publisher,
+            batch_size,
+            backoff,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Processes the single batch of the pending events: fetches, publishes, and marks them as dispatched.
+    /// Returns the number of the dispatched events.
+    pub async fn process_batch(&self) -> Result<usize, Error> {
+        let events = self.outbox.fetch_pending(self.batch_size).await?;
+        if events.is_empty() {
+            return Ok(0);
+        }
+        self.publisher.publish(&events).await?;
+        self.outbox.mark_dispatched(&events).await?;
+        Ok(events.len())
+    }
+
+    /// Processes the single batch of the pending events, retrying it according to the [Backoff].
+    /// Returns the number of the dispatched events, or the error of the last attempt.
+    pub async fn process_batch_with_retry<F, Fut>(&self, sleep: &F) -> Result<usize, Error>
+    where
+        F: Fn(Duration) -> Fut,
+        Fut: Future<Output = ()>,
+    {
+        let mut attempt = 1;
+        loop {
+            match self.process_batch().await {
+                Err(_) if attempt < self.backoff.max_attempts => {
+                    sleep(self.backoff.delay(attempt)).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Runs the processing loop: the batches are processed until the outbox is drained, and then the outbox is polled every `poll_interval`.
+    /// It is meant to be spawned as a background task, and it returns only if the batch could not be processed within the [Backoff] attempts.
+    pub async fn run<F, Fut>(&self, sleep: &F, poll_interval: Duration) -> Error
+    where
+        F: Fn(Duration) -> Fut,
+        Fut: Future<Output = ()>,
+    {
+        loop {
+            match self.process_batch_with_retry(sleep).await {
+                Ok(0) => sleep(poll_interval).await,
+                Ok(_) => {}
+                Err(error) => return error,
+            }
+        }
+    }
+}
@@ -0,0 +1,194 @@
+use alloc::boxed::Box;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+use crate::{EvolveFunction, InitialStateFunction, MaybeSend, MaybeSync, ProcessReactFunction};
+
+/// [ProcessManager] is a datatype that represents the orchestrating (stateful) variant of the [crate::saga::Saga].
+/// It decides what to execute next (`A`), based on the action result (`AR`) and the current state of the process (`S`).
+/// It has three generic parameters `AR`/Action Result, `S`/State, `A`/Action , representing the type of the values that ProcessManager may contain or use.
+/// `'a` is used as a lifetime parameter, indicating that all references contained within the struct (e.g., references within the function closures) must have a lifetime that is at least as long as 'a.
+///
+/// Long-running business processes can track their progress in the state, and make stateful routing decisions.
+///
+/// ## Example
+///
+/// ```
+/// use fmodel_rust::process_manager::{ProcessComputation, ProcessManager};
+///
+/// fn process_manager<'a>() -> ProcessManager<'a, OrderEvent, OrderProcessState, ShipmentCommand> {
+///     ProcessManager {
+///         react: Box::new(|state, event| match event {
+///             OrderEvent::Created(created_event) => Ok(vec![]),
+///             OrderEvent::Paid(paid_event) => {
+///                 if state.is_created {
+///                     Ok(vec![ShipmentCommand::Create(CreateShipmentCommand {
+///                         order_id: paid_event.order_id,
+///                     })])
+///                 } else {
+///                     Ok(vec![])
+///                 }
+///             }
+///         }),
+///         evolve: Box::new(|state, event| {
+///             let mut new_state = state.clone();
+///             match event {
+///                 OrderEvent::Created(_) => new_state.is_created = true,
+///                 OrderEvent::Paid(_) => new_state.is_paid = true,
+///             }
+///             new_state
+///         }),
+///         initial_state: Box::new(|| OrderProcessState {
+///             is_created: false,
+///             is_paid: false,
+///         }),
+///     }
+/// }
+///
+/// #[derive(Debug, PartialEq)]
+/// pub enum ShipmentCommand {
+///     Create(CreateShipmentCommand),
+/// }
+///
+/// #[derive(Debug, PartialEq)]
+/// pub struct CreateShipmentCommand {
+///     pub order_id: u32,
+/// }
+///
+/// #[derive(Debug)]
+/// pub enum OrderEvent {
+///     Created(OrderCreatedEvent),
+///     Paid(OrderPaidEvent),
+/// }
+///
+/// #[derive(Debug)]
+/// pub struct OrderCreatedEvent {
+///     pub order_id: u32,
+/// }
+///
+/// #[derive(Debug)]
+/// pub struct OrderPaidEvent {
+///     pub order_id: u32,
+/// }
+///
+/// #[derive(Debug, Clone, PartialEq)]
+/// pub struct OrderProcessState {
+///     pub is_created: bool,
+///     pub is_paid: bool,
+/// }
+///
+/// let process_manager = process_manager();
+/// let (state, commands) = process_manager
+///     .compute_new_state_and_actions(None, &OrderEvent::Created(OrderCreatedEvent { order_id: 1 }))
+///     .unwrap();
+/// assert_eq!(commands, vec![]);
+/// let (state, commands) = process_manager
+///     .compute_new_state_and_actions(Some(state), &OrderEvent::Paid(OrderPaidEvent { order_id: 1 }))
+///     .unwrap();
+/// assert_eq!(commands, vec![ShipmentCommand::Create(CreateShipmentCommand { order_id: 1 })]);
+/// assert_eq!(state, OrderProcessState { is_created: true, is_paid: true });
+/// ```
+pub struct ProcessManager<'a, AR: 'a, S: 'a, A: 'a, Error: 'a = ()> {
+    /// The `react` function is driving the next action based on the current state and the action result.
+    pub react: ProcessReactFunction<'a, AR, S, A, Error>,
+    /// The `evolve` function is used to evolve the state of the process based on the current state and the action result.
+    pub evolve: EvolveFunction<'a, S, AR>,
+    /// The `initial_state` function is used to produce the initial state of the process.
+    pub initial_state: InitialStateFunction<'a, S>,
+}
+
+impl<'a, AR, S, A, Error> ProcessManager<'a, AR, S, A, Error> {
+    /// Maps the ProcessManager over the A/Action type parameter.
+    /// Creates a new instance of [ProcessManager]`<AR, S, A2, Error>`.
+    pub fn map_action<A2, F>(self, f: &'a F) -> ProcessManager<'a, AR, S, A2, Error>
+    where
+        F: Fn(&A) -> A2 + MaybeSend + MaybeSync,
+    {
+        let new_react = Box::new(move |s: &S, ar: &AR| {
+            (self.react)(s, ar).map(|result| result.into_iter().map(|a: A| f(&a)).collect())
+        });
+
+        ProcessManager {
+            react: new_react,
+            evolve: self.evolve,
+            initial_state: self.initial_state,
+        }
+    }
+
+    /// Maps the ProcessManager over the AR/ActionResult type parameter.
+    /// Creates a new instance of [ProcessManager]`<AR2, S, A, Error>`.
+    pub fn map_action_result<AR2, F>(self, f: &'a F) -> ProcessManager<'a, AR2, S, A, Error>
+    where
+        F: Fn(&AR2) -> AR + MaybeSend + MaybeSync,
+    {
+        let new_react = Box::new(move |s: &S, ar2: &AR2| {
+            let ar = f(ar2);
+            (self.react)(s, &ar)
+        });
+
+        let new_evolve = Box::new(move |s: &S, ar2: &AR2| {
+            let ar = f(ar2);
+            (self.evolve)(s, &ar)
+        });
+
+        ProcessManager {
+            react: new_react,
+            evolve: new_evolve,
+            initial_state: self.initial_state,
+        }
+    }
+
+    /// Maps the ProcessManager over the Error type parameter.
+    /// Creates a new instance of [ProcessManager]`<AR, S, A, Error2>`.
+    pub fn map_error<Error2, F>(self, f: &'a F) -> ProcessManager<'a, AR, S, A, Error2>
+    where
+        F: Fn(&Error) -> Error2 + MaybeSend + MaybeSync,
+    {
+        let new_react = Box::new(move |s: &S, ar: &AR| (self.react)(s, ar).map_err(|e| f(&e)));
+
+        ProcessManager {
+            react: new_react,
+            evolve: self.evolve,
+            initial_state: self.initial_state,
+        }
+    }
+}
+
+/// Formalizes the `Process Computation` algorithm for the `process manager` to handle events/action_results based on the current state, and produce new state and new commands/actions.
+pub trait ProcessComputation<AR, S, A, Error = ()> {
+    /// Computes new state and new commands/actions based on the current state and the event/action_result.
+    fn compute_new_state_and_actions(
+        &self,
+        current_state: Option<S>,
+        action_result: &AR,
+    ) -> Result<(S, Vec<A>), Error>;
+}
+
+impl<AR, S, A, Error> ProcessComputation<AR, S, A, Error> for ProcessManager<'_, AR, S, A, Error> {
+    /// Computes new state and new commands/actions based on the current state and the event/action_result.
+    fn compute_new_state_and_actions(
+        &self,
+        current_state: Option<S>,
+        action_result: &AR,
+    ) -> Result<(S, Vec<A>), Error> {
+        let effective_current_state = current_state.unwrap_or_else(|| (self.initial_state)());
+        let actions = (self.react)(&effective_current_state, action_result)?;
+        let new_state = (self.evolve)(&effective_current_state, action_result);
+        Ok((new_state, actions))
+    }
+}
+
+/// Shares the `process manager` (for example, the `'static` one) among many consumers/tasks.
+impl<AR, S, A, Error, P> ProcessComputation<AR, S, A, Error> for Arc<P>
+where
+    P: ProcessComputation<AR, S, A, Error>,
+{
+    /// Computes new state and new commands/actions based on the current state and the event/action_result.
+    fn compute_new_state_and_actions(
+        &self,
+        current_state: Option<S>,
+        action_result: &AR,
+    ) -> Result<(S, Vec<A>), Error> {
+        (**self).compute_new_state_and_actions(current_state, action_result)
+    }
+}
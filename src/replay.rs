@@ -0,0 +1,218 @@
+use std::future::Future;
+use std::marker::PhantomData;
+
+use crate::aggregate::EventRepository;
+use crate::view::ViewStateComputation;
+use crate::MaybeSend;
+
+/// Event Repository trait with timestamps
+///
+/// Extends the [EventRepository] with the ability to fetch the events together with the time they were saved, so the state can be replayed to the point in time (see [EventReplayer::replay_until_timestamp]).
+///
+/// Generic parameters:
+///
+/// - `C` - Command
+/// - `E` - Event
+/// - `Version` - Version/Offset/Sequence number
+/// - `Error` - Error
+pub trait EventRepositoryWithTimestamps<C, E, Version, Error>:
+    EventRepository<C, E, Version, Error>
+{
+    /// Fetches current events, based on the command, together with their timestamps (in milliseconds since the UNIX epoch).
+    /// Desugared `async fn fetch_events_with_timestamps(&self, command: &C) -> Result<Vec<(E, Version, u128)>, Error>;` to a normal `fn` that returns `impl Future`, and adds bound `MaybeSend` (`Send` with the default `send` feature)
+    /// You can freely move between the `async fn` and `-> impl Future` spelling in your traits and impls. This is true even when one form has a Send bound.
+    fn fetch_events_with_timestamps(
+        &self,
+        command: &C,
+    ) -> impl Future<Output = Result<Vec<(E, Version, u128)>, Error>> + MaybeSend;
+}
+
+/// The state replayed to the point in time, together with the version of the latest event included in it (`None` if no event is included).
+#[derive(Debug, Clone, PartialEq)]
+pub struct PointInTimeState<S, Version> {
+    /// The replayed state
+    pub state: S,
+    /// The version of the latest replayed event
+    pub version: Option<Version>,
+}
+
+/// The difference of the state at the point in time and the current state.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StateDiff<S, Version> {
+    /// The state at the point in time
+    pub past: PointInTimeState<S, Version>,
+    /// The current state
+    pub current: PointInTimeState<S, Version>,
+    /// The number of the events that happened after the point in time
+    pub events_after: usize,
+}
+
+impl<S, Version> StateDiff<S, Version>
+where
+    S: PartialEq,
+{
+    /// Returns `true` if the current state differs from the state at the point in time.
+    pub fn is_changed(&self) -> bool {
+        self.past.state != self.current.state
+    }
+}
+
+/// Event Replayer.
+///
+/// It is the point-in-time debugging tool: it fetches the events of the stream from the [EventRepository], and replays the events up to the version (or the timestamp) bound through the `View` / [ViewStateComputation], reconstructing the state as of that point.
+/// The [StateDiff] compares the reconstructed state to the current state. Use [crate::decider::Decider::to_view] to replay the write-model state of the decider.
+///
+/// Generic parameters:
+///
+/// - `C` - Command
+/// - `S` - State
+/// - `E` - Event
+/// - `Repository` - Event repository
+/// - `View` - View
+/// - `Version` - Version/Offset/Sequence number
+/// - `Error` - Error
+pub struct EventReplayer<C, S, E, Repository, View, Version, Error>
+where
+    Repository: EventRepository<C, E, Version, Error>,
+    View: ViewStateComputation<E, S>,
+{
+    repository: Repository,
+    view: View,
+    _marker: PhantomData<(C, S, E, Version, Error)>,
+}
+
+impl<C, S, E, Repository, View, Version, Error>
+    EventReplayer<C, S, E, Repository, View, Version, Error>
+where
+    Repository: EventRepository<C, E, Version, Error>,
+    View: ViewStateComputation<E, S>,
+    Version: Clone,
+{
+    /// Creates a new instance of [EventReplayer].
+    pub fn new(repository: Repository, view: View) -> Self {
+        EventReplayer {
+            repository,
+            view,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Reconstructs the state as of the `version`, from the events up to (and including) that version.
+    pub async fn replay_until_version(
+        &self,
+        command: &C,
+        version: &Version,
+    ) -> Result<PointInTimeState<S, Version>, Error>
+    where
+        Version: PartialOrd,
+    {
+        let events = self.repository.fetch_events(command).await?;
+        let past = count_until(&events, |(_, event_version)| event_version <= version);
+        Ok(self.replay(&events[..past], None))
+    }
+
+    /// Reconstructs the state as of the `version`, and compares it to the current state.
+    pub async fn diff_until_version(
+        &self,
+        command: &C,
+        version: &Version,
+    ) -> Result<StateDiff<S, Version>, Error>
+    where
+        Version: PartialOrd,
+        S: Clone,
+    {
+        let events = self.repository.fetch_events(command).await?;
+        let past = count_until(&events, |(_, event_version)| event_version <= version);
+        Ok(self.diff(&events, past))
+    }
+
+    /// Reconstructs the state as of the `timestamp` (in milliseconds since the UNIX epoch), from the events saved up to (and including) that time.
+    pub async fn replay_until_timestamp(
+        &self,
+        command: &C,
+        timestamp: u128,
+    ) -> Result<PointInTimeState<S, Version>, Error>
+    where
+        Repository: EventRepositoryWithTimestamps<C, E, Version, Error>,
+    {
+        let (events, past) = self.fetch_until_timestamp(command, timestamp).await?;
+        Ok(self.replay(&events[..past], None))
+    }
+
+    /// Reconstructs the state as of the `timestamp` (in milliseconds since the UNIX epoch), and compares it to the current state.
+    pub async fn diff_until_timestamp(
+        &self,
+        command: &C,
+        timestamp: u128,
+    ) -> Result<StateDiff<S, Version>, Error>
+    where
+        Repository: EventRepositoryWithTimestamps<C, E, Version, Error>,
+        S: Clone,
+    {
+        let (events, past) = self.fetch_until_timestamp(command, timestamp).await?;
+        Ok(self.diff(&events, past))
+    }
+
+    /// Fetches the events of the stream, returning them without the timestamps, together with the number of the events saved up to (and including) the `timestamp`.
+    async fn fetch_until_timestamp(
+        &self,
+        command: &C,
+        timestamp: u128,
+    ) -> Result<(Vec<(E, Version)>, usize), Error>
+    where
+        Repository: EventRepositoryWithTimestamps<C, E, Version, Error>,
+    {
+        let events = self
+            .repository
+            .fetch_events_with_timestamps(command)
+            .await?;
+        let past = count_until(&events, |(_, _, saved)| *saved <= timestamp);
+        let events = events
+            .into_iter()
+            .map(|(event, version, _)| (event, version))
+            .collect();
+        Ok((events, past))
+    }
+
+    /// Replays the events onto the state (the initial state of the view, if there is none).
+    fn replay(
+        &self,
+        events: &[(E, Version)],
+        from: Option<PointInTimeState<S, Version>>,
+    ) -> PointInTimeState<S, Version> {
+        let (state, version) = match from {
+            Some(PointInTimeState { state, version }) => (Some(state), version),
+            None => (None, None),
+        };
+        let event_refs: Vec<&E> = events.iter().map(|(event, _)| event).collect();
+        PointInTimeState {
+            state: self.view.compute_new_state(state, &event_refs),
+            version: events
+                .last()
+                .map(|(_, version)| version.clone())
+                .or(version),
+        }
+    }
+
+    /// Replays the first `past` events into the past state, and the rest of the events onto it, into the current state.
+    fn diff(&self, events: &[(E, Version)], past: usize) -> StateDiff<S, Version>
+    where
+        S: Clone,
+    {
+        let past_state = self.replay(&events[..past], None);
+        let current_state = self.replay(&events[past..], Some(past_state.clone()));
+        StateDiff {
+            past: past_state,
+            current: current_state,
+            events_after: events.len() - past,
+        }
+    }
+}
+
+/// Counts the leading events (ordered by the version) that are within the bound.
+fn count_until<T, F>(events: &[T], within: F) -> usize
+where
+    F: Fn(&T) -> bool,
+{
+    events.iter().take_while(|event| within(event)).count()
+}
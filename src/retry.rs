@@ -0,0 +1,121 @@
+use std::future::Future;
+use std::time::Duration;
+
+use crate::aggregate::{EventRepository, RetryPolicy, StateRepository};
+use crate::saga_manager::ActionPublisher;
+use crate::{MaybeSend, MaybeSync};
+
+/// Recognizes the transient errors (timeouts, dropped connections, ...), that are worth retrying.
+pub trait TransientError {
+    /// Returns `true` if the error is transient, and the failed call could be retried
+    fn is_transient(&self) -> bool;
+}
+
+/// Retrying decorator.
+///
+/// It decorates the repository ([EventRepository], [StateRepository]) or the publisher ([ActionPublisher]), and retries the calls that failed with the [TransientError], according to the [RetryPolicy].
+/// Pass it to the aggregate or the saga manager in place of the decorated repository/publisher.
+///
+/// Retry the `save` only if the storage is not committing the write that is reported as failed (or if the write is idempotent), otherwise the events/state could be saved twice.
+///
+/// The delay between the attempts is slept with the injected `sleep` function (see [Async runtime](crate#async-runtime)).
+///
+/// Generic parameters:
+///
+/// - `T` - Decorated repository/publisher
+/// - `Sleep` - Sleep function `Fn(Duration) -> impl Future<Output = ()>`
+pub struct Retrying<T, Sleep> {
+    inner: T,
+    retry_policy: RetryPolicy,
+    sleep: Sleep,
+}
+
+impl<T, Sleep> Retrying<T, Sleep> {
+    /// Creates a new instance of [Retrying].
+    pub fn new(inner: T, retry_policy: RetryPolicy, sleep: Sleep) -> Self {
+        Retrying {
+            inner,
+            retry_policy,
+            sleep,
+        }
+    }
+
+    /// Runs the `operation`, retrying it while it fails with the transient error and the attempts are not exhausted.
+    async fn retry<R, Error, F, Fut, SleepFut>(&self, operation: F) -> Result<R, Error>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = Result<R, Error>>,
+        Sleep: Fn(Duration) -> SleepFut,
+        SleepFut: Future<Output = ()>,
+        Error: TransientError,
+    {
+        let mut attempt = 1;
+        loop {
+            let delay = match operation().await {
+                Err(error) if error.is_transient() && attempt < self.retry_policy.max_attempts => {
+                    self.retry_policy.delay(attempt)
+                }
+                result => return result,
+            };
+            (self.sleep)(delay).await;
+            attempt += 1;
+        }
+    }
+}
+
+impl<C, E, Version, Error, Repository, Sleep, SleepFut> EventRepository<C, E, Version, Error>
+    for Retrying<Repository, Sleep>
+where
+    Repository: EventRepository<C, E, Version, Error> + MaybeSync,
+    Sleep: Fn(Duration) -> SleepFut + MaybeSync,
+    SleepFut: Future<Output = ()> + MaybeSend,
+    C: MaybeSync,
+    E: MaybeSync,
+    Version: MaybeSync,
+    Error: TransientError,
+{
+    async fn fetch_events(&self, command: &C) -> Result<Vec<(E, Version)>, Error> {
+        self.retry(|| self.inner.fetch_events(command)).await
+    }
+
+    async fn save(&self, events: &[E]) -> Result<Vec<(E, Version)>, Error> {
+        self.retry(|| self.inner.save(events)).await
+    }
+
+    async fn version_provider(&self, event: &E) -> Result<Option<Version>, Error> {
+        self.retry(|| self.inner.version_provider(event)).await
+    }
+}
+
+impl<C, S, Version, Error, Repository, Sleep, SleepFut> StateRepository<C, S, Version, Error>
+    for Retrying<Repository, Sleep>
+where
+    Repository: StateRepository<C, S, Version, Error> + MaybeSync,
+    Sleep: Fn(Duration) -> SleepFut + MaybeSync,
+    SleepFut: Future<Output = ()> + MaybeSend,
+    C: MaybeSync,
+    S: MaybeSync,
+    Version: MaybeSync,
+    Error: TransientError,
+{
+    async fn fetch_state(&self, command: &C) -> Result<Option<(S, Version)>, Error> {
+        self.retry(|| self.inner.fetch_state(command)).await
+    }
+
+    async fn save(&self, state: &S, version: &Option<Version>) -> Result<(S, Version), Error> {
+        self.retry(|| self.inner.save(state, version)).await
+    }
+}
+
+impl<A, Error, Publisher, Sleep, SleepFut> ActionPublisher<A, Error> for Retrying<Publisher, Sleep>
+where
+    Publisher: ActionPublisher<A, Error> + MaybeSync,
+    Sleep: Fn(Duration) -> SleepFut + MaybeSync,
+    SleepFut: Future<Output = ()> + MaybeSend,
+    A: MaybeSync,
+    Error: TransientError,
+{
+    async fn publish(&self, action: &[A]) -> Result<Vec<A>, Error> {
+        self.retry(|| self.inner.publish(action)).await
+    }
+}
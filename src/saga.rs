@@ -1,4 +1,15 @@
-use crate::{ReactFunction, Sum};
+use alloc::boxed::Box;
+use alloc::sync::Arc;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::future::Future;
+use core::time::Duration;
+
+use crate::scheduler::ScheduledAction;
+use crate::{
+    AsyncReactFunction, MaybeSend, MaybeSync, OwnedReactFunction, ReactFunction, Sum, Sum3, Sum4,
+    Variant,
+};
 
 /// [Saga] is a datatype that represents the central point of control, deciding what to execute next (`A`), based on the action result (`AR`).
 /// It has two generic parameters `AR`/Action Result, `A`/Action , representing the type of the values that Saga may contain or use.
@@ -16,18 +27,18 @@ use crate::{ReactFunction, Sum};
 ///     Saga {
 ///         react: Box::new(|event| match event {
 ///             OrderEvent::Created(created_event) => {
-///                 vec![ShipmentCommand::Create(CreateShipmentCommand {
+///                 Ok(vec![ShipmentCommand::Create(CreateShipmentCommand {
 ///                     shipment_id: created_event.order_id,
 ///                     order_id: created_event.order_id,
 ///                     customer_name: created_event.customer_name.to_owned(),
 ///                     items: created_event.items.to_owned(),
-///                 })]
+///                 })])
 ///             }
 ///             OrderEvent::Updated(_updated_event) => {
-///                 vec![]
+///                 Ok(vec![])
 ///             }
 ///             OrderEvent::Cancelled(_cancelled_event) => {
-///                 vec![]
+///                 Ok(vec![])
 ///             }
 ///         }),
 ///     }
@@ -81,31 +92,30 @@ use crate::{ReactFunction, Sum};
 ///
 /// let commands = (saga.react)(&order_created_event);
 /// ```
-pub struct Saga<'a, AR: 'a, A: 'a> {
+pub struct Saga<'a, AR: 'a, A: 'a, Error: 'a = ()> {
     /// The `react` function is driving the next action based on the action result.
-    pub react: ReactFunction<'a, AR, A>,
+    pub react: ReactFunction<'a, AR, A, Error>,
 }
 
-impl<'a, AR, A> Saga<'a, AR, A> {
+impl<'a, AR, A, Error> Saga<'a, AR, A, Error> {
     /// Maps the Saga over the A/Action type parameter.
-    /// Creates a new instance of [Saga]`<AR, A2>`.
-    pub fn map_action<A2, F>(self, f: &'a F) -> Saga<'a, AR, A2>
+    /// Creates a new instance of [Saga]`<AR, A2, Error>`.
+    pub fn map_action<A2, F>(self, f: &'a F) -> Saga<'a, AR, A2, Error>
     where
-        F: Fn(&A) -> A2 + Send + Sync,
+        F: Fn(&A) -> A2 + MaybeSend + MaybeSync,
     {
         let new_react = Box::new(move |ar: &AR| {
-            let a = (self.react)(ar);
-            a.into_iter().map(|a: A| f(&a)).collect()
+            (self.react)(ar).map(|result| result.into_iter().map(|a: A| f(&a)).collect())
         });
 
         Saga { react: new_react }
     }
 
     /// Maps the Saga over the AR/ActionResult type parameter.
-    /// Creates a new instance of [Saga]`<AR2, A>`.
-    pub fn map_action_result<AR2, F>(self, f: &'a F) -> Saga<'a, AR2, A>
+    /// Creates a new instance of [Saga]`<AR2, A, Error>`.
+    pub fn map_action_result<AR2, F>(self, f: &'a F) -> Saga<'a, AR2, A, Error>
     where
-        F: Fn(&AR2) -> AR + Send + Sync,
+        F: Fn(&AR2) -> AR + MaybeSend + MaybeSync,
     {
         let new_react = Box::new(move |ar2: &AR2| {
             let ar = f(ar2);
@@ -115,17 +125,185 @@ impl<'a, AR, A> Saga<'a, AR, A> {
         Saga { react: new_react }
     }
 
+    /// Maps the Saga over the Error type parameter.
+    /// Creates a new instance of [Saga]`<AR, A, Error2>`.
+    pub fn map_error<Error2, F>(self, f: &'a F) -> Saga<'a, AR, A, Error2>
+    where
+        F: Fn(&Error) -> Error2 + MaybeSend + MaybeSync,
+    {
+        let new_react = Box::new(move |ar: &AR| (self.react)(ar).map_err(|e| f(&e)));
+
+        Saga { react: new_react }
+    }
+
+    /// Maps the Saga over the A/Action type parameter, with the fallible conversion (for example, `TryFrom`).
+    /// Creates a new instance of [Saga]`<AR, A2, Error>` that skips the actions that can not be converted, or fails on the first of them, according to the [ConversionPolicy].
+    pub fn try_map_action<A2, F>(
+        self,
+        f: &'a F,
+        policy: ConversionPolicy,
+    ) -> Saga<'a, AR, A2, Error>
+    where
+        F: Fn(&A) -> Result<A2, Error> + MaybeSend + MaybeSync,
+    {
+        let new_react = Box::new(move |ar: &AR| {
+            let converted = (self.react)(ar)?.into_iter().map(|a: A| f(&a));
+            match policy {
+                ConversionPolicy::Skip => Ok(converted.filter_map(Result::ok).collect()),
+                ConversionPolicy::Fail => converted.collect(),
+            }
+        });
+
+        Saga { react: new_react }
+    }
+
+    /// Maps the Saga over the AR/ActionResult type parameter, with the fallible conversion (for example, `TryFrom`).
+    /// Creates a new instance of [Saga]`<AR2, A, Error>` that produces no actions for the action results that can not be converted, or fails on them, according to the [ConversionPolicy].
+    pub fn try_map_action_result<AR2, F>(
+        self,
+        f: &'a F,
+        policy: ConversionPolicy,
+    ) -> Saga<'a, AR2, A, Error>
+    where
+        F: Fn(&AR2) -> Result<AR, Error> + MaybeSend + MaybeSync,
+    {
+        let new_react = Box::new(move |ar2: &AR2| match (f(ar2), policy) {
+            (Ok(ar), _) => (self.react)(&ar),
+            (Err(_), ConversionPolicy::Skip) => Ok(vec![]),
+            (Err(error), ConversionPolicy::Fail) => Err(error),
+        });
+
+        Saga { react: new_react }
+    }
+
+    /// Filters the action results the Saga reacts to.
+    /// Creates a new instance of [Saga]`<AR, A, Error>` that reacts only if the predicate holds, and produces no actions otherwise.
+    pub fn filter<F>(self, predicate: &'a F) -> Saga<'a, AR, A, Error>
+    where
+        F: Fn(&AR) -> bool + MaybeSend + MaybeSync,
+    {
+        let new_react = Box::new(move |ar: &AR| {
+            if predicate(ar) {
+                (self.react)(ar)
+            } else {
+                Ok(vec![])
+            }
+        });
+
+        Saga { react: new_react }
+    }
+
+    /// Adds the compensation to the Saga.
+    /// Creates a new instance of [Saga]`<AR, A, Error>` that, when the action result reports that the (previously produced) action failed downstream, produces the compensating actions of that action, instead of reacting to it.
+    ///
+    /// - `failed_action` - extracts the failed action from the action result, returning `None` if the action result is not reporting the failure
+    /// - `compensate` - the compensating action factory, declared per action (an empty vector if the action needs no compensation)
+    pub fn with_compensation<F1, F2>(
+        self,
+        failed_action: &'a F1,
+        compensate: &'a F2,
+    ) -> Saga<'a, AR, A, Error>
+    where
+        F1: Fn(&AR) -> Option<A> + MaybeSend + MaybeSync,
+        F2: Fn(&A) -> Vec<A> + MaybeSend + MaybeSync,
+    {
+        let new_react = Box::new(move |ar: &AR| match failed_action(ar) {
+            Some(action) => Ok(compensate(&action)),
+            None => (self.react)(ar),
+        });
+
+        Saga { react: new_react }
+    }
+
+    /// Adds the deadline to the Saga.
+    /// Creates a new instance of [Saga]`<AR, ScheduledAction<A>, Error>` that publishes the actions of the saga immediately, and schedules the deadline action (if any) produced by the `action_factory`, to be published after the `delay`.
+    /// The deadline action is usually routed back to the saga or the process manager as the `scheduler::TimerFired` action result, to express "if X does not happen within T, do Y".
+    pub fn with_deadline<F>(
+        self,
+        delay: Duration,
+        action_factory: &'a F,
+    ) -> Saga<'a, AR, ScheduledAction<A>, Error>
+    where
+        F: Fn(&AR) -> Option<A> + MaybeSend + MaybeSync,
+    {
+        let new_react = Box::new(move |ar: &AR| {
+            (self.react)(ar).map(|result| {
+                result
+                    .into_iter()
+                    .map(ScheduledAction::now)
+                    .chain(action_factory(ar).map(|action| ScheduledAction::after(action, delay)))
+                    .collect()
+            })
+        });
+
+        Saga { react: new_react }
+    }
+
     /// Combines two sagas into one.
     /// Creates a new instance of a Saga by combining two sagas of type `AR`, `A` and `AR2`, `A2` into a new saga of type `Sum<AR, AR2>`, `Sum<A2, A>`
-    pub fn combine<AR2, A2>(self, saga2: Saga<'a, AR2, A2>) -> Saga<'a, Sum<AR, AR2>, Sum<A2, A>> {
+    pub fn combine<AR2, A2>(
+        self,
+        saga2: Saga<'a, AR2, A2, Error>,
+    ) -> Saga<'a, Sum<AR, AR2>, Sum<A2, A>, Error> {
         let new_react = Box::new(move |ar: &Sum<AR, AR2>| match ar {
             Sum::First(ar) => {
                 let a = (self.react)(ar);
-                a.into_iter().map(|a: A| Sum::Second(a)).collect()
+                a.map(|result| result.into_iter().map(|a: A| Sum::Second(a)).collect())
             }
             Sum::Second(ar2) => {
                 let a2 = (saga2.react)(ar2);
-                a2.into_iter().map(|a: A2| Sum::First(a)).collect()
+                a2.map(|result| result.into_iter().map(|a: A2| Sum::First(a)).collect())
+            }
+        });
+
+        Saga { react: new_react }
+    }
+
+    /// Combines three sagas into one.
+    /// Creates a new instance of a Saga by combining three sagas of type `AR`, `A`, `AR2`, `A2` and `AR3`, `A3` into a new saga of type `Sum3<AR, AR2, AR3>`, `Sum3<A, A2, A3>`
+    /// It is avoiding the deeply nested `Sum<Sum<AR, AR2>, AR3>` types that `combine` would produce.
+    pub fn combine3<AR2, A2, AR3, A3>(
+        self,
+        saga2: Saga<'a, AR2, A2, Error>,
+        saga3: Saga<'a, AR3, A3, Error>,
+    ) -> Saga<'a, Sum3<AR, AR2, AR3>, Sum3<A, A2, A3>, Error> {
+        let new_react = Box::new(move |ar: &Sum3<AR, AR2, AR3>| match ar {
+            Sum3::First(ar) => {
+                (self.react)(ar).map(|result| result.into_iter().map(Sum3::First).collect())
+            }
+            Sum3::Second(ar2) => {
+                (saga2.react)(ar2).map(|result| result.into_iter().map(Sum3::Second).collect())
+            }
+            Sum3::Third(ar3) => {
+                (saga3.react)(ar3).map(|result| result.into_iter().map(Sum3::Third).collect())
+            }
+        });
+
+        Saga { react: new_react }
+    }
+
+    /// Combines four sagas into one.
+    /// Creates a new instance of a Saga by combining four sagas into a new saga of type `Sum4<AR, AR2, AR3, AR4>`, `Sum4<A, A2, A3, A4>`
+    /// It is avoiding the deeply nested `Sum<Sum<Sum<AR, AR2>, AR3>, AR4>` types that `combine` would produce.
+    #[allow(clippy::type_complexity)]
+    pub fn combine4<AR2, A2, AR3, A3, AR4, A4>(
+        self,
+        saga2: Saga<'a, AR2, A2, Error>,
+        saga3: Saga<'a, AR3, A3, Error>,
+        saga4: Saga<'a, AR4, A4, Error>,
+    ) -> Saga<'a, Sum4<AR, AR2, AR3, AR4>, Sum4<A, A2, A3, A4>, Error> {
+        let new_react = Box::new(move |ar: &Sum4<AR, AR2, AR3, AR4>| match ar {
+            Sum4::First(ar) => {
+                (self.react)(ar).map(|result| result.into_iter().map(Sum4::First).collect())
+            }
+            Sum4::Second(ar2) => {
+                (saga2.react)(ar2).map(|result| result.into_iter().map(Sum4::Second).collect())
+            }
+            Sum4::Third(ar3) => {
+                (saga3.react)(ar3).map(|result| result.into_iter().map(Sum4::Third).collect())
+            }
+            Sum4::Fourth(ar4) => {
+                (saga4.react)(ar4).map(|result| result.into_iter().map(Sum4::Fourth).collect())
             }
         });
 
@@ -134,33 +312,422 @@ impl<'a, AR, A> Saga<'a, AR, A> {
 
     /// Merges two sagas into one.
     /// Creates a new instance of a Saga by merging two sagas of type `AR`, `A` and `AR`, `A2` into a new saga of type `AR`, `Sum<A, A2>`
-    pub fn merge<A2>(self, saga2: Saga<'a, AR, A2>) -> Saga<'a, AR, Sum<A2, A>> {
+    pub fn merge<A2>(self, saga2: Saga<'a, AR, A2, Error>) -> Saga<'a, AR, Sum<A2, A>, Error> {
+        self.merge_with(saga2, MergeOrdering::Sequential)
+    }
+
+    /// Merges two sagas into one, ordering the actions of both sagas according to the [MergeOrdering].
+    /// Creates a new instance of a Saga by merging two sagas of type `AR`, `A` and `AR`, `A2` into a new saga of type `AR`, `Sum<A2, A>`
+    pub fn merge_with<A2>(
+        self,
+        saga2: Saga<'a, AR, A2, Error>,
+        ordering: MergeOrdering,
+    ) -> Saga<'a, AR, Sum<A2, A>, Error> {
         let new_react = Box::new(move |ar: &AR| {
-            let a: Vec<Sum<A2, A>> = (self.react)(ar)
+            let a: Vec<Sum<A2, A>> = (self.react)(ar)?
                 .into_iter()
                 .map(|a: A| Sum::Second(a))
                 .collect();
-            let a2: Vec<Sum<A2, A>> = (saga2.react)(ar)
+            let a2: Vec<Sum<A2, A>> = (saga2.react)(ar)?
                 .into_iter()
                 .map(|a2: A2| Sum::First(a2))
                 .collect();
 
-            a.into_iter().chain(a2).collect()
+            Ok(ordering.order(a, a2))
         });
 
         Saga { react: new_react }
     }
+
+    /// Merges two sagas that produce the same action type into one.
+    /// Creates a new instance of a Saga by merging two sagas of type `AR`, `A` and `AR`, `A` into a new saga of type `AR`, `A`
+    /// Similar to `merge`, but the actions of both sagas are concatenated, without wrapping them into the `Sum` type.
+    pub fn merge_same(self, saga2: Saga<'a, AR, A, Error>) -> Saga<'a, AR, A, Error> {
+        let new_react = Box::new(move |ar: &AR| {
+            let a: Vec<A> = (self.react)(ar)?;
+            let a2: Vec<A> = (saga2.react)(ar)?;
+
+            Ok(a.into_iter().chain(a2).collect())
+        });
+
+        Saga { react: new_react }
+    }
+}
+
+/// The ordering of the actions of the merged sagas.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MergeOrdering {
+    /// The actions of the first saga, followed by the actions of the second saga.
+    #[default]
+    Sequential,
+    /// The actions of the second saga, followed by the actions of the first saga.
+    Reversed,
+    /// The actions of both sagas are interleaved, starting with the first saga. The remaining actions of the longer saga are appended at the end.
+    Interleave,
+}
+
+impl MergeOrdering {
+    /// Orders the actions of the first and the second saga.
+    fn order<A>(&self, a: Vec<A>, a2: Vec<A>) -> Vec<A> {
+        match self {
+            MergeOrdering::Sequential => a.into_iter().chain(a2).collect(),
+            MergeOrdering::Reversed => a2.into_iter().chain(a).collect(),
+            MergeOrdering::Interleave => {
+                let mut result = Vec::with_capacity(a.len() + a2.len());
+                let mut a = a.into_iter();
+                let mut a2 = a2.into_iter();
+                loop {
+                    match (a.next(), a2.next()) {
+                        (None, None) => break,
+                        (first, second) => result.extend(first.into_iter().chain(second)),
+                    }
+                }
+                result
+            }
+        }
+    }
+}
+
+/// The policy of the fallible conversion of the action results/actions of the saga (see `try_map_action_result` and `try_map_action`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ConversionPolicy {
+    /// The action results/actions that can not be converted are skipped.
+    #[default]
+    Skip,
+    /// The saga fails with the conversion error.
+    Fail,
 }
 
 /// Formalizes the `Action Computation` algorithm for the `saga` to handle events/action_results, and produce new commands/actions.
-pub trait ActionComputation<AR, A> {
+pub trait ActionComputation<AR, A, Error = ()> {
     /// Computes new commands/actions based on the event/action_result.
-    fn compute_new_actions(&self, event: &AR) -> Vec<A>;
+    fn compute_new_actions(&self, event: &AR) -> Result<Vec<A>, Error>;
+}
+
+impl<AR, A, Error> ActionComputation<AR, A, Error> for Saga<'_, AR, A, Error> {
+    /// Computes new commands/actions based on the event/action_result.
+    fn compute_new_actions(&self, event: &AR) -> Result<Vec<A>, Error> {
+        (self.react)(event)
+    }
 }
 
-impl<AR, A> ActionComputation<AR, A> for Saga<'_, AR, A> {
+/// Shares the `saga` (for example, the `'static` one) among many saga managers/tasks.
+impl<AR, A, Error, T> ActionComputation<AR, A, Error> for Arc<T>
+where
+    T: ActionComputation<AR, A, Error>,
+{
     /// Computes new commands/actions based on the event/action_result.
-    fn compute_new_actions(&self, event: &AR) -> Vec<A> {
-        (self.react)(event).into_iter().collect()
+    fn compute_new_actions(&self, event: &AR) -> Result<Vec<A>, Error> {
+        (**self).compute_new_actions(event)
+    }
+}
+
+/// The registered saga, returning `None` if it is not interested in the action result
+#[cfg(feature = "send")]
+type RegisteredReactFunction<'a, AR, A, Error> =
+    Box<dyn Fn(&AR) -> Option<Result<Vec<A>, Error>> + 'a + Send + Sync>;
+/// The registered saga, returning `None` if it is not interested in the action result
+#[cfg(not(feature = "send"))]
+type RegisteredReactFunction<'a, AR, A, Error> =
+    Box<dyn Fn(&AR) -> Option<Result<Vec<A>, Error>> + 'a>;
+
+/// [SagaRegistry] is routing the action results/events to the interested sagas.
+///
+/// The sagas are registered per variant of the action result (via [Variant]), or for all the action results, and the action result is dispatched to all the interested sagas.
+/// The actions of the sagas are aggregated in the order of the registration. It is avoiding the deeply nested `Sum` types that `combine` would produce for many sagas.
+///
+/// Generic parameters:
+///
+/// - `AR` - Action Result / Event
+/// - `A` - Action / Command
+/// - `Error` - Error
+pub struct SagaRegistry<'a, AR: 'a, A: 'a, Error: 'a = ()> {
+    sagas: Vec<RegisteredReactFunction<'a, AR, A, Error>>,
+}
+
+impl<AR, A, Error> Default for SagaRegistry<'_, AR, A, Error> {
+    fn default() -> Self {
+        SagaRegistry { sagas: vec![] }
+    }
+}
+
+impl<'a, AR, A, Error> SagaRegistry<'a, AR, A, Error> {
+    /// Creates a new, empty instance of [SagaRegistry].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers the saga that is interested in the action results of the `V` variant.
+    pub fn register<V>(mut self, saga: Saga<'a, V, A, Error>) -> Self
+    where
+        V: Variant<AR>,
+    {
+        self.sagas.push(Box::new(move |ar: &AR| {
+            V::from_variant(ar).map(|v| (saga.react)(v))
+        }));
+        self
+    }
+
+    /// Registers the saga that is interested in all the action results.
+    pub fn register_all(mut self, saga: Saga<'a, AR, A, Error>) -> Self {
+        self.sagas
+            .push(Box::new(move |ar: &AR| Some((saga.react)(ar))));
+        self
+    }
+
+    /// Builds the [Saga] out of the registered sagas.
+    pub fn build(self) -> Saga<'a, AR, A, Error> {
+        Saga {
+            react: Box::new(move |ar: &AR| self.compute_new_actions(ar)),
+        }
+    }
+}
+
+impl<AR, A, Error> ActionComputation<AR, A, Error> for SagaRegistry<'_, AR, A, Error> {
+    /// Computes new commands/actions based on the event/action_result, by dispatching it to all the interested sagas.
+    fn compute_new_actions(&self, event: &AR) -> Result<Vec<A>, Error> {
+        let mut actions = vec![];
+        for result in self.sagas.iter().filter_map(|saga| saga(event)) {
+            actions.extend(result?);
+        }
+        Ok(actions)
+    }
+}
+
+/// [AsyncSaga] is a datatype that represents the central point of control, deciding what to execute next (`A`), based on the action result (`AR`), asynchronously.
+/// It is the [Saga] whose `react` function returns a future, so it can consult the remote services (for example, a pricing service) before it decides what to execute next.
+///
+/// The returned future is not borrowing the action result. Clone the data you need from the action result into the future.
+///
+/// ## Example
+///
+/// ```
+/// use fmodel_rust::saga::AsyncSaga;
+///
+/// async fn price(order_id: u32) -> u32 {
+///     order_id * 10
+/// }
+///
+/// fn saga<'a>() -> AsyncSaga<'a, OrderEvent, PaymentCommand> {
+///     AsyncSaga {
+///         react: Box::new(|event| {
+///             let order_id = match event {
+///                 OrderEvent::Created(order_id) => *order_id,
+///             };
+///             Box::pin(async move {
+///                 let amount = price(order_id).await;
+///                 Ok(vec![PaymentCommand::Charge(order_id, amount)])
+///             })
+///         }),
+///     }
+/// }
+///
+/// pub enum OrderEvent {
+///     Created(u32),
+/// }
+///
+/// #[derive(Debug, PartialEq)]
+/// pub enum PaymentCommand {
+///     Charge(u32, u32),
+/// }
+/// ```
+pub struct AsyncSaga<'a, AR: 'a, A: 'a, Error: 'a = ()> {
+    /// The `react` function is driving the next action based on the action result, asynchronously.
+    pub react: AsyncReactFunction<'a, AR, A, Error>,
+}
+
+impl<'a, AR, A, Error> AsyncSaga<'a, AR, A, Error> {
+    /// Maps the AsyncSaga over the A/Action type parameter.
+    /// Creates a new instance of [AsyncSaga]`<AR, A2, Error>`.
+    pub fn map_action<A2, F>(self, f: &'a F) -> AsyncSaga<'a, AR, A2, Error>
+    where
+        F: Fn(&A) -> A2 + MaybeSend + MaybeSync,
+    {
+        let new_react: AsyncReactFunction<'a, AR, A2, Error> = Box::new(move |ar: &AR| {
+            let actions = (self.react)(ar);
+            Box::pin(async move {
+                actions
+                    .await
+                    .map(|result| result.into_iter().map(|a: A| f(&a)).collect())
+            })
+        });
+
+        AsyncSaga { react: new_react }
+    }
+
+    /// Maps the AsyncSaga over the AR/ActionResult type parameter.
+    /// Creates a new instance of [AsyncSaga]`<AR2, A, Error>`.
+    pub fn map_action_result<AR2, F>(self, f: &'a F) -> AsyncSaga<'a, AR2, A, Error>
+    where
+        F: Fn(&AR2) -> AR + MaybeSend + MaybeSync,
+    {
+        let new_react: AsyncReactFunction<'a, AR2, A, Error> = Box::new(move |ar2: &AR2| {
+            let ar = f(ar2);
+            (self.react)(&ar)
+        });
+
+        AsyncSaga { react: new_react }
+    }
+
+    /// Maps the AsyncSaga over the Error type parameter.
+    /// Creates a new instance of [AsyncSaga]`<AR, A, Error2>`.
+    pub fn map_error<Error2, F>(self, f: &'a F) -> AsyncSaga<'a, AR, A, Error2>
+    where
+        F: Fn(&Error) -> Error2 + MaybeSend + MaybeSync,
+    {
+        let new_react: AsyncReactFunction<'a, AR, A, Error2> = Box::new(move |ar: &AR| {
+            let actions = (self.react)(ar);
+            Box::pin(async move { actions.await.map_err(|e| f(&e)) })
+        });
+
+        AsyncSaga { react: new_react }
+    }
+}
+
+impl<'a, AR, A, Error> From<Saga<'a, AR, A, Error>> for AsyncSaga<'a, AR, A, Error>
+where
+    A: MaybeSend,
+    Error: MaybeSend,
+{
+    /// Lifts the [Saga] into the [AsyncSaga], so it can be used (or combined) where the asynchronous saga is expected.
+    fn from(saga: Saga<'a, AR, A, Error>) -> Self {
+        let new_react: AsyncReactFunction<'a, AR, A, Error> = Box::new(move |ar: &AR| {
+            let actions = (saga.react)(ar);
+            Box::pin(async move { actions })
+        });
+
+        AsyncSaga { react: new_react }
+    }
+}
+
+/// Formalizes the `Action Computation` algorithm for the `async saga` to handle events/action_results, and produce new commands/actions, asynchronously.
+pub trait AsyncActionComputation<AR, A, Error = ()> {
+    /// Computes new commands/actions based on the event/action_result.
+    /// Desugared `async fn compute_new_actions(&self, event: &AR) -> Result<Vec<A>, Error>;` to a normal `fn` that returns `impl Future`, and adds bound `MaybeSend` (`Send` with the default `send` feature)
+    /// You can freely move between the `async fn` and `-> impl Future` spelling in your traits and impls. This is true even when one form has a Send bound.
+    fn compute_new_actions(
+        &self,
+        event: &AR,
+    ) -> impl Future<Output = Result<Vec<A>, Error>> + MaybeSend;
+}
+
+impl<AR, A, Error> AsyncActionComputation<AR, A, Error> for AsyncSaga<'_, AR, A, Error> {
+    /// Computes new commands/actions based on the event/action_result.
+    fn compute_new_actions(
+        &self,
+        event: &AR,
+    ) -> impl Future<Output = Result<Vec<A>, Error>> + MaybeSend {
+        (self.react)(event)
+    }
+}
+
+/// Shares the `async saga` (for example, the `'static` one) among many consumers/tasks.
+impl<AR, A, Error, T> AsyncActionComputation<AR, A, Error> for Arc<T>
+where
+    T: AsyncActionComputation<AR, A, Error>,
+{
+    /// Computes new commands/actions based on the event/action_result.
+    fn compute_new_actions(
+        &self,
+        event: &AR,
+    ) -> impl Future<Output = Result<Vec<A>, Error>> + MaybeSend {
+        (**self).compute_new_actions(event)
+    }
+}
+
+/// [OwnedSaga] is a datatype that represents the central point of control, deciding what to execute next (`A`), based on the owned action result (`AR`).
+/// It is the variant of the [Saga] that takes the action result by value, so the large payloads (Strings, collections) can be moved out of the action result into the actions, instead of cloned.
+/// Every [Saga] can be lifted into the [OwnedSaga] (see `From`).
+///
+/// ## Example
+///
+/// ```
+/// use fmodel_rust::saga::{OwnedActionComputation, OwnedSaga};
+///
+/// let saga: OwnedSaga<(u32, String), String> = OwnedSaga {
+///     react: Box::new(|(_order_id, customer_name)| Ok(vec![customer_name])),
+/// };
+/// assert_eq!(saga.compute_new_actions_owned((1, "John Doe".to_string())), Ok(vec!["John Doe".to_string()]));
+/// ```
+pub struct OwnedSaga<'a, AR: 'a, A: 'a, Error: 'a = ()> {
+    /// The `react` function is driving the next action based on the owned action result.
+    pub react: OwnedReactFunction<'a, AR, A, Error>,
+}
+
+impl<'a, AR, A, Error> OwnedSaga<'a, AR, A, Error> {
+    /// Maps the OwnedSaga over the A/Action type parameter.
+    /// Creates a new instance of [OwnedSaga]`<AR, A2, Error>`.
+    pub fn map_action<A2, F>(self, f: &'a F) -> OwnedSaga<'a, AR, A2, Error>
+    where
+        F: Fn(A) -> A2 + MaybeSend + MaybeSync,
+    {
+        let new_react = Box::new(move |ar: AR| {
+            (self.react)(ar).map(|result| result.into_iter().map(f).collect())
+        });
+
+        OwnedSaga { react: new_react }
+    }
+
+    /// Maps the OwnedSaga over the AR/ActionResult type parameter.
+    /// Creates a new instance of [OwnedSaga]`<AR2, A, Error>`.
+    pub fn map_action_result<AR2, F>(self, f: &'a F) -> OwnedSaga<'a, AR2, A, Error>
+    where
+        F: Fn(AR2) -> AR + MaybeSend + MaybeSync,
+    {
+        let new_react = Box::new(move |ar2: AR2| (self.react)(f(ar2)));
+
+        OwnedSaga { react: new_react }
+    }
+
+    /// Maps the OwnedSaga over the Error type parameter.
+    /// Creates a new instance of [OwnedSaga]`<AR, A, Error2>`.
+    pub fn map_error<Error2, F>(self, f: &'a F) -> OwnedSaga<'a, AR, A, Error2>
+    where
+        F: Fn(&Error) -> Error2 + MaybeSend + MaybeSync,
+    {
+        let new_react = Box::new(move |ar: AR| (self.react)(ar).map_err(|e| f(&e)));
+
+        OwnedSaga { react: new_react }
+    }
+}
+
+impl<'a, AR, A, Error> From<Saga<'a, AR, A, Error>> for OwnedSaga<'a, AR, A, Error> {
+    /// Lifts the [Saga] into the [OwnedSaga], so it can be used (or combined) where the owned saga is expected.
+    fn from(saga: Saga<'a, AR, A, Error>) -> Self {
+        let new_react: OwnedReactFunction<'a, AR, A, Error> =
+            Box::new(move |ar: AR| (saga.react)(&ar));
+
+        OwnedSaga { react: new_react }
+    }
+}
+
+/// Formalizes the `Action Computation` algorithm for the `saga` to handle the owned events/action_results, and produce new commands/actions.
+pub trait OwnedActionComputation<AR, A, Error = ()> {
+    /// Computes new commands/actions based on the owned event/action_result.
+    fn compute_new_actions_owned(&self, event: AR) -> Result<Vec<A>, Error>;
+}
+
+impl<AR, A, Error> OwnedActionComputation<AR, A, Error> for OwnedSaga<'_, AR, A, Error> {
+    /// Computes new commands/actions based on the owned event/action_result.
+    fn compute_new_actions_owned(&self, event: AR) -> Result<Vec<A>, Error> {
+        (self.react)(event)
+    }
+}
+
+impl<AR, A, Error> OwnedActionComputation<AR, A, Error> for Saga<'_, AR, A, Error> {
+    /// Computes new commands/actions based on the owned event/action_result.
+    fn compute_new_actions_owned(&self, event: AR) -> Result<Vec<A>, Error> {
+        (self.react)(&event)
+    }
+}
+
+/// Shares the `owned saga` (for example, the `'static` one) among many consumers/tasks.
+impl<AR, A, Error, T> OwnedActionComputation<AR, A, Error> for Arc<T>
+where
+    T: OwnedActionComputation<AR, A, Error>,
+{
+    /// Computes new commands/actions based on the owned event/action_result.
+    fn compute_new_actions_owned(&self, event: AR) -> Result<Vec<A>, Error> {
+        (**self).compute_new_actions_owned(event)
     }
 }
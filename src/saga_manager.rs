@@ -1,7 +1,14 @@
+use std::collections::VecDeque;
+use std::fmt::{self, Display, Formatter};
 use std::future::Future;
 use std::marker::PhantomData;
 
+use crate::concurrency::{join_all, partition_by_key};
+use crate::dead_letter::{DeadLetter, DeadLetterRepository};
+use crate::deduplication::IdempotencyKey;
+use crate::materialized_view::CheckpointRepository;
 use crate::saga::ActionComputation;
+use crate::{Identifier, MaybeSend, MaybeSync};
 
 /// Publishes the action/command to some external system.
 ///
@@ -11,9 +18,191 @@ use crate::saga::ActionComputation;
 /// - `Error` - error
 pub trait ActionPublisher<A, Error> {
     /// Publishes the action/command to some external system, returning either the actions that are successfully published or error.
-    /// Desugared `async fn publish(&self, action: &[A]) -> Result<Vec<A>, Error>;` to a normal `fn` that returns `impl Future`, and adds bound `Send`.
+    /// Desugared `async fn publish(&self, action: &[A]) -> Result<Vec<A>, Error>;` to a normal `fn` that returns `impl Future`, and adds bound `MaybeSend` (`Send` with the default `send` feature).
     /// You can freely move between the `async fn` and `-> impl Future` spelling in your traits and impls. This is true even when one form has a Send bound.
-    fn publish(&self, action: &[A]) -> impl Future<Output = Result<Vec<A>, Error>> + Send;
+    fn publish(&self, action: &[A]) -> impl Future<Output = Result<Vec<A>, Error>> + MaybeSend;
+}
+
+/// Publishes the action/command together with the metadata to some external system.
+///
+/// Extends the [ActionPublisher] with the ability to publish the actions together with the metadata (correlation id, causation id, tenant id, user info, ...), without polluting the domain types.
+///
+/// Generic parameter:
+///
+/// - `A`. - action
+/// - `M` - metadata
+/// - `Error` - error
+pub trait ActionPublisherWithMetadata<A, M, Error>: ActionPublisher<A, Error> {
+    /// Publishes the action/command together with the metadata to some external system, returning either the actions that are successfully published or error.
+    /// Desugared `async fn publish_with_metadata(&self, action: &[(A, M)]) -> Result<Vec<A>, Error>;` to a normal `fn` that returns `impl Future`, and adds bound `MaybeSend` (`Send` with the default `send` feature).
+    /// You can freely move between the `async fn` and `-> impl Future` spelling in your traits and impls. This is true even when one form has a Send bound.
+    fn publish_with_metadata(
+        &self,
+        action: &[(A, M)],
+    ) -> impl Future<Output = Result<Vec<A>, Error>> + MaybeSend;
+}
+
+/// The action/command, together with its idempotency key.
+///
+/// The key is deterministic: it is the [IdempotencyKey] of the action result, followed by the index of the action (`<key>:<index>`), so the action that is computed again (the action result is delivered at least once) gets the same key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ActionEnvelope<A> {
+    /// The action/command
+    pub action: A,
+    /// The idempotency key of the action
+    pub idempotency_key: String,
+}
+
+impl<A> ActionEnvelope<A> {
+    /// Creates a new instance of [ActionEnvelope], with the idempotency key derived from the action result and the index of the action.
+    pub fn new<AR: IdempotencyKey>(action: A, action_result: &AR, index: usize) -> Self {
+        ActionEnvelope {
+            action,
+            idempotency_key: format!("{}:{}", action_result.idempotency_key(), index),
+        }
+    }
+}
+
+impl<A> IdempotencyKey for ActionEnvelope<A> {
+    fn idempotency_key(&self) -> String {
+        self.idempotency_key.clone()
+    }
+}
+
+/// Publishes the action/command together with the idempotency key to some external system.
+///
+/// Extends the [ActionPublisher] with the ability to publish the [ActionEnvelope]s, so the duplicated actions can be recognized downstream (see [crate::deduplication::IdempotentActionPublisher]).
+///
+/// Generic parameter:
+///
+/// - `A`. - action
+/// - `Error` - error
+pub trait ActionPublisherWithIdempotency<A, Error>: ActionPublisher<A, Error> {
+    /// Publishes the action/command together with the idempotency key to some external system, returning either the actions that are successfully published or error.
+    /// Desugared `async fn publish_idempotent(&self, action: &[ActionEnvelope<A>]) -> Result<Vec<A>, Error>;` to a normal `fn` that returns `impl Future`, and adds bound `MaybeSend` (`Send` with the default `send` feature).
+    /// You can freely move between the `async fn` and `-> impl Future` spelling in your traits and impls. This is true even when one form has a Send bound.
+    fn publish_idempotent(
+        &self,
+        action: &[ActionEnvelope<A>],
+    ) -> impl Future<Output = Result<Vec<A>, Error>> + MaybeSend;
+}
+
+/// Publishes the action/command and saves the checkpoint of the handled action result, atomically.
+///
+/// Extends the [ActionPublisher] and the [CheckpointRepository] of the saga manager, that participate in the same transaction (for example, the outbox table and the checkpoint table of the same database).
+/// The actions of the action result are published if and only if its checkpoint is saved, so the action result that is delivered again (after the crash) is recognized by the checkpoint, and its actions are not published twice (see [SagaManager::handle_transactional]).
+///
+/// Generic parameter:
+///
+/// - `A`. - action
+/// - `Checkpoint` - Checkpoint/Offset/Sequence number of the action result
+/// - `Error` - error
+pub trait TransactionalPublisher<A, Checkpoint, Error>:
+    ActionPublisher<A, Error> + CheckpointRepository<Checkpoint, Error>
+{
+    /// Publishes the action/command to some external system and saves the checkpoint in one transaction, returning either the actions that are successfully published or error.
+    /// Desugared `async fn publish_with_checkpoint(&self, action: &[A], checkpoint: &Checkpoint) -> Result<Vec<A>, Error>;` to a normal `fn` that returns `impl Future`, and adds bound `MaybeSend` (`Send` with the default `send` feature).
+    /// You can freely move between the `async fn` and `-> impl Future` spelling in your traits and impls. This is true even when one form has a Send bound.
+    fn publish_with_checkpoint(
+        &self,
+        action: &[A],
+        checkpoint: &Checkpoint,
+    ) -> impl Future<Output = Result<Vec<A>, Error>> + MaybeSend;
+}
+
+/// The outcome of the interception of the action result by the [SagaInterceptor].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Interception<AR> {
+    /// The action result is passed through unchanged
+    Unchanged,
+    /// The action result is replaced by the modified one
+    Modified(AR),
+    /// The action result is dropped, so the saga does not react to it
+    Dropped,
+}
+
+/// Intercepts the action results and the actions that are flowing through the [SagaManager].
+///
+/// It is used to apply the cross-cutting concerns (enriching with correlation ids, dropping duplicates, rate-limiting, ...) around the saga, without embedding them in the saga.
+/// The interceptors are composable: a tuple `(I1, I2)` of interceptors is an interceptor that is running `I1` first and `I2` second.
+///
+/// Generic parameters:
+///
+/// - `AR` - Action Result / Event
+/// - `A` - Action / Command
+/// - `Error` - Error
+pub trait SagaInterceptor<AR, A, Error> {
+    /// Intercepts the action result before the saga reacts to it, returning either the [Interception] (the action result is unchanged, modified or dropped), or error.
+    /// The action result is borrowed, so it is not cloned when it passes through unchanged.
+    /// Desugared `async fn intercept_action_result(&self, action_result: &AR) -> Result<Interception<AR>, Error>;` to a normal `fn` that returns `impl Future`, and adds bound `MaybeSend` (`Send` with the default `send` feature).
+    /// You can freely move between the `async fn` and `-> impl Future` spelling in your traits and impls. This is true even when one form has a Send bound.
+    fn intercept_action_result(
+        &self,
+        action_result: &AR,
+    ) -> impl Future<Output = Result<Interception<AR>, Error>> + MaybeSend;
+    /// Intercepts the new actions before they are published, returning either the (modified/filtered) actions or error.
+    /// Desugared `async fn intercept_actions(&self, actions: Vec<A>) -> Result<Vec<A>, Error>;` to a normal `fn` that returns `impl Future`, and adds bound `MaybeSend` (`Send` with the default `send` feature).
+    /// You can freely move between the `async fn` and `-> impl Future` spelling in your traits and impls. This is true even when one form has a Send bound.
+    fn intercept_actions(
+        &self,
+        actions: Vec<A>,
+    ) -> impl Future<Output = Result<Vec<A>, Error>> + MaybeSend;
+}
+
+/// Chains two interceptors: the first interceptor is running before the second one. The chain stops once the action result is dropped.
+impl<AR, A, Error, I1, I2> SagaInterceptor<AR, A, Error> for (I1, I2)
+where
+    I1: SagaInterceptor<AR, A, Error> + MaybeSync,
+    I2: SagaInterceptor<AR, A, Error> + MaybeSync,
+    AR: MaybeSend + MaybeSync,
+    A: MaybeSend,
+    Error: MaybeSend,
+{
+    async fn intercept_action_result(&self, action_result: &AR) -> Result<Interception<AR>, Error> {
+        match self.0.intercept_action_result(action_result).await? {
+            Interception::Unchanged => self.1.intercept_action_result(action_result).await,
+            Interception::Modified(action_result) => {
+                match self.1.intercept_action_result(&action_result).await? {
+                    Interception::Unchanged => Ok(Interception::Modified(action_result)),
+                    interception => Ok(interception),
+                }
+            }
+            Interception::Dropped => Ok(Interception::Dropped),
+        }
+    }
+
+    async fn intercept_actions(&self, actions: Vec<A>) -> Result<Vec<A>, Error> {
+        let actions = self.0.intercept_actions(actions).await?;
+        self.1.intercept_actions(actions).await
+    }
+}
+
+/// The no-op interceptor: the action results and the actions are passed through unchanged. It is the interceptor of the [SagaManager] by default.
+impl<AR, A, Error> SagaInterceptor<AR, A, Error> for ()
+where
+    AR: MaybeSync,
+    A: MaybeSend,
+{
+    async fn intercept_action_result(
+        &self,
+        _action_result: &AR,
+    ) -> Result<Interception<AR>, Error> {
+        Ok(Interception::Unchanged)
+    }
+
+    async fn intercept_actions(&self, actions: Vec<A>) -> Result<Vec<A>, Error> {
+        Ok(actions)
+    }
+}
+
+/// The action result, together with the new actions that failed to be published.
+/// It is the message of the [DeadLetter] that the [SagaManager] parks.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnpublishedActions<AR, A> {
+    /// The action result the saga reacted to
+    pub action_result: AR,
+    /// The new actions that failed to be published
+    pub actions: Vec<A>,
 }
 
 /// Saga Manager.
@@ -26,36 +215,40 @@ pub trait ActionPublisher<A, Error> {
 /// - `AR` - Action Result / Event
 /// - `Publisher` - Action Publisher
 /// - `Error` - Error
-pub struct SagaManager<A, AR, Publisher, Saga, Error>
+/// - `Interceptor` - Saga interceptor (chain), `()` if there is none. See [SagaManager::with_interceptor].
+pub struct SagaManager<A, AR, Publisher, Saga, Error, Interceptor = ()>
 where
     Publisher: ActionPublisher<A, Error>,
-    Saga: ActionComputation<AR, A>,
+    Saga: ActionComputation<AR, A, Error>,
 {
     action_publisher: Publisher,
     saga: Saga,
+    interceptor: Interceptor,
+    max_in_flight: usize,
     _marker: PhantomData<(A, AR, Error)>,
 }
 
-impl<A, AR, Publisher, Saga, Error> ActionComputation<AR, A>
-    for SagaManager<A, AR, Publisher, Saga, Error>
+impl<A, AR, Publisher, Saga, Error, Interceptor> ActionComputation<AR, A, Error>
+    for SagaManager<A, AR, Publisher, Saga, Error, Interceptor>
 where
     Publisher: ActionPublisher<A, Error>,
-    Saga: ActionComputation<AR, A>,
+    Saga: ActionComputation<AR, A, Error>,
 {
     /// Computes new actions based on the action result.
-    fn compute_new_actions(&self, action_result: &AR) -> Vec<A> {
+    fn compute_new_actions(&self, action_result: &AR) -> Result<Vec<A>, Error> {
         self.saga.compute_new_actions(action_result)
     }
 }
 
-impl<A, AR, Publisher, Saga, Error> ActionPublisher<A, Error>
-    for SagaManager<A, AR, Publisher, Saga, Error>
+impl<A, AR, Publisher, Saga, Error, Interceptor> ActionPublisher<A, Error>
+    for SagaManager<A, AR, Publisher, Saga, Error, Interceptor>
 where
-    Publisher: ActionPublisher<A, Error> + Sync,
-    Saga: ActionComputation<AR, A> + Sync,
-    A: Sync,
-    AR: Sync,
-    Error: Sync,
+    Publisher: ActionPublisher<A, Error> + MaybeSync,
+    Saga: ActionComputation<AR, A, Error> + MaybeSync,
+    Interceptor: MaybeSync,
+    A: MaybeSync,
+    AR: MaybeSync,
+    Error: MaybeSync,
 {
     /// Publishes the action/command to some external system, returning either the actions that are successfully published or error.
     async fn publish(&self, action: &[A]) -> Result<Vec<A>, Error> {
@@ -65,27 +258,353 @@ where
 
 impl<A, AR, Publisher, Saga, Error> SagaManager<A, AR, Publisher, Saga, Error>
 where
-    Publisher: ActionPublisher<A, Error> + Sync,
-    Saga: ActionComputation<AR, A> + Sync,
-    A: Sync,
-    AR: Sync,
-    Error: Sync,
+    Publisher: ActionPublisher<A, Error> + MaybeSync,
+    Saga: ActionComputation<AR, A, Error> + MaybeSync,
+    A: MaybeSync,
+    AR: MaybeSync,
+    Error: MaybeSync,
 {
     /// Creates a new instance of [SagaManager].
     pub fn new(action_publisher: Publisher, saga: Saga) -> Self {
         SagaManager {
             action_publisher,
             saga,
+            interceptor: (),
+            max_in_flight: 1,
+            _marker: PhantomData,
+        }
+    }
+    /// Creates a new instance of [SagaManager] that is intercepting the action results and the actions with the `interceptor` (chain), in every `handle*` method.
+    pub fn with_interceptor<Interceptor>(
+        self,
+        interceptor: Interceptor,
+    ) -> SagaManager<A, AR, Publisher, Saga, Error, Interceptor>
+    where
+        Interceptor: SagaInterceptor<AR, A, Error>,
+    {
+        SagaManager {
+            action_publisher: self.action_publisher,
+            saga: self.saga,
+            interceptor,
+            max_in_flight: self.max_in_flight,
             _marker: PhantomData,
         }
     }
+}
+
+impl<A, AR, Publisher, Saga, Error, Interceptor>
+    SagaManager<A, AR, Publisher, Saga, Error, Interceptor>
+where
+    Publisher: ActionPublisher<A, Error>,
+    Saga: ActionComputation<AR, A, Error>,
+{
+    /// Sets the maximum number of the concurrent publishing calls of [SagaManager::handle_concurrently]. It is `1` (sequential publishing) by default.
+    pub fn with_max_in_flight(mut self, max_in_flight: usize) -> Self {
+        self.max_in_flight = max_in_flight.max(1);
+        self
+    }
+}
+
+impl<A, AR, Publisher, Saga, Error, Interceptor>
+    SagaManager<A, AR, Publisher, Saga, Error, Interceptor>
+where
+    Publisher: ActionPublisher<A, Error> + MaybeSync,
+    Saga: ActionComputation<AR, A, Error> + MaybeSync,
+    Interceptor: SagaInterceptor<AR, A, Error> + MaybeSync,
+    A: MaybeSync,
+    AR: MaybeSync,
+    Error: MaybeSync,
+{
     /// Handles the `action result` by computing new `actions` based on `action result`, and publishing new `actions` to the external system.
     /// In most cases:
     ///  - the `action result` is an `event` that you react,
     ///  - the `actions` are `commands` that you publish downstream.
     pub async fn handle(&self, action_result: &AR) -> Result<Vec<A>, Error> {
-        let new_actions = self.compute_new_actions(action_result);
+        let Some((_, new_actions)) = self.compute_intercepted_actions(action_result).await? else {
+            return Ok(vec![]);
+        };
         let published_actions = self.publish(&new_actions).await?;
         Ok(published_actions)
     }
+    /// Handles the `action result` with metadata by computing new `actions` based on `action result`, and publishing new `actions` together with the metadata to the external system.
+    pub async fn handle_with_metadata<M>(
+        &self,
+        action_result: &AR,
+        metadata: &M,
+    ) -> Result<Vec<A>, Error>
+    where
+        Publisher: ActionPublisherWithMetadata<A, M, Error>,
+        M: Clone + MaybeSync,
+    {
+        let Some((_, new_actions)) = self.compute_intercepted_actions(action_result).await? else {
+            return Ok(vec![]);
+        };
+        let new_actions = new_actions
+            .into_iter()
+            .map(|action| (action, metadata.clone()))
+            .collect::<Vec<(A, M)>>();
+        let published_actions = self
+            .action_publisher
+            .publish_with_metadata(&new_actions)
+            .await?;
+        Ok(published_actions)
+    }
+    /// Handles the `action result` by computing new `actions` based on `action result`, and publishing new `actions` together with the deterministic idempotency keys to the external system.
+    /// It is avoiding the duplicated downstream actions, when the `action result` is delivered (and handled) more than once.
+    pub async fn handle_idempotent(&self, action_result: &AR) -> Result<Vec<A>, Error>
+    where
+        Publisher: ActionPublisherWithIdempotency<A, Error>,
+        AR: IdempotencyKey,
+    {
+        let Some((modified_action_result, new_actions)) =
+            self.compute_intercepted_actions(action_result).await?
+        else {
+            return Ok(vec![]);
+        };
+        let action_result = modified_action_result.as_ref().unwrap_or(action_result);
+        let new_actions = new_actions
+            .into_iter()
+            .enumerate()
+            .map(|(index, action)| ActionEnvelope::new(action, action_result, index))
+            .collect::<Vec<ActionEnvelope<A>>>();
+        let published_actions = self
+            .action_publisher
+            .publish_idempotent(&new_actions)
+            .await?;
+        Ok(published_actions)
+    }
+    /// Handles the `action result` at the `checkpoint` (its version/offset/sequence number) by computing new `actions` based on `action result`, and publishing new `actions` and saving the `checkpoint` atomically, by the [TransactionalPublisher].
+    /// The `action result` at or before the saved checkpoint is already handled, so no actions are published for it.
+    pub async fn handle_transactional<Checkpoint>(
+        &self,
+        action_result: &AR,
+        checkpoint: &Checkpoint,
+    ) -> Result<Vec<A>, Error>
+    where
+        Publisher: TransactionalPublisher<A, Checkpoint, Error>,
+        Checkpoint: PartialOrd + MaybeSync,
+    {
+        let saved_checkpoint = self.action_publisher.fetch_checkpoint().await?;
+        if saved_checkpoint.is_some_and(|saved_checkpoint| saved_checkpoint >= *checkpoint) {
+            return Ok(vec![]);
+        }
+        // The checkpoint of the dropped action result is saved as well, with no actions
+        let new_actions = self
+            .compute_intercepted_actions(action_result)
+            .await?
+            .map(|(_, new_actions)| new_actions)
+            .unwrap_or_default();
+        let published_actions = self
+            .action_publisher
+            .publish_with_checkpoint(&new_actions, checkpoint)
+            .await?;
+        Ok(published_actions)
+    }
+    /// Handles the `action result` by computing new `actions` based on `action result`, and publishing new `actions` to the external system concurrently.
+    /// The new `actions` are partitioned by the [Identifier] of their target into (at most) `max_in_flight` partitions, and the partitions are published concurrently, preserving the order of the actions per target.
+    /// The published actions are returned in the order the actions are computed by the saga.
+    pub async fn handle_concurrently(&self, action_result: &AR) -> Result<Vec<A>, Error>
+    where
+        A: Identifier,
+    {
+        let Some((_, new_actions)) = self.compute_intercepted_actions(action_result).await? else {
+            return Ok(vec![]);
+        };
+        let partitions = partition_by_key(
+            new_actions.into_iter().enumerate().collect(),
+            self.max_in_flight,
+            |(_, action): &(usize, A)| action.identifier(),
+        )
+        .into_iter()
+        .map(|partition| partition.into_iter().unzip())
+        .collect::<Vec<(Vec<usize>, Vec<A>)>>();
+        let published_actions = join_all(
+            partitions
+                .iter()
+                .map(|(_, partition)| self.action_publisher.publish(partition))
+                .collect(),
+        )
+        .await
+        .into_iter()
+        .collect::<Result<Vec<Vec<A>>, Error>>()?;
+        let mut published_actions = partitions
+            .into_iter()
+            .zip(published_actions)
+            .flat_map(|((indexes, _), published)| indexes.into_iter().zip(published))
+            .collect::<Vec<(usize, A)>>();
+        published_actions.sort_by_key(|(index, _)| *index);
+        Ok(published_actions
+            .into_iter()
+            .map(|(_, action)| action)
+            .collect())
+    }
+    /// Handles the `action result` by computing new `actions` based on `action result`, and publishing new `actions` to the external system.
+    /// The new `actions` that fail to be published are parked as the [DeadLetter] in the `dead_letters` repository, and `None` is returned.
+    pub async fn handle_with_dead_letters<DeadLetters>(
+        &self,
+        action_result: &AR,
+        dead_letters: &DeadLetters,
+    ) -> Result<Option<Vec<A>>, Error>
+    where
+        DeadLetters: DeadLetterRepository<UnpublishedActions<AR, A>, Error>,
+        AR: Clone,
+    {
+        let Some((modified_action_result, new_actions)) =
+            self.compute_intercepted_actions(action_result).await?
+        else {
+            return Ok(Some(vec![]));
+        };
+        self.publish_or_park(
+            UnpublishedActions {
+                action_result: modified_action_result.unwrap_or_else(|| action_result.clone()),
+                actions: new_actions,
+            },
+            dead_letters,
+        )
+        .await
+    }
+    /// Replays (at most `limit`) parked dead letters, one at a time, by publishing their `actions` again, returning the actions that are published.
+    /// The `actions` of the dead letter that fails to be published again are parked again, with the new error.
+    /// The dead letter is acknowledged only after it is published or parked again, so the dead letters are not lost if the replay fails in between.
+    pub async fn replay_dead_letters<DeadLetters>(
+        &self,
+        dead_letters: &DeadLetters,
+        limit: usize,
+    ) -> Result<Vec<A>, Error>
+    where
+        DeadLetters: DeadLetterRepository<UnpublishedActions<AR, A>, Error>,
+        AR: Clone,
+        A: Clone,
+    {
+        let mut published_actions = vec![];
+        for dead_letter in dead_letters.fetch_dead_letters(limit).await? {
+            if let Some(actions) = self
+                .publish_or_park(dead_letter.message.clone(), dead_letters)
+                .await?
+            {
+                published_actions.extend(actions);
+            }
+            dead_letters.acknowledge(&dead_letter).await?;
+        }
+        Ok(published_actions)
+    }
+    /// Publishes the `actions`, parking them as the [DeadLetter] if the publishing fails.
+    async fn publish_or_park<DeadLetters>(
+        &self,
+        message: UnpublishedActions<AR, A>,
+        dead_letters: &DeadLetters,
+    ) -> Result<Option<Vec<A>>, Error>
+    where
+        DeadLetters: DeadLetterRepository<UnpublishedActions<AR, A>, Error>,
+    {
+        match self.action_publisher.publish(&message.actions).await {
+            Ok(published_actions) => Ok(Some(published_actions)),
+            Err(error) => {
+                dead_letters.park(DeadLetter { message, error }).await?;
+                Ok(None)
+            }
+        }
+    }
+    /// Runs the `action result` through the interceptor (chain), and computes new `actions` based on the intercepted `action result`, running them through the interceptor (chain) as well.
+    /// It returns the modified `action result` (`None` if it is unchanged) together with the intercepted new `actions`, or `None` if the `action result` is dropped by the interceptor.
+    async fn compute_intercepted_actions(
+        &self,
+        action_result: &AR,
+    ) -> Result<Option<(Option<AR>, Vec<A>)>, Error> {
+        let modified_action_result = match self
+            .interceptor
+            .intercept_action_result(action_result)
+            .await?
+        {
+            Interception::Unchanged => None,
+            Interception::Modified(action_result) => Some(action_result),
+            Interception::Dropped => return Ok(None),
+        };
+        let new_actions =
+            self.compute_new_actions(modified_action_result.as_ref().unwrap_or(action_result))?;
+        let new_actions = self.interceptor.intercept_actions(new_actions).await?;
+        Ok(Some((modified_action_result, new_actions)))
+    }
+}
+
+/// Step limit error - the [SagaRunner] did not reach the fixed point within the `max_steps` steps.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StepLimitExceeded {
+    /// The maximum number of the steps (handled actions)
+    pub max_steps: usize,
+}
+
+impl Display for StepLimitExceeded {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Step limit exceeded: the saga did not settle within {} steps",
+            self.max_steps
+        )
+    }
+}
+
+impl std::error::Error for StepLimitExceeded {}
+
+/// Saga Runner.
+///
+/// It runs the saga recursively, in process: the actions are handled by the `handler` (for example, the local command handlers/aggregates), and the saga reacts to the resulting action results, until no new actions are emitted (the fixed point).
+/// It is useful for the in-process workflows and the deterministic end-to-end tests of the choreographies.
+///
+/// Every handled action is one step. The run fails with the [StepLimitExceeded] once the `max_steps` are exceeded, so the cyclic choreographies are not running forever.
+///
+/// Generic parameters:
+/// - `A` - Action / Command
+/// - `AR` - Action Result / Event
+/// - `Saga` - Saga
+/// - `Handler` - Action handler `Fn(&A) -> impl Future<Output = Result<Vec<AR>, Error>>`
+/// - `Error` - Error
+pub struct SagaRunner<A, AR, Saga, Handler, Error>
+where
+    Saga: ActionComputation<AR, A, Error>,
+{
+    saga: Saga,
+    handler: Handler,
+    max_steps: usize,
+    _marker: PhantomData<(A, AR, Error)>,
+}
+
+impl<A, AR, Saga, Handler, Error> SagaRunner<A, AR, Saga, Handler, Error>
+where
+    Saga: ActionComputation<AR, A, Error>,
+{
+    /// Creates a new instance of [SagaRunner].
+    pub fn new(saga: Saga, handler: Handler, max_steps: usize) -> Self {
+        SagaRunner {
+            saga,
+            handler,
+            max_steps,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Runs the saga, starting from the `action result`, until the fixed point is reached.
+    /// Returns all the handled actions, in the (breadth-first) order of handling.
+    pub async fn run<Fut>(&self, action_result: AR) -> Result<Vec<A>, Error>
+    where
+        Handler: Fn(&A) -> Fut,
+        Fut: Future<Output = Result<Vec<AR>, Error>>,
+        Error: From<StepLimitExceeded>,
+    {
+        let mut action_results = VecDeque::from([action_result]);
+        let mut handled_actions = vec![];
+        while let Some(action_result) = action_results.pop_front() {
+            for action in self.saga.compute_new_actions(&action_result)? {
+                if handled_actions.len() == self.max_steps {
+                    return Err(StepLimitExceeded {
+                        max_steps: self.max_steps,
+                    }
+                    .into());
+                }
+                action_results.extend((self.handler)(&action).await?);
+                handled_actions.push(action);
+            }
+        }
+        Ok(handled_actions)
+    }
 }
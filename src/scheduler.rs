@@ -0,0 +1,166 @@
+use core::time::Duration;
+
+#[cfg(feature = "std")]
+use std::future::Future;
+
+#[cfg(feature = "std")]
+use crate::saga_manager::ActionPublisher;
+#[cfg(feature = "std")]
+use crate::{MaybeSend, MaybeSync};
+
+/// The action/command that is published after the `delay`.
+///
+/// It is used to model the process deadlines/timeouts in the saga layer, for example, "cancel the order if it is not paid in 30 minutes".
+/// The saga computes the [ScheduledAction]s, instead of the plain actions, and the [Scheduling] publisher is publishing them now or later.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScheduledAction<A> {
+    /// The action/command
+    pub action: A,
+    /// The delay after which the action is published. The action with the zero delay is published immediately
+    pub delay: Duration,
+}
+
+impl<A> ScheduledAction<A> {
+    /// Creates a new instance of [ScheduledAction] that is published immediately.
+    pub fn now(action: A) -> Self {
+        ScheduledAction {
+            action,
+            delay: Duration::ZERO,
+        }
+    }
+
+    /// Creates a new instance of [ScheduledAction] that is published after the `delay`.
+    pub fn after(action: A, delay: Duration) -> Self {
+        ScheduledAction { action, delay }
+    }
+
+    /// Returns `true` if the action is not published immediately.
+    pub fn is_delayed(&self) -> bool {
+        !self.delay.is_zero()
+    }
+}
+
+/// The action result reporting that the timer of the process fired, once the deadline is reached.
+///
+/// Schedule it (wrapped in your action type) by the saga (see `saga::Saga::with_deadline`), route it back to the saga or the process manager once it is due, and react to it, for example, "cancel the order if it is not paid in 30 minutes".
+/// The `timer` identifies the deadline (for example, the order id, or the enum of the deadlines of the process).
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimerFired<T> {
+    /// The timer/deadline that fired
+    pub timer: T,
+}
+
+impl<T> TimerFired<T> {
+    /// Creates a new instance of [TimerFired].
+    pub fn new(timer: T) -> Self {
+        TimerFired { timer }
+    }
+}
+
+/// Scheduler trait
+///
+/// It is used to store the delayed actions durably (a table, a delayed queue, ...), and to deliver them once they are due (enabled by the `std` feature).
+///
+/// Generic parameters:
+///
+/// - `A` - Action / Command
+/// - `Error` - Error
+#[cfg(feature = "std")]
+pub trait Scheduler<A, Error> {
+    /// Schedules the delayed actions, returning either the actions that are successfully scheduled or error.
+    /// Desugared `async fn schedule(&self, actions: &[ScheduledAction<A>]) -> Result<Vec<ScheduledAction<A>>, Error>;` to a normal `fn` that returns `impl Future`, and adds bound `MaybeSend` (`Send` with the default `send` feature).
+    /// You can freely move between the `async fn` and `-> impl Future` spelling in your traits and impls. This is true even when one form has a Send bound.
+    fn schedule(
+        &self,
+        actions: &[ScheduledAction<A>],
+    ) -> impl Future<Output = Result<Vec<ScheduledAction<A>>, Error>> + MaybeSend;
+    /// Fetches the actions that are due, without removing them from the scheduler.
+    /// Desugared `async fn fetch_due(&self) -> Result<Vec<A>, Error>;` to a normal `fn` that returns `impl Future`, and adds bound `MaybeSend` (`Send` with the default `send` feature).
+    /// You can freely move between the `async fn` and `-> impl Future` spelling in your traits and impls. This is true even when one form has a Send bound.
+    fn fetch_due(&self) -> impl Future<Output = Result<Vec<A>, Error>> + MaybeSend;
+    /// Marks the due actions as dispatched, removing them from the scheduler.
+    /// Desugared `async fn mark_dispatched(&self, actions: &[A]) -> Result<(), Error>;` to a normal `fn` that returns `impl Future`, and adds bound `MaybeSend` (`Send` with the default `send` feature).
+    /// You can freely move between the `async fn` and `-> impl Future` spelling in your traits and impls. This is true even when one form has a Send bound.
+    fn mark_dispatched(&self, actions: &[A])
+        -> impl Future<Output = Result<(), Error>> + MaybeSend;
+}
+
+/// Scheduling publisher.
+///
+/// It is an [ActionPublisher] of the [ScheduledAction]s: the immediate actions are published by the `Publisher`, and the delayed actions are scheduled by the [Scheduler].
+/// Use it with the `SagaManager` and the saga that is computing the [ScheduledAction]s, and call [Scheduling::dispatch_due] periodically to publish the due actions (enabled by the `std` feature).
+///
+/// Generic parameters:
+///
+/// - `Publisher` - Action publisher
+/// - `Schedule` - Scheduler
+#[cfg(feature = "std")]
+pub struct Scheduling<Publisher, Schedule> {
+    publisher: Publisher,
+    scheduler: Schedule,
+}
+
+#[cfg(feature = "std")]
+impl<Publisher, Schedule> Scheduling<Publisher, Schedule> {
+    /// Creates a new instance of [Scheduling].
+    pub fn new(publisher: Publisher, scheduler: Schedule) -> Self {
+        Scheduling {
+            publisher,
+            scheduler,
+        }
+    }
+
+    /// Publishes the actions that are due, returning either the actions that are successfully published or error.
+    /// The due actions are marked as dispatched only after they are published, so the actions that fail to be published are fetched again by the next call.
+    pub async fn dispatch_due<A, Error>(&self) -> Result<Vec<A>, Error>
+    where
+        Publisher: ActionPublisher<A, Error>,
+        Schedule: Scheduler<A, Error>,
+    {
+        let due_actions = self.scheduler.fetch_due().await?;
+        if due_actions.is_empty() {
+            return Ok(vec![]);
+        }
+        let published_actions = self.publisher.publish(&due_actions).await?;
+        self.scheduler.mark_dispatched(&due_actions).await?;
+        Ok(published_actions)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<A, Error, Publisher, Schedule> ActionPublisher<ScheduledAction<A>, Error>
+    for Scheduling<Publisher, Schedule>
+where
+    Publisher: ActionPublisher<A, Error> + MaybeSync,
+    Schedule: Scheduler<A, Error> + MaybeSync,
+    A: Clone + MaybeSend + MaybeSync,
+{
+    /// Publishes the immediate actions, and schedules the delayed actions, returning the published actions followed by the scheduled actions.
+    async fn publish(
+        &self,
+        action: &[ScheduledAction<A>],
+    ) -> Result<Vec<ScheduledAction<A>>, Error> {
+        let (delayed, immediate): (Vec<_>, Vec<_>) = action
+            .iter()
+            .cloned()
+            .partition(ScheduledAction::is_delayed);
+        let immediate = immediate
+            .into_iter()
+            .map(|scheduled| scheduled.action)
+            .collect::<Vec<A>>();
+        let mut published = if immediate.is_empty() {
+            vec![]
+        } else {
+            self.publisher
+                .publish(&immediate)
+                .await?
+                .into_iter()
+                .map(ScheduledAction::now)
+                .collect()
+        };
+        if !delayed.is_empty() {
+            published.extend(self.scheduler.schedule(&delayed).await?);
+        }
+        Ok(published)
+    }
+}
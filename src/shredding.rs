@@ -0,0 +1,360 @@
+use std::future::Future;
+use std::marker::PhantomData;
+
+use crate::aggregate::{
+    EventRepository, EventRepositoryWithMetadata, EventRepositoryWithOptimisticLocking,
+    EventTailRepository,
+};
+use crate::envelope::EventEnvelope;
+use crate::{MaybeSend, MaybeSync};
+
+/// The value of the personal data whose encryption key is deleted (shredded).
+pub const ERASED_PERSONAL_DATA: &str = "<erased>";
+
+/// Designates the personal data of the event.
+///
+/// Implement it on the event (or the state) type, to name the data subject (the customer, the user, ...) the personal data belongs to, and to map its personal data fields (the name, the address, ...).
+/// The fields are encrypted on save, and decrypted on load, by the [CryptoShredder].
+pub trait PersonalData: Sized {
+    /// Returns the identifier of the data subject, or `None` if the event contains no personal data.
+    fn data_subject(&self) -> Option<String>;
+    /// Maps the personal data fields, failing on the first field that can not be mapped.
+    fn try_map_personal_data<Error, F>(self, f: F) -> Result<Self, Error>
+    where
+        F: FnMut(String) -> Result<String, Error>;
+}
+
+/// Encryption Key Store trait
+///
+/// It is used to store the data keys per data subject. Deleting the key of the data subject makes its personal data unreadable in all the events (crypto-shredding), without rewriting the event streams.
+/// The deleted key is recorded as the tombstone, so the key of the shredded data subject is never created again, and its new personal data is erased on save.
+///
+/// Generic parameters:
+///
+/// - `Key` - Data key
+/// - `Error` - Error
+pub trait EncryptionKeyStore<Key, Error> {
+    /// Fetches the key of the data subject, or `None` if the key does not exist (or is deleted).
+    /// Desugared `async fn fetch_key(&self, subject: &str) -> Result<Option<Key>, Error>;` to a normal `fn` that returns `impl Future`, and adds bound `MaybeSend` (`Send` with the default `send` feature).
+    /// You can freely move between the `async fn` and `-> impl Future` spelling in your traits and impls. This is true even when one form has a Send bound.
+    fn fetch_key(
+        &self,
+        subject: &str,
+    ) -> impl Future<Output = Result<Option<Key>, Error>> + MaybeSend;
+    /// Fetches the key of the data subject, creating it if it does not exist yet (get-or-create).
+    /// It must return the existing key, and not replace it, as the concurrent writers may create the key of the same data subject at the same time: the replaced key would make the personal data encrypted with the first key unreadable.
+    /// Desugared `async fn create_key(&self, subject: &str) -> Result<Key, Error>;` to a normal `fn` that returns `impl Future`, and adds bound `MaybeSend` (`Send` with the default `send` feature).
+    /// You can freely move between the `async fn` and `-> impl Future` spelling in your traits and impls. This is true even when one form has a Send bound.
+    fn create_key(&self, subject: &str) -> impl Future<Output = Result<Key, Error>> + MaybeSend;
+    /// Deletes the key of the data subject, recording the tombstone of the data subject.
+    /// Desugared `async fn delete_key(&self, subject: &str) -> Result<(), Error>;` to a normal `fn` that returns `impl Future`, and adds bound `MaybeSend` (`Send` with the default `send` feature).
+    /// You can freely move between the `async fn` and `-> impl Future` spelling in your traits and impls. This is true even when one form has a Send bound.
+    fn delete_key(&self, subject: &str) -> impl Future<Output = Result<(), Error>> + MaybeSend;
+    /// Returns `true` if the key of the data subject is deleted (there is the tombstone of the data subject).
+    /// Desugared `async fn is_shredded(&self, subject: &str) -> Result<bool, Error>;` to a normal `fn` that returns `impl Future`, and adds bound `MaybeSend` (`Send` with the default `send` feature).
+    /// You can freely move between the `async fn` and `-> impl Future` spelling in your traits and impls. This is true even when one form has a Send bound.
+    fn is_shredded(&self, subject: &str) -> impl Future<Output = Result<bool, Error>> + MaybeSend;
+}
+
+/// Cipher trait
+///
+/// It encrypts and decrypts the personal data fields with the data key. Plug the cipher of your choice (AES-GCM, ChaCha20-Poly1305, ...), together with the text encoding of the ciphertext (base64, hex, ...).
+///
+/// Generic parameters:
+///
+/// - `Key` - Data key
+/// - `Error` - Error
+pub trait Cipher<Key, Error> {
+    /// Encrypts the plaintext with the key.
+    fn encrypt(&self, key: &Key, plaintext: &str) -> Result<String, Error>;
+    /// Decrypts the ciphertext with the key.
+    fn decrypt(&self, key: &Key, ciphertext: &str) -> Result<String, Error>;
+}
+
+/// Crypto Shredder.
+///
+/// It encrypts the [PersonalData] of the events on save, and decrypts it on load, transparently to the deciders and views.
+/// The personal data of the data subject whose key is deleted ([CryptoShredder::shred]) is loaded as [ERASED_PERSONAL_DATA], so the right to erasure is granted without rewriting the event streams.
+/// Decorate the event repository with the [EncryptingEventRepository] to apply it on every save and fetch.
+///
+/// Generic parameters:
+///
+/// - `KeyStore` - Encryption key store
+/// - `C` - Cipher
+pub struct CryptoShredder<KeyStore, C> {
+    key_store: KeyStore,
+    cipher: C,
+}
+
+impl<KeyStore, C> CryptoShredder<KeyStore, C> {
+    /// Creates a new instance of [CryptoShredder].
+    pub fn new(key_store: KeyStore, cipher: C) -> Self {
+        CryptoShredder { key_store, cipher }
+    }
+
+    /// Encrypts the personal data of the event, creating the key of the data subject if it does not exist yet.
+    /// The personal data of the shredded data subject is erased instead, and its key is not created again.
+    pub async fn encrypt<E, Key, Error>(&self, event: E) -> Result<E, Error>
+    where
+        E: PersonalData,
+        KeyStore: EncryptionKeyStore<Key, Error>,
+        C: Cipher<Key, Error>,
+    {
+        let Some(subject) = event.data_subject() else {
+            return Ok(event);
+        };
+        let key = match self.key_store.fetch_key(&subject).await? {
+            Some(key) => key,
+            None if self.key_store.is_shredded(&subject).await? => {
+                return event.try_map_personal_data(|_| Ok(ERASED_PERSONAL_DATA.to_string()));
+            }
+            None => self.key_store.create_key(&subject).await?,
+        };
+        event.try_map_personal_data(|plaintext| self.cipher.encrypt(&key, &plaintext))
+    }
+
+    /// Decrypts the personal data of the event, erasing it if the key of the data subject is deleted.
+    pub async fn decrypt<E, Key, Error>(&self, event: E) -> Result<E, Error>
+    where
+        E: PersonalData,
+        KeyStore: EncryptionKeyStore<Key, Error>,
+        C: Cipher<Key, Error>,
+    {
+        let Some(subject) = event.data_subject() else {
+            return Ok(event);
+        };
+        match self.key_store.fetch_key(&subject).await? {
+            Some(key) => {
+                event.try_map_personal_data(|ciphertext| self.cipher.decrypt(&key, &ciphertext))
+            }
+            None => event.try_map_personal_data(|_| Ok(ERASED_PERSONAL_DATA.to_string())),
+        }
+    }
+
+    /// Encrypts the personal data of the enveloped event, keeping the event type, version, timestamp and metadata.
+    pub async fn encrypt_envelope<E, Version, M, Key, Error>(
+        &self,
+        envelope: EventEnvelope<E, Version, M>,
+    ) -> Result<EventEnvelope<E, Version, M>, Error>
+    where
+        E: PersonalData,
+        KeyStore: EncryptionKeyStore<Key, Error>,
+        C: Cipher<Key, Error>,
+    {
+        let EventEnvelope {
+            event,
+            event_type,
+            version,
+            timestamp,
+            metadata,
+        } = envelope;
+        Ok(EventEnvelope {
+            event: self.encrypt(event).await?,
+            event_type,
+            version,
+            timestamp,
+            metadata,
+        })
+    }
+
+    /// Decrypts the personal data of the enveloped event, keeping the event type, version, timestamp and metadata.
+    pub async fn decrypt_envelope<E, Version, M, Key, Error>(
+        &self,
+        envelope: EventEnvelope<E, Version, M>,
+    ) -> Result<EventEnvelope<E, Version, M>, Error>
+    where
+        E: PersonalData,
+        KeyStore: EncryptionKeyStore<Key, Error>,
+        C: Cipher<Key, Error>,
+    {
+        let EventEnvelope {
+            event,
+            event_type,
+            version,
+            timestamp,
+            metadata,
+        } = envelope;
+        Ok(EventEnvelope {
+            event: self.decrypt(event).await?,
+            event_type,
+            version,
+            timestamp,
+            metadata,
+        })
+    }
+
+    /// Shreds the personal data of the data subject, by deleting its key (see [EncryptionKeyStore::delete_key]).
+    pub async fn shred<Key, Error>(&self, subject: &str) -> Result<(), Error>
+    where
+        KeyStore: EncryptionKeyStore<Key, Error>,
+    {
+        self.key_store.delete_key(subject).await
+    }
+}
+
+/// Encrypting Event Repository.
+///
+/// It decorates the [EventRepository], and applies the [CryptoShredder] to the events: the personal data is encrypted on save, and decrypted on read, so the deciders only see the plaintext, and the event store only sees the ciphertext.
+/// The saved events are returned decrypted (the personal data of the shredded data subject is returned erased).
+///
+/// Generic parameters:
+///
+/// - `C` - Command
+/// - `E` - Event
+/// - `Repository` - Event repository
+/// - `KeyStore` - Encryption key store
+/// - `Ciph` - Cipher
+/// - `Key` - Data key
+/// - `Version` - Version/Offset/Sequence number
+/// - `Error` - Error
+pub struct EncryptingEventRepository<C, E, Repository, KeyStore, Ciph, Key, Version, Error>
+where
+    Repository: EventRepository<C, E, Version, Error>,
+{
+    repository: Repository,
+    shredder: CryptoShredder<KeyStore, Ciph>,
+    _marker: PhantomData<(C, E, Key, Version, Error)>,
+}
+
+impl<C, E, Repository, KeyStore, Ciph, Key, Version, Error>
+    EncryptingEventRepository<C, E, Repository, KeyStore, Ciph, Key, Version, Error>
+where
+    Repository: EventRepository<C, E, Version, Error>,
+    KeyStore: EncryptionKeyStore<Key, Error>,
+    Ciph: Cipher<Key, Error>,
+    E: PersonalData + Clone,
+{
+    /// Creates a new instance of [EncryptingEventRepository].
+    pub fn new(repository: Repository, shredder: CryptoShredder<KeyStore, Ciph>) -> Self {
+        EncryptingEventRepository {
+            repository,
+            shredder,
+            _marker: PhantomData,
+        }
+    }
+
+    async fn encrypt_all(&self, events: &[E]) -> Result<Vec<E>, Error> {
+        let mut encrypted = Vec::with_capacity(events.len());
+        for event in events {
+            encrypted.push(self.shredder.encrypt(event.clone()).await?);
+        }
+        Ok(encrypted)
+    }
+
+    async fn decrypt_all(&self, events: Vec<(E, Version)>) -> Result<Vec<(E, Version)>, Error> {
+        let mut decrypted = Vec::with_capacity(events.len());
+        for (event, version) in events {
+            decrypted.push((self.shredder.decrypt(event).await?, version));
+        }
+        Ok(decrypted)
+    }
+}
+
+impl<C, E, Repository, KeyStore, Ciph, Key, Version, Error> EventRepository<C, E, Version, Error>
+    for EncryptingEventRepository<C, E, Repository, KeyStore, Ciph, Key, Version, Error>
+where
+    Repository: EventRepository<C, E, Version, Error> + MaybeSync,
+    KeyStore: EncryptionKeyStore<Key, Error> + MaybeSync,
+    Ciph: Cipher<Key, Error> + MaybeSync,
+    C: MaybeSync,
+    E: PersonalData + Clone + MaybeSend + MaybeSync,
+    Key: MaybeSend + MaybeSync,
+    Version: MaybeSend + MaybeSync,
+    Error: MaybeSend + MaybeSync,
+{
+    /// Fetches current events, based on the command, and decrypts them.
+    async fn fetch_events(&self, command: &C) -> Result<Vec<(E, Version)>, Error> {
+        let events = self.repository.fetch_events(command).await?;
+        self.decrypt_all(events).await
+    }
+    /// Encrypts the events, and saves them.
+    async fn save(&self, events: &[E]) -> Result<Vec<(E, Version)>, Error> {
+        let encrypted = self.encrypt_all(events).await?;
+        let saved = self.repository.save(&encrypted).await?;
+        self.decrypt_all(saved).await
+    }
+    /// Version provider. It is used to provide the version/sequence of the event. Optimistic locking is useing this version to check if the event is already saved.
+    async fn version_provider(&self, event: &E) -> Result<Option<Version>, Error> {
+        self.repository.version_provider(event).await
+    }
+}
+
+impl<C, E, Repository, KeyStore, Ciph, Key, Version, Error>
+    EventTailRepository<C, E, Version, Error>
+    for EncryptingEventRepository<C, E, Repository, KeyStore, Ciph, Key, Version, Error>
+where
+    Repository: EventTailRepository<C, E, Version, Error> + MaybeSync,
+    KeyStore: EncryptionKeyStore<Key, Error> + MaybeSync,
+    Ciph: Cipher<Key, Error> + MaybeSync,
+    C: MaybeSync,
+    E: PersonalData + Clone + MaybeSend + MaybeSync,
+    Key: MaybeSend + MaybeSync,
+    Version: MaybeSend + MaybeSync,
+    Error: MaybeSend + MaybeSync,
+{
+    /// Fetches current events that happened after the given version, based on the command, and decrypts them.
+    async fn fetch_events_after(
+        &self,
+        command: &C,
+        version: &Version,
+    ) -> Result<Vec<(E, Version)>, Error> {
+        let events = self.repository.fetch_events_after(command, version).await?;
+        self.decrypt_all(events).await
+    }
+}
+
+impl<C, E, Repository, KeyStore, Ciph, Key, Version, Error>
+    EventRepositoryWithOptimisticLocking<C, E, Version, Error>
+    for EncryptingEventRepository<C, E, Repository, KeyStore, Ciph, Key, Version, Error>
+where
+    Repository: EventRepositoryWithOptimisticLocking<C, E, Version, Error> + MaybeSync,
+    KeyStore: EncryptionKeyStore<Key, Error> + MaybeSync,
+    Ciph: Cipher<Key, Error> + MaybeSync,
+    C: MaybeSync,
+    E: PersonalData + Clone + MaybeSend + MaybeSync,
+    Key: MaybeSend + MaybeSync,
+    Version: MaybeSend + MaybeSync,
+    Error: MaybeSend + MaybeSync,
+{
+    /// Encrypts the events, and saves them, if the latest version of the stream matches the expected version.
+    async fn save_with_expected_version(
+        &self,
+        events: &[E],
+        expected_version: &Option<Version>,
+    ) -> Result<Vec<(E, Version)>, Error> {
+        let encrypted = self.encrypt_all(events).await?;
+        let saved = self
+            .repository
+            .save_with_expected_version(&encrypted, expected_version)
+            .await?;
+        self.decrypt_all(saved).await
+    }
+}
+
+impl<C, E, M, Repository, KeyStore, Ciph, Key, Version, Error>
+    EventRepositoryWithMetadata<C, E, M, Version, Error>
+    for EncryptingEventRepository<C, E, Repository, KeyStore, Ciph, Key, Version, Error>
+where
+    Repository: EventRepositoryWithMetadata<C, E, M, Version, Error> + MaybeSync,
+    KeyStore: EncryptionKeyStore<Key, Error> + MaybeSync,
+    Ciph: Cipher<Key, Error> + MaybeSync,
+    C: MaybeSync,
+    E: PersonalData + Clone + MaybeSend + MaybeSync,
+    M: Clone + MaybeSend + MaybeSync,
+    Key: MaybeSend + MaybeSync,
+    Version: MaybeSend + MaybeSync,
+    Error: MaybeSend + MaybeSync,
+{
+    /// Encrypts the events, and saves them together with the metadata.
+    async fn save_with_metadata(&self, events: &[(E, M)]) -> Result<Vec<(E, Version)>, Error> {
+        let mut encrypted = Vec::with_capacity(events.len());
+        for (event, metadata) in events {
+            encrypted.push((
+                self.shredder.encrypt(event.clone()).await?,
+                metadata.clone(),
+            ));
+        }
+        let saved = self.repository.save_with_metadata(&encrypted).await?;
+        self.decrypt_all(saved).await
+    }
+}
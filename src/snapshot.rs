@@ -0,0 +1,78 @@
+use serde::{Deserialize, Serialize};
+
+use crate::upcaster::Upcaster;
+use crate::DeciderInfo;
+
+/// Snapshot of the state.
+///
+/// It is the stable wire format of the snapshots: the state is stored together with the version of the latest event that is included in the snapshot, the type of the decider that produced the state, and the schema version of the state.
+/// It derives [Serialize] and [Deserialize], so it can be stored with any `serde` data format (JSON, CBOR, MessagePack, ...). See [SnapshotSerializer].
+///
+/// Generic parameters:
+///
+/// - `S` - State
+/// - `Version` - Version/Offset/Sequence number of the latest event included in the snapshot
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Snapshot<S, Version> {
+    /// The state
+    pub state: S,
+    /// The version of the latest event that is included in the snapshot
+    pub version: Version,
+    /// The stable name of the decider type that produced the state
+    pub decider_type: String,
+    /// The schema version of the state. Bump it whenever the state changes incompatibly
+    pub schema_version: u32,
+}
+
+impl<S, Version> Snapshot<S, Version> {
+    /// Creates a new instance of [Snapshot].
+    pub fn new(state: S, version: Version, decider_type: &str, schema_version: u32) -> Self {
+        Snapshot {
+            state,
+            version,
+            decider_type: decider_type.to_string(),
+            schema_version,
+        }
+    }
+
+    /// Creates a new instance of [Snapshot], tagged with the name of the decider `D`, and with the version of the decider as the schema version (see [DeciderInfo]).
+    pub fn for_decider<D: DeciderInfo>(state: S, version: Version) -> Self {
+        Snapshot::new(state, version, D::decider_name(), D::decider_version())
+    }
+
+    /// Returns `true` if the snapshot is produced by the current version of the decider `D`, so it can be loaded without upcasting.
+    pub fn is_current<D: DeciderInfo>(&self) -> bool {
+        self.decider_type == D::decider_name() && self.schema_version == D::decider_version()
+    }
+
+    /// Migrates the state of the loaded snapshot to the new schema, by the [Upcaster].
+    /// Creates a new instance of [Snapshot]`<New, Version>` with the given `schema_version`.
+    pub fn upcast<New>(
+        self,
+        upcaster: &Upcaster<S, New>,
+        schema_version: u32,
+    ) -> Snapshot<New, Version> {
+        Snapshot {
+            state: upcaster.upcast(&self.state),
+            version: self.version,
+            decider_type: self.decider_type,
+            schema_version,
+        }
+    }
+}
+
+/// Snapshot Serializer trait
+///
+/// It is used to serialize the [Snapshot] to the bytes, and to deserialize it back, with the data format of your choice (for example, `serde_json`).
+///
+/// Generic parameters:
+///
+/// - `S` - State
+/// - `Version` - Version/Offset/Sequence number
+/// - `Error` - Error
+pub trait SnapshotSerializer<S, Version, Error> {
+    /// Serializes the snapshot to the bytes.
+    fn serialize(&self, snapshot: &Snapshot<S, Version>) -> Result<Vec<u8>, Error>;
+    /// Deserializes the snapshot from the bytes.
+    fn deserialize(&self, bytes: &[u8]) -> Result<Snapshot<S, Version>, Error>;
+}
@@ -0,0 +1,323 @@
+use std::fmt::Debug;
+
+use crate::decider::{Decider, EventComputation, StateComputation};
+use crate::saga::{ActionComputation, Saga};
+use crate::view::{View, ViewStateComputation};
+
+/// [DeciderTestSpecification] is a `Given-When-Then` test specification DSL for the [Decider].
+/// It standardizes the testing of the deciders in the event-sourced and state-stored manner.
+///
+/// - event-sourced: `given(events).when(command).then(expected_events)`
+/// - state-stored: `given_state(state).when(command).then_state(expected_state)`
+/// - both: `...when(command).then_error(expected_error)`
+///
+/// ## Example
+///
+/// ```
+/// use fmodel_rust::decider::Decider;
+/// use fmodel_rust::specification::DeciderTestSpecification;
+///
+/// fn decider<'a>() -> Decider<'a, OrderCommand, OrderState, OrderEvent, OrderError> {
+///     Decider {
+///         decide: Box::new(|command, state| match command {
+///             OrderCommand::Create(order_id) => Ok(vec![OrderEvent::Created(*order_id)]),
+///             OrderCommand::Cancel(order_id) => {
+///                 if state.order_id == *order_id {
+///                     Ok(vec![OrderEvent::Cancelled(*order_id)])
+///                 } else {
+///                     Err(OrderError::NotFound(*order_id))
+///                 }
+///             }
+///         }),
+///         evolve: Box::new(|state, event| match event {
+///             OrderEvent::Created(order_id) => OrderState { order_id: *order_id, is_cancelled: false },
+///             OrderEvent::Cancelled(order_id) => OrderState { order_id: *order_id, is_cancelled: true },
+///         }),
+///         initial_state: Box::new(|| OrderState { order_id: 0, is_cancelled: false }),
+///     }
+/// }
+///
+/// #[derive(Debug)]
+/// pub enum OrderCommand {
+///     Create(u32),
+///     Cancel(u32),
+/// }
+///
+/// #[derive(Debug, PartialEq)]
+/// pub enum OrderEvent {
+///     Created(u32),
+///     Cancelled(u32),
+/// }
+///
+/// #[derive(Debug, PartialEq)]
+/// pub enum OrderError {
+///     NotFound(u32),
+/// }
+///
+/// #[derive(Debug, Clone, PartialEq)]
+/// pub struct OrderState {
+///     order_id: u32,
+///     is_cancelled: bool,
+/// }
+///
+/// DeciderTestSpecification::default()
+///     .for_decider(decider())
+///     .given(vec![OrderEvent::Created(1)])
+///     .when(OrderCommand::Cancel(1))
+///     .then(vec![OrderEvent::Cancelled(1)]);
+///
+/// DeciderTestSpecification::default()
+///     .for_decider(decider())
+///     .given_state(Some(OrderState { order_id: 1, is_cancelled: false }))
+///     .when(OrderCommand::Cancel(1))
+///     .then_state(OrderState { order_id: 1, is_cancelled: true });
+///
+/// DeciderTestSpecification::default()
+///     .for_decider(decider())
+///     .given(vec![])
+///     .when(OrderCommand::Cancel(1))
+///     .then_error(OrderError::NotFound(1));
+/// ```
+pub struct DeciderTestSpecification<'a, C, S, E, Error = ()> {
+    events: Vec<E>,
+    state: Option<S>,
+    command: Option<C>,
+    decider: Option<Decider<'a, C, S, E, Error>>,
+}
+
+impl<C, S, E, Error> Default for DeciderTestSpecification<'_, C, S, E, Error> {
+    fn default() -> Self {
+        DeciderTestSpecification {
+            events: Vec::new(),
+            state: None,
+            command: None,
+            decider: None,
+        }
+    }
+}
+
+impl<'a, C, S, E, Error> DeciderTestSpecification<'a, C, S, E, Error>
+where
+    S: Debug + PartialEq,
+    E: Debug + PartialEq,
+    Error: Debug + PartialEq,
+{
+    /// Specifies the decider under test.
+    pub fn for_decider(mut self, decider: Decider<'a, C, S, E, Error>) -> Self {
+        self.decider = Some(decider);
+        self
+    }
+
+    /// Given the current events (event-sourced).
+    pub fn given(mut self, events: Vec<E>) -> Self {
+        self.events = events;
+        self
+    }
+
+    /// Given the current state (state-stored).
+    pub fn given_state(mut self, state: Option<S>) -> Self {
+        self.state = state;
+        self
+    }
+
+    /// When the command is handled.
+    pub fn when(mut self, command: C) -> Self {
+        self.command = Some(command);
+        self
+    }
+
+    /// Then the expected new events are produced (event-sourced).
+    pub fn then(self, expected_events: Vec<E>) {
+        let decider = self.decider.expect("Decider must be initialized");
+        let command = self.command.expect("Command must be initialized");
+        let new_events = decider.compute_new_events(&self.events, &command);
+        assert_eq!(new_events, Ok(expected_events));
+    }
+
+    /// Then the expected new state is produced (state-stored).
+    pub fn then_state(self, expected_state: S) {
+        let decider = self.decider.expect("Decider must be initialized");
+        let command = self.command.expect("Command must be initialized");
+        let new_state = decider.compute_new_state(self.state, &command);
+        assert_eq!(new_state, Ok(expected_state));
+    }
+
+    /// Then the expected error is produced.
+    /// If the current state is given, the state-stored computation is used, otherwise the event-sourced one.
+    pub fn then_error(self, expected_error: Error) {
+        let decider = self.decider.expect("Decider must be initialized");
+        let command = self.command.expect("Command must be initialized");
+        match self.state {
+            Some(state) => {
+                let new_state = decider.compute_new_state(Some(state), &command);
+                assert_eq!(new_state, Err(expected_error));
+            }
+            None => {
+                let new_events = decider.compute_new_events(&self.events, &command);
+                assert_eq!(new_events, Err(expected_error));
+            }
+        }
+    }
+}
+
+/// [ViewTestSpecification] is a `Given-Then` test specification DSL for the [View].
+///
+/// - `given(events).then_state(expected_state)`
+///
+/// ## Example
+///
+/// ```
+/// use fmodel_rust::specification::ViewTestSpecification;
+/// use fmodel_rust::view::View;
+///
+/// fn view<'a>() -> View<'a, u32, OrderEvent> {
+///     View {
+///         evolve: Box::new(|state, event| match event {
+///             OrderEvent::Created(_) => state + 1,
+///             OrderEvent::Cancelled(_) => state - 1,
+///         }),
+///         initial_state: Box::new(|| 0),
+///     }
+/// }
+///
+/// pub enum OrderEvent {
+///     Created(u32),
+///     Cancelled(u32),
+/// }
+///
+/// ViewTestSpecification::default()
+///     .for_view(view())
+///     .given(vec![OrderEvent::Created(1), OrderEvent::Created(2), OrderEvent::Cancelled(1)])
+///     .then_state(1);
+/// ```
+pub struct ViewTestSpecification<'a, S, E> {
+    events: Vec<E>,
+    state: Option<S>,
+    view: Option<View<'a, S, E>>,
+}
+
+impl<S, E> Default for ViewTestSpecification<'_, S, E> {
+    fn default() -> Self {
+        ViewTestSpecification {
+            events: Vec::new(),
+            state: None,
+            view: None,
+        }
+    }
+}
+
+impl<'a, S, E> ViewTestSpecification<'a, S, E>
+where
+    S: Debug + PartialEq,
+{
+    /// Specifies the view under test.
+    pub fn for_view(mut self, view: View<'a, S, E>) -> Self {
+        self.view = Some(view);
+        self
+    }
+
+    /// Given the events that are handled by the view.
+    pub fn given(mut self, events: Vec<E>) -> Self {
+        self.events = events;
+        self
+    }
+
+    /// Given the current state of the view. If not given, the initial state of the view is used.
+    pub fn given_state(mut self, state: Option<S>) -> Self {
+        self.state = state;
+        self
+    }
+
+    /// Then the expected new state is produced.
+    pub fn then_state(self, expected_state: S) {
+        let view = self.view.expect("View must be initialized");
+        let events = self.events.iter().collect::<Vec<&E>>();
+        let new_state = view.compute_new_state(self.state, &events);
+        assert_eq!(new_state, expected_state);
+    }
+}
+
+/// [SagaTestSpecification] is a `When-Then` test specification DSL for the [Saga].
+///
+/// - `when(action_result).then(expected_actions)`
+/// - `when(action_result).then_error(expected_error)`
+///
+/// ## Example
+///
+/// ```
+/// use fmodel_rust::saga::Saga;
+/// use fmodel_rust::specification::SagaTestSpecification;
+///
+/// fn saga<'a>() -> Saga<'a, OrderEvent, ShipmentCommand> {
+///     Saga {
+///         react: Box::new(|event| match event {
+///             OrderEvent::Created(order_id) => Ok(vec![ShipmentCommand::Create(*order_id)]),
+///         }),
+///     }
+/// }
+///
+/// pub enum OrderEvent {
+///     Created(u32),
+/// }
+///
+/// #[derive(Debug, PartialEq)]
+/// pub enum ShipmentCommand {
+///     Create(u32),
+/// }
+///
+/// SagaTestSpecification::default()
+///     .for_saga(saga())
+///     .when(OrderEvent::Created(1))
+///     .then(vec![ShipmentCommand::Create(1)]);
+/// ```
+pub struct SagaTestSpecification<'a, AR, A, Error = ()> {
+    action_result: Option<AR>,
+    saga: Option<Saga<'a, AR, A, Error>>,
+}
+
+impl<AR, A, Error> Default for SagaTestSpecification<'_, AR, A, Error> {
+    fn default() -> Self {
+        SagaTestSpecification {
+            action_result: None,
+            saga: None,
+        }
+    }
+}
+
+impl<'a, AR, A, Error> SagaTestSpecification<'a, AR, A, Error>
+where
+    A: Debug + PartialEq,
+    Error: Debug + PartialEq,
+{
+    /// Specifies the saga under test.
+    pub fn for_saga(mut self, saga: Saga<'a, AR, A, Error>) -> Self {
+        self.saga = Some(saga);
+        self
+    }
+
+    /// When the action result is handled.
+    pub fn when(mut self, action_result: AR) -> Self {
+        self.action_result = Some(action_result);
+        self
+    }
+
+    /// Then the expected new actions are produced.
+    pub fn then(self, expected_actions: Vec<A>) {
+        let saga = self.saga.expect("Saga must be initialized");
+        let action_result = self
+            .action_result
+            .expect("Action result must be initialized");
+        let new_actions = saga.compute_new_actions(&action_result);
+        assert_eq!(new_actions, Ok(expected_actions));
+    }
+
+    /// Then the expected error is produced.
+    pub fn then_error(self, expected_error: Error) {
+        let saga = self.saga.expect("Saga must be initialized");
+        let action_result = self
+            .action_result
+            .expect("Action result must be initialized");
+        let new_actions = saga.compute_new_actions(&action_result);
+        assert_eq!(new_actions, Err(expected_error));
+    }
+}
@@ -0,0 +1,303 @@
+use std::fmt::{self, Display, Formatter};
+use std::future::Future;
+use std::marker::PhantomData;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use crate::concurrency::{join_all, partition_by_key};
+use crate::envelope::EventEnvelope;
+use crate::materialized_view::{CheckpointRepository, MaterializedView, ViewStateRepository};
+use crate::saga::ActionComputation;
+use crate::saga_manager::{ActionPublisher, SagaInterceptor, SagaManager};
+use crate::view::ViewStateComputation;
+use crate::{Identifier, MaybeSend, MaybeSync};
+
+/// Event Subscription trait
+///
+/// It is used to read the event stream (the event store, the message broker, ...) from the given checkpoint, batch by batch.
+///
+/// Generic parameters:
+///
+/// - `E` - Event
+/// - `Version` - Version/Offset/Sequence number
+/// - `M` - Metadata
+/// - `Checkpoint` - Checkpoint/Offset/Sequence number of the subscription
+/// - `Error` - Error
+pub trait EventSubscription<E, Version, M, Checkpoint, Error> {
+    /// Polls the next batch of the events after the `checkpoint` (from the beginning of the stream, if there is no checkpoint), returning the events and the checkpoint after the batch.
+    /// Desugared `async fn poll(&self, checkpoint: &Option<Checkpoint>) -> Result<(Vec<EventEnvelope<E, Version, M>>, Option<Checkpoint>), Error>;` to a normal `fn` that returns `impl Future`, and adds bound `MaybeSend` (`Send` with the default `send` feature).
+    /// You can freely move between the `async fn` and `-> impl Future` spelling in your traits and impls. This is true even when one form has a Send bound.
+    #[allow(clippy::type_complexity)]
+    fn poll(
+        &self,
+        checkpoint: &Option<Checkpoint>,
+    ) -> impl Future<Output = Result<(Vec<EventEnvelope<E, Version, M>>, Option<Checkpoint>), Error>>
+           + MaybeSend;
+}
+
+/// Event Handler trait
+///
+/// It is used by the [Subscriber] to handle the polled events. It is implemented by the [MaterializedView] and the [SagaManager].
+///
+/// Generic parameters:
+///
+/// - `E` - Event
+/// - `Error` - Error
+pub trait EventHandler<E, Error> {
+    /// Handles the event.
+    /// Desugared `async fn handle_event(&self, event: &E) -> Result<(), Error>;` to a normal `fn` that returns `impl Future`, and adds bound `MaybeSend` (`Send` with the default `send` feature).
+    /// You can freely move between the `async fn` and `-> impl Future` spelling in your traits and impls. This is true even when one form has a Send bound.
+    fn handle_event(&self, event: &E) -> impl Future<Output = Result<(), Error>> + MaybeSend;
+}
+
+impl<S, E, Repository, View, Error> EventHandler<E, Error>
+    for MaterializedView<S, E, Repository, View, Error>
+where
+    Repository: ViewStateRepository<E, S, Error> + MaybeSync,
+    View: ViewStateComputation<E, S> + MaybeSync,
+    E: MaybeSync,
+    S: MaybeSend + MaybeSync,
+    Error: MaybeSync,
+{
+    async fn handle_event(&self, event: &E) -> Result<(), Error> {
+        self.handle(event).await.map(|_| ())
+    }
+}
+
+impl<A, AR, Publisher, Saga, Error, Interceptor> EventHandler<AR, Error>
+    for SagaManager<A, AR, Publisher, Saga, Error, Interceptor>
+where
+    Publisher: ActionPublisher<A, Error> + MaybeSync,
+    Saga: ActionComputation<AR, A, Error> + MaybeSync,
+    Interceptor: SagaInterceptor<AR, A, Error> + MaybeSync,
+    A: MaybeSend + MaybeSync,
+    AR: MaybeSend + MaybeSync,
+    Error: MaybeSync,
+{
+    async fn handle_event(&self, event: &AR) -> Result<(), Error> {
+        self.handle(event).await.map(|_| ())
+    }
+}
+
+/// Subscriber.
+///
+/// It polls the [EventSubscription] from the last saved checkpoint, handles the events by the [EventHandler] (the [MaterializedView] or the [SagaManager]), and saves the new checkpoint to the [CheckpointRepository].
+/// The checkpoint is saved once the whole batch is handled, so the events are handled at least once: the batch that failed is polled and handled again.
+///
+/// The subscriber starts in the catch-up phase, reading the stored events batch by batch, and it switches to the live phase once the poll returns no events (it is caught up).
+/// The phase is exposed by [Subscriber::is_live], so the readers (for example, the HTTP handlers) can gate the reads of the view on its freshness.
+///
+/// The empty polls are followed by the injected `sleep` (see [Async runtime](crate#async-runtime)).
+///
+/// Generic parameters:
+///
+/// - `E` - Event
+/// - `Version` - Version/Offset/Sequence number
+/// - `M` - Metadata
+/// - `Checkpoint` - Checkpoint/Offset/Sequence number of the subscription
+/// - `Error` - Error
+/// - `Subscription` - Event subscription
+/// - `Handler` - Event handler
+/// - `Checkpoints` - Checkpoint repository
+pub struct Subscriber<E, Version, M, Checkpoint, Error, Subscription, Handler, Checkpoints>
+where
+    Subscription: EventSubscription<E, Version, M, Checkpoint, Error>,
+    Handler: EventHandler<E, Error>,
+    Checkpoints: CheckpointRepository<Checkpoint, Error>,
+{
+    subscription: Subscription,
+    handler: Handler,
+    checkpoint_repository: Checkpoints,
+    live: AtomicBool,
+    _marker: PhantomData<(E, Version, M, Checkpoint, Error)>,
+}
+
+impl<E, Version, M, Checkpoint, Error, Subscription, Handler, Checkpoints>
+    Subscriber<E, Version, M, Checkpoint, Error, Subscription, Handler, Checkpoints>
+where
+    Subscription: EventSubscription<E, Version, M, Checkpoint, Error>,
+    Handler: EventHandler<E, Error>,
+    Checkpoints: CheckpointRepository<Checkpoint, Error>,
+{
+    /// Creates a new instance of [Subscriber].
+    pub fn new(
+        subscription: Subscription,
+        handler: Handler,
+        checkpoint_repository: Checkpoints,
+    ) -> Self {
+        Subscriber {
+            subscription,
+            handler,
+            checkpoint_repository,
+            live: AtomicBool::new(false),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns `true` once the subscription is caught up, and it is handling the live events.
+    pub fn is_live(&self) -> bool {
+        self.live.load(Ordering::Acquire)
+    }
+
+    /// Polls and handles the batches of the stored events until the subscription is caught up, and switches it to the live phase.
+    /// Returns the number of the handled events.
+    pub async fn catch_up(&self) -> Result<usize, Error> {
+        let mut handled = 0;
+        loop {
+            match self.poll_once().await? {
+                0 => return Ok(handled),
+                count => handled += count,
+            }
+        }
+    }
+
+    /// Polls and handles the single batch of the events, saving the new checkpoint.
+    /// Returns the number of the handled events.
+    pub async fn poll_once(&self) -> Result<usize, Error> {
+        let checkpoint = self.checkpoint_repository.fetch_checkpoint().await?;
+        let (events, new_checkpoint) = self.subscription.poll(&checkpoint).await?;
+        for envelope in &events {
+            self.handler.handle_event(&envelope.event).await?;
+        }
+        if let Some(new_checkpoint) = new_checkpoint {
+            self.checkpoint_repository
+                .save_checkpoint(&new_checkpoint)
+                .await?;
+        }
+        if events.is_empty() {
+            self.live.store(true, Ordering::Release);
+        }
+        Ok(events.len())
+    }
+
+    /// Polls and handles the single batch of the events, saving the new checkpoint.
+    /// The events are partitioned by the identifier into (at most) `workers` partitions, and the partitions are handled concurrently, preserving the order of the events per identifier.
+    /// The partitions are running on the current task: use it with the handlers that are waiting on the I/O (databases, brokers, ...).
+    /// Returns the number of the handled events.
+    pub async fn poll_once_partitioned(&self, workers: usize) -> Result<usize, Error>
+    where
+        E: Identifier,
+    {
+        let checkpoint = self.checkpoint_repository.fetch_checkpoint().await?;
+        let (events, new_checkpoint) = self.subscription.poll(&checkpoint).await?;
+        let partitions = partition_by_key(
+            events.iter().map(|envelope| &envelope.event).collect(),
+            workers,
+            |event: &&E| event.identifier(),
+        );
+        join_all(
+            partitions
+                .into_iter()
+                .map(|partition| async move {
+                    for event in partition {
+                        self.handler.handle_event(event).await?;
+                    }
+                    Ok(())
+                })
+                .collect(),
+        )
+        .await
+        .into_iter()
+        .collect::<Result<Vec<()>, Error>>()?;
+        if let Some(new_checkpoint) = new_checkpoint {
+            self.checkpoint_repository
+                .save_checkpoint(&new_checkpoint)
+                .await?;
+        }
+        if events.is_empty() {
+            self.live.store(true, Ordering::Release);
+        }
+        Ok(events.len())
+    }
+
+    /// Runs the subscription loop: the batches are polled and handled until the subscription is caught up (see [Subscriber::is_live]), and then the subscription is polled every `poll_interval`.
+    /// It is meant to be spawned as a background task, and it returns only if the batch could not be handled. Consider the retrying repositories/publishers for the transient failures.
+    pub async fn run<F, Fut>(&self, sleep: &F, poll_interval: Duration) -> Error
+    where
+        F: Fn(Duration) -> Fut,
+        Fut: Future<Output = ()>,
+    {
+        loop {
+            match self.poll_once().await {
+                Ok(0) => sleep(poll_interval).await,
+                Ok(_) => {}
+                Err(error) => return error,
+            }
+        }
+    }
+}
+
+/// Projection timeout error - the checkpoint of the projection did not reach the awaited position within the `timeout`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProjectionTimeout {
+    /// The timeout of the waiting
+    pub timeout: Duration,
+}
+
+impl Display for ProjectionTimeout {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Projection timeout: the projection did not reach the position within {:?}",
+            self.timeout
+        )
+    }
+}
+
+impl std::error::Error for ProjectionTimeout {}
+
+/// Projection Waiter.
+///
+/// It is the read-your-writes consistency helper: given the position (version/sequence number) returned by the aggregate save, it waits until the checkpoint of the materialized view (saved by the [Subscriber] or the `ProjectionRebuilder`) passes that position, so the view reflects the write.
+/// The checkpoint is polled every `poll_interval`, until the `timeout` elapses.
+/// The elapsed time is measured with the [Instant], so it includes the latency of the checkpoint fetches, and the `timeout` holds even with the zero `poll_interval`.
+///
+/// It waits between the polls with the injected `sleep` (see [Async runtime](crate#async-runtime)).
+///
+/// Generic parameters:
+///
+/// - `Checkpoints` - Checkpoint repository of the materialized view
+/// - `Sleep` - Sleep function `Fn(Duration) -> impl Future<Output = ()>`
+pub struct ProjectionWaiter<Checkpoints, Sleep> {
+    checkpoint_repository: Checkpoints,
+    sleep: Sleep,
+    poll_interval: Duration,
+}
+
+impl<Checkpoints, Sleep> ProjectionWaiter<Checkpoints, Sleep> {
+    /// Creates a new instance of [ProjectionWaiter].
+    pub fn new(checkpoint_repository: Checkpoints, sleep: Sleep, poll_interval: Duration) -> Self {
+        ProjectionWaiter {
+            checkpoint_repository,
+            sleep,
+            poll_interval,
+        }
+    }
+
+    /// Waits until the checkpoint of the projection reaches (or passes) the `position`, failing with the [ProjectionTimeout] once the `timeout` elapses.
+    pub async fn wait_for_projection<Checkpoint, Error, SleepFut>(
+        &self,
+        position: &Checkpoint,
+        timeout: Duration,
+    ) -> Result<(), Error>
+    where
+        Checkpoints: CheckpointRepository<Checkpoint, Error>,
+        Checkpoint: PartialOrd,
+        Sleep: Fn(Duration) -> SleepFut,
+        SleepFut: Future<Output = ()>,
+        Error: From<ProjectionTimeout>,
+    {
+        let started = Instant::now();
+        loop {
+            let checkpoint = self.checkpoint_repository.fetch_checkpoint().await?;
+            if checkpoint.is_some_and(|checkpoint| checkpoint >= *position) {
+                return Ok(());
+            }
+            let elapsed = started.elapsed();
+            if elapsed >= timeout {
+                return Err(ProjectionTimeout { timeout }.into());
+            }
+            (self.sleep)(self.poll_interval.min(timeout - elapsed)).await;
+        }
+    }
+}
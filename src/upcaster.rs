@@ -0,0 +1,230 @@
+use alloc::boxed::Box;
+#[cfg(feature = "std")]
+use std::marker::PhantomData;
+
+#[cfg(feature = "std")]
+use crate::aggregate::{
+    EventRepository, EventRepositoryWithMetadata, EventRepositoryWithOptimisticLocking,
+    EventTailRepository,
+};
+use crate::{MaybeSend, MaybeSync, UpcastFunction};
+
+/// [Upcaster] is a datatype that migrates the old, persisted events to the new schema, on read.
+///
+/// It is a chain of the upcasting steps, where each step is either:
+/// - raw: `Old -> Old`, migrating the event within the same (raw/versioned) type, or
+/// - typed: `Old -> New`, migrating the event to the new type.
+///
+/// Generic parameters:
+///
+/// - `Old` - The persisted (old) event type
+/// - `New` - The upcasted (new) event type
+///
+/// ## Example
+///
+/// ```
+/// use fmodel_rust::upcaster::Upcaster;
+///
+/// #[derive(Debug, Clone, PartialEq)]
+/// pub enum StoredOrderEvent {
+///     CreatedV1(u32),
+///     CreatedV2(u32, String),
+/// }
+///
+/// #[derive(Debug, PartialEq)]
+/// pub enum OrderEvent {
+///     Created { order_id: u32, customer_name: String },
+/// }
+///
+/// let upcaster: Upcaster<StoredOrderEvent, OrderEvent> = Upcaster::identity()
+///     .and_then(|event: &StoredOrderEvent| match event {
+///         StoredOrderEvent::CreatedV1(order_id) => StoredOrderEvent::CreatedV2(*order_id, "unknown".to_string()),
+///         event => event.clone(),
+///     })
+///     .and_then(|event: &StoredOrderEvent| match event {
+///         StoredOrderEvent::CreatedV2(order_id, customer_name) => OrderEvent::Created {
+///             order_id: *order_id,
+///             customer_name: customer_name.to_owned(),
+///         },
+///         StoredOrderEvent::CreatedV1(_) => unreachable!(),
+///     });
+///
+/// assert_eq!(
+///     upcaster.upcast(&StoredOrderEvent::CreatedV1(1)),
+///     OrderEvent::Created { order_id: 1, customer_name: "unknown".to_string() }
+/// );
+/// ```
+pub struct Upcaster<'a, Old: 'a, New: 'a = Old> {
+    /// The `upcast` function is used to migrate the old event to the new event.
+    pub upcast: UpcastFunction<'a, Old, New>,
+}
+
+impl<'a, E> Upcaster<'a, E, E>
+where
+    E: Clone,
+{
+    /// Creates the identity [Upcaster], that is not migrating the events. It is the starting point of the chain.
+    pub fn identity() -> Self {
+        Upcaster {
+            upcast: Box::new(|event: &E| event.clone()),
+        }
+    }
+}
+
+impl<'a, Old, New> Upcaster<'a, Old, New> {
+    /// Creates a new instance of [Upcaster] out of the single upcasting step.
+    pub fn new<F>(f: F) -> Self
+    where
+        F: Fn(&Old) -> New + 'a + MaybeSend + MaybeSync,
+    {
+        Upcaster {
+            upcast: Box::new(f),
+        }
+    }
+
+    /// Appends the next upcasting step to the chain.
+    /// Creates a new instance of [Upcaster]`<Old, New2>`.
+    pub fn and_then<New2, F>(self, f: F) -> Upcaster<'a, Old, New2>
+    where
+        F: Fn(&New) -> New2 + 'a + MaybeSend + MaybeSync,
+    {
+        Upcaster {
+            upcast: Box::new(move |old: &Old| f(&(self.upcast)(old))),
+        }
+    }
+
+    /// Upcasts the old event to the new event.
+    pub fn upcast(&self, event: &Old) -> New {
+        (self.upcast)(event)
+    }
+}
+
+/// Upcasting Event Repository.
+///
+/// It decorates the [EventRepository], and applies the [Upcaster] to the events on read, so the old persisted events are migrated before they reach the `decider`.
+/// Saving is delegated to the decorated repository as is (enabled by the `std` feature).
+///
+/// Generic parameters:
+///
+/// - `C` - Command
+/// - `E` - Event
+/// - `Repository` - Event repository
+/// - `Version` - Version/Offset/Sequence number
+/// - `Error` - Error
+#[cfg(feature = "std")]
+pub struct UpcastingEventRepository<'a, C, E, Repository, Version, Error>
+where
+    Repository: EventRepository<C, E, Version, Error>,
+{
+    repository: Repository,
+    upcaster: Upcaster<'a, E>,
+    _marker: PhantomData<(C, E, Version, Error)>,
+}
+
+#[cfg(feature = "std")]
+impl<'a, C, E, Repository, Version, Error>
+    UpcastingEventRepository<'a, C, E, Repository, Version, Error>
+where
+    Repository: EventRepository<C, E, Version, Error>,
+{
+    /// Creates a new instance of [UpcastingEventRepository].
+    pub fn new(repository: Repository, upcaster: Upcaster<'a, E>) -> Self {
+        UpcastingEventRepository {
+            repository,
+            upcaster,
+            _marker: PhantomData,
+        }
+    }
+
+    fn upcast_all(&self, events: Vec<(E, Version)>) -> Vec<(E, Version)> {
+        events
+            .into_iter()
+            .map(|(event, version)| (self.upcaster.upcast(&event), version))
+            .collect()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<C, E, Repository, Version, Error> EventRepository<C, E, Version, Error>
+    for UpcastingEventRepository<'_, C, E, Repository, Version, Error>
+where
+    Repository: EventRepository<C, E, Version, Error> + MaybeSync,
+    C: MaybeSync,
+    E: MaybeSync,
+    Version: MaybeSync,
+    Error: MaybeSync,
+{
+    /// Fetches current events, based on the command, and upcasts them.
+    async fn fetch_events(&self, command: &C) -> Result<Vec<(E, Version)>, Error> {
+        let events = self.repository.fetch_events(command).await?;
+        Ok(self.upcast_all(events))
+    }
+    /// Saves events.
+    async fn save(&self, events: &[E]) -> Result<Vec<(E, Version)>, Error> {
+        self.repository.save(events).await
+    }
+    /// Version provider. It is used to provide the version/sequence of the event. Optimistic locking is useing this version to check if the event is already saved.
+    async fn version_provider(&self, event: &E) -> Result<Option<Version>, Error> {
+        self.repository.version_provider(event).await
+    }
+}
+
+#[cfg(feature = "std")]
+impl<C, E, Repository, Version, Error> EventTailRepository<C, E, Version, Error>
+    for UpcastingEventRepository<'_, C, E, Repository, Version, Error>
+where
+    Repository: EventTailRepository<C, E, Version, Error> + MaybeSync,
+    C: MaybeSync,
+    E: MaybeSync,
+    Version: MaybeSync,
+    Error: MaybeSync,
+{
+    /// Fetches current events that happened after the given version, based on the command, and upcasts them.
+    async fn fetch_events_after(
+        &self,
+        command: &C,
+        version: &Version,
+    ) -> Result<Vec<(E, Version)>, Error> {
+        let events = self.repository.fetch_events_after(command, version).await?;
+        Ok(self.upcast_all(events))
+    }
+}
+
+#[cfg(feature = "std")]
+impl<C, E, Repository, Version, Error> EventRepositoryWithOptimisticLocking<C, E, Version, Error>
+    for UpcastingEventRepository<'_, C, E, Repository, Version, Error>
+where
+    Repository: EventRepositoryWithOptimisticLocking<C, E, Version, Error> + MaybeSync,
+    C: MaybeSync,
+    E: MaybeSync,
+    Version: MaybeSync,
+    Error: MaybeSync,
+{
+    /// Saves events, if the latest version of the stream matches the expected version.
+    async fn save_with_expected_version(
+        &self,
+        events: &[E],
+        expected_version: &Option<Version>,
+    ) -> Result<Vec<(E, Version)>, Error> {
+        self.repository
+            .save_with_expected_version(events, expected_version)
+            .await
+    }
+}
+
+#[cfg(feature = "std")]
+impl<C, E, M, Repository, Version, Error> EventRepositoryWithMetadata<C, E, M, Version, Error>
+    for UpcastingEventRepository<'_, C, E, Repository, Version, Error>
+where
+    Repository: EventRepositoryWithMetadata<C, E, M, Version, Error> + MaybeSync,
+    C: MaybeSync,
+    E: MaybeSync,
+    M: MaybeSync,
+    Version: MaybeSync,
+    Error: MaybeSync,
+{
+    /// Saves events together with the metadata.
+    async fn save_with_metadata(&self, events: &[(E, M)]) -> Result<Vec<(E, Version)>, Error> {
+        self.repository.save_with_metadata(events).await
+    }
+}
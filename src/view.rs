@@ -1,4 +1,12 @@
-use crate::{EvolveFunction, InitialStateFunction, Sum};
+use alloc::borrow::ToOwned;
+use alloc::boxed::Box;
+use alloc::sync::Arc;
+
+use crate::upcaster::Upcaster;
+use crate::{
+    EvolveFunction, EvolveMutFunction, InitialStateFunction, MaybeSend, MaybeSync, Sum,
+    TryEvolveFunction,
+};
 
 /// [View] represents the event handling algorithm, responsible for translating the events into denormalized state, which is more adequate for querying.
 /// It has two generic parameters `S`/State, `E`/Event , representing the type of the values that View may contain or use.
@@ -90,8 +98,8 @@ impl<'a, S, E> View<'a, S, E> {
     /// Creates a new instance of [View]`<S2, E>`.
     pub fn map_state<S2, F1, F2>(self, f1: &'a F1, f2: &'a F2) -> View<'a, S2, E>
     where
-        F1: Fn(&S2) -> S + Send + Sync,
-        F2: Fn(&S) -> S2 + Send + Sync,
+        F1: Fn(&S2) -> S + MaybeSend + MaybeSync,
+        F2: Fn(&S) -> S2 + MaybeSend + MaybeSync,
     {
         let new_evolve = Box::new(move |s2: &S2, e: &E| {
             let s = f1(s2);
@@ -110,7 +118,7 @@ impl<'a, S, E> View<'a, S, E> {
     /// Creates a new instance of [View]`<S, E2>`.
     pub fn map_event<E2, F>(self, f: &'a F) -> View<'a, S, E2>
     where
-        F: Fn(&E2) -> E + Send + Sync,
+        F: Fn(&E2) -> E + MaybeSend + MaybeSync,
     {
         let new_evolve = Box::new(move |s: &S, e2: &E2| {
             let e = f(e2);
@@ -125,6 +133,22 @@ impl<'a, S, E> View<'a, S, E> {
         }
     }
 
+    /// Upcasts the View over the E/Event type parameter, so it can handle the old events.
+    /// Creates a new instance of [View]`<S, Old>`.
+    pub fn upcast<Old>(self, upcaster: Upcaster<'a, Old, E>) -> View<'a, S, Old> {
+        let new_evolve = Box::new(move |s: &S, old: &Old| {
+            let e = upcaster.upcast(old);
+            (self.evolve)(s, &e)
+        });
+
+        let new_initial_state = Box::new(move || (self.initial_state)());
+
+        View {
+            evolve: new_evolve,
+            initial_state: new_initial_state,
+        }
+    }
+
     /// Combines two views into one.
     /// Creates a new instance of a View by combining two views of type `S`, `E` and `S2`, `E2` into a new view of type `(S, S2)`, `Sum<E, E2>`
     /// Combines two views that operate on different event types (`E`` and `E2``) into a new view operating on `Sum<E, E2>`
@@ -189,6 +213,78 @@ impl<'a, S, E> View<'a, S, E> {
     }
 }
 
+/// [FallibleView] represents the event handling algorithm that may fail to apply the event (corrupt data, unknown version, ...), instead of panicking.
+/// It has three generic parameters `S`/State, `E`/Event and `Error`, representing the type of the values that FallibleView may contain or use.
+pub struct FallibleView<'a, S: 'a, E: 'a, Error: 'a = ()> {
+    /// The `evolve` function is the main state evolution algorithm, failing if the event can not be applied.
+    pub evolve: TryEvolveFunction<'a, S, E, Error>,
+    /// The `initial_state` function is the initial state.
+    pub initial_state: InitialStateFunction<'a, S>,
+}
+
+impl<'a, S, E, Error> FallibleView<'a, S, E, Error> {
+    /// Maps the FallibleView over the Error type parameter.
+    /// Creates a new instance of [FallibleView]`<S, E, Error2>`.
+    pub fn map_error<Error2, F>(self, f: &'a F) -> FallibleView<'a, S, E, Error2>
+    where
+        F: Fn(&Error) -> Error2 + MaybeSend + MaybeSync,
+    {
+        let new_evolve = Box::new(move |s: &S, e: &E| (self.evolve)(s, e).map_err(|e| f(&e)));
+
+        FallibleView {
+            evolve: new_evolve,
+            initial_state: self.initial_state,
+        }
+    }
+}
+
+impl<'a, S, E, Error> From<View<'a, S, E>> for FallibleView<'a, S, E, Error> {
+    fn from(view: View<'a, S, E>) -> Self {
+        let evolve = view.evolve;
+        FallibleView {
+            evolve: Box::new(move |s: &S, e: &E| Ok(evolve(s, e))),
+            initial_state: view.initial_state,
+        }
+    }
+}
+
+/// [FoldingView] is the variant of the [View] that evolves the state in place (`evolve_mut: Fn(&mut S, &E)`), instead of producing the new state per event.
+/// It is cheaper to replay the long event streams into the large states, and it can be used by the materialized views wherever the [View] is used.
+pub struct FoldingView<'a, S: 'a, E: 'a> {
+    /// The `evolve_mut` function is the main state evolution algorithm, evolving the current state in place.
+    pub evolve_mut: EvolveMutFunction<'a, S, E>,
+    /// The `initial_state` function is the initial state.
+    pub initial_state: InitialStateFunction<'a, S>,
+}
+
+impl<'a, S, E> From<View<'a, S, E>> for FoldingView<'a, S, E> {
+    fn from(view: View<'a, S, E>) -> Self {
+        let evolve = view.evolve;
+        FoldingView {
+            evolve_mut: Box::new(move |s: &mut S, e: &E| *s = evolve(s, e)),
+            initial_state: view.initial_state,
+        }
+    }
+}
+
+/// Formalizes the fallible `State Computation` algorithm for the `view` to handle events based on the current state, and produce new state or error.
+pub trait FallibleViewStateComputation<E, S, Error = ()> {
+    /// Computes new state based on the current state and the events, failing on the first event that can not be applied.
+    fn try_compute_new_state(&self, current_state: Option<S>, events: &[&E]) -> Result<S, Error>;
+}
+
+impl<S, E, Error> FallibleViewStateComputation<E, S, Error> for FallibleView<'_, S, E, Error> {
+    /// Computes new state based on the current state and the events, failing on the first event that can not be applied.
+    fn try_compute_new_state(&self, current_state: Option<S>, events: &[&E]) -> Result<S, Error> {
+        let effective_current_state = current_state.unwrap_or_else(|| (self.initial_state)());
+        events
+            .iter()
+            .try_fold(effective_current_state, |state, event| {
+                (self.evolve)(&state, event)
+            })
+    }
+}
+
 /// Formalizes the `State Computation` algorithm for the `view` to handle events based on the current state, and produce new state.
 pub trait ViewStateComputation<E, S> {
     /// Computes new state based on the current state and the events.
@@ -204,3 +300,25 @@ impl<S, E> ViewStateComputation<E, S> for View<'_, S, E> {
         })
     }
 }
+
+impl<S, E> ViewStateComputation<E, S> for FoldingView<'_, S, E> {
+    /// Computes new state based on the current state and the events.
+    fn compute_new_state(&self, current_state: Option<S>, events: &[&E]) -> S {
+        let mut effective_current_state = current_state.unwrap_or_else(|| (self.initial_state)());
+        events
+            .iter()
+            .for_each(|event| (self.evolve_mut)(&mut effective_current_state, event));
+        effective_current_state
+    }
+}
+
+/// Shares the `view` (for example, the `'static` one) among many materialized views/tasks.
+impl<S, E, V> ViewStateComputation<E, S> for Arc<V>
+where
+    V: ViewStateComputation<E, S>,
+{
+    /// Computes new state based on the current state and the events.
+    fn compute_new_state(&self, current_state: Option<S>, events: &[&E]) -> S {
+        (**self).compute_new_state(current_state, events)
+    }
+}
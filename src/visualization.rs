@@ -0,0 +1,243 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::choreography::{escape_mermaid, Choreography};
+use crate::{DeciderInfo, SagaInfo, ViewInfo};
+
+/// The kind of the component of the application
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ComponentKind {
+    /// The decider, handling the commands
+    Decider,
+    /// The view, handling the events
+    View,
+    /// The saga, handling the action results
+    Saga,
+}
+
+impl ComponentKind {
+    fn stereotype(&self) -> &'static str {
+        match self {
+            ComponentKind::Decider => "decider",
+            ComponentKind::View => "view",
+            ComponentKind::Saga => "saga",
+        }
+    }
+}
+
+/// The component of the application, described by its Info trait (`DeciderInfo`, `ViewInfo` or `SagaInfo`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Component {
+    /// The kind of the component
+    pub kind: ComponentKind,
+    /// The stable name of the component
+    pub name: &'static str,
+    /// The version of the component
+    pub version: u32,
+    /// The names of the handled message (command/event/action result) types
+    pub handles: &'static [&'static str],
+}
+
+/// [ApplicationDiagram] renders the composed application (the deciders, views and sagas, and the message types they handle) as the Mermaid class/flow diagram, or as the PlantUML class diagram.
+///
+/// The components are described by the [DeciderInfo], [ViewInfo] and [SagaInfo] traits (manually implemented, or derived), so the diagram stays in sync with the code.
+/// The recorded [Choreography] adds the reactions of the sagas (action result -> action) to the flow diagram.
+///
+/// ## Example
+///
+/// ```
+/// use fmodel_rust::visualization::ApplicationDiagram;
+/// use fmodel_rust::DeciderInfo;
+///
+/// struct OrderCommand;
+///
+/// impl DeciderInfo for OrderCommand {
+///     fn decider_name() -> &'static str {
+///         "Order"
+///     }
+///     fn decider_version() -> u32 {
+///         1
+///     }
+///     fn handled_commands() -> &'static [&'static str] {
+///         &["CreateOrder"]
+///     }
+/// }
+///
+/// let diagram = ApplicationDiagram::new().decider::<OrderCommand>();
+/// assert_eq!(
+///     diagram.to_mermaid_flowchart(),
+///     "flowchart LR\n    decider_Order[\"Order v1\"]\n    message_CreateOrder([\"CreateOrder\"])\n    message_CreateOrder --> decider_Order\n"
+/// );
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ApplicationDiagram {
+    components: Vec<Component>,
+    choreography: Choreography,
+}
+
+impl ApplicationDiagram {
+    /// Creates a new, empty instance of [ApplicationDiagram].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the decider, described by the [DeciderInfo].
+    pub fn decider<D: DeciderInfo>(self) -> Self {
+        self.component(Component {
+            kind: ComponentKind::Decider,
+            name: D::decider_name(),
+            version: D::decider_version(),
+            handles: D::handled_commands(),
+        })
+    }
+
+    /// Adds the view, described by the [ViewInfo].
+    pub fn view<V: ViewInfo>(self) -> Self {
+        self.component(Component {
+            kind: ComponentKind::View,
+            name: V::view_name(),
+            version: V::view_version(),
+            handles: V::handled_events(),
+        })
+    }
+
+    /// Adds the saga, described by the [SagaInfo].
+    pub fn saga<S: SagaInfo>(self) -> Self {
+        self.component(Component {
+            kind: ComponentKind::Saga,
+            name: S::saga_name(),
+            version: S::saga_version(),
+            handles: S::handled_action_results(),
+        })
+    }
+
+    /// Adds the component.
+    pub fn component(mut self, component: Component) -> Self {
+        self.components.push(component);
+        self
+    }
+
+    /// Adds the recorded reactions of the sagas to the flow diagram.
+    pub fn choreography(mut self, choreography: Choreography) -> Self {
+        self.choreography = choreography;
+        self
+    }
+
+    /// Returns the components, in the order they are added.
+    pub fn components(&self) -> &[Component] {
+        &self.components
+    }
+
+    /// Renders the application as the Mermaid class diagram: a class per component, listing the handled message types.
+    pub fn to_mermaid_class_diagram(&self) -> String {
+        let mut diagram = String::from("classDiagram\n");
+        for component in &self.components {
+            diagram.push_str(&format!("    class {} {{\n", component_id(component)));
+            diagram.push_str(&format!("        <<{}>>\n", component.kind.stereotype()));
+            diagram.push_str(&format!("        version {}\n", component.version));
+            for message in component.handles {
+                diagram.push_str(&format!("        {}\n", message));
+            }
+            diagram.push_str("    }\n");
+        }
+        diagram
+    }
+
+    /// Renders the application as the Mermaid flowchart: the message types flow into the components that handle them, and into the actions the sagas react with.
+    pub fn to_mermaid_flowchart(&self) -> String {
+        let mut diagram = String::from("flowchart LR\n");
+        let mut messages: Vec<&str> = vec![];
+        for component in &self.components {
+            diagram.push_str(&format!(
+                "    {}[\"{} v{}\"]\n",
+                component_id(component),
+                escape_mermaid(component.name),
+                component.version
+            ));
+        }
+        let handled = self
+            .components
+            .iter()
+            .flat_map(|component| component.handles.iter().copied());
+        let reacted = self
+            .choreography
+            .reactions()
+            .iter()
+            .flat_map(|reaction| [reaction.action_result.as_str(), reaction.action.as_str()]);
+        for message in handled.chain(reacted) {
+            if !messages.contains(&message) {
+                messages.push(message);
+                diagram.push_str(&format!(
+                    "    {}([\"{}\"])\n",
+                    message_id(message),
+                    escape_mermaid(message)
+                ));
+            }
+        }
+        for component in &self.components {
+            for message in component.handles {
+                diagram.push_str(&format!(
+                    "    {} --> {}\n",
+                    message_id(message),
+                    component_id(component)
+                ));
+            }
+        }
+        for reaction in self.choreography.reactions() {
+            diagram.push_str(&format!(
+                "    {} -.-> {}\n",
+                message_id(&reaction.action_result),
+                message_id(&reaction.action)
+            ));
+        }
+        diagram
+    }
+
+    /// Renders the application as the PlantUML class diagram: a class per component, listing the handled message types.
+    pub fn to_plantuml(&self) -> String {
+        let mut diagram = String::from("@startuml\n");
+        for component in &self.components {
+            diagram.push_str(&format!(
+                "class \"{}\" as {} <<{}>> {{\n",
+                escape_plantuml(component.name),
+                component_id(component),
+                component.kind.stereotype()
+            ));
+            diagram.push_str(&format!("  version {}\n", component.version));
+            for message in component.handles {
+                diagram.push_str(&format!("  {}\n", message));
+            }
+            diagram.push_str("}\n");
+        }
+        diagram.push_str("@enduml\n");
+        diagram
+    }
+}
+
+/// Escapes the double quotes of the PlantUML name, as the numeric character reference (PlantUML does not support the backslash escapes).
+fn escape_plantuml(name: &str) -> String {
+    name.replace('"', "&#34;")
+}
+
+/// The diagram identifier of the component
+fn component_id(component: &Component) -> String {
+    format!(
+        "{}_{}",
+        component.kind.stereotype(),
+        sanitize(component.name)
+    )
+}
+
+/// The diagram identifier of the message type
+fn message_id(message: &str) -> String {
+    format!("message_{}", sanitize(message))
+}
+
+/// Replaces the characters that are not allowed in the diagram identifiers
+fn sanitize(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
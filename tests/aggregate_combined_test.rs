@@ -1,5 +1,10 @@
+// The concurrency tests need the `Send` aggregates, so the fixtures are unused without the `send` feature
+#![cfg_attr(not(feature = "send"), allow(dead_code, unused_imports))]
+
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex, RwLock};
+#[cfg(feature = "send")]
 use std::thread;
 
 use fmodel_rust::aggregate::{
@@ -77,7 +82,7 @@ impl EventRepository<Command, Event, i32, AggregateError> for InMemoryEventRepos
             .into_iter()
             .filter(|(e, _)| e.identifier() == event.identifier())
             .map(|(_, version)| version)
-            .last())
+            .next_back())
     }
 }
 
@@ -214,20 +219,14 @@ fn shipment_decider<'a>() -> Decider<'a, ShipmentCommand, ShipmentState, Shipmen
 fn order_saga<'a>() -> Saga<'a, OrderEvent, ShipmentCommand> {
     Saga {
         react: Box::new(|event| match event {
-            OrderEvent::Created(evt) => {
-                vec![ShipmentCommand::Create(CreateShipmentCommand {
-                    shipment_id: evt.order_id,
-                    order_id: evt.order_id,
-                    customer_name: evt.customer_name.to_owned(),
-                    items: evt.items.to_owned(),
-                })]
-            }
-            OrderEvent::Updated(_) => {
-                vec![]
-            }
-            OrderEvent::Cancelled(_) => {
-                vec![]
-            }
+            OrderEvent::Created(evt) => Ok(vec![ShipmentCommand::Create(CreateShipmentCommand {
+                shipment_id: evt.order_id,
+                order_id: evt.order_id,
+                customer_name: evt.customer_name.to_owned(),
+                items: evt.items.to_owned(),
+            })]),
+            OrderEvent::Updated(_) => Ok(vec![]),
+            OrderEvent::Cancelled(_) => Ok(vec![]),
         }),
     }
 }
@@ -236,15 +235,16 @@ fn shipment_saga<'a>() -> Saga<'a, ShipmentEvent, OrderCommand> {
     Saga {
         react: Box::new(|event| match event {
             ShipmentEvent::Created(evt) => {
-                vec![OrderCommand::Update(api::UpdateOrderCommand {
+                Ok(vec![OrderCommand::Update(api::UpdateOrderCommand {
                     order_id: evt.order_id,
                     new_items: evt.items.to_owned(),
-                })]
+                })])
             }
         }),
     }
 }
 
+#[cfg(feature = "send")]
 #[tokio::test]
 async fn event_sourced_aggregate_test() {
     let combined_decider = order_decider()
@@ -360,6 +360,7 @@ async fn event_sourced_aggregate_test() {
     handle2.join().unwrap().await;
 }
 
+#[cfg(feature = "send")]
 #[tokio::test]
 async fn orchestrated_event_sourced_aggregate_test() {
     let combined_decider = order_decider()
@@ -374,7 +375,7 @@ async fn orchestrated_event_sourced_aggregate_test() {
     let aggregate = Arc::new(EventSourcedOrchestratingAggregate::new(
         repository,
         combined_decider.map_error(&|()| AggregateError::DomainError("Decider error".to_string())),
-        combined_saga,
+        combined_saga.map_error(&|()| AggregateError::DomainError("Saga error".to_string())),
     ));
     // Makes a clone of the Arc pointer.
     // This creates another pointer to the same allocation, increasing the strong reference count.
@@ -516,6 +517,7 @@ async fn orchestrated_event_sourced_aggregate_test() {
     handle2.join().unwrap().await;
 }
 
+#[cfg(feature = "send")]
 #[tokio::test]
 async fn state_stored_aggregate_test() {
     let combined_decider = order_decider()
@@ -692,6 +694,7 @@ async fn state_stored_aggregate_test() {
     handle2.join().unwrap().await;
 }
 
+#[cfg(feature = "send")]
 #[tokio::test]
 async fn state_stored_combined_test() {
     let combined_decider = order_decider()
@@ -708,7 +711,7 @@ async fn state_stored_combined_test() {
     let aggregate = Arc::new(StateStoredOrchestratingAggregate::new(
         repository,
         combined_decider.map_error(&|()| AggregateError::DomainError("Decider error".to_string())),
-        combined_saga,
+        combined_saga.map_error(&|()| AggregateError::DomainError("Saga error".to_string())),
     ));
     let aggregate2 = Arc::clone(&aggregate);
 
@@ -873,3 +876,103 @@ async fn state_stored_combined_test() {
     handle1.join().unwrap().await;
     handle2.join().unwrap().await;
 }
+
+#[tokio::test]
+async fn orchestrated_saga_error_test() {
+    let combined_decider = order_decider()
+        .combine(shipment_decider())
+        .map_command(&command_from_sum)
+        .map_event(&event_from_sum, &sum_to_event);
+    // The shipment saga fails to react on the created shipment
+    let failing_shipment_saga: Saga<ShipmentEvent, OrderCommand> = Saga {
+        react: Box::new(|_| Err(())),
+    };
+    let combined_saga = order_saga()
+        .combine(failing_shipment_saga)
+        .map_action(&sum_to_command)
+        .map_action_result(&event_from_sum);
+    let aggregate = EventSourcedOrchestratingAggregate::new(
+        InMemoryEventRepository::new(),
+        combined_decider.map_error(&|()| AggregateError::DomainError("Decider error".to_string())),
+        combined_saga.map_error(&|()| AggregateError::DomainError("Saga error".to_string())),
+    );
+    let command = Command::OrderCreate(CreateOrderCommand {
+        order_id: 1,
+        customer_name: "John Doe".to_string(),
+        items: vec!["Item 1".to_string(), "Item 2".to_string()],
+    });
+
+    // The error of the saga is propagated, and none of the computed events is saved
+    let result = aggregate.handle(&command).await;
+    assert!(matches!(result, Err(AggregateError::DomainError(error)) if error == "Saga error"));
+    assert!(aggregate.fetch_events(&command).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn event_sourced_filtered_aggregate_test() {
+    let combined_decider = order_decider()
+        .combine(shipment_decider())
+        .map_command(&command_from_sum)
+        .map_event(&event_from_sum, &sum_to_event);
+    // Counts the replayed events
+    let replayed = Arc::new(AtomicUsize::new(0));
+    let counter = Arc::clone(&replayed);
+    let evolve = combined_decider.evolve;
+    let counting_decider = Decider {
+        decide: combined_decider.decide,
+        evolve: Box::new(move |state, event| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            evolve(state, event)
+        }),
+        initial_state: combined_decider.initial_state,
+    };
+    let aggregate = EventSourcedAggregate::new(
+        InMemoryEventRepository::new(),
+        counting_decider.map_error(&|()| AggregateError::DomainError("Decider error".to_string())),
+    );
+    let relevant = |command: &Command, event: &Event| {
+        command_from_sum(command).is_same_variant(&event_from_sum(event))
+    };
+
+    // The order and the shipment share the stream (the same identifier)
+    let command = Command::OrderCreate(CreateOrderCommand {
+        order_id: 1,
+        customer_name: "John Doe".to_string(),
+        items: vec!["Item 1".to_string(), "Item 2".to_string()],
+    });
+    let result = aggregate.handle_with_filter(&command, &relevant).await;
+    assert!(result.is_ok());
+    let command = Command::ShipmentCreate(CreateShipmentCommand {
+        shipment_id: 1,
+        order_id: 1,
+        customer_name: "John Doe".to_string(),
+        items: vec!["Item 1".to_string(), "Item 2".to_string()],
+    });
+    let result = aggregate.handle_with_filter(&command, &relevant).await;
+    assert_eq!(
+        result.unwrap(),
+        [(
+            Event::ShipmentCreated(ShipmentCreatedEvent {
+                shipment_id: 1,
+                order_id: 1,
+                customer_name: "John Doe".to_string(),
+                items: vec!["Item 1".to_string(), "Item 2".to_string()],
+            }),
+            1
+        )]
+    );
+    // The order event is not replayed by the shipment command
+    assert_eq!(replayed.load(Ordering::SeqCst), 0);
+
+    let command = Command::OrderCancel(CancelOrderCommand { order_id: 1 });
+    let result = aggregate.handle_with_filter(&command, &relevant).await;
+    assert_eq!(
+        result.unwrap(),
+        [(
+            Event::OrderCancelled(OrderCancelledEvent { order_id: 1 }),
+            2
+        )]
+    );
+    // Only the order event is replayed by the order command
+    assert_eq!(replayed.load(Ordering::SeqCst), 1);
+}
@@ -1,11 +1,15 @@
+// The concurrency tests need the `Send` aggregates, so the fixtures are unused without the `send` feature
+#![cfg_attr(not(feature = "send"), allow(dead_code, unused_imports))]
+
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
+#[cfg(feature = "send")]
 use std::thread;
 
 use fmodel_rust::aggregate::{
     EventRepository, EventSourcedAggregate, StateRepository, StateStoredAggregate,
 };
-use fmodel_rust::decider::Decider;
+use fmodel_rust::decider::{Decider, Decision};
 use fmodel_rust::Identifier;
 
 use crate::api::{
@@ -77,7 +81,7 @@ impl EventRepository<OrderCommand, OrderEvent, i32, AggregateError>
             .into_iter()
             .filter(|(e, _)| e.identifier() == event.identifier())
             .map(|(_, version)| version)
-            .last())
+            .next_back())
     }
 }
 
@@ -178,6 +182,7 @@ fn decider<'a>() -> Decider<'a, OrderCommand, OrderState, OrderEvent> {
     }
 }
 
+#[cfg(feature = "send")]
 #[tokio::test]
 async fn es_test() {
     let repository = InMemoryOrderEventRepository::new();
@@ -289,6 +294,7 @@ async fn es_test() {
     handle2.join().unwrap().await;
 }
 
+#[cfg(feature = "send")]
 #[tokio::test]
 async fn ss_test() {
     let repository = InMemoryOrderStateRepository::new();
@@ -411,3 +417,86 @@ async fn ss_test() {
     handle1.join().unwrap().await;
     handle2.join().unwrap().await;
 }
+
+#[tokio::test]
+async fn fetch_current_state_test() {
+    let aggregate = EventSourcedAggregate::new(
+        InMemoryOrderEventRepository::new(),
+        decider().map_error(&|()| AggregateError::DomainError("Decider error".to_string())),
+    );
+    let command = OrderCommand::Create(CreateOrderCommand {
+        order_id: 1,
+        customer_name: "John Doe".to_string(),
+        items: vec!["Item 1".to_string(), "Item 2".to_string()],
+    });
+    // The initial state of the empty stream
+    let query = OrderCommand::Cancel(CancelOrderCommand { order_id: 1 });
+    assert_eq!(
+        aggregate.fetch_current_state(&query).await.unwrap(),
+        OrderState {
+            order_id: 0,
+            customer_name: "".to_string(),
+            items: Vec::new(),
+            is_cancelled: false,
+        }
+    );
+
+    assert!(aggregate.handle(&command).await.is_ok());
+    // The query is not handled, no new events are saved
+    let expected_state = OrderState {
+        order_id: 1,
+        customer_name: "John Doe".to_string(),
+        items: vec!["Item 1".to_string(), "Item 2".to_string()],
+        is_cancelled: false,
+    };
+    assert_eq!(
+        aggregate.fetch_current_state(&query).await.unwrap(),
+        expected_state
+    );
+    assert_eq!(
+        aggregate.fetch_current_state(&query).await.unwrap(),
+        expected_state
+    );
+}
+
+#[tokio::test]
+async fn decide_or_fail_test() {
+    let decider: Decider<OrderCommand, OrderState, OrderEvent, AggregateError> =
+        Decider::decide_or_fail(
+            |command, state| match command {
+                OrderCommand::Create(cmd) if state.order_id == 0 => {
+                    Decision::Accepted(vec![OrderEvent::Created(OrderCreatedEvent {
+                        order_id: cmd.order_id,
+                        customer_name: cmd.customer_name.to_owned(),
+                        items: cmd.items.to_owned(),
+                    })])
+                }
+                OrderCommand::Create(_) => Decision::Rejected(AggregateError::DomainError(
+                    "Order already exists".to_string(),
+                )),
+                _ => Decision::Accepted(vec![]),
+            },
+            decider().evolve,
+            decider().initial_state,
+        );
+    let repository = InMemoryOrderEventRepository::new();
+    let aggregate = EventSourcedAggregate::new(repository, decider);
+    let command = OrderCommand::Create(CreateOrderCommand {
+        order_id: 1,
+        customer_name: "John Doe".to_string(),
+        items: vec!["Item 1".to_string(), "Item 2".to_string()],
+    });
+
+    assert!(aggregate.handle(&command).await.is_ok());
+    // The rejected command fails, and nothing is persisted
+    let result = aggregate.handle(&command).await;
+    assert!(matches!(
+        result,
+        Err(AggregateError::DomainError(reason)) if reason == "Order already exists"
+    ));
+    assert_eq!(aggregate.fetch_events(&command).await.unwrap().len(), 1);
+
+    let decision: Decision<OrderEvent, &str> = Decision::Rejected("Rejected");
+    assert!(decision.is_rejected());
+    assert_eq!(Result::from(decision), Err("Rejected"));
+}
@@ -6,6 +6,7 @@ use fmodel_rust::Identifier;
 
 /// The state of the Order entity
 #[derive(Debug, Clone, PartialEq)]
+#[allow(dead_code)]
 pub struct OrderState {
     pub order_id: u32,
     pub customer_name: String,
@@ -15,6 +16,7 @@ pub struct OrderState {
 
 /// The state of the ViewOrder entity / It represents the Query Model
 #[derive(Debug, Clone, PartialEq)]
+#[allow(dead_code)]
 pub struct OrderViewState {
     pub order_id: u32,
     pub customer_name: String,
@@ -22,8 +24,23 @@ pub struct OrderViewState {
     pub is_cancelled: bool,
 }
 
+impl Identifier for OrderState {
+    #[allow(dead_code)]
+    fn identifier(&self) -> String {
+        self.order_id.to_string()
+    }
+}
+
+impl Identifier for OrderViewState {
+    #[allow(dead_code)]
+    fn identifier(&self) -> String {
+        self.order_id.to_string()
+    }
+}
+
 /// A second version of the ViewOrder entity / It represents the Query Model
 #[derive(Debug, Clone, PartialEq)]
+#[allow(dead_code)]
 pub struct OrderView2State {
     pub order_id: u32,
     pub customer_name: String,
@@ -114,6 +131,7 @@ impl Identifier for OrderEvent {
 
 /// The state of the Shipment entity
 #[derive(Debug, Clone, PartialEq)]
+#[allow(dead_code)]
 pub struct ShipmentState {
     pub shipment_id: u32,
     pub order_id: u32,
@@ -123,6 +141,7 @@ pub struct ShipmentState {
 
 /// The state of the ViewShipment entity / It represents the Query Model
 #[derive(Debug, Clone, PartialEq)]
+#[allow(dead_code)]
 pub struct ShipmentViewState {
     pub shipment_id: u32,
     pub order_id: u32,
@@ -170,11 +189,10 @@ pub struct ShipmentCreatedEvent {
 }
 
 /// Provides a way to get the id of the Shipment events
-impl ShipmentEvent {
-    #[allow(dead_code)]
-    pub fn id(&self) -> u32 {
+impl Identifier for ShipmentEvent {
+    fn identifier(&self) -> String {
         match self {
-            ShipmentEvent::Created(c) => c.shipment_id.to_owned(),
+            ShipmentEvent::Created(c) => c.shipment_id.to_string(),
         }
     }
 }
@@ -1,6 +1,8 @@
 use derive_more::Display;
+use fmodel_rust::aggregate::{ConcurrencyError, OptimisticLockingError};
 use fmodel_rust::{Identifier, Sum};
 use std::error::Error;
+use std::fmt::Debug;
 
 use crate::api::{
     CancelOrderCommand, CreateOrderCommand, CreateShipmentCommand, OrderCancelledEvent,
@@ -135,10 +137,23 @@ pub enum AggregateError {
     SaveEvents(String),
     FetchState(String),
     SaveState(String),
+    ConcurrencyError(String),
 }
 
 impl Error for AggregateError {}
 
+impl<Version: Debug> From<ConcurrencyError<Version>> for AggregateError {
+    fn from(error: ConcurrencyError<Version>) -> Self {
+        AggregateError::ConcurrencyError(error.to_string())
+    }
+}
+
+impl OptimisticLockingError for AggregateError {
+    fn is_concurrency_error(&self) -> bool {
+        matches!(self, AggregateError::ConcurrencyError(_))
+    }
+}
+
 /// Error type for the application/materialized view
 #[derive(Debug, Display)]
 #[allow(dead_code)]
@@ -150,9 +165,10 @@ pub enum MaterializedViewError {
 impl Error for MaterializedViewError {}
 
 /// Error type for the saga manager
-#[derive(Debug, Display)]
+#[derive(Debug, Clone, Display)]
 #[allow(dead_code)]
 pub enum SagaManagerError {
+    DomainError(String),
     PublishAction(String),
 }
 
@@ -0,0 +1,279 @@
+use std::sync::{Arc, Mutex};
+
+use fmodel_rust::aggregate::{
+    EventRepository, EventRepositoryWithOptimisticLocking, EventTailRepository,
+};
+use fmodel_rust::cache::{EventSourcedCachingAggregate, LruStateCache, StateCache};
+use fmodel_rust::decider::Decider;
+use fmodel_rust::in_memory::InMemoryEventRepository;
+
+use crate::api::{
+    CreateOrderCommand, OrderCommand, OrderCreatedEvent, OrderEvent, OrderState, OrderUpdatedEvent,
+    UpdateOrderCommand,
+};
+use crate::application::AggregateError;
+
+mod api;
+mod application;
+
+fn decider<'a>() -> Decider<'a, OrderCommand, OrderState, OrderEvent, AggregateError> {
+    Decider {
+        decide: Box::new(|command, _state| match command {
+            OrderCommand::Create(cmd) => Ok(vec![OrderEvent::Created(OrderCreatedEvent {
+                order_id: cmd.order_id,
+                customer_name: cmd.customer_name.to_owned(),
+                items: cmd.items.to_owned(),
+            })]),
+            OrderCommand::Update(cmd) => Ok(vec![OrderEvent::Updated(OrderUpdatedEvent {
+                order_id: cmd.order_id,
+                updated_items: cmd.new_items.to_owned(),
+            })]),
+            OrderCommand::Cancel(_) => Ok(vec![]),
+        }),
+        evolve: Box::new(|state, event| {
+            let mut new_state = state.clone();
+            match event {
+                OrderEvent::Created(evt) => {
+                    new_state.order_id = evt.order_id;
+                    new_state.customer_name = evt.customer_name.to_owned();
+                    new_state.items = evt.items.to_owned();
+                }
+                OrderEvent::Updated(evt) => {
+                    new_state.items = evt.updated_items.to_owned();
+                }
+                OrderEvent::Cancelled(_) => {
+                    new_state.is_cancelled = true;
+                }
+            }
+            new_state
+        }),
+        initial_state: Box::new(|| OrderState {
+            order_id: 0,
+            customer_name: "".to_string(),
+            items: Vec::new(),
+            is_cancelled: false,
+        }),
+    }
+}
+
+fn create_command(order_id: u32) -> OrderCommand {
+    OrderCommand::Create(CreateOrderCommand {
+        order_id,
+        customer_name: "John Doe".to_string(),
+        items: vec!["Item 1".to_string()],
+    })
+}
+
+fn update_command(order_id: u32, item: &str) -> OrderCommand {
+    OrderCommand::Update(UpdateOrderCommand {
+        order_id,
+        new_items: vec![item.to_string()],
+    })
+}
+
+#[tokio::test]
+async fn test() {
+    let cache = Arc::new(LruStateCache::new(10));
+    let aggregate = EventSourcedCachingAggregate::new(
+        InMemoryEventRepository::new(),
+        Arc::clone(&cache),
+        decider(),
+    );
+
+    aggregate.handle(&create_command(1)).await.unwrap();
+    aggregate
+        .handle(&update_command(1, "Item 2"))
+        .await
+        .unwrap();
+
+    // The new state is cached, with the version of the latest saved event
+    let cached: Option<(OrderState, u64)> =
+        StateCache::<_, _, AggregateError>::get(cache.as_ref(), "1")
+            .await
+            .unwrap();
+    let (state, version) = cached.unwrap();
+    assert_eq!(state.items, vec!["Item 2".to_string()]);
+    assert_eq!(version, 1);
+
+    // The events saved behind the cache (by another process) are replayed onto the cached state
+    let _ = aggregate
+        .save(&[OrderEvent::Updated(OrderUpdatedEvent {
+            order_id: 1,
+            updated_items: vec!["Item 3".to_string()],
+        })])
+        .await;
+    let state = aggregate
+        .fetch_current_state(&create_command(1))
+        .await
+        .unwrap();
+    assert_eq!(state.items, vec!["Item 3".to_string()]);
+
+    let saved = aggregate
+        .handle(&update_command(1, "Item 4"))
+        .await
+        .unwrap();
+    assert_eq!(saved.first().unwrap().1, 3);
+}
+
+#[tokio::test]
+async fn lru_state_cache_test() {
+    let cache: LruStateCache<String, u64> = LruStateCache::new(2);
+    let state = "state".to_string();
+    StateCache::<_, _, AggregateError>::put(&cache, "1", &state, &0)
+        .await
+        .unwrap();
+    StateCache::<_, _, AggregateError>::put(&cache, "2", &state, &0)
+        .await
+        .unwrap();
+    // "1" is used, so "2" is the least recently used one, and it is evicted
+    let cached = StateCache::<_, _, AggregateError>::get(&cache, "1").await;
+    assert_eq!(cached.unwrap(), Some((state.clone(), 0)));
+    StateCache::<_, _, AggregateError>::put(&cache, "3", &state, &0)
+        .await
+        .unwrap();
+    assert_eq!(cache.len(), 2);
+    let cached = StateCache::<_, _, AggregateError>::get(&cache, "2").await;
+    assert_eq!(cached.unwrap(), None);
+
+    StateCache::<_, _, AggregateError>::invalidate(&cache, "1")
+        .await
+        .unwrap();
+    let cached = StateCache::<_, _, AggregateError>::get(&cache, "1").await;
+    assert_eq!(cached.unwrap(), None);
+    assert_eq!(cache.len(), 1);
+}
+
+/// A state cache that is failing to read and cache the state - infrastructure
+struct FailingStateCache;
+
+impl StateCache<OrderState, u64, AggregateError> for FailingStateCache {
+    async fn get(&self, _id: &str) -> Result<Option<(OrderState, u64)>, AggregateError> {
+        Err(AggregateError::FetchEvents("Cache is down".to_string()))
+    }
+
+    async fn put(
+        &self,
+        _id: &str,
+        _state: &OrderState,
+        _version: &u64,
+    ) -> Result<(), AggregateError> {
+        Err(AggregateError::SaveEvents("Cache is down".to_string()))
+    }
+
+    async fn invalidate(&self, _id: &str) -> Result<(), AggregateError> {
+        Err(AggregateError::SaveEvents("Cache is down".to_string()))
+    }
+}
+
+#[tokio::test]
+async fn cache_failure_test() {
+    let aggregate = EventSourcedCachingAggregate::new(
+        InMemoryEventRepository::new(),
+        FailingStateCache,
+        decider(),
+    );
+
+    // The events are replayed from the repository and saved, even though the cache fails
+    let saved = aggregate.handle(&create_command(1)).await.unwrap();
+    assert_eq!(saved.len(), 1);
+    let saved = aggregate
+        .handle(&update_command(1, "Item 2"))
+        .await
+        .unwrap();
+    assert_eq!(saved.first().unwrap().1, 1);
+}
+
+/// An in-memory event repository, that is saving the concurrent event right after the tail of the events is fetched - infrastructure
+struct ConcurrentlyModifiedEventRepository {
+    repository: InMemoryEventRepository<OrderEvent>,
+    concurrent_event: Arc<Mutex<Option<OrderEvent>>>,
+}
+
+impl EventRepository<OrderCommand, OrderEvent, u64, AggregateError>
+    for ConcurrentlyModifiedEventRepository
+{
+    async fn fetch_events(
+        &self,
+        command: &OrderCommand,
+    ) -> Result<Vec<(OrderEvent, u64)>, AggregateError> {
+        self.repository.fetch_events(command).await
+    }
+
+    async fn save(&self, events: &[OrderEvent]) -> Result<Vec<(OrderEvent, u64)>, AggregateError> {
+        EventRepository::<OrderCommand, _, _, _>::save(&self.repository, events).await
+    }
+
+    async fn version_provider(&self, event: &OrderEvent) -> Result<Option<u64>, AggregateError> {
+        EventRepository::<OrderCommand, _, _, _>::version_provider(&self.repository, event).await
+    }
+}
+
+impl EventTailRepository<OrderCommand, OrderEvent, u64, AggregateError>
+    for ConcurrentlyModifiedEventRepository
+{
+    async fn fetch_events_after(
+        &self,
+        command: &OrderCommand,
+        version: &u64,
+    ) -> Result<Vec<(OrderEvent, u64)>, AggregateError> {
+        let events = self.repository.fetch_events_after(command, version).await;
+        let concurrent_event = self.concurrent_event.lock().unwrap().take();
+        if let Some(event) = concurrent_event {
+            EventRepository::<OrderCommand, _, _, AggregateError>::save(&self.repository, &[event])
+                .await?;
+        }
+        events
+    }
+}
+
+impl EventRepositoryWithOptimisticLocking<OrderCommand, OrderEvent, u64, AggregateError>
+    for ConcurrentlyModifiedEventRepository
+{
+    async fn save_with_expected_version(
+        &self,
+        events: &[OrderEvent],
+        expected_version: &Option<u64>,
+    ) -> Result<Vec<(OrderEvent, u64)>, AggregateError> {
+        EventRepositoryWithOptimisticLocking::<OrderCommand, _, _, _>::save_with_expected_version(
+            &self.repository,
+            events,
+            expected_version,
+        )
+        .await
+    }
+}
+
+#[tokio::test]
+async fn concurrent_modification_test() {
+    let cache = Arc::new(LruStateCache::new(10));
+    let concurrent_event = Arc::new(Mutex::new(None));
+    let aggregate = EventSourcedCachingAggregate::new(
+        ConcurrentlyModifiedEventRepository {
+            repository: InMemoryEventRepository::new(),
+            concurrent_event: Arc::clone(&concurrent_event),
+        },
+        Arc::clone(&cache),
+        decider(),
+    );
+    aggregate.handle(&create_command(1)).await.unwrap();
+
+    // The event is saved by another process, after the cached state is replayed
+    *concurrent_event.lock().unwrap() = Some(OrderEvent::Updated(OrderUpdatedEvent {
+        order_id: 1,
+        updated_items: vec!["Item 2".to_string()],
+    }));
+    let result = aggregate.handle(&update_command(1, "Item 3")).await;
+    assert!(matches!(result, Err(AggregateError::ConcurrencyError(_))));
+
+    // The stale state is not cached, so the concurrent event is not lost
+    let cached: Option<(OrderState, u64)> =
+        StateCache::<_, _, AggregateError>::get(cache.as_ref(), "1")
+            .await
+            .unwrap();
+    assert_eq!(cached, None);
+    let state = aggregate
+        .fetch_current_state(&create_command(1))
+        .await
+        .unwrap();
+    assert_eq!(state.items, vec!["Item 2".to_string()]);
+}
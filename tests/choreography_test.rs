@@ -0,0 +1,206 @@
+use fmodel_rust::choreography::{Choreography, Reaction};
+use fmodel_rust::saga::Saga;
+
+use crate::api::{
+    CreateShipmentCommand, OrderCancelledEvent, OrderCreatedEvent, OrderEvent, OrderUpdatedEvent,
+    ShipmentCommand, ShipmentCreatedEvent, UpdateOrderCommand,
+};
+use crate::application::{Command, Event};
+
+mod api;
+mod application;
+
+fn order_saga<'a>() -> Saga<'a, Event, Command> {
+    Saga {
+        react: Box::new(|event| match event {
+            Event::OrderCreated(evt) => Ok(vec![Command::ShipmentCreate(CreateShipmentCommand {
+                shipment_id: evt.order_id,
+                order_id: evt.order_id,
+                customer_name: evt.customer_name.to_owned(),
+                items: evt.items.to_owned(),
+            })]),
+            _ => Ok(vec![]),
+        }),
+    }
+}
+
+fn shipment_saga<'a>() -> Saga<'a, Event, Command> {
+    Saga {
+        react: Box::new(|event| match event {
+            Event::ShipmentCreated(evt) => Ok(vec![Command::OrderUpdate(UpdateOrderCommand {
+                order_id: evt.order_id,
+                new_items: evt.items.to_owned(),
+            })]),
+            _ => Ok(vec![]),
+        }),
+    }
+}
+
+fn event_name(event: &Event) -> String {
+    match event {
+        Event::ShipmentCreated(_) => "ShipmentCreated",
+        Event::OrderCreated(_) => "OrderCreated",
+        Event::OrderUpdated(_) => "OrderUpdated",
+        Event::OrderCancelled(_) => "OrderCancelled",
+    }
+    .to_string()
+}
+
+fn command_name(command: &Command) -> String {
+    match command {
+        Command::ShipmentCreate(_) => "CreateShipment",
+        Command::OrderCreate(_) => "CreateOrder",
+        Command::OrderUpdate(_) => "UpdateOrder",
+        Command::OrderCancel(_) => "CancelOrder",
+    }
+    .to_string()
+}
+
+fn samples() -> Vec<Event> {
+    vec![
+        Event::OrderCreated(OrderCreatedEvent {
+            order_id: 1,
+            customer_name: "John Doe".to_string(),
+            items: vec!["Item 1".to_string()],
+        }),
+        Event::OrderCreated(OrderCreatedEvent {
+            order_id: 2,
+            customer_name: "John Doe".to_string(),
+            items: vec!["Item 2".to_string()],
+        }),
+        Event::OrderUpdated(OrderUpdatedEvent {
+            order_id: 1,
+            updated_items: vec!["Item 3".to_string()],
+        }),
+        Event::OrderCancelled(OrderCancelledEvent { order_id: 1 }),
+        Event::ShipmentCreated(ShipmentCreatedEvent {
+            shipment_id: 1,
+            order_id: 1,
+            customer_name: "John Doe".to_string(),
+            items: vec!["Item 1".to_string()],
+        }),
+    ]
+}
+
+#[test]
+fn test() {
+    let choreography = Choreography::new()
+        .record(&order_saga(), &samples(), &event_name, &command_name)
+        .record(&shipment_saga(), &samples(), &event_name, &command_name);
+
+    // The same reaction is recorded once
+    assert_eq!(
+        choreography.reactions(),
+        [
+            Reaction {
+                action_result: "OrderCreated".to_string(),
+                action: "CreateShipment".to_string(),
+            },
+            Reaction {
+                action_result: "ShipmentCreated".to_string(),
+                action: "UpdateOrder".to_string(),
+            },
+        ]
+    );
+    assert_eq!(
+        choreography.to_mermaid(),
+        "flowchart LR
+    n0[\"OrderCreated\"]
+    n1[\"CreateShipment\"]
+    n2[\"OrderUpdated\"]
+    n3[\"OrderCancelled\"]
+    n4[\"ShipmentCreated\"]
+    n5[\"UpdateOrder\"]
+    n0 --> n1
+    n4 --> n5
+"
+    );
+    assert_eq!(
+        choreography.to_graphviz(),
+        "digraph {
+    rankdir=LR;
+    n0 [label=\"OrderCreated\"];
+    n1 [label=\"CreateShipment\"];
+    n2 [label=\"OrderUpdated\"];
+    n3 [label=\"OrderCancelled\"];
+    n4 [label=\"ShipmentCreated\"];
+    n5 [label=\"UpdateOrder\"];
+    n0 -> n1;
+    n4 -> n5;
+}
+"
+    );
+}
+
+#[test]
+fn typed_sagas_test() {
+    // The sagas of the different types are recorded into the same choreography
+    let saga: Saga<OrderEvent, ShipmentCommand> = Saga {
+        react: Box::new(|event| match event {
+            OrderEvent::Created(evt) => Ok(vec![ShipmentCommand::Create(CreateShipmentCommand {
+                shipment_id: evt.order_id,
+                order_id: evt.order_id,
+                customer_name: evt.customer_name.to_owned(),
+                items: evt.items.to_owned(),
+            })]),
+            _ => Err(()),
+        }),
+    };
+    let choreography = Choreography::new().record(
+        &saga,
+        &[
+            OrderEvent::Created(OrderCreatedEvent {
+                order_id: 1,
+                customer_name: "John Doe".to_string(),
+                items: vec!["Item 1".to_string()],
+            }),
+            OrderEvent::Cancelled(OrderCancelledEvent { order_id: 1 }),
+        ],
+        &|event| {
+            format!("{:?}", event)
+                .split('(')
+                .next()
+                .unwrap()
+                .to_string()
+        },
+        &|_command: &ShipmentCommand| "CreateShipment".to_string(),
+    );
+    // The failed reaction is not recorded
+    assert_eq!(
+        choreography.reactions(),
+        [Reaction {
+            action_result: "Created".to_string(),
+            action: "CreateShipment".to_string(),
+        }]
+    );
+}
+
+#[test]
+fn quoted_names_test() {
+    let choreography = Choreography::new().record(
+        &order_saga(),
+        &samples()[..1],
+        &|_event: &Event| "Order \"Created\"".to_string(),
+        &command_name,
+    );
+
+    // The double quotes are escaped the way each renderer supports
+    assert_eq!(
+        choreography.to_mermaid(),
+        "flowchart LR
+    n0[\"Order #quot;Created#quot;\"]
+    n1[\"CreateShipment\"]
+    n0 --> n1
+"
+    );
+    assert_eq!(
+        choreography.to_graphviz(),
+        "digraph {
+    rankdir=LR;
+    n0 [label=\"Order \\\"Created\\\"\"];
+    n1 [label=\"CreateShipment\"];
+    n0 -> n1;
+}
+"
+    );
+}
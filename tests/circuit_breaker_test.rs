@@ -0,0 +1,194 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use fmodel_rust::circuit_breaker::{
+    CircuitBreaker, CircuitBreakerPolicy, CircuitOpen, CircuitState,
+};
+use fmodel_rust::metrics::{
+    MetricsRecorder, CIRCUIT_BREAKER_CLOSED, CIRCUIT_BREAKER_HALF_OPENED, CIRCUIT_BREAKER_OPENED,
+};
+use fmodel_rust::retry::TransientError;
+use fmodel_rust::saga_manager::ActionPublisher;
+
+use crate::api::{CreateShipmentCommand, ShipmentCommand};
+use crate::application::SagaManagerError;
+
+mod api;
+mod application;
+
+impl TransientError for SagaManagerError {
+    fn is_transient(&self) -> bool {
+        matches!(self, SagaManagerError::PublishAction(_))
+    }
+}
+
+impl From<CircuitOpen> for SagaManagerError {
+    fn from(error: CircuitOpen) -> Self {
+        SagaManagerError::DomainError(error.to_string())
+    }
+}
+
+/// A simple in-memory metrics recorder, counting the state changes of the circuit - infrastructure
+#[derive(Default)]
+struct InMemoryMetricsRecorder {
+    counters: Mutex<HashMap<&'static str, u64>>,
+}
+
+impl InMemoryMetricsRecorder {
+    fn counter(&self, name: &'static str) -> u64 {
+        self.counters
+            .lock()
+            .unwrap()
+            .get(name)
+            .copied()
+            .unwrap_or(0)
+    }
+}
+
+impl MetricsRecorder for InMemoryMetricsRecorder {
+    fn increment_counter(&self, name: &'static str, value: u64) {
+        *self.counters.lock().unwrap().entry(name).or_default() += value;
+    }
+
+    fn record_histogram(&self, _name: &'static str, _value: f64) {}
+}
+
+/// An action publisher that is failing while the broker is down, and counting the publications - infrastructure
+#[derive(Default, Clone)]
+struct BrokerActionPublisher {
+    down: Arc<Mutex<bool>>,
+    publications: Arc<Mutex<usize>>,
+}
+
+impl ActionPublisher<ShipmentCommand, SagaManagerError> for BrokerActionPublisher {
+    async fn publish(
+        &self,
+        action: &[ShipmentCommand],
+    ) -> Result<Vec<ShipmentCommand>, SagaManagerError> {
+        *self.publications.lock().unwrap() += 1;
+        // The network round trip, so the concurrent publications interleave
+        tokio::task::yield_now().await;
+        if *self.down.lock().unwrap() {
+            return Err(SagaManagerError::PublishAction(
+                "Broker unavailable".to_string(),
+            ));
+        }
+        Ok(Vec::from(action))
+    }
+}
+
+fn actions() -> Vec<ShipmentCommand> {
+    vec![ShipmentCommand::Create(CreateShipmentCommand {
+        shipment_id: 1,
+        order_id: 1,
+        customer_name: "John Doe".to_string(),
+        items: vec!["Item 1".to_string()],
+    })]
+}
+
+fn policy(open_duration: Duration) -> CircuitBreakerPolicy {
+    CircuitBreakerPolicy {
+        window_size: 4,
+        minimum_calls: 2,
+        failure_rate_threshold: 0.5,
+        open_duration,
+    }
+}
+
+#[tokio::test]
+async fn open_test() {
+    let publisher = BrokerActionPublisher::default();
+    *publisher.down.lock().unwrap() = true;
+    let recorder = InMemoryMetricsRecorder::default();
+    let circuit_breaker = CircuitBreaker::new(
+        publisher.clone(),
+        policy(Duration::from_secs(60)),
+        &recorder,
+    );
+
+    assert!(circuit_breaker.publish(&actions()).await.is_err());
+    assert_eq!(circuit_breaker.state(), CircuitState::Closed);
+    assert!(circuit_breaker.publish(&actions()).await.is_err());
+    assert_eq!(circuit_breaker.state(), CircuitState::Open);
+    assert_eq!(recorder.counter(CIRCUIT_BREAKER_OPENED), 1);
+
+    // The open circuit fails fast, without calling the broker
+    let result = circuit_breaker.publish(&actions()).await;
+    assert!(matches!(result, Err(SagaManagerError::DomainError(_))));
+    assert_eq!(*publisher.publications.lock().unwrap(), 2);
+}
+
+#[tokio::test]
+async fn half_open_test() {
+    let publisher = BrokerActionPublisher::default();
+    *publisher.down.lock().unwrap() = true;
+    let recorder = InMemoryMetricsRecorder::default();
+    let circuit_breaker = CircuitBreaker::new(publisher.clone(), policy(Duration::ZERO), &recorder);
+
+    assert!(circuit_breaker.publish(&actions()).await.is_err());
+    assert!(circuit_breaker.publish(&actions()).await.is_err());
+    assert_eq!(circuit_breaker.state(), CircuitState::Open);
+
+    // The trial call fails, and the circuit is reopened
+    assert!(circuit_breaker.publish(&actions()).await.is_err());
+    assert_eq!(circuit_breaker.state(), CircuitState::Open);
+    assert_eq!(recorder.counter(CIRCUIT_BREAKER_HALF_OPENED), 1);
+    assert_eq!(recorder.counter(CIRCUIT_BREAKER_OPENED), 2);
+
+    // The broker recovers, the trial call succeeds, and the circuit is closed
+    *publisher.down.lock().unwrap() = false;
+    assert_eq!(
+        circuit_breaker.publish(&actions()).await.unwrap(),
+        actions()
+    );
+    assert_eq!(circuit_breaker.state(), CircuitState::Closed);
+    assert_eq!(recorder.counter(CIRCUIT_BREAKER_HALF_OPENED), 2);
+    assert_eq!(recorder.counter(CIRCUIT_BREAKER_CLOSED), 1);
+}
+
+#[tokio::test]
+async fn half_open_probe_test() {
+    let publisher = BrokerActionPublisher::default();
+    *publisher.down.lock().unwrap() = true;
+    let recorder = InMemoryMetricsRecorder::default();
+    let circuit_breaker = CircuitBreaker::new(publisher.clone(), policy(Duration::ZERO), &recorder);
+
+    assert!(circuit_breaker.publish(&actions()).await.is_err());
+    assert!(circuit_breaker.publish(&actions()).await.is_err());
+    assert_eq!(circuit_breaker.state(), CircuitState::Open);
+
+    // Only the single probing call reaches the recovered broker, the concurrent call is rejected
+    *publisher.down.lock().unwrap() = false;
+    let actions = actions();
+    let (probe, concurrent) = tokio::join!(
+        circuit_breaker.publish(&actions),
+        circuit_breaker.publish(&actions)
+    );
+    assert_eq!(probe.unwrap(), actions);
+    assert!(matches!(concurrent, Err(SagaManagerError::DomainError(_))));
+    assert_eq!(*publisher.publications.lock().unwrap(), 3);
+    assert_eq!(circuit_breaker.state(), CircuitState::Closed);
+}
+
+#[tokio::test]
+async fn failure_rate_test() {
+    let publisher = BrokerActionPublisher::default();
+    let recorder = InMemoryMetricsRecorder::default();
+    let circuit_breaker = CircuitBreaker::new(
+        publisher.clone(),
+        policy(Duration::from_secs(60)),
+        &recorder,
+    );
+
+    // One failure out of three calls is below the threshold
+    assert!(circuit_breaker.publish(&actions()).await.is_ok());
+    assert!(circuit_breaker.publish(&actions()).await.is_ok());
+    *publisher.down.lock().unwrap() = true;
+    assert!(circuit_breaker.publish(&actions()).await.is_err());
+    assert_eq!(circuit_breaker.state(), CircuitState::Closed);
+
+    // Two failures out of four calls reach the threshold
+    assert!(circuit_breaker.publish(&actions()).await.is_err());
+    assert_eq!(circuit_breaker.state(), CircuitState::Open);
+}
@@ -0,0 +1,199 @@
+use std::sync::Mutex;
+
+use fmodel_rust::aggregate::{EventSourcedAggregate, StateRepository, StateStoredAggregate};
+use fmodel_rust::command_bus::{CommandBus, CommandHandler, UnhandledCommand};
+use fmodel_rust::decider::Decider;
+use fmodel_rust::in_memory::InMemoryEventRepository;
+use fmodel_rust::{Sum, Variant};
+
+use crate::api::{
+    CreateOrderCommand, CreateShipmentCommand, OrderCommand, OrderCreatedEvent, OrderEvent,
+    OrderState, ShipmentCommand, ShipmentCreatedEvent, ShipmentEvent, ShipmentState,
+};
+use crate::application::AggregateError;
+
+mod api;
+mod application;
+
+/// The command of the whole application, routed by the command bus
+#[derive(Debug, Clone)]
+enum AppCommand {
+    Order(OrderCommand),
+    Shipment(ShipmentCommand),
+    Audit,
+}
+
+impl Variant<AppCommand> for OrderCommand {
+    fn from_variant(value: &AppCommand) -> Option<&Self> {
+        match value {
+            AppCommand::Order(cmd) => Some(cmd),
+            _ => None,
+        }
+    }
+}
+
+impl Variant<AppCommand> for ShipmentCommand {
+    fn from_variant(value: &AppCommand) -> Option<&Self> {
+        match value {
+            AppCommand::Shipment(cmd) => Some(cmd),
+            _ => None,
+        }
+    }
+}
+
+impl From<UnhandledCommand> for AggregateError {
+    fn from(error: UnhandledCommand) -> Self {
+        AggregateError::DomainError(error.to_string())
+    }
+}
+
+fn order_decider<'a>() -> Decider<'a, OrderCommand, OrderState, OrderEvent, AggregateError> {
+    Decider {
+        decide: Box::new(|command, _state| match command {
+            OrderCommand::Create(cmd) => Ok(vec![OrderEvent::Created(OrderCreatedEvent {
+                order_id: cmd.order_id,
+                customer_name: cmd.customer_name.to_owned(),
+                items: cmd.items.to_owned(),
+            })]),
+            _ => Ok(vec![]),
+        }),
+        evolve: Box::new(|state, event| {
+            let mut new_state = state.clone();
+            if let OrderEvent::Created(evt) = event {
+                new_state.order_id = evt.order_id;
+            }
+            new_state
+        }),
+        initial_state: Box::new(|| OrderState {
+            order_id: 0,
+            customer_name: "".to_string(),
+            items: Vec::new(),
+            is_cancelled: false,
+        }),
+    }
+}
+
+fn shipment_decider<'a>(
+) -> Decider<'a, ShipmentCommand, ShipmentState, ShipmentEvent, AggregateError> {
+    Decider {
+        decide: Box::new(|command, _state| match command {
+            ShipmentCommand::Create(cmd) => {
+                Ok(vec![ShipmentEvent::Created(ShipmentCreatedEvent {
+                    shipment_id: cmd.shipment_id,
+                    order_id: cmd.order_id,
+                    customer_name: cmd.customer_name.to_owned(),
+                    items: cmd.items.to_owned(),
+                })])
+            }
+        }),
+        evolve: Box::new(|_state, event| match event {
+            ShipmentEvent::Created(evt) => ShipmentState {
+                shipment_id: evt.shipment_id,
+                order_id: evt.order_id,
+                customer_name: evt.customer_name.to_owned(),
+                items: evt.items.to_owned(),
+            },
+        }),
+        initial_state: Box::new(|| ShipmentState {
+            shipment_id: 0,
+            order_id: 0,
+            customer_name: "".to_string(),
+            items: Vec::new(),
+        }),
+    }
+}
+
+/// A simple in-memory state repository of the shipments - infrastructure
+#[derive(Default)]
+struct InMemoryShipmentStateRepository {
+    states: Mutex<Vec<(ShipmentState, i32)>>,
+}
+
+impl StateRepository<ShipmentCommand, ShipmentState, i32, AggregateError>
+    for InMemoryShipmentStateRepository
+{
+    async fn fetch_state(
+        &self,
+        command: &ShipmentCommand,
+    ) -> Result<Option<(ShipmentState, i32)>, AggregateError> {
+        let ShipmentCommand::Create(cmd) = command;
+        Ok(self
+            .states
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|(state, _)| state.shipment_id == cmd.shipment_id)
+            .cloned())
+    }
+
+    async fn save(
+        &self,
+        state: &ShipmentState,
+        version: &Option<i32>,
+    ) -> Result<(ShipmentState, i32), AggregateError> {
+        let version = version.map_or(0, |version| version + 1);
+        self.states.lock().unwrap().push((state.clone(), version));
+        Ok((state.clone(), version))
+    }
+}
+
+type AppOutput = Sum<Vec<(OrderEvent, u64)>, (ShipmentState, i32)>;
+
+#[tokio::test]
+async fn test() {
+    let bus: CommandBus<AppCommand, AppOutput, AggregateError> = CommandBus::new()
+        .register(
+            EventSourcedAggregate::new(InMemoryEventRepository::new(), order_decider()),
+            &Sum::First,
+        )
+        .register(
+            StateStoredAggregate::new(
+                InMemoryShipmentStateRepository::default(),
+                shipment_decider(),
+            ),
+            &Sum::Second,
+        );
+
+    let result = bus
+        .dispatch(&AppCommand::Order(OrderCommand::Create(
+            CreateOrderCommand {
+                order_id: 1,
+                customer_name: "John Doe".to_string(),
+                items: vec!["Item 1".to_string()],
+            },
+        )))
+        .await;
+    assert!(matches!(
+        result,
+        Ok(Sum::First(events)) if events == vec![(OrderEvent::Created(OrderCreatedEvent {
+            order_id: 1,
+            customer_name: "John Doe".to_string(),
+            items: vec!["Item 1".to_string()],
+        }), 0)]
+    ));
+
+    let shipment_command = AppCommand::Shipment(ShipmentCommand::Create(CreateShipmentCommand {
+        shipment_id: 2,
+        order_id: 1,
+        customer_name: "John Doe".to_string(),
+        items: vec!["Item 1".to_string()],
+    }));
+    // The bus is a command handler itself
+    let result = bus.handle_command(&shipment_command).await;
+    assert!(matches!(
+        result,
+        Ok(Sum::Second((state, 0))) if state == ShipmentState {
+            shipment_id: 2,
+            order_id: 1,
+            customer_name: "John Doe".to_string(),
+            items: vec!["Item 1".to_string()],
+        }
+    ));
+
+    // No handler is registered for the audit commands
+    let result = bus.dispatch(&AppCommand::Audit).await;
+    assert!(matches!(
+        result,
+        Err(AggregateError::DomainError(message)) if message == UnhandledCommand.to_string()
+    ));
+}
@@ -0,0 +1,162 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use fmodel_rust::dead_letter::{DeadLetter, DeadLetterRepository};
+use fmodel_rust::materialized_view::{DeadLetteringMaterializedView, ViewStateRepository};
+use fmodel_rust::view::{FallibleView, FallibleViewStateComputation, View};
+use fmodel_rust::Identifier;
+
+use crate::api::{
+    OrderCancelledEvent, OrderCreatedEvent, OrderEvent, OrderUpdatedEvent, OrderViewState,
+};
+
+mod api;
+mod application;
+
+/// The view is failing to apply the events of the orders that are not created (yet)
+fn view<'a>() -> FallibleView<'a, OrderViewState, OrderEvent, String> {
+    FallibleView {
+        evolve: Box::new(|state, event| {
+            let mut new_state = state.clone();
+            match event {
+                OrderEvent::Created(evt) => {
+                    new_state.order_id = evt.order_id;
+                    new_state.customer_name = evt.customer_name.to_owned();
+                    new_state.items = evt.items.to_owned();
+                }
+                OrderEvent::Updated(evt) if state.order_id == evt.order_id => {
+                    new_state.items = evt.updated_items.to_owned();
+                }
+                OrderEvent::Cancelled(evt) if state.order_id == evt.order_id => {
+                    new_state.is_cancelled = true;
+                }
+                event => return Err(format!("Order {} is not created", event.identifier())),
+            }
+            Ok(new_state)
+        }),
+        initial_state: Box::new(|| OrderViewState {
+            order_id: 0,
+            customer_name: "".to_string(),
+            items: Vec::new(),
+            is_cancelled: false,
+        }),
+    }
+}
+
+/// A simple in-memory view state repository - infrastructure
+#[derive(Default)]
+struct InMemoryViewOrderStateRepository {
+    states: Mutex<HashMap<u32, OrderViewState>>,
+}
+
+impl ViewStateRepository<OrderEvent, OrderViewState, String> for InMemoryViewOrderStateRepository {
+    async fn fetch_state(&self, event: &OrderEvent) -> Result<Option<OrderViewState>, String> {
+        Ok(self
+            .states
+            .lock()
+            .unwrap()
+            .get(&event.identifier().parse::<u32>().unwrap())
+            .cloned())
+    }
+
+    async fn save(&self, state: &OrderViewState) -> Result<OrderViewState, String> {
+        self.states
+            .lock()
+            .unwrap()
+            .insert(state.order_id, state.clone());
+        Ok(state.clone())
+    }
+}
+
+/// A simple in-memory dead letter queue - infrastructure
+#[derive(Default)]
+struct InMemoryDeadLetterQueue {
+    dead_letters: Mutex<Vec<DeadLetter<OrderEvent, String>>>,
+}
+
+impl DeadLetterRepository<OrderEvent, String> for &InMemoryDeadLetterQueue {
+    async fn park(&self, dead_letter: DeadLetter<OrderEvent, String>) -> Result<(), String> {
+        self.dead_letters.lock().unwrap().push(dead_letter);
+        Ok(())
+    }
+
+    async fn fetch_dead_letters(
+        &self,
+        limit: usize,
+    ) -> Result<Vec<DeadLetter<OrderEvent, String>>, String> {
+        Ok(self
+            .dead_letters
+            .lock()
+            .unwrap()
+            .iter()
+            .take(limit)
+            .cloned()
+            .collect())
+    }
+
+    async fn acknowledge(
+        &self,
+        dead_letter: &DeadLetter<OrderEvent, String>,
+    ) -> Result<(), String> {
+        self.dead_letters
+            .lock()
+            .unwrap()
+            .retain(|parked| parked != dead_letter);
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn test() {
+    let dead_letter_queue = InMemoryDeadLetterQueue::default();
+    let materialized_view = DeadLetteringMaterializedView::new(
+        InMemoryViewOrderStateRepository::default(),
+        view(),
+        &dead_letter_queue,
+    );
+
+    let orphan_event = OrderEvent::Updated(OrderUpdatedEvent {
+        order_id: 2,
+        updated_items: vec!["Item 2".to_string()],
+    });
+    let result = materialized_view.handle(&orphan_event).await;
+    assert_eq!(result, Ok(None));
+    assert_eq!(
+        *dead_letter_queue.dead_letters.lock().unwrap(),
+        [DeadLetter {
+            message: orphan_event,
+            error: "Order 2 is not created".to_string(),
+        }]
+    );
+
+    // The processing continues with the next events
+    let result = materialized_view
+        .handle(&OrderEvent::Created(OrderCreatedEvent {
+            order_id: 1,
+            customer_name: "John Doe".to_string(),
+            items: vec!["Item 1".to_string()],
+        }))
+        .await;
+    assert!(result.unwrap().is_some());
+    let result = materialized_view
+        .handle(&OrderEvent::Cancelled(OrderCancelledEvent { order_id: 1 }))
+        .await;
+    assert_eq!(
+        result,
+        Ok(Some(OrderViewState {
+            order_id: 1,
+            customer_name: "John Doe".to_string(),
+            items: vec!["Item 1".to_string()],
+            is_cancelled: true,
+        }))
+    );
+}
+
+#[test]
+fn from_view_test() {
+    let view: FallibleView<u32, u32, String> = FallibleView::from(View {
+        evolve: Box::new(|state: &u32, event: &u32| state + event),
+        initial_state: Box::new(|| 0),
+    });
+    assert_eq!(view.try_compute_new_state(None, &[&1, &2]), Ok(3));
+}
@@ -0,0 +1,173 @@
+use fmodel_rust::decider::{Decider, EventComputation, StateComputation};
+use fmodel_rust::decider_builder::DeciderBuilder;
+use fmodel_rust::Variant;
+
+use crate::api::{
+    CancelOrderCommand, CreateOrderCommand, OrderCancelledEvent, OrderCommand, OrderCreatedEvent,
+    OrderEvent, OrderState, OrderUpdatedEvent, UpdateOrderCommand,
+};
+
+mod api;
+mod application;
+
+impl Variant<OrderCommand> for CreateOrderCommand {
+    fn from_variant(value: &OrderCommand) -> Option<&Self> {
+        match value {
+            OrderCommand::Create(cmd) => Some(cmd),
+            _ => None,
+        }
+    }
+}
+
+impl Variant<OrderCommand> for CancelOrderCommand {
+    fn from_variant(value: &OrderCommand) -> Option<&Self> {
+        match value {
+            OrderCommand::Cancel(cmd) => Some(cmd),
+            _ => None,
+        }
+    }
+}
+
+impl Variant<OrderEvent> for OrderCreatedEvent {
+    fn from_variant(value: &OrderEvent) -> Option<&Self> {
+        match value {
+            OrderEvent::Created(evt) => Some(evt),
+            _ => None,
+        }
+    }
+}
+
+impl Variant<OrderEvent> for OrderCancelledEvent {
+    fn from_variant(value: &OrderEvent) -> Option<&Self> {
+        match value {
+            OrderEvent::Cancelled(evt) => Some(evt),
+            _ => None,
+        }
+    }
+}
+
+fn decider<'a>() -> Decider<'a, OrderCommand, OrderState, OrderEvent, String> {
+    DeciderBuilder::new(|| OrderState {
+        order_id: 0,
+        customer_name: "".to_string(),
+        items: Vec::new(),
+        is_cancelled: false,
+    })
+    .when(|cmd: &CreateOrderCommand, _state: &OrderState| {
+        Ok(vec![OrderEvent::Created(OrderCreatedEvent {
+            order_id: cmd.order_id,
+            customer_name: cmd.customer_name.to_owned(),
+            items: cmd.items.to_owned(),
+        })])
+    })
+    .when(|cmd: &CancelOrderCommand, state: &OrderState| {
+        if state.order_id == cmd.order_id {
+            Ok(vec![OrderEvent::Cancelled(OrderCancelledEvent {
+                order_id: cmd.order_id,
+            })])
+        } else {
+            Err(format!("Order {} does not exist", cmd.order_id))
+        }
+    })
+    .evolve_when(|_state: &OrderState, evt: &OrderCreatedEvent| OrderState {
+        order_id: evt.order_id,
+        customer_name: evt.customer_name.to_owned(),
+        items: evt.items.to_owned(),
+        is_cancelled: false,
+    })
+    .evolve_when(
+        |state: &OrderState, _evt: &OrderCancelledEvent| OrderState {
+            is_cancelled: true,
+            ..state.clone()
+        },
+    )
+    .build()
+}
+
+#[test]
+fn test() {
+    let decider = decider();
+    let order_created_event = OrderEvent::Created(OrderCreatedEvent {
+        order_id: 1,
+        customer_name: "John Doe".to_string(),
+        items: vec!["Item 1".to_string()],
+    });
+
+    let new_events = decider.compute_new_events(
+        &[],
+        &OrderCommand::Create(CreateOrderCommand {
+            order_id: 1,
+            customer_name: "John Doe".to_string(),
+            items: vec!["Item 1".to_string()],
+        }),
+    );
+    assert_eq!(new_events, Ok(vec![order_created_event.clone()]));
+
+    let cancel_command = OrderCommand::Cancel(CancelOrderCommand { order_id: 1 });
+    let new_events =
+        decider.compute_new_events(std::slice::from_ref(&order_created_event), &cancel_command);
+    assert_eq!(
+        new_events,
+        Ok(vec![OrderEvent::Cancelled(OrderCancelledEvent {
+            order_id: 1
+        })])
+    );
+    let new_events = decider.compute_new_events(&[], &cancel_command);
+    assert_eq!(new_events, Err("Order 1 does not exist".to_string()));
+
+    // No handler is registered for the update command / updated event
+    let update_command = OrderCommand::Update(UpdateOrderCommand {
+        order_id: 1,
+        new_items: vec!["Item 2".to_string()],
+    });
+    assert_eq!(
+        decider.compute_new_events(std::slice::from_ref(&order_created_event), &update_command),
+        Ok(vec![])
+    );
+    let state = decider.fold_to_state(&[
+        order_created_event,
+        OrderEvent::Updated(OrderUpdatedEvent {
+            order_id: 1,
+            updated_items: vec!["Item 2".to_string()],
+        }),
+        OrderEvent::Cancelled(OrderCancelledEvent { order_id: 1 }),
+    ]);
+    assert_eq!(
+        state,
+        OrderState {
+            order_id: 1,
+            customer_name: "John Doe".to_string(),
+            items: vec!["Item 1".to_string()],
+            is_cancelled: true,
+        }
+    );
+    assert_eq!(
+        decider.compute_new_state(Some(state.clone()), &update_command),
+        Ok(state)
+    );
+}
+
+#[test]
+fn on_command_test() {
+    // The catch-all handler is used for the commands that are not handled by the variant handlers
+    let decider: Decider<OrderCommand, u32, u32> = DeciderBuilder::new(|| 0)
+        .when(|cmd: &CreateOrderCommand, _state: &u32| Ok(vec![cmd.order_id]))
+        .on_command(|_cmd: &OrderCommand, state: &u32| Ok(vec![*state + 100]))
+        .on_event(|_state: &u32, event: &u32| *event)
+        .build();
+
+    let events = decider.compute_new_events(
+        &[],
+        &OrderCommand::Create(CreateOrderCommand {
+            order_id: 1,
+            customer_name: "John Doe".to_string(),
+            items: vec![],
+        }),
+    );
+    assert_eq!(events, Ok(vec![1]));
+    let events = decider.compute_new_events(
+        &[7],
+        &OrderCommand::Cancel(CancelOrderCommand { order_id: 1 }),
+    );
+    assert_eq!(events, Ok(vec![107]));
+}
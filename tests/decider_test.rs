@@ -1,4 +1,12 @@
-use fmodel_rust::decider::{Decider, EventComputation, StateComputation};
+use std::sync::atomic::{AtomicUsize, Ordering};
+#[cfg(feature = "send")]
+use std::sync::Arc;
+#[cfg(feature = "send")]
+use std::thread;
+
+use fmodel_rust::decider::{Decider, EventComputation, FoldingDecider, StateComputation};
+use fmodel_rust::view::{View, ViewStateComputation};
+use fmodel_rust::{Sum, Sum3};
 
 use crate::api::{
     CancelOrderCommand, CreateOrderCommand, CreateShipmentCommand, OrderCancelledEvent,
@@ -213,3 +221,356 @@ fn test() {
         })
     );
 }
+
+#[test]
+fn combine3_test() {
+    // Decider<Sum3<OrderCommand, ShipmentCommand, OrderCommand>, (OrderState, ShipmentState, OrderState), Sum3<OrderEvent, ShipmentEvent, OrderEvent>>
+    let combined_decider = order_decider().combine3(shipment_decider(), order_decider());
+
+    let create_shipment_command = Sum3::Second(ShipmentCommand::Create(CreateShipmentCommand {
+        shipment_id: 1,
+        order_id: 1,
+        customer_name: "John Doe".to_string(),
+        items: vec!["Item 1".to_string(), "Item 2".to_string()],
+    }));
+    let new_events = combined_decider.compute_new_events(&[], &create_shipment_command);
+    assert_eq!(
+        new_events,
+        Ok(vec![Sum3::Second(ShipmentEvent::Created(
+            ShipmentCreatedEvent {
+                shipment_id: 1,
+                order_id: 1,
+                customer_name: "John Doe".to_string(),
+                items: vec!["Item 1".to_string(), "Item 2".to_string()],
+            }
+        ))])
+    );
+
+    let create_order_command = Sum3::Third(OrderCommand::Create(CreateOrderCommand {
+        order_id: 1,
+        customer_name: "John Doe".to_string(),
+        items: vec!["Item 1".to_string(), "Item 2".to_string()],
+    }));
+    let new_state = combined_decider.compute_new_state(None, &create_order_command);
+    assert_eq!(
+        new_state,
+        Ok((
+            OrderState {
+                order_id: 0,
+                customer_name: "".to_string(),
+                items: Vec::new(),
+                is_cancelled: false,
+            },
+            ShipmentState {
+                shipment_id: 0,
+                order_id: 0,
+                customer_name: "".to_string(),
+                items: Vec::new(),
+            },
+            OrderState {
+                order_id: 1,
+                customer_name: "John Doe".to_string(),
+                items: vec!["Item 1".to_string(), "Item 2".to_string()],
+                is_cancelled: false,
+            }
+        ))
+    );
+}
+
+#[test]
+fn merge_test() {
+    // Decider<Sum<OrderCommand, OrderCommand>, OrderState, Sum<OrderEvent, OrderEvent>>
+    let merged_decider = order_decider().merge(order_decider());
+
+    let create_order_command = Sum::Second(OrderCommand::Create(CreateOrderCommand {
+        order_id: 1,
+        customer_name: "John Doe".to_string(),
+        items: vec!["Item 1".to_string(), "Item 2".to_string()],
+    }));
+    let new_events = merged_decider.compute_new_events(&[], &create_order_command);
+    assert_eq!(
+        new_events,
+        Ok(vec![Sum::Second(OrderEvent::Created(OrderCreatedEvent {
+            order_id: 1,
+            customer_name: "John Doe".to_string(),
+            items: vec!["Item 1".to_string(), "Item 2".to_string()],
+        }))])
+    );
+
+    let cancel_command = Sum::First(OrderCommand::Cancel(CancelOrderCommand { order_id: 1 }));
+    let new_state = merged_decider
+        .compute_new_state(None, &create_order_command)
+        .and_then(|state| merged_decider.compute_new_state(Some(state), &cancel_command));
+    assert_eq!(
+        new_state,
+        Ok(OrderState {
+            order_id: 1,
+            customer_name: "John Doe".to_string(),
+            items: vec!["Item 1".to_string(), "Item 2".to_string()],
+            is_cancelled: true,
+        })
+    );
+}
+
+#[test]
+fn fold_to_state_test() {
+    let decider = order_decider();
+    assert_eq!(
+        decider.fold_to_state(&[]),
+        OrderState {
+            order_id: 0,
+            customer_name: "".to_string(),
+            items: Vec::new(),
+            is_cancelled: false,
+        }
+    );
+    let events = vec![
+        OrderEvent::Created(OrderCreatedEvent {
+            order_id: 1,
+            customer_name: "John Doe".to_string(),
+            items: vec!["Item 1".to_string(), "Item 2".to_string()],
+        }),
+        OrderEvent::Updated(OrderUpdatedEvent {
+            order_id: 1,
+            updated_items: vec!["Item 3".to_string()],
+        }),
+        OrderEvent::Cancelled(OrderCancelledEvent { order_id: 1 }),
+    ];
+    assert_eq!(
+        decider.fold_to_state(&events),
+        OrderState {
+            order_id: 1,
+            customer_name: "John Doe".to_string(),
+            items: vec!["Item 3".to_string()],
+            is_cancelled: true,
+        }
+    );
+}
+
+#[test]
+fn to_view_test() {
+    let events = vec![
+        OrderEvent::Created(OrderCreatedEvent {
+            order_id: 1,
+            customer_name: "John Doe".to_string(),
+            items: vec!["Item 1".to_string(), "Item 2".to_string()],
+        }),
+        OrderEvent::Cancelled(OrderCancelledEvent { order_id: 1 }),
+    ];
+    let expected_state = order_decider().fold_to_state(&events);
+    let view = order_decider().to_view();
+    assert_eq!(
+        view.compute_new_state(None, &events.iter().collect::<Vec<_>>()),
+        expected_state
+    );
+    let view: View<OrderState, OrderEvent> = order_decider().into();
+    assert_eq!(
+        view.compute_new_state(None, &events.iter().collect::<Vec<_>>()),
+        expected_state
+    );
+}
+
+#[cfg(feature = "send")]
+#[test]
+fn shared_decider_test() {
+    // The `'static` decider is shared among many threads
+    let decider: Arc<Decider<'static, OrderCommand, OrderState, OrderEvent>> =
+        Arc::new(order_decider());
+    let handles = (1..=2)
+        .map(|order_id| {
+            let decider = Arc::clone(&decider);
+            thread::spawn(move || {
+                decider.compute_new_events(
+                    &[],
+                    &OrderCommand::Create(CreateOrderCommand {
+                        order_id,
+                        customer_name: "John Doe".to_string(),
+                        items: vec!["Item 1".to_string()],
+                    }),
+                )
+            })
+        })
+        .collect::<Vec<_>>();
+    for (order_id, handle) in (1..=2).zip(handles) {
+        assert_eq!(
+            handle.join().unwrap(),
+            Ok(vec![OrderEvent::Created(OrderCreatedEvent {
+                order_id,
+                customer_name: "John Doe".to_string(),
+                items: vec!["Item 1".to_string()],
+            })])
+        );
+    }
+}
+
+#[test]
+fn combine_via_sum_test() {
+    let combined_decider: Decider<
+        Sum<OrderCommand, ShipmentCommand>,
+        Sum<OrderState, ShipmentState>,
+        Sum<OrderEvent, ShipmentEvent>,
+    > = order_decider().combine_via_sum(shipment_decider());
+
+    let create_shipment_command = Sum::Second(ShipmentCommand::Create(CreateShipmentCommand {
+        shipment_id: 1,
+        order_id: 1,
+        customer_name: "John Doe".to_string(),
+        items: vec!["Item 1".to_string(), "Item 2".to_string()],
+    }));
+    let shipment_created_event = Sum::Second(ShipmentEvent::Created(ShipmentCreatedEvent {
+        shipment_id: 1,
+        order_id: 1,
+        customer_name: "John Doe".to_string(),
+        items: vec!["Item 1".to_string(), "Item 2".to_string()],
+    }));
+    let shipment_state = Sum::Second(ShipmentState {
+        shipment_id: 1,
+        order_id: 1,
+        customer_name: "John Doe".to_string(),
+        items: vec!["Item 1".to_string(), "Item 2".to_string()],
+    });
+
+    // The initial state belongs to the first decider, and it is treated as the initial state of the second decider
+    let new_events = combined_decider.compute_new_events(&[], &create_shipment_command);
+    assert_eq!(new_events, Ok(vec![shipment_created_event.clone()]));
+    let new_state = combined_decider.compute_new_state(None, &create_shipment_command);
+    assert_eq!(new_state, Ok(shipment_state.clone()));
+    assert_eq!(
+        combined_decider.fold_to_state(&[shipment_created_event]),
+        shipment_state
+    );
+
+    let order_created_event = Sum::First(OrderEvent::Created(OrderCreatedEvent {
+        order_id: 1,
+        customer_name: "John Doe".to_string(),
+        items: vec!["Item 1".to_string(), "Item 2".to_string()],
+    }));
+    let cancel_order_command = Sum::First(OrderCommand::Cancel(CancelOrderCommand { order_id: 1 }));
+    let new_events =
+        combined_decider.compute_new_events(&[order_created_event], &cancel_order_command);
+    assert_eq!(
+        new_events,
+        Ok(vec![Sum::First(OrderEvent::Cancelled(
+            OrderCancelledEvent { order_id: 1 }
+        ))])
+    );
+}
+
+#[test]
+fn cache_initial_state_test() {
+    let initial_state_calls = AtomicUsize::new(0);
+    let decider = Decider {
+        initial_state: Box::new(|| {
+            initial_state_calls.fetch_add(1, Ordering::SeqCst);
+            (order_decider().initial_state)()
+        }),
+        ..order_decider()
+    }
+    .cache_initial_state();
+    assert_eq!(initial_state_calls.load(Ordering::SeqCst), 0);
+
+    let command = OrderCommand::Cancel(CancelOrderCommand { order_id: 1 });
+    assert_eq!(decider.compute_new_events(&[], &command), Ok(vec![]));
+    assert_eq!(
+        decider.compute_new_state(None, &command),
+        Ok(decider.fold_to_state(&[]))
+    );
+    assert_eq!(initial_state_calls.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn chain_decide_test() {
+    // The empty order is cancelled right after it is created, within the same command
+    let decider = order_decider().chain_decide(|command, state| match command {
+        OrderCommand::Create(cmd) if state.order_id == cmd.order_id && state.items.is_empty() => {
+            Ok(vec![OrderEvent::Cancelled(OrderCancelledEvent {
+                order_id: cmd.order_id,
+            })])
+        }
+        _ => Ok(vec![]),
+    });
+    let command = OrderCommand::Create(CreateOrderCommand {
+        order_id: 1,
+        customer_name: "John Doe".to_string(),
+        items: vec![],
+    });
+    let created = OrderEvent::Created(OrderCreatedEvent {
+        order_id: 1,
+        customer_name: "John Doe".to_string(),
+        items: vec![],
+    });
+    let cancelled = OrderEvent::Cancelled(OrderCancelledEvent { order_id: 1 });
+    assert_eq!(
+        decider.compute_new_events(&[], &command),
+        Ok(vec![created, cancelled])
+    );
+    assert!(
+        decider
+            .compute_new_state(None, &command)
+            .unwrap()
+            .is_cancelled
+    );
+
+    let command = OrderCommand::Create(CreateOrderCommand {
+        order_id: 1,
+        customer_name: "John Doe".to_string(),
+        items: vec!["Item 1".to_string()],
+    });
+    assert_eq!(decider.compute_new_events(&[], &command).unwrap().len(), 1);
+}
+
+fn folding_order_decider<'a>() -> FoldingDecider<'a, OrderCommand, OrderState, OrderEvent> {
+    let decider = order_decider();
+    FoldingDecider {
+        decide: decider.decide,
+        evolve_mut: Box::new(|state, event| match event {
+            OrderEvent::Created(evt) => {
+                state.order_id = evt.order_id;
+                state.customer_name = evt.customer_name.to_owned();
+                state.items = evt.items.to_owned();
+            }
+            OrderEvent::Updated(evt) => {
+                state.items = evt.updated_items.to_owned();
+            }
+            OrderEvent::Cancelled(_) => {
+                state.is_cancelled = true;
+            }
+        }),
+        initial_state: decider.initial_state,
+    }
+}
+
+#[test]
+fn folding_decider_test() {
+    let events = vec![
+        OrderEvent::Created(OrderCreatedEvent {
+            order_id: 1,
+            customer_name: "John Doe".to_string(),
+            items: vec!["Item 1".to_string(), "Item 2".to_string()],
+        }),
+        OrderEvent::Updated(OrderUpdatedEvent {
+            order_id: 1,
+            updated_items: vec!["Item 3".to_string()],
+        }),
+    ];
+    let cancel_command = OrderCommand::Cancel(CancelOrderCommand { order_id: 1 });
+    let decider = order_decider();
+    let folding_deciders = [folding_order_decider(), order_decider().into()];
+
+    // The folding deciders (in place, and converted) behave the same as the decider
+    for folding_decider in folding_deciders {
+        assert_eq!(
+            folding_decider.fold_to_state(&events),
+            decider.fold_to_state(&events)
+        );
+        assert_eq!(
+            folding_decider.compute_new_events(&events, &cancel_command),
+            decider.compute_new_events(&events, &cancel_command)
+        );
+        let current_state = decider.fold_to_state(&events);
+        assert_eq!(
+            folding_decider.compute_new_state(Some(current_state.clone()), &cancel_command),
+            decider.compute_new_state(Some(current_state), &cancel_command)
+        );
+    }
+}
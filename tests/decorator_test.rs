@@ -0,0 +1,127 @@
+use std::sync::{Arc, Mutex};
+
+use fmodel_rust::aggregate::{EventRepository, EventSourcedAggregate};
+use fmodel_rust::decider::Decider;
+use fmodel_rust::decorator::{AggregateDecorator, Authorization, DecoratedAggregate};
+
+use crate::api::{CreateOrderCommand, OrderCommand, OrderCreatedEvent, OrderEvent, OrderState};
+use crate::application::AggregateError;
+
+mod api;
+mod application;
+
+/// An event repository that is not storing the events - infrastructure
+struct OrderEventRepository;
+
+impl EventRepository<OrderCommand, OrderEvent, i32, AggregateError> for OrderEventRepository {
+    async fn fetch_events(
+        &self,
+        _command: &OrderCommand,
+    ) -> Result<Vec<(OrderEvent, i32)>, AggregateError> {
+        Ok(vec![])
+    }
+
+    async fn save(&self, events: &[OrderEvent]) -> Result<Vec<(OrderEvent, i32)>, AggregateError> {
+        Ok(events.iter().map(|event| (event.clone(), 1)).collect())
+    }
+
+    async fn version_provider(&self, _event: &OrderEvent) -> Result<Option<i32>, AggregateError> {
+        Ok(None)
+    }
+}
+
+/// The metadata of the command, carrying the user info
+struct UserMetadata {
+    roles: Vec<String>,
+}
+
+/// An auditing decorator, recording the saved events
+#[derive(Default)]
+struct Audit {
+    events: Arc<Mutex<Vec<(OrderEvent, i32)>>>,
+}
+
+impl AggregateDecorator<OrderCommand, OrderEvent, i32, UserMetadata, AggregateError> for Audit {
+    async fn before_handle(
+        &self,
+        _command: &OrderCommand,
+        _metadata: &UserMetadata,
+    ) -> Result<(), AggregateError> {
+        Ok(())
+    }
+
+    async fn after_handle(&self, events: &[(OrderEvent, i32)]) {
+        self.events.lock().unwrap().extend_from_slice(events);
+    }
+}
+
+fn decider<'a>() -> Decider<'a, OrderCommand, OrderState, OrderEvent> {
+    Decider {
+        decide: Box::new(|command, _state| match command {
+            OrderCommand::Create(cmd) => Ok(vec![OrderEvent::Created(OrderCreatedEvent {
+                order_id: cmd.order_id,
+                customer_name: cmd.customer_name.to_owned(),
+                items: cmd.items.to_owned(),
+            })]),
+            _ => Ok(vec![]),
+        }),
+        evolve: Box::new(|state, _event| state.clone()),
+        initial_state: Box::new(|| OrderState {
+            order_id: 0,
+            customer_name: "".to_string(),
+            items: Vec::new(),
+            is_cancelled: false,
+        }),
+    }
+}
+
+/// Only the users with the `admin` role can create the orders
+fn policy(command: &OrderCommand, metadata: &UserMetadata) -> Result<(), AggregateError> {
+    match command {
+        OrderCommand::Create(_) if !metadata.roles.contains(&"admin".to_string()) => Err(
+            AggregateError::DomainError("User is not authorized".to_string()),
+        ),
+        _ => Ok(()),
+    }
+}
+
+#[tokio::test]
+async fn test() {
+    let audit = Audit::default();
+    let audited_events = audit.events.clone();
+    let aggregate = DecoratedAggregate::new(
+        EventSourcedAggregate::new(
+            OrderEventRepository,
+            decider().map_error(&|()| AggregateError::DomainError("Decider error".to_string())),
+        ),
+        (Authorization::new(policy), audit),
+    );
+    let command = OrderCommand::Create(CreateOrderCommand {
+        order_id: 1,
+        customer_name: "John Doe".to_string(),
+        items: vec!["Item 1".to_string()],
+    });
+    let created = (
+        OrderEvent::Created(OrderCreatedEvent {
+            order_id: 1,
+            customer_name: "John Doe".to_string(),
+            items: vec!["Item 1".to_string()],
+        }),
+        1,
+    );
+
+    let user = UserMetadata {
+        roles: vec!["user".to_string()],
+    };
+    let result = aggregate.handle(&command, &user).await;
+    assert!(result.is_err());
+    // The rejected command is not handled, so there is nothing to audit
+    assert!(audited_events.lock().unwrap().is_empty());
+
+    let admin = UserMetadata {
+        roles: vec!["admin".to_string()],
+    };
+    let result = aggregate.handle(&command, &admin).await;
+    assert_eq!(result.unwrap(), std::slice::from_ref(&created));
+    assert_eq!(*audited_events.lock().unwrap(), [created]);
+}
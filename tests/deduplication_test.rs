@@ -0,0 +1,294 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use fmodel_rust::aggregate::{EventRepository, EventSourcedAggregate};
+use fmodel_rust::decider::Decider;
+use fmodel_rust::deduplication::{
+    CommandDeduplication, DeduplicationStore, IdempotencyKey, IdempotentActionPublisher,
+    MissingIdempotencyKey,
+};
+use fmodel_rust::saga::Saga;
+use fmodel_rust::saga_manager::{ActionEnvelope, ActionPublisher, SagaManager};
+
+use crate::api::{
+    CreateOrderCommand, CreateShipmentCommand, OrderCommand, OrderCreatedEvent, OrderEvent,
+    OrderState, ShipmentCommand,
+};
+use crate::application::{AggregateError, SagaManagerError};
+
+mod api;
+mod application;
+
+/// An event repository that is counting the saved events - infrastructure
+struct CountingOrderEventRepository {
+    saved: Mutex<usize>,
+}
+
+impl EventRepository<OrderCommand, OrderEvent, i32, AggregateError>
+    for CountingOrderEventRepository
+{
+    async fn fetch_events(
+        &self,
+        _command: &OrderCommand,
+    ) -> Result<Vec<(OrderEvent, i32)>, AggregateError> {
+        Ok(vec![])
+    }
+
+    async fn save(&self, events: &[OrderEvent]) -> Result<Vec<(OrderEvent, i32)>, AggregateError> {
+        let mut saved = self.saved.lock().unwrap();
+        *saved += events.len();
+        Ok(events
+            .iter()
+            .map(|event| (event.clone(), *saved as i32))
+            .collect())
+    }
+
+    async fn version_provider(&self, _event: &OrderEvent) -> Result<Option<i32>, AggregateError> {
+        Ok(None)
+    }
+}
+
+/// A simple in-memory deduplication store - infrastructure
+#[derive(Default)]
+struct InMemoryDeduplicationStore {
+    results: Mutex<HashMap<String, Vec<(OrderEvent, i32)>>>,
+}
+
+impl DeduplicationStore<Vec<(OrderEvent, i32)>, AggregateError> for InMemoryDeduplicationStore {
+    async fn fetch_result(
+        &self,
+        key: &str,
+    ) -> Result<Option<Vec<(OrderEvent, i32)>>, AggregateError> {
+        Ok(self.results.lock().unwrap().get(key).cloned())
+    }
+
+    async fn save_result(
+        &self,
+        key: &str,
+        result: &Vec<(OrderEvent, i32)>,
+    ) -> Result<(), AggregateError> {
+        self.results
+            .lock()
+            .unwrap()
+            .insert(key.to_string(), result.clone());
+        Ok(())
+    }
+}
+
+/// The order commands are deduplicated by the order id, for the sake of the test
+impl IdempotencyKey for OrderCommand {
+    fn idempotency_key(&self) -> String {
+        match self {
+            OrderCommand::Create(cmd) => format!("create-{}", cmd.order_id),
+            OrderCommand::Update(cmd) => format!("update-{}", cmd.order_id),
+            OrderCommand::Cancel(cmd) => format!("cancel-{}", cmd.order_id),
+        }
+    }
+}
+
+fn decider<'a>() -> Decider<'a, OrderCommand, OrderState, OrderEvent> {
+    Decider {
+        decide: Box::new(|command, _state| match command {
+            OrderCommand::Create(cmd) => Ok(vec![OrderEvent::Created(OrderCreatedEvent {
+                order_id: cmd.order_id,
+                customer_name: cmd.customer_name.to_owned(),
+                items: cmd.items.to_owned(),
+            })]),
+            _ => Ok(vec![]),
+        }),
+        evolve: Box::new(|state, _event| state.clone()),
+        initial_state: Box::new(|| OrderState {
+            order_id: 0,
+            customer_name: "".to_string(),
+            items: Vec::new(),
+            is_cancelled: false,
+        }),
+    }
+}
+
+#[tokio::test]
+async fn test() {
+    let aggregate = CommandDeduplication::new(
+        EventSourcedAggregate::new(
+            CountingOrderEventRepository {
+                saved: Mutex::new(0),
+            },
+            decider().map_error(&|()| AggregateError::DomainError("Decider error".to_string())),
+        ),
+        InMemoryDeduplicationStore::default(),
+    );
+    let command = OrderCommand::Create(CreateOrderCommand {
+        order_id: 1,
+        customer_name: "John Doe".to_string(),
+        items: vec!["Item 1".to_string()],
+    });
+    let expected = [(
+        OrderEvent::Created(OrderCreatedEvent {
+            order_id: 1,
+            customer_name: "John Doe".to_string(),
+            items: vec!["Item 1".to_string()],
+        }),
+        1,
+    )];
+
+    assert_eq!(aggregate.handle(&command).await.unwrap(), expected);
+    // The duplicated command is not handled again, the stored result is replayed
+    assert_eq!(aggregate.handle(&command).await.unwrap(), expected);
+
+    let command = OrderCommand::Create(CreateOrderCommand {
+        order_id: 2,
+        customer_name: "John Doe".to_string(),
+        items: vec!["Item 1".to_string()],
+    });
+    assert_eq!(aggregate.handle(&command).await.unwrap()[0].1, 2);
+}
+
+/// The order events are identified by the order id and the variant, for the sake of the test
+impl IdempotencyKey for OrderEvent {
+    fn idempotency_key(&self) -> String {
+        match self {
+            OrderEvent::Created(evt) => format!("created-{}", evt.order_id),
+            OrderEvent::Updated(evt) => format!("updated-{}", evt.order_id),
+            OrderEvent::Cancelled(evt) => format!("cancelled-{}", evt.order_id),
+        }
+    }
+}
+
+/// An action publisher that is recording the published actions - infrastructure
+#[derive(Default)]
+struct RecordingActionPublisher {
+    published: Mutex<Vec<ShipmentCommand>>,
+}
+
+impl ActionPublisher<ShipmentCommand, SagaManagerError> for &RecordingActionPublisher {
+    async fn publish(
+        &self,
+        action: &[ShipmentCommand],
+    ) -> Result<Vec<ShipmentCommand>, SagaManagerError> {
+        self.published.lock().unwrap().extend_from_slice(action);
+        Ok(Vec::from(action))
+    }
+}
+
+/// A simple in-memory store of the published actions - infrastructure
+#[derive(Default)]
+struct InMemoryPublishedActionStore {
+    actions: Mutex<HashMap<String, ShipmentCommand>>,
+}
+
+impl DeduplicationStore<ShipmentCommand, SagaManagerError> for InMemoryPublishedActionStore {
+    async fn fetch_result(&self, key: &str) -> Result<Option<ShipmentCommand>, SagaManagerError> {
+        Ok(self.actions.lock().unwrap().get(key).cloned())
+    }
+
+    async fn save_result(
+        &self,
+        key: &str,
+        result: &ShipmentCommand,
+    ) -> Result<(), SagaManagerError> {
+        self.actions
+            .lock()
+            .unwrap()
+            .insert(key.to_string(), result.clone());
+        Ok(())
+    }
+}
+
+impl From<MissingIdempotencyKey> for SagaManagerError {
+    fn from(error: MissingIdempotencyKey) -> Self {
+        SagaManagerError::PublishAction(error.to_string())
+    }
+}
+
+fn saga<'a>() -> Saga<'a, OrderEvent, ShipmentCommand> {
+    Saga {
+        react: Box::new(|event| match event {
+            OrderEvent::Created(evt) => Ok(vec![ShipmentCommand::Create(CreateShipmentCommand {
+                shipment_id: evt.order_id,
+                order_id: evt.order_id,
+                customer_name: evt.customer_name.to_owned(),
+                items: evt.items.to_owned(),
+            })]),
+            _ => Ok(vec![]),
+        }),
+    }
+}
+
+#[tokio::test]
+async fn idempotent_action_publishing_test() {
+    let publisher = RecordingActionPublisher::default();
+    let saga_manager = SagaManager::new(
+        IdempotentActionPublisher::new(&publisher, InMemoryPublishedActionStore::default()),
+        saga().map_error(&|()| SagaManagerError::DomainError("Saga error".to_string())),
+    );
+    let event = OrderEvent::Created(OrderCreatedEvent {
+        order_id: 1,
+        customer_name: "John Doe".to_string(),
+        items: vec!["Item 1".to_string()],
+    });
+    let expected = vec![ShipmentCommand::Create(CreateShipmentCommand {
+        shipment_id: 1,
+        order_id: 1,
+        customer_name: "John Doe".to_string(),
+        items: vec!["Item 1".to_string()],
+    })];
+
+    assert_eq!(
+        saga_manager.handle_idempotent(&event).await.unwrap(),
+        expected
+    );
+    // The redelivered event computes the same keys, so the action is not published again
+    assert_eq!(
+        saga_manager.handle_idempotent(&event).await.unwrap(),
+        expected
+    );
+    assert_eq!(*publisher.published.lock().unwrap(), expected);
+}
+
+#[tokio::test]
+async fn publishing_without_idempotency_keys_test() {
+    let publisher = RecordingActionPublisher::default();
+    let saga_manager = SagaManager::new(
+        IdempotentActionPublisher::new(&publisher, InMemoryPublishedActionStore::default()),
+        saga().map_error(&|()| SagaManagerError::DomainError("Saga error".to_string())),
+    );
+    let event = OrderEvent::Created(OrderCreatedEvent {
+        order_id: 1,
+        customer_name: "John Doe".to_string(),
+        items: vec!["Item 1".to_string()],
+    });
+
+    // The plain handle can not deduplicate the actions, so it fails instead of publishing them
+    assert!(matches!(
+        saga_manager.handle(&event).await,
+        Err(SagaManagerError::PublishAction(error)) if error == MissingIdempotencyKey.to_string()
+    ));
+    assert!(publisher.published.lock().unwrap().is_empty());
+}
+
+#[test]
+fn action_envelope_test() {
+    let event = OrderEvent::Created(OrderCreatedEvent {
+        order_id: 1,
+        customer_name: "John Doe".to_string(),
+        items: vec![],
+    });
+    // The keys are deterministic, and distinct per action index and action result
+    let key = ActionEnvelope::new((), &event, 0).idempotency_key;
+    assert_eq!(key, "created-1:0");
+    assert_eq!(key, ActionEnvelope::new((), &event, 0).idempotency_key);
+    assert_ne!(key, ActionEnvelope::new((), &event, 1).idempotency_key);
+    assert_ne!(
+        key,
+        ActionEnvelope::new(
+            (),
+            &OrderEvent::Created(OrderCreatedEvent {
+                order_id: 2,
+                customer_name: "John Doe".to_string(),
+                items: vec![],
+            }),
+            0
+        )
+        .idempotency_key
+    );
+}
@@ -0,0 +1,68 @@
+use fmodel_rust::envelope::{unwrap_events, wrap_events, EventEnvelope};
+use fmodel_rust::EventName;
+
+use crate::api::{OrderCancelledEvent, OrderCreatedEvent, OrderEvent};
+
+mod api;
+mod application;
+
+impl EventName for OrderEvent {
+    fn event_name(&self) -> &'static str {
+        match self {
+            OrderEvent::Created(_) => "OrderCreated",
+            OrderEvent::Updated(_) => "OrderUpdated",
+            OrderEvent::Cancelled(_) => "OrderCancelled",
+        }
+    }
+}
+
+/// Infrastructure metadata - it is not part of the domain model
+#[derive(Debug, Clone, PartialEq)]
+struct Metadata {
+    correlation_id: String,
+}
+
+#[test]
+fn test() {
+    let order_created_event = OrderEvent::Created(OrderCreatedEvent {
+        order_id: 1,
+        customer_name: "John Doe".to_string(),
+        items: vec!["Item 1".to_string(), "Item 2".to_string()],
+    });
+    let order_cancelled_event = OrderEvent::Cancelled(OrderCancelledEvent { order_id: 1 });
+    let metadata = Metadata {
+        correlation_id: "correlation-1".to_string(),
+    };
+
+    let envelope = EventEnvelope::new(order_created_event.clone(), 0, metadata.clone());
+    assert_eq!(envelope.event_type, "OrderCreated");
+    assert_eq!(envelope.version, 0);
+    assert_eq!(envelope.metadata, metadata);
+    assert!(envelope.timestamp > 0);
+
+    let mapped_envelope = envelope
+        .clone()
+        .map_event(&|event: OrderEvent| match event {
+            OrderEvent::Created(evt) => evt.order_id,
+            OrderEvent::Updated(evt) => evt.order_id,
+            OrderEvent::Cancelled(evt) => evt.order_id,
+        });
+    assert_eq!(mapped_envelope.event, 1);
+    assert_eq!(mapped_envelope.event_type, "OrderCreated");
+    assert_eq!(mapped_envelope.timestamp, envelope.timestamp);
+    assert_eq!(envelope.into_event(), order_created_event);
+
+    let events = vec![
+        (order_created_event.clone(), 0),
+        (order_cancelled_event.clone(), 1),
+    ];
+    let envelopes = wrap_events(events.clone(), &metadata);
+    assert_eq!(
+        envelopes
+            .iter()
+            .map(|envelope| envelope.event_type.as_str())
+            .collect::<Vec<&str>>(),
+        vec!["OrderCreated", "OrderCancelled"]
+    );
+    assert_eq!(unwrap_events(envelopes), events);
+}
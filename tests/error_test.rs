@@ -0,0 +1,116 @@
+use fmodel_rust::aggregate::{
+    ConcurrencyError, EventRepository, EventSourcedAggregate, OptimisticLockingError,
+};
+use fmodel_rust::decider::Decider;
+use fmodel_rust::error::Error;
+
+use crate::api::{
+    CancelOrderCommand, CreateOrderCommand, OrderCancelledEvent, OrderCommand, OrderCreatedEvent,
+    OrderEvent, OrderState,
+};
+
+mod api;
+mod application;
+
+/// An event repository that is not able to fetch the events of the order `2` - infrastructure
+struct FailingOrderEventRepository;
+
+impl EventRepository<OrderCommand, OrderEvent, i32, Error> for FailingOrderEventRepository {
+    async fn fetch_events(&self, command: &OrderCommand) -> Result<Vec<(OrderEvent, i32)>, Error> {
+        match command {
+            OrderCommand::Cancel(cmd) if cmd.order_id == 2 => {
+                Err(Error::FetchEvents("Connection refused".to_string()))
+            }
+            _ => Ok(vec![]),
+        }
+    }
+
+    async fn save(&self, events: &[OrderEvent]) -> Result<Vec<(OrderEvent, i32)>, Error> {
+        Ok(events.iter().map(|event| (event.clone(), 0)).collect())
+    }
+
+    async fn version_provider(&self, _event: &OrderEvent) -> Result<Option<i32>, Error> {
+        Ok(None)
+    }
+}
+
+/// Decider is rejecting the cancellation of the non-existing order
+fn decider<'a>() -> Decider<'a, OrderCommand, OrderState, OrderEvent, String> {
+    Decider {
+        decide: Box::new(|command, state| match command {
+            OrderCommand::Create(cmd) => Ok(vec![OrderEvent::Created(OrderCreatedEvent {
+                order_id: cmd.order_id,
+                customer_name: cmd.customer_name.to_owned(),
+                items: cmd.items.to_owned(),
+            })]),
+            OrderCommand::Update(_) => Ok(vec![]),
+            OrderCommand::Cancel(cmd) => {
+                if state.order_id == cmd.order_id {
+                    Ok(vec![OrderEvent::Cancelled(OrderCancelledEvent {
+                        order_id: cmd.order_id,
+                    })])
+                } else {
+                    Err(format!("Order {} does not exist", cmd.order_id))
+                }
+            }
+        }),
+        evolve: Box::new(|state, event| {
+            let mut new_state = state.clone();
+            if let OrderEvent::Created(evt) = event {
+                new_state.order_id = evt.order_id;
+            }
+            new_state
+        }),
+        initial_state: Box::new(|| OrderState {
+            order_id: 0,
+            customer_name: "".to_string(),
+            items: Vec::new(),
+            is_cancelled: false,
+        }),
+    }
+}
+
+#[tokio::test]
+async fn test() {
+    let aggregate = EventSourcedAggregate::new(
+        FailingOrderEventRepository,
+        decider().map_error(&|e: &String| Error::Domain(e.to_owned())),
+    );
+
+    let result = aggregate
+        .handle(&OrderCommand::Create(CreateOrderCommand {
+            order_id: 1,
+            customer_name: "John Doe".to_string(),
+            items: vec!["Item 1".to_string()],
+        }))
+        .await;
+    assert!(result.is_ok());
+
+    let result = aggregate
+        .handle(&OrderCommand::Cancel(CancelOrderCommand { order_id: 1 }))
+        .await;
+    assert_eq!(
+        result,
+        Err(Error::Domain("Order 1 does not exist".to_string()))
+    );
+
+    let result = aggregate
+        .handle(&OrderCommand::Cancel(CancelOrderCommand { order_id: 2 }))
+        .await;
+    assert_eq!(
+        result,
+        Err(Error::FetchEvents("Connection refused".to_string()))
+    );
+    assert_eq!(
+        result.unwrap_err().to_string(),
+        "Failed to fetch events: Connection refused"
+    );
+
+    let error: Error = ConcurrencyError {
+        expected_version: Some(1),
+        actual_version: Some(2),
+    }
+    .into();
+    assert!(error.is_concurrency_error());
+    assert!(!Error::<String>::SaveEvents("Disk full".to_string()).is_concurrency_error());
+}
@@ -0,0 +1,177 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use fmodel_rust::aggregate::EventSourcedAggregate;
+use fmodel_rust::decider::Decider;
+use fmodel_rust::event_bus::EventBus;
+use fmodel_rust::in_memory::InMemoryEventRepository;
+use fmodel_rust::materialized_view::{MaterializedView, ViewStateRepository};
+use fmodel_rust::outbox::EventPublishingAggregate;
+use fmodel_rust::view::View;
+use fmodel_rust::{Identifier, Variant};
+
+use crate::api::{
+    CancelOrderCommand, CreateOrderCommand, OrderCancelledEvent, OrderCommand, OrderCreatedEvent,
+    OrderEvent, OrderState, OrderViewState,
+};
+use crate::application::AggregateError;
+
+mod api;
+mod application;
+
+impl Variant<OrderEvent> for OrderCreatedEvent {
+    fn from_variant(value: &OrderEvent) -> Option<&Self> {
+        match value {
+            OrderEvent::Created(evt) => Some(evt),
+            _ => None,
+        }
+    }
+}
+
+fn decider<'a>() -> Decider<'a, OrderCommand, OrderState, OrderEvent, AggregateError> {
+    Decider {
+        decide: Box::new(|command, _state| match command {
+            OrderCommand::Create(cmd) => Ok(vec![OrderEvent::Created(OrderCreatedEvent {
+                order_id: cmd.order_id,
+                customer_name: cmd.customer_name.to_owned(),
+                items: cmd.items.to_owned(),
+            })]),
+            OrderCommand::Cancel(cmd) => Ok(vec![OrderEvent::Cancelled(OrderCancelledEvent {
+                order_id: cmd.order_id,
+            })]),
+            OrderCommand::Update(_) => Ok(vec![]),
+        }),
+        evolve: Box::new(|state, _event| state.clone()),
+        initial_state: Box::new(|| OrderState {
+            order_id: 0,
+            customer_name: "".to_string(),
+            items: Vec::new(),
+            is_cancelled: false,
+        }),
+    }
+}
+
+fn view<'a>() -> View<'a, OrderViewState, OrderEvent> {
+    View {
+        evolve: Box::new(|state, event| {
+            let mut new_state = state.clone();
+            match event {
+                OrderEvent::Created(evt) => {
+                    new_state.order_id = evt.order_id;
+                    new_state.customer_name = evt.customer_name.to_owned();
+                    new_state.items = evt.items.to_owned();
+                }
+                OrderEvent::Updated(evt) => {
+                    new_state.items = evt.updated_items.to_owned();
+                }
+                OrderEvent::Cancelled(_) => {
+                    new_state.is_cancelled = true;
+                }
+            }
+            new_state
+        }),
+        initial_state: Box::new(|| OrderViewState {
+            order_id: 0,
+            customer_name: "".to_string(),
+            items: Vec::new(),
+            is_cancelled: false,
+        }),
+    }
+}
+
+/// A simple in-memory view state repository, shared with the test - infrastructure
+#[derive(Default, Clone)]
+struct InMemoryViewOrderStateRepository {
+    states: Arc<Mutex<HashMap<String, OrderViewState>>>,
+}
+
+impl ViewStateRepository<OrderEvent, OrderViewState, AggregateError>
+    for InMemoryViewOrderStateRepository
+{
+    async fn fetch_state(
+        &self,
+        event: &OrderEvent,
+    ) -> Result<Option<OrderViewState>, AggregateError> {
+        Ok(self
+            .states
+            .lock()
+            .unwrap()
+            .get(&event.identifier())
+            .cloned())
+    }
+
+    async fn save(&self, state: &OrderViewState) -> Result<OrderViewState, AggregateError> {
+        self.states
+            .lock()
+            .unwrap()
+            .insert(state.identifier(), state.clone());
+        Ok(state.clone())
+    }
+}
+
+#[tokio::test]
+async fn test() {
+    let repository = InMemoryViewOrderStateRepository::default();
+    let created_orders = Arc::new(Mutex::new(vec![]));
+    let recorded = Arc::clone(&created_orders);
+    let bus = EventBus::new()
+        .subscribe_all(MaterializedView::new(repository.clone(), view()))
+        .subscribe_fn(move |event: OrderCreatedEvent| {
+            recorded.lock().unwrap().push(event.order_id);
+            async { Ok(()) }
+        });
+    let aggregate = EventPublishingAggregate::new(
+        EventSourcedAggregate::new(InMemoryEventRepository::new(), decider()),
+        bus,
+    );
+
+    let result = aggregate
+        .handle(&OrderCommand::Create(CreateOrderCommand {
+            order_id: 1,
+            customer_name: "John Doe".to_string(),
+            items: vec!["Item 1".to_string()],
+        }))
+        .await;
+    assert!(result.is_ok());
+    let result = aggregate
+        .handle(&OrderCommand::Cancel(CancelOrderCommand { order_id: 1 }))
+        .await;
+    assert!(result.is_ok());
+
+    // The view is subscribed to all the events, and the closure to the created events only
+    assert_eq!(
+        repository.states.lock().unwrap().get("1").cloned(),
+        Some(OrderViewState {
+            order_id: 1,
+            customer_name: "John Doe".to_string(),
+            items: vec!["Item 1".to_string()],
+            is_cancelled: true,
+        })
+    );
+    assert_eq!(*created_orders.lock().unwrap(), vec![1]);
+}
+
+#[tokio::test]
+async fn failing_subscriber_test() {
+    let delivered = Arc::new(Mutex::new(0));
+    let counter = Arc::clone(&delivered);
+    let bus: EventBus<OrderEvent, AggregateError> = EventBus::new()
+        .subscribe_fn(|_event: OrderCreatedEvent| async {
+            Err(AggregateError::DomainError("Subscriber failed".to_string()))
+        })
+        .subscribe_fn(move |_event: OrderCreatedEvent| {
+            *counter.lock().unwrap() += 1;
+            async { Ok(()) }
+        });
+
+    // The delivery stops on the first subscriber that fails
+    let result = bus
+        .dispatch(&OrderEvent::Created(OrderCreatedEvent {
+            order_id: 1,
+            customer_name: "John Doe".to_string(),
+            items: vec!["Item 1".to_string()],
+        }))
+        .await;
+    assert!(matches!(result, Err(AggregateError::DomainError(_))));
+    assert_eq!(*delivered.lock().unwrap(), 0);
+}
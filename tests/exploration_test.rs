@@ -0,0 +1,147 @@
+use fmodel_rust::exploration::{StateMachine, Transition};
+use fmodel_rust::view::View;
+
+use crate::api::{
+    OrderCancelledEvent, OrderCreatedEvent, OrderEvent, OrderUpdatedEvent, OrderViewState,
+};
+
+mod api;
+mod application;
+
+fn order_view<'a>() -> View<'a, OrderViewState, OrderEvent> {
+    View {
+        evolve: Box::new(|state, event| {
+            let mut new_state = state.clone();
+            match event {
+                OrderEvent::Created(evt) => {
+                    new_state.order_id = evt.order_id;
+                    new_state.customer_name = evt.customer_name.to_owned();
+                    new_state.items = evt.items.to_owned();
+                }
+                OrderEvent::Updated(evt) => {
+                    new_state.items = evt.updated_items.to_owned();
+                }
+                OrderEvent::Cancelled(_) => {
+                    new_state.is_cancelled = true;
+                }
+            }
+            new_state
+        }),
+        initial_state: Box::new(|| OrderViewState {
+            order_id: 0,
+            customer_name: "".to_string(),
+            items: Vec::new(),
+            is_cancelled: false,
+        }),
+    }
+}
+
+/// Generates the sample events that are allowed in the order lifecycle: created once, then updated or cancelled
+fn order_events(state: &OrderViewState) -> Vec<OrderEvent> {
+    if state.order_id == 0 {
+        vec![OrderEvent::Created(OrderCreatedEvent {
+            order_id: 1,
+            customer_name: "John Doe".to_string(),
+            items: vec!["Item 1".to_string()],
+        })]
+    } else if !state.is_cancelled {
+        vec![
+            OrderEvent::Updated(OrderUpdatedEvent {
+                order_id: 1,
+                updated_items: vec!["Item 2".to_string()],
+            }),
+            OrderEvent::Cancelled(OrderCancelledEvent { order_id: 1 }),
+        ]
+    } else {
+        vec![]
+    }
+}
+
+fn order_state(order_id: u32, item: &str, is_cancelled: bool) -> OrderViewState {
+    OrderViewState {
+        order_id,
+        customer_name: if order_id == 0 { "" } else { "John Doe" }.to_string(),
+        items: if item.is_empty() {
+            vec![]
+        } else {
+            vec![item.to_string()]
+        },
+        is_cancelled,
+    }
+}
+
+fn event_label(event: &OrderEvent) -> String {
+    match event {
+        OrderEvent::Created(_) => "Created".to_string(),
+        OrderEvent::Updated(_) => "Updated".to_string(),
+        OrderEvent::Cancelled(_) => "Cancelled".to_string(),
+    }
+}
+
+#[test]
+fn explore_test() {
+    let machine = StateMachine::from_view(&order_view(), &order_events, 10);
+
+    assert!(machine.is_complete());
+    assert_eq!(
+        machine.states(),
+        &[
+            order_state(0, "", false),
+            order_state(1, "Item 1", false),
+            order_state(1, "Item 2", false),
+            order_state(1, "Item 1", true),
+            order_state(1, "Item 2", true),
+        ]
+    );
+    assert_eq!(
+        machine
+            .transitions()
+            .iter()
+            .map(|transition| (transition.from, transition.to))
+            .collect::<Vec<_>>(),
+        vec![(0, 1), (1, 2), (1, 3), (2, 2), (2, 4)]
+    );
+    assert!(matches!(
+        machine.transitions()[0],
+        Transition {
+            event: OrderEvent::Created(_),
+            ..
+        }
+    ));
+
+    // The order can not be cancelled before it is created
+    let cancelled_before_created = order_state(0, "", true);
+    assert_eq!(
+        machine.unreachable_states(&[
+            order_state(1, "Item 2", true),
+            cancelled_before_created.clone()
+        ]),
+        vec![&cancelled_before_created]
+    );
+}
+
+#[test]
+fn explore_limit_test() {
+    let machine = StateMachine::from_view(&order_view(), &order_events, 3);
+
+    assert!(!machine.is_complete());
+    assert_eq!(machine.states().len(), 3);
+    assert_eq!(machine.transitions().len(), 3);
+}
+
+#[test]
+fn mermaid_test() {
+    let machine = StateMachine::from_view(&order_view(), &order_events, 10);
+
+    assert_eq!(
+        machine.to_mermaid(
+            &|state: &OrderViewState| format!(
+                "{}{}",
+                state.items.join(", "),
+                if state.is_cancelled { " (cancelled)" } else { "" }
+            ),
+            &event_label
+        ),
+        "stateDiagram-v2\n    s0 : \n    s1 : Item 1\n    s2 : Item 2\n    s3 : Item 1 (cancelled)\n    s4 : Item 2 (cancelled)\n    [*] --> s0\n    s0 --> s1 : Created\n    s1 --> s2 : Updated\n    s1 --> s3 : Cancelled\n    s2 --> s2 : Updated\n    s2 --> s4 : Cancelled\n"
+    );
+}
@@ -0,0 +1,72 @@
+use fmodel_rust::fixtures::{fold_view, read_json, read_ndjson, FixtureError};
+use fmodel_rust::view::View;
+
+use crate::api::{OrderCancelledEvent, OrderCreatedEvent, OrderEvent};
+
+mod api;
+mod application;
+
+/// Parses the `{"created": <order_id>}` and `{"cancelled": <order_id>}` events. Use `serde_json::from_str` in real life.
+fn parse_event(json: &str) -> Result<OrderEvent, String> {
+    let (name, order_id) = json
+        .trim()
+        .trim_start_matches('{')
+        .trim_end_matches('}')
+        .split_once(':')
+        .ok_or(format!("Malformed event: {}", json))?;
+    let order_id = order_id.trim().parse::<u32>().map_err(|e| e.to_string())?;
+    match name.trim().trim_matches('"') {
+        "created" => Ok(OrderEvent::Created(OrderCreatedEvent {
+            order_id,
+            customer_name: "John Doe".to_string(),
+            items: vec![],
+        })),
+        "cancelled" => Ok(OrderEvent::Cancelled(OrderCancelledEvent { order_id })),
+        name => Err(format!("Unknown event: {}", name)),
+    }
+}
+
+fn parse_events(json: &str) -> Result<Vec<OrderEvent>, String> {
+    json.trim()
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .split(',')
+        .map(parse_event)
+        .collect()
+}
+
+/// Counts the active orders
+fn view<'a>() -> View<'a, i32, OrderEvent> {
+    View {
+        evolve: Box::new(|state, event| match event {
+            OrderEvent::Created(_) => state + 1,
+            OrderEvent::Cancelled(_) => state - 1,
+            OrderEvent::Updated(_) => *state,
+        }),
+        initial_state: Box::new(|| 0),
+    }
+}
+
+#[test]
+fn test() {
+    let events = read_ndjson("tests/fixtures/order_events.ndjson", parse_event).unwrap();
+    assert_eq!(events.len(), 3);
+    assert_eq!(fold_view(&view(), &events), 1);
+
+    let events = read_json("tests/fixtures/order_events.json", parse_events).unwrap();
+    assert_eq!(events.len(), 3);
+    assert_eq!(fold_view(&view(), &events), 1);
+}
+
+#[test]
+fn error_test() {
+    let result = read_ndjson("tests/fixtures/missing.ndjson", parse_event);
+    assert!(matches!(result, Err(FixtureError::Io(_))));
+
+    // The JSON array is not a valid NDJSON event
+    let result = read_ndjson("tests/fixtures/order_events.json", parse_event);
+    assert!(matches!(
+        result,
+        Err(FixtureError::Parse { line: Some(1), .. })
+    ));
+}
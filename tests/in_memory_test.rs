@@ -0,0 +1,389 @@
+use fmodel_rust::aggregate::{
+    EventRepository, EventRepositoryWithArchiving, EventSourcedAggregate,
+    EventSourcedSnapshottingAggregate, MissingSnapshot, StateRepository, StateStoredAggregate,
+};
+use fmodel_rust::decider::Decider;
+use fmodel_rust::in_memory::{
+    InMemoryEventRepository, InMemorySnapshotRepository, InMemoryStateRepository,
+    InMemoryViewStateRepository,
+};
+use fmodel_rust::materialized_view::{MaterializedView, ViewOutcome, ViewStateRepository};
+use fmodel_rust::view::View;
+use fmodel_rust::Identifier;
+
+use crate::api::{
+    CreateOrderCommand, OrderCancelledEvent, OrderCommand, OrderCreatedEvent, OrderEvent,
+    OrderState, OrderUpdatedEvent, OrderViewState, UpdateOrderCommand,
+};
+use crate::application::{AggregateError, MaterializedViewError};
+
+mod api;
+mod application;
+
+impl From<MissingSnapshot> for AggregateError {
+    fn from(error: MissingSnapshot) -> Self {
+        AggregateError::FetchState(error.to_string())
+    }
+}
+
+fn decider<'a>() -> Decider<'a, OrderCommand, OrderState, OrderEvent> {
+    Decider {
+        decide: Box::new(|command, _state| match command {
+            OrderCommand::Create(cmd) => Ok(vec![OrderEvent::Created(OrderCreatedEvent {
+                order_id: cmd.order_id,
+                customer_name: cmd.customer_name.to_owned(),
+                items: cmd.items.to_owned(),
+            })]),
+            OrderCommand::Update(cmd) => Ok(vec![OrderEvent::Updated(OrderUpdatedEvent {
+                order_id: cmd.order_id,
+                updated_items: cmd.new_items.to_owned(),
+            })]),
+            OrderCommand::Cancel(_) => Ok(vec![]),
+        }),
+        evolve: Box::new(|state, event| {
+            let mut new_state = state.clone();
+            match event {
+                OrderEvent::Created(evt) => {
+                    new_state.order_id = evt.order_id;
+                    new_state.customer_name = evt.customer_name.to_owned();
+                    new_state.items = evt.items.to_owned();
+                }
+                OrderEvent::Updated(evt) => {
+                    new_state.items = evt.updated_items.to_owned();
+                }
+                OrderEvent::Cancelled(_) => {
+                    new_state.is_cancelled = true;
+                }
+            }
+            new_state
+        }),
+        initial_state: Box::new(|| OrderState {
+            order_id: 0,
+            customer_name: "".to_string(),
+            items: Vec::new(),
+            is_cancelled: false,
+        }),
+    }
+}
+
+fn view<'a>() -> View<'a, OrderViewState, OrderEvent> {
+    View {
+        evolve: Box::new(|state, event| {
+            let mut new_state = state.clone();
+            match event {
+                OrderEvent::Created(evt) => {
+                    new_state.order_id = evt.order_id;
+                    new_state.customer_name = evt.customer_name.to_owned();
+                    new_state.items = evt.items.to_owned();
+                }
+                OrderEvent::Updated(evt) => {
+                    new_state.items = evt.updated_items.to_owned();
+                }
+                OrderEvent::Cancelled(_) => {
+                    new_state.is_cancelled = true;
+                }
+            }
+            new_state
+        }),
+        initial_state: Box::new(|| OrderViewState {
+            order_id: 0,
+            customer_name: "".to_string(),
+            items: Vec::new(),
+            is_cancelled: false,
+        }),
+    }
+}
+
+fn create_command(order_id: u32) -> OrderCommand {
+    OrderCommand::Create(CreateOrderCommand {
+        order_id,
+        customer_name: "John Doe".to_string(),
+        items: vec!["Item 1".to_string(), "Item 2".to_string()],
+    })
+}
+
+fn update_command(order_id: u32) -> OrderCommand {
+    OrderCommand::Update(UpdateOrderCommand {
+        order_id,
+        new_items: vec!["Item 3".to_string()],
+    })
+}
+
+#[tokio::test]
+async fn event_sourced_aggregate_test() {
+    let aggregate = EventSourcedAggregate::new(
+        InMemoryEventRepository::new(),
+        decider().map_error(&|()| AggregateError::DomainError("Decider error".to_string())),
+    );
+    let result = aggregate.handle(&create_command(1)).await;
+    assert_eq!(
+        result.unwrap(),
+        [(
+            OrderEvent::Created(OrderCreatedEvent {
+                order_id: 1,
+                customer_name: "John Doe".to_string(),
+                items: vec!["Item 1".to_string(), "Item 2".to_string()],
+            }),
+            0
+        )]
+    );
+    let result = aggregate.handle(&create_command(2)).await;
+    assert_eq!(result.unwrap().first().unwrap().1, 0);
+    let result = aggregate.handle(&update_command(1)).await;
+    assert_eq!(
+        result.unwrap(),
+        [(
+            OrderEvent::Updated(OrderUpdatedEvent {
+                order_id: 1,
+                updated_items: vec!["Item 3".to_string()],
+            }),
+            1
+        )]
+    );
+}
+
+#[tokio::test]
+async fn state_stored_aggregate_test() {
+    let aggregate = StateStoredAggregate::new(
+        InMemoryStateRepository::new(),
+        decider().map_error(&|()| AggregateError::DomainError("Decider error".to_string())),
+    );
+    let result = aggregate.handle(&create_command(1)).await;
+    assert_eq!(
+        result.unwrap(),
+        (
+            OrderState {
+                order_id: 1,
+                customer_name: "John Doe".to_string(),
+                items: vec!["Item 1".to_string(), "Item 2".to_string()],
+                is_cancelled: false,
+            },
+            0
+        )
+    );
+    let result = aggregate.handle(&update_command(1)).await;
+    assert_eq!(
+        result.unwrap(),
+        (
+            OrderState {
+                order_id: 1,
+                customer_name: "John Doe".to_string(),
+                items: vec!["Item 3".to_string()],
+                is_cancelled: false,
+            },
+            1
+        )
+    );
+}
+
+#[tokio::test]
+async fn materialized_view_test() {
+    let materialized_view: MaterializedView<_, _, _, _, MaterializedViewError> =
+        MaterializedView::new(InMemoryViewStateRepository::new(), view());
+    let result = materialized_view
+        .handle(&OrderEvent::Created(OrderCreatedEvent {
+            order_id: 1,
+            customer_name: "John Doe".to_string(),
+            items: vec!["Item 1".to_string(), "Item 2".to_string()],
+        }))
+        .await;
+    assert!(result.is_ok());
+    let result = materialized_view
+        .handle(&OrderEvent::Updated(OrderUpdatedEvent {
+            order_id: 1,
+            updated_items: vec!["Item 3".to_string()],
+        }))
+        .await;
+    assert_eq!(
+        result.unwrap(),
+        OrderViewState {
+            order_id: 1,
+            customer_name: "John Doe".to_string(),
+            items: vec!["Item 3".to_string()],
+            is_cancelled: false,
+        }
+    );
+}
+
+#[tokio::test]
+async fn materialized_view_deletion_test() {
+    let materialized_view: MaterializedView<_, _, _, _, MaterializedViewError> =
+        MaterializedView::new(InMemoryViewStateRepository::new(), view());
+    // The cancelled orders are purged from the view
+    let deleted = |_event: &OrderEvent, state: &OrderViewState| state.is_cancelled;
+    let created_event = OrderEvent::Created(OrderCreatedEvent {
+        order_id: 1,
+        customer_name: "John Doe".to_string(),
+        items: vec!["Item 1".to_string(), "Item 2".to_string()],
+    });
+    let result = materialized_view
+        .handle_with_deletion(&created_event, &deleted)
+        .await;
+    assert_eq!(
+        result.unwrap(),
+        ViewOutcome::Updated(OrderViewState {
+            order_id: 1,
+            customer_name: "John Doe".to_string(),
+            items: vec!["Item 1".to_string(), "Item 2".to_string()],
+            is_cancelled: false,
+        })
+    );
+    let result = materialized_view
+        .handle_with_deletion(
+            &OrderEvent::Cancelled(OrderCancelledEvent { order_id: 1 }),
+            &deleted,
+        )
+        .await;
+    assert_eq!(result.unwrap(), ViewOutcome::Deleted);
+    assert_eq!(
+        materialized_view.fetch_state(&created_event).await.unwrap(),
+        None
+    );
+}
+
+#[tokio::test]
+async fn state_stored_concurrency_test() {
+    let repository = InMemoryStateRepository::new();
+    let state = OrderState {
+        order_id: 1,
+        customer_name: "John Doe".to_string(),
+        items: vec!["Item 1".to_string(), "Item 2".to_string()],
+        is_cancelled: false,
+    };
+    let result: Result<_, AggregateError> =
+        StateRepository::<OrderCommand, _, _, _>::save(&repository, &state, &None).await;
+    assert_eq!(result.unwrap().1, 0);
+    let result: Result<_, AggregateError> =
+        StateRepository::<OrderCommand, _, _, _>::save(&repository, &state, &None).await;
+    assert!(matches!(result, Err(AggregateError::ConcurrencyError(_))));
+}
+
+#[tokio::test]
+async fn event_sourced_aggregate_batch_test() {
+    let aggregate = EventSourcedAggregate::new(
+        InMemoryEventRepository::new(),
+        decider().map_error(&|()| AggregateError::DomainError("Decider error".to_string())),
+    );
+    let result = aggregate
+        .handle_all(&[create_command(1), create_command(2), update_command(1)])
+        .await
+        .unwrap();
+    assert_eq!(
+        result
+            .iter()
+            .map(|(event, version)| (event.identifier(), *version))
+            .collect::<Vec<_>>(),
+        [
+            ("1".to_string(), 0),
+            ("2".to_string(), 0),
+            ("1".to_string(), 1)
+        ]
+    );
+    let result = aggregate.handle(&update_command(1)).await;
+    assert_eq!(result.unwrap().first().unwrap().1, 2);
+}
+
+#[tokio::test]
+async fn state_stored_aggregate_batch_test() {
+    let aggregate = StateStoredAggregate::new(
+        InMemoryStateRepository::new(),
+        decider().map_error(&|()| AggregateError::DomainError("Decider error".to_string())),
+    );
+    let result = aggregate
+        .handle_all(&[create_command(1), create_command(2), update_command(1)])
+        .await
+        .unwrap();
+    assert_eq!(
+        result,
+        [
+            (
+                OrderState {
+                    order_id: 1,
+                    customer_name: "John Doe".to_string(),
+                    items: vec!["Item 3".to_string()],
+                    is_cancelled: false,
+                },
+                0
+            ),
+            (
+                OrderState {
+                    order_id: 2,
+                    customer_name: "John Doe".to_string(),
+                    items: vec!["Item 1".to_string(), "Item 2".to_string()],
+                    is_cancelled: false,
+                },
+                0
+            )
+        ]
+    );
+}
+
+#[tokio::test]
+async fn event_sourced_snapshotting_aggregate_archiving_test() {
+    let aggregate = EventSourcedSnapshottingAggregate::new(
+        InMemoryEventRepository::new(),
+        InMemorySnapshotRepository::new(),
+        decider().map_error(&|()| AggregateError::DomainError("Decider error".to_string())),
+        2,
+    );
+    aggregate.handle(&create_command(1)).await.unwrap();
+
+    // The stream can not be archived before the first snapshot is saved
+    let result = aggregate.archive(&create_command(1)).await;
+    assert!(matches!(result, Err(AggregateError::FetchState(_))));
+
+    // The snapshot is saved at version 1, so the first two events are archived
+    aggregate.handle(&update_command(1)).await.unwrap();
+    aggregate.handle(&create_command(2)).await.unwrap();
+    let archived = aggregate.archive(&create_command(1)).await.unwrap();
+    assert_eq!(archived.iter().map(|(_, v)| *v).collect::<Vec<_>>(), [0, 1]);
+    let events = aggregate.fetch_events(&create_command(1)).await.unwrap();
+    assert!(events.is_empty());
+
+    // The state is restored from the snapshot, and the versions of the archived events are not reused
+    let saved = aggregate.handle(&update_command(1)).await.unwrap();
+    assert_eq!(saved.first().unwrap().1, 2);
+    let state = aggregate
+        .fetch_current_state(&create_command(1))
+        .await
+        .unwrap();
+    assert_eq!(state.items, vec!["Item 3".to_string()]);
+
+    // The other streams are not archived
+    let events = aggregate.fetch_events(&create_command(2)).await.unwrap();
+    assert_eq!(events.len(), 1);
+}
+
+#[tokio::test]
+async fn event_sourced_snapshotting_aggregate_truncation_test() {
+    let aggregate = EventSourcedSnapshottingAggregate::new(
+        InMemoryEventRepository::new(),
+        InMemorySnapshotRepository::new(),
+        decider().map_error(&|()| AggregateError::DomainError("Decider error".to_string())),
+        2,
+    );
+    aggregate.handle(&create_command(1)).await.unwrap();
+    aggregate.handle(&update_command(1)).await.unwrap();
+
+    let truncated = aggregate.truncate(&create_command(1)).await.unwrap();
+    assert_eq!(truncated, 2);
+    let events = aggregate.fetch_events(&create_command(1)).await.unwrap();
+    assert!(events.is_empty());
+}
+
+#[tokio::test]
+async fn event_repository_truncation_test() {
+    let repository = InMemoryEventRepository::new();
+    let _: Result<_, AggregateError> = EventRepository::<OrderCommand, _, _, _>::save(
+        &repository,
+        &[OrderEvent::Cancelled(OrderCancelledEvent { order_id: 1 })],
+    )
+    .await;
+
+    // The truncated events are deleted, not archived
+    let truncated: Result<usize, AggregateError> = repository
+        .truncate_events_until(&create_command(1), &0)
+        .await;
+    assert_eq!(truncated.unwrap(), 1);
+    assert!(repository.archived_events().is_empty());
+}
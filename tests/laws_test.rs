@@ -0,0 +1,262 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use fmodel_rust::decider::Decider;
+use fmodel_rust::saga::Saga;
+use fmodel_rust::{laws, Sum, Sum3};
+
+use crate::api::{
+    CancelOrderCommand, CreateOrderCommand, CreateShipmentCommand, OrderCancelledEvent,
+    OrderCommand, OrderCreatedEvent, OrderEvent, OrderState, OrderUpdatedEvent, ShipmentCommand,
+    ShipmentCreatedEvent, ShipmentEvent, ShipmentState, UpdateOrderCommand,
+};
+
+mod api;
+mod application;
+
+fn order_decider<'a>() -> Decider<'a, OrderCommand, OrderState, OrderEvent> {
+    Decider {
+        decide: Box::new(|command, state| match command {
+            OrderCommand::Create(cmd) => Ok(vec![OrderEvent::Created(OrderCreatedEvent {
+                order_id: cmd.order_id,
+                customer_name: cmd.customer_name.to_owned(),
+                items: cmd.items.to_owned(),
+            })]),
+            OrderCommand::Update(cmd) => {
+                if state.order_id == cmd.order_id {
+                    Ok(vec![OrderEvent::Updated(OrderUpdatedEvent {
+                        order_id: cmd.order_id,
+                        updated_items: cmd.new_items.to_owned(),
+                    })])
+                } else {
+                    Ok(vec![])
+                }
+            }
+            OrderCommand::Cancel(cmd) => {
+                if state.order_id == cmd.order_id {
+                    Ok(vec![OrderEvent::Cancelled(OrderCancelledEvent {
+                        order_id: cmd.order_id,
+                    })])
+                } else {
+                    Ok(vec![])
+                }
+            }
+        }),
+        evolve: Box::new(|state, event| {
+            let mut new_state = state.clone();
+            match event {
+                OrderEvent::Created(evt) => {
+                    new_state.order_id = evt.order_id;
+                    new_state.customer_name = evt.customer_name.to_owned();
+                    new_state.items = evt.items.to_owned();
+                }
+                OrderEvent::Updated(evt) => {
+                    new_state.items = evt.updated_items.to_owned();
+                }
+                OrderEvent::Cancelled(_) => {
+                    new_state.is_cancelled = true;
+                }
+            }
+            new_state
+        }),
+        initial_state: Box::new(|| OrderState {
+            order_id: 0,
+            customer_name: "".to_string(),
+            items: Vec::new(),
+            is_cancelled: false,
+        }),
+    }
+}
+
+fn shipment_decider<'a>() -> Decider<'a, ShipmentCommand, ShipmentState, ShipmentEvent> {
+    Decider {
+        decide: Box::new(|command, _state| match command {
+            ShipmentCommand::Create(cmd) => {
+                Ok(vec![ShipmentEvent::Created(ShipmentCreatedEvent {
+                    shipment_id: cmd.shipment_id,
+                    order_id: cmd.order_id,
+                    customer_name: cmd.customer_name.to_owned(),
+                    items: cmd.items.to_owned(),
+                })])
+            }
+        }),
+        evolve: Box::new(|state, event| {
+            let mut new_state = state.clone();
+            match event {
+                ShipmentEvent::Created(evt) => {
+                    new_state.shipment_id = evt.shipment_id;
+                    new_state.order_id = evt.order_id;
+                    new_state.customer_name = evt.customer_name.to_owned();
+                    new_state.items = evt.items.to_owned();
+                }
+            }
+            new_state
+        }),
+        initial_state: Box::new(|| ShipmentState {
+            shipment_id: 0,
+            order_id: 0,
+            customer_name: "".to_string(),
+            items: Vec::new(),
+        }),
+    }
+}
+
+fn order_events() -> Vec<Vec<OrderEvent>> {
+    let created = OrderEvent::Created(OrderCreatedEvent {
+        order_id: 1,
+        customer_name: "John Doe".to_string(),
+        items: vec!["Item 1".to_string()],
+    });
+    let updated = OrderEvent::Updated(OrderUpdatedEvent {
+        order_id: 1,
+        updated_items: vec!["Item 2".to_string()],
+    });
+    let cancelled = OrderEvent::Cancelled(OrderCancelledEvent { order_id: 1 });
+    vec![
+        vec![],
+        vec![created.clone()],
+        vec![created.clone(), updated.clone()],
+        vec![created, updated, cancelled],
+    ]
+}
+
+fn order_commands() -> Vec<OrderCommand> {
+    vec![
+        OrderCommand::Create(CreateOrderCommand {
+            order_id: 1,
+            customer_name: "John Doe".to_string(),
+            items: vec!["Item 1".to_string()],
+        }),
+        OrderCommand::Update(UpdateOrderCommand {
+            order_id: 1,
+            new_items: vec!["Item 3".to_string()],
+        }),
+        OrderCommand::Cancel(CancelOrderCommand { order_id: 1 }),
+    ]
+}
+
+#[test]
+fn decider_laws_test() {
+    let decider = order_decider();
+    for events in order_events() {
+        assert!(laws::fold_is_deterministic(&decider, &events));
+        for command in order_commands() {
+            assert!(laws::decide_is_deterministic(
+                &decider,
+                &command,
+                &decider.fold_to_state(&events)
+            ));
+            assert!(laws::computations_agree(&decider, &events, &command));
+        }
+        for event in order_events().concat() {
+            assert!(laws::state_converges(&decider, &events, &event));
+        }
+    }
+}
+
+#[test]
+fn combine_laws_test() {
+    let shipment_created = ShipmentEvent::Created(ShipmentCreatedEvent {
+        shipment_id: 1,
+        order_id: 1,
+        customer_name: "John Doe".to_string(),
+        items: vec!["Item 1".to_string()],
+    });
+    let create_shipment = ShipmentCommand::Create(CreateShipmentCommand {
+        shipment_id: 1,
+        order_id: 1,
+        customer_name: "John Doe".to_string(),
+        items: vec!["Item 1".to_string()],
+    });
+    for events in order_events() {
+        let events = events
+            .into_iter()
+            .map(Sum::First)
+            .chain(std::iter::once(Sum::Second(shipment_created.clone())))
+            .collect::<Vec<_>>();
+        for command in order_commands() {
+            assert!(laws::combine_round_trips(
+                order_decider,
+                shipment_decider,
+                &events,
+                &Sum::First(command)
+            ));
+        }
+        assert!(laws::combine_round_trips(
+            order_decider,
+            shipment_decider,
+            &events,
+            &Sum::Second(create_shipment.clone())
+        ));
+    }
+}
+
+#[test]
+fn impure_decide_test() {
+    // The decide function that depends on the hidden (mutable) state is breaking the law
+    let counter = AtomicU32::new(0);
+    let decider: Decider<u32, u32, u32> = Decider {
+        decide: Box::new(|command, _state| {
+            Ok(vec![*command + counter.fetch_add(1, Ordering::SeqCst)])
+        }),
+        evolve: Box::new(|_state, event| *event),
+        initial_state: Box::new(|| 0),
+    };
+    assert!(!laws::decide_is_deterministic(&decider, &1, &0));
+}
+
+fn order_saga<'a>() -> Saga<'a, OrderEvent, ShipmentCommand> {
+    Saga {
+        react: Box::new(|event| match event {
+            OrderEvent::Created(evt) => Ok(vec![ShipmentCommand::Create(CreateShipmentCommand {
+                shipment_id: evt.order_id,
+                order_id: evt.order_id,
+                customer_name: evt.customer_name.to_owned(),
+                items: evt.items.to_owned(),
+            })]),
+            _ => Ok(vec![]),
+        }),
+    }
+}
+
+fn number_saga<'a>() -> Saga<'a, u32, u32> {
+    Saga {
+        react: Box::new(|n| Ok((0..*n).collect())),
+    }
+}
+
+fn text_saga<'a>() -> Saga<'a, String, String> {
+    Saga {
+        react: Box::new(|text| match text.as_str() {
+            "" => Err(()),
+            text => Ok(text.split(' ').map(str::to_string).collect()),
+        }),
+    }
+}
+
+#[test]
+fn saga_laws_test() {
+    for event in order_events().concat() {
+        assert!(laws::saga_map_action_identity(order_saga, &event));
+        assert!(laws::saga_map_action_result_identity(order_saga, &event));
+        assert!(laws::saga_merge_concatenates(
+            order_saga, order_saga, &event
+        ));
+    }
+    for action_result in [
+        Sum3::First(OrderEvent::Created(OrderCreatedEvent {
+            order_id: 1,
+            customer_name: "John Doe".to_string(),
+            items: vec!["Item 1".to_string()],
+        })),
+        Sum3::Second(3),
+        Sum3::Third("John Doe".to_string()),
+        Sum3::Third("".to_string()),
+    ] {
+        assert!(laws::saga_combine_is_associative(
+            order_saga,
+            number_saga,
+            text_saga,
+            &action_result
+        ));
+    }
+}
@@ -0,0 +1,248 @@
+use std::sync::{Arc, Mutex};
+
+use fmodel_rust::aggregate::{EventRepository, EventRepositoryWithMetadata, EventSourcedAggregate};
+use fmodel_rust::decider::Decider;
+use fmodel_rust::saga::Saga;
+use fmodel_rust::saga_manager::{ActionPublisher, ActionPublisherWithMetadata, SagaManager};
+use fmodel_rust::Identifier;
+
+use crate::api::{
+    CreateOrderCommand, CreateShipmentCommand, OrderCommand, OrderCreatedEvent, OrderEvent,
+    OrderState, ShipmentCommand,
+};
+use crate::application::{AggregateError, SagaManagerError};
+
+mod api;
+mod application;
+
+/// Infrastructure metadata - it is not part of the domain model
+#[derive(Debug, Clone, PartialEq)]
+struct Metadata {
+    correlation_id: String,
+    tenant_id: String,
+}
+
+type StoredEvents = Arc<Mutex<Vec<(OrderEvent, Option<Metadata>, i32)>>>;
+
+/// A simple in-memory event repository, storing the events together with the metadata - infrastructure
+struct InMemoryOrderEventRepository {
+    events: StoredEvents,
+}
+
+impl InMemoryOrderEventRepository {
+    fn new(events: StoredEvents) -> Self {
+        InMemoryOrderEventRepository { events }
+    }
+}
+
+impl EventRepository<OrderCommand, OrderEvent, i32, AggregateError>
+    for InMemoryOrderEventRepository
+{
+    async fn fetch_events(
+        &self,
+        command: &OrderCommand,
+    ) -> Result<Vec<(OrderEvent, i32)>, AggregateError> {
+        Ok(self
+            .events
+            .lock()
+            .unwrap()
+            .clone()
+            .into_iter()
+            .filter(|(event, _, _)| event.identifier() == command.identifier())
+            .map(|(event, _, version)| (event, version))
+            .collect())
+    }
+
+    async fn save(&self, events: &[OrderEvent]) -> Result<Vec<(OrderEvent, i32)>, AggregateError> {
+        let events = events
+            .iter()
+            .map(|event| (event.clone(), None))
+            .collect::<Vec<(OrderEvent, Option<Metadata>)>>();
+        self.append(&events).await
+    }
+
+    async fn version_provider(&self, event: &OrderEvent) -> Result<Option<i32>, AggregateError> {
+        Ok(self
+            .events
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(e, _, _)| e.identifier() == event.identifier())
+            .map(|(_, _, version)| *version)
+            .next_back())
+    }
+}
+
+impl EventRepositoryWithMetadata<OrderCommand, OrderEvent, Metadata, i32, AggregateError>
+    for InMemoryOrderEventRepository
+{
+    async fn save_with_metadata(
+        &self,
+        events: &[(OrderEvent, Metadata)],
+    ) -> Result<Vec<(OrderEvent, i32)>, AggregateError> {
+        let events = events
+            .iter()
+            .map(|(event, metadata)| (event.clone(), Some(metadata.clone())))
+            .collect::<Vec<(OrderEvent, Option<Metadata>)>>();
+        self.append(&events).await
+    }
+}
+
+impl InMemoryOrderEventRepository {
+    async fn append(
+        &self,
+        events: &[(OrderEvent, Option<Metadata>)],
+    ) -> Result<Vec<(OrderEvent, i32)>, AggregateError> {
+        let mut latest_version = self
+            .version_provider(&events.first().unwrap().0)
+            .await?
+            .unwrap_or(-1);
+        let events = events
+            .iter()
+            .map(|(event, metadata)| {
+                latest_version += 1;
+                (event.clone(), metadata.clone(), latest_version)
+            })
+            .collect::<Vec<(OrderEvent, Option<Metadata>, i32)>>();
+        self.events.lock().unwrap().extend_from_slice(&events);
+        Ok(events
+            .into_iter()
+            .map(|(event, _, version)| (event, version))
+            .collect())
+    }
+}
+
+type PublishedActions = Arc<Mutex<Vec<(ShipmentCommand, Option<Metadata>)>>>;
+
+/// Simple action publisher that records the published actions together with the metadata - infrastructure
+struct InMemoryActionPublisher {
+    actions: PublishedActions,
+}
+
+impl InMemoryActionPublisher {
+    fn new(actions: PublishedActions) -> Self {
+        InMemoryActionPublisher { actions }
+    }
+}
+
+impl ActionPublisher<ShipmentCommand, SagaManagerError> for InMemoryActionPublisher {
+    async fn publish(
+        &self,
+        action: &[ShipmentCommand],
+    ) -> Result<Vec<ShipmentCommand>, SagaManagerError> {
+        self.actions
+            .lock()
+            .unwrap()
+            .extend(action.iter().map(|a| (a.clone(), None)));
+        Ok(Vec::from(action))
+    }
+}
+
+impl ActionPublisherWithMetadata<ShipmentCommand, Metadata, SagaManagerError>
+    for InMemoryActionPublisher
+{
+    async fn publish_with_metadata(
+        &self,
+        action: &[(ShipmentCommand, Metadata)],
+    ) -> Result<Vec<ShipmentCommand>, SagaManagerError> {
+        self.actions
+            .lock()
+            .unwrap()
+            .extend(action.iter().map(|(a, m)| (a.clone(), Some(m.clone()))));
+        Ok(action.iter().map(|(a, _)| a.clone()).collect())
+    }
+}
+
+fn decider<'a>() -> Decider<'a, OrderCommand, OrderState, OrderEvent> {
+    Decider {
+        decide: Box::new(|command, _state| match command {
+            OrderCommand::Create(cmd) => Ok(vec![OrderEvent::Created(OrderCreatedEvent {
+                order_id: cmd.order_id,
+                customer_name: cmd.customer_name.to_owned(),
+                items: cmd.items.to_owned(),
+            })]),
+            OrderCommand::Update(_) => Ok(vec![]),
+            OrderCommand::Cancel(_) => Ok(vec![]),
+        }),
+        evolve: Box::new(|state, event| {
+            let mut new_state = state.clone();
+            if let OrderEvent::Created(evt) = event {
+                new_state.order_id = evt.order_id;
+                new_state.customer_name = evt.customer_name.to_owned();
+                new_state.items = evt.items.to_owned();
+            }
+            new_state
+        }),
+        initial_state: Box::new(|| OrderState {
+            order_id: 0,
+            customer_name: "".to_string(),
+            items: Vec::new(),
+            is_cancelled: false,
+        }),
+    }
+}
+
+fn saga<'a>() -> Saga<'a, OrderEvent, ShipmentCommand> {
+    Saga {
+        react: Box::new(|event| match event {
+            OrderEvent::Created(evt) => Ok(vec![ShipmentCommand::Create(CreateShipmentCommand {
+                shipment_id: evt.order_id,
+                order_id: evt.order_id,
+                customer_name: evt.customer_name.to_owned(),
+                items: evt.items.to_owned(),
+            })]),
+            OrderEvent::Updated(_) => Ok(vec![]),
+            OrderEvent::Cancelled(_) => Ok(vec![]),
+        }),
+    }
+}
+
+#[tokio::test]
+async fn test() {
+    let metadata = Metadata {
+        correlation_id: "correlation-1".to_string(),
+        tenant_id: "tenant-1".to_string(),
+    };
+    let order_created_event = OrderEvent::Created(OrderCreatedEvent {
+        order_id: 1,
+        customer_name: "John Doe".to_string(),
+        items: vec!["Item 1".to_string(), "Item 2".to_string()],
+    });
+
+    let events: StoredEvents = Arc::new(Mutex::new(vec![]));
+    let aggregate = EventSourcedAggregate::new(
+        InMemoryOrderEventRepository::new(Arc::clone(&events)),
+        decider().map_error(&|()| AggregateError::DomainError("Decider error".to_string())),
+    );
+    let command = OrderCommand::Create(CreateOrderCommand {
+        order_id: 1,
+        customer_name: "John Doe".to_string(),
+        items: vec!["Item 1".to_string(), "Item 2".to_string()],
+    });
+    let result = aggregate.handle_with_metadata(&command, &metadata).await;
+    assert_eq!(result.unwrap(), [(order_created_event.clone(), 0)]);
+    assert_eq!(
+        *events.lock().unwrap(),
+        vec![(order_created_event.clone(), Some(metadata.clone()), 0)]
+    );
+
+    let actions: PublishedActions = Arc::new(Mutex::new(vec![]));
+    let saga_manager = SagaManager::new(
+        InMemoryActionPublisher::new(Arc::clone(&actions)),
+        saga().map_error(&|()| SagaManagerError::DomainError("Saga error".to_string())),
+    );
+    let shipment_command = ShipmentCommand::Create(CreateShipmentCommand {
+        shipment_id: 1,
+        order_id: 1,
+        customer_name: "John Doe".to_string(),
+        items: vec!["Item 1".to_string(), "Item 2".to_string()],
+    });
+    let result = saga_manager
+        .handle_with_metadata(&order_created_event, &metadata)
+        .await;
+    assert_eq!(result.unwrap(), vec![shipment_command.clone()]);
+    assert_eq!(
+        *actions.lock().unwrap(),
+        vec![(shipment_command, Some(metadata))]
+    );
+}
@@ -0,0 +1,181 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use fmodel_rust::aggregate::{EventRepository, EventSourcedAggregate};
+use fmodel_rust::decider::Decider;
+use fmodel_rust::metrics::{
+    Metered, MetricsRecorder, COMMANDS_FAILED, COMMANDS_HANDLED, EVENTS_PRODUCED, EVENTS_REPLAYED,
+    HANDLE_DURATION, SAGA_ACTIONS_EMITTED,
+};
+use fmodel_rust::saga::{ActionComputation, Saga};
+
+use crate::api::{
+    CancelOrderCommand, CreateOrderCommand, CreateShipmentCommand, OrderCommand, OrderCreatedEvent,
+    OrderEvent, OrderState, ShipmentCommand,
+};
+use crate::application::AggregateError;
+
+mod api;
+mod application;
+
+/// A simple in-memory metrics recorder - infrastructure
+#[derive(Default)]
+struct InMemoryMetricsRecorder {
+    counters: Mutex<HashMap<&'static str, u64>>,
+    histograms: Mutex<HashMap<&'static str, Vec<f64>>>,
+}
+
+impl InMemoryMetricsRecorder {
+    fn counter(&self, name: &'static str) -> u64 {
+        self.counters
+            .lock()
+            .unwrap()
+            .get(name)
+            .copied()
+            .unwrap_or(0)
+    }
+
+    fn histogram(&self, name: &'static str) -> Vec<f64> {
+        self.histograms
+            .lock()
+            .unwrap()
+            .get(name)
+            .cloned()
+            .unwrap_or_default()
+    }
+}
+
+impl MetricsRecorder for InMemoryMetricsRecorder {
+    fn increment_counter(&self, name: &'static str, value: u64) {
+        *self.counters.lock().unwrap().entry(name).or_default() += value;
+    }
+
+    fn record_histogram(&self, name: &'static str, value: f64) {
+        self.histograms
+            .lock()
+            .unwrap()
+            .entry(name)
+            .or_default()
+            .push(value);
+    }
+}
+
+/// A simple in-memory event repository - infrastructure
+#[derive(Default)]
+struct InMemoryOrderEventRepository {
+    events: Mutex<Vec<(OrderEvent, i32)>>,
+}
+
+impl EventRepository<OrderCommand, OrderEvent, i32, AggregateError>
+    for InMemoryOrderEventRepository
+{
+    async fn fetch_events(
+        &self,
+        _command: &OrderCommand,
+    ) -> Result<Vec<(OrderEvent, i32)>, AggregateError> {
+        Ok(self.events.lock().unwrap().clone())
+    }
+
+    async fn save(&self, events: &[OrderEvent]) -> Result<Vec<(OrderEvent, i32)>, AggregateError> {
+        let mut stored = self.events.lock().unwrap();
+        let saved = events
+            .iter()
+            .map(|event| (event.clone(), stored.len() as i32))
+            .collect::<Vec<_>>();
+        stored.extend_from_slice(&saved);
+        Ok(saved)
+    }
+
+    async fn version_provider(&self, _event: &OrderEvent) -> Result<Option<i32>, AggregateError> {
+        Ok(None)
+    }
+}
+
+/// Decider is rejecting the cancellation of the non-existing order
+fn decider<'a>() -> Decider<'a, OrderCommand, OrderState, OrderEvent, AggregateError> {
+    Decider {
+        decide: Box::new(|command, state| match command {
+            OrderCommand::Create(cmd) => Ok(vec![OrderEvent::Created(OrderCreatedEvent {
+                order_id: cmd.order_id,
+                customer_name: cmd.customer_name.to_owned(),
+                items: cmd.items.to_owned(),
+            })]),
+            OrderCommand::Cancel(cmd) if state.order_id != cmd.order_id => Err(
+                AggregateError::DomainError("Order does not exist".to_string()),
+            ),
+            _ => Ok(vec![]),
+        }),
+        evolve: Box::new(|state, event| {
+            let mut new_state = state.clone();
+            if let OrderEvent::Created(evt) = event {
+                new_state.order_id = evt.order_id;
+            }
+            new_state
+        }),
+        initial_state: Box::new(|| OrderState {
+            order_id: 0,
+            customer_name: "".to_string(),
+            items: Vec::new(),
+            is_cancelled: false,
+        }),
+    }
+}
+
+fn saga<'a>() -> Saga<'a, OrderEvent, ShipmentCommand> {
+    Saga {
+        react: Box::new(|event| match event {
+            OrderEvent::Created(evt) => Ok(vec![ShipmentCommand::Create(CreateShipmentCommand {
+                shipment_id: evt.order_id,
+                order_id: evt.order_id,
+                customer_name: evt.customer_name.to_owned(),
+                items: evt.items.to_owned(),
+            })]),
+            _ => Ok(vec![]),
+        }),
+    }
+}
+
+#[tokio::test]
+async fn aggregate_test() {
+    let recorder = Arc::new(InMemoryMetricsRecorder::default());
+    let aggregate = Metered::new(
+        EventSourcedAggregate::new(
+            Metered::new(InMemoryOrderEventRepository::default(), recorder.clone()),
+            decider(),
+        ),
+        recorder.clone(),
+    );
+
+    let result = aggregate
+        .handle(&OrderCommand::Create(CreateOrderCommand {
+            order_id: 1,
+            customer_name: "John Doe".to_string(),
+            items: vec!["Item 1".to_string()],
+        }))
+        .await;
+    assert!(result.is_ok());
+    let result = aggregate
+        .handle(&OrderCommand::Cancel(CancelOrderCommand { order_id: 2 }))
+        .await;
+    assert!(result.is_err());
+
+    assert_eq!(recorder.counter(COMMANDS_HANDLED), 2);
+    assert_eq!(recorder.counter(COMMANDS_FAILED), 1);
+    assert_eq!(recorder.counter(EVENTS_PRODUCED), 1);
+    assert_eq!(recorder.histogram(HANDLE_DURATION).len(), 2);
+    assert_eq!(recorder.histogram(EVENTS_REPLAYED), [0.0, 1.0]);
+}
+
+#[test]
+fn saga_test() {
+    let recorder = InMemoryMetricsRecorder::default();
+    let saga = Metered::new(saga(), &recorder);
+
+    let actions = saga.compute_new_actions(&OrderEvent::Created(OrderCreatedEvent {
+        order_id: 1,
+        customer_name: "John Doe".to_string(),
+        items: vec!["Item 1".to_string()],
+    }));
+    assert_eq!(actions.unwrap().len(), 1);
+    assert_eq!(recorder.counter(SAGA_ACTIONS_EMITTED), 1);
+}
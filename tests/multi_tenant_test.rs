@@ -0,0 +1,220 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use fmodel_rust::aggregate::{EventRepository, EventRepositoryWithContext, EventSourcedAggregate};
+use fmodel_rust::decider::Decider;
+use fmodel_rust::materialized_view::{
+    MaterializedView, ViewStateRepository, ViewStateRepositoryWithContext,
+};
+use fmodel_rust::view::View;
+use fmodel_rust::Identifier;
+
+use crate::api::{CreateOrderCommand, OrderCommand, OrderCreatedEvent, OrderEvent, OrderState};
+use crate::application::{AggregateError, MaterializedViewError};
+
+mod api;
+mod application;
+
+/// The tenant of the multi-tenant system - infrastructure context
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct TenantId(String);
+
+/// A simple in-memory event repository, partitioning the event streams by the tenant - infrastructure
+#[derive(Default)]
+struct InMemoryOrderEventRepository {
+    events: Mutex<Vec<(TenantId, OrderEvent, i32)>>,
+}
+
+impl EventRepository<OrderCommand, OrderEvent, i32, AggregateError>
+    for InMemoryOrderEventRepository
+{
+    async fn fetch_events(
+        &self,
+        _command: &OrderCommand,
+    ) -> Result<Vec<(OrderEvent, i32)>, AggregateError> {
+        Err(AggregateError::FetchEvents(
+            "The tenant is required".to_string(),
+        ))
+    }
+
+    async fn save(&self, _events: &[OrderEvent]) -> Result<Vec<(OrderEvent, i32)>, AggregateError> {
+        Err(AggregateError::SaveEvents(
+            "The tenant is required".to_string(),
+        ))
+    }
+
+    async fn version_provider(&self, _event: &OrderEvent) -> Result<Option<i32>, AggregateError> {
+        Ok(None)
+    }
+}
+
+impl EventRepositoryWithContext<OrderCommand, OrderEvent, TenantId, i32, AggregateError>
+    for InMemoryOrderEventRepository
+{
+    async fn fetch_events_with_context(
+        &self,
+        command: &OrderCommand,
+        context: &TenantId,
+    ) -> Result<Vec<(OrderEvent, i32)>, AggregateError> {
+        Ok(self
+            .events
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(tenant_id, event, _)| {
+                tenant_id == context && event.identifier() == command.identifier()
+            })
+            .map(|(_, event, version)| (event.clone(), *version))
+            .collect())
+    }
+
+    async fn save_with_context(
+        &self,
+        events: &[OrderEvent],
+        context: &TenantId,
+    ) -> Result<Vec<(OrderEvent, i32)>, AggregateError> {
+        let mut stored_events = self.events.lock().unwrap();
+        let mut saved_events = vec![];
+        for event in events {
+            let version = stored_events.len() as i32 + 1;
+            stored_events.push((context.clone(), event.clone(), version));
+            saved_events.push((event.clone(), version));
+        }
+        Ok(saved_events)
+    }
+}
+
+/// A simple in-memory view state repository, partitioning the read model by the tenant - infrastructure
+#[derive(Default)]
+struct InMemoryOrderCountRepository {
+    counts: Mutex<HashMap<TenantId, usize>>,
+}
+
+impl ViewStateRepository<OrderEvent, usize, MaterializedViewError>
+    for InMemoryOrderCountRepository
+{
+    async fn fetch_state(
+        &self,
+        _event: &OrderEvent,
+    ) -> Result<Option<usize>, MaterializedViewError> {
+        Err(MaterializedViewError::FetchState(
+            "The tenant is required".to_string(),
+        ))
+    }
+
+    async fn save(&self, _state: &usize) -> Result<usize, MaterializedViewError> {
+        Err(MaterializedViewError::SaveState(
+            "The tenant is required".to_string(),
+        ))
+    }
+}
+
+impl ViewStateRepositoryWithContext<OrderEvent, usize, TenantId, MaterializedViewError>
+    for InMemoryOrderCountRepository
+{
+    async fn fetch_state_with_context(
+        &self,
+        _event: &OrderEvent,
+        context: &TenantId,
+    ) -> Result<Option<usize>, MaterializedViewError> {
+        Ok(self.counts.lock().unwrap().get(context).copied())
+    }
+
+    async fn save_with_context(
+        &self,
+        state: &usize,
+        context: &TenantId,
+    ) -> Result<usize, MaterializedViewError> {
+        self.counts.lock().unwrap().insert(context.clone(), *state);
+        Ok(*state)
+    }
+}
+
+/// The order can be created only once
+fn decider<'a>() -> Decider<'a, OrderCommand, OrderState, OrderEvent, AggregateError> {
+    Decider {
+        decide: Box::new(|command, state| match command {
+            OrderCommand::Create(cmd) if state.order_id == 0 => {
+                Ok(vec![OrderEvent::Created(OrderCreatedEvent {
+                    order_id: cmd.order_id,
+                    customer_name: cmd.customer_name.to_owned(),
+                    items: cmd.items.to_owned(),
+                })])
+            }
+            OrderCommand::Create(_) => Err(AggregateError::DomainError(
+                "The order already exists".to_string(),
+            )),
+            _ => Ok(vec![]),
+        }),
+        evolve: Box::new(|state, event| match event {
+            OrderEvent::Created(evt) => OrderState {
+                order_id: evt.order_id,
+                customer_name: evt.customer_name.to_owned(),
+                items: evt.items.to_owned(),
+                is_cancelled: false,
+            },
+            _ => state.clone(),
+        }),
+        initial_state: Box::new(|| OrderState {
+            order_id: 0,
+            customer_name: "".to_string(),
+            items: Vec::new(),
+            is_cancelled: false,
+        }),
+    }
+}
+
+/// Counts the created orders
+fn view<'a>() -> View<'a, usize, OrderEvent> {
+    View {
+        evolve: Box::new(|state, event| match event {
+            OrderEvent::Created(_) => state + 1,
+            _ => *state,
+        }),
+        initial_state: Box::new(|| 0),
+    }
+}
+
+#[tokio::test]
+async fn test() {
+    let aggregate = EventSourcedAggregate::new(InMemoryOrderEventRepository::default(), decider());
+    let materialized_view = MaterializedView::new(InMemoryOrderCountRepository::default(), view());
+    let command = OrderCommand::Create(CreateOrderCommand {
+        order_id: 1,
+        customer_name: "John Doe".to_string(),
+        items: vec!["Item 1".to_string()],
+    });
+    let tenant_a = TenantId("a".to_string());
+    let tenant_b = TenantId("b".to_string());
+
+    let events = aggregate
+        .handle_with_context(&command, &tenant_a)
+        .await
+        .unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(
+        materialized_view
+            .handle_with_context(&events[0].0, &tenant_a)
+            .await
+            .unwrap(),
+        1
+    );
+
+    // The same order id is free within the other tenant
+    let events = aggregate
+        .handle_with_context(&command, &tenant_b)
+        .await
+        .unwrap();
+    assert_eq!(events[0].1, 2);
+    assert_eq!(
+        materialized_view
+            .handle_with_context(&events[0].0, &tenant_b)
+            .await
+            .unwrap(),
+        1
+    );
+
+    // The order already exists within the same tenant
+    let result = aggregate.handle_with_context(&command, &tenant_a).await;
+    assert!(matches!(result, Err(AggregateError::DomainError(_))));
+}
@@ -0,0 +1,270 @@
+use std::future::ready;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use fmodel_rust::aggregate::{
+    ConcurrencyError, EventRepository, EventRepositoryWithOptimisticLocking, EventSourcedAggregate,
+    Resolution, RetryPolicy,
+};
+use fmodel_rust::decider::Decider;
+use fmodel_rust::Identifier;
+
+use crate::api::{
+    OrderCommand, OrderCreatedEvent, OrderEvent, OrderState, OrderUpdatedEvent, UpdateOrderCommand,
+};
+use crate::application::AggregateError;
+
+mod api;
+mod application;
+
+/// A simple in-memory event repository with optimistic locking - infrastructure
+/// It simulates the concurrent writer by appending a foreign event right after the events are fetched, `concurrent_writes` times.
+struct InMemoryOrderEventRepository {
+    events: Mutex<Vec<(OrderEvent, i32)>>,
+    concurrent_writes: Mutex<u32>,
+}
+
+impl InMemoryOrderEventRepository {
+    fn new(events: Vec<(OrderEvent, i32)>, concurrent_writes: u32) -> Self {
+        InMemoryOrderEventRepository {
+            events: Mutex::new(events),
+            concurrent_writes: Mutex::new(concurrent_writes),
+        }
+    }
+}
+
+impl EventRepository<OrderCommand, OrderEvent, i32, AggregateError>
+    for InMemoryOrderEventRepository
+{
+    async fn fetch_events(
+        &self,
+        command: &OrderCommand,
+    ) -> Result<Vec<(OrderEvent, i32)>, AggregateError> {
+        let events: Vec<(OrderEvent, i32)> = self
+            .events
+            .lock()
+            .unwrap()
+            .clone()
+            .into_iter()
+            .filter(|(event, _)| event.identifier() == command.identifier())
+            .collect();
+        let mut concurrent_writes = self.concurrent_writes.lock().unwrap();
+        if *concurrent_writes > 0 {
+            *concurrent_writes -= 1;
+            let concurrent_event = OrderEvent::Updated(OrderUpdatedEvent {
+                order_id: command.identifier().parse::<u32>().unwrap(),
+                updated_items: vec!["Concurrent item".to_string()],
+            });
+            let version = events.last().map_or(0, |(_, version)| version + 1);
+            self.events
+                .lock()
+                .unwrap()
+                .push((concurrent_event, version));
+        }
+        Ok(events)
+    }
+
+    async fn save(&self, events: &[OrderEvent]) -> Result<Vec<(OrderEvent, i32)>, AggregateError> {
+        let expected_version = self.version_provider(events.first().unwrap()).await?;
+        self.save_with_expected_version(events, &expected_version)
+            .await
+    }
+
+    async fn version_provider(&self, event: &OrderEvent) -> Result<Option<i32>, AggregateError> {
+        Ok(self
+            .events
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(e, _)| e.identifier() == event.identifier())
+            .map(|(_, version)| *version)
+            .next_back())
+    }
+}
+
+impl EventRepositoryWithOptimisticLocking<OrderCommand, OrderEvent, i32, AggregateError>
+    for InMemoryOrderEventRepository
+{
+    async fn save_with_expected_version(
+        &self,
+        events: &[OrderEvent],
+        expected_version: &Option<i32>,
+    ) -> Result<Vec<(OrderEvent, i32)>, AggregateError> {
+        let actual_version = self.version_provider(events.first().unwrap()).await?;
+        if actual_version != *expected_version {
+            return Err(ConcurrencyError {
+                expected_version: *expected_version,
+                actual_version,
+            }
+            .into());
+        }
+        let mut latest_version = actual_version.unwrap_or(-1);
+        let events = events
+            .iter()
+            .map(|event| {
+                latest_version += 1;
+                (event.clone(), latest_version)
+            })
+            .collect::<Vec<(OrderEvent, i32)>>();
+        self.events.lock().unwrap().extend_from_slice(&events);
+        Ok(events)
+    }
+}
+
+fn decider<'a>() -> Decider<'a, OrderCommand, OrderState, OrderEvent> {
+    Decider {
+        decide: Box::new(|command, _state| match command {
+            OrderCommand::Create(cmd) => Ok(vec![OrderEvent::Created(OrderCreatedEvent {
+                order_id: cmd.order_id,
+                customer_name: cmd.customer_name.to_owned(),
+                items: cmd.items.to_owned(),
+            })]),
+            OrderCommand::Update(cmd) => Ok(vec![OrderEvent::Updated(OrderUpdatedEvent {
+                order_id: cmd.order_id,
+                updated_items: cmd.new_items.to_owned(),
+            })]),
+            OrderCommand::Cancel(_) => Ok(vec![]),
+        }),
+        evolve: Box::new(|state, event| {
+            let mut new_state = state.clone();
+            match event {
+                OrderEvent::Created(evt) => {
+                    new_state.order_id = evt.order_id;
+                    new_state.customer_name = evt.customer_name.to_owned();
+                    new_state.items = evt.items.to_owned();
+                }
+                OrderEvent::Updated(evt) => {
+                    new_state.items = evt.updated_items.to_owned();
+                }
+                OrderEvent::Cancelled(_) => {
+                    new_state.is_cancelled = true;
+                }
+            }
+            new_state
+        }),
+        initial_state: Box::new(|| OrderState {
+            order_id: 0,
+            customer_name: "".to_string(),
+            items: Vec::new(),
+            is_cancelled: false,
+        }),
+    }
+}
+
+fn created_event() -> OrderEvent {
+    OrderEvent::Created(OrderCreatedEvent {
+        order_id: 1,
+        customer_name: "John Doe".to_string(),
+        items: vec!["Item 1".to_string(), "Item 2".to_string()],
+    })
+}
+
+fn update_command() -> OrderCommand {
+    OrderCommand::Update(UpdateOrderCommand {
+        order_id: 1,
+        new_items: vec!["Item 3".to_string()],
+    })
+}
+
+#[tokio::test]
+async fn retry_test() {
+    let aggregate = EventSourcedAggregate::new(
+        InMemoryOrderEventRepository::new(vec![(created_event(), 0)], 2),
+        decider().map_error(&|()| AggregateError::DomainError("Decider error".to_string())),
+    );
+    let delays = Mutex::new(vec![]);
+    let sleep = |delay: Duration| {
+        delays.lock().unwrap().push(delay);
+        ready(())
+    };
+    let result = aggregate
+        .handle_with_retry(
+            &update_command(),
+            &RetryPolicy::fixed(3, Duration::from_millis(10)),
+            &sleep,
+        )
+        .await;
+    assert_eq!(
+        result.unwrap(),
+        [(
+            OrderEvent::Updated(OrderUpdatedEvent {
+                order_id: 1,
+                updated_items: vec!["Item 3".to_string()],
+            }),
+            3
+        )]
+    );
+    assert_eq!(
+        *delays.lock().unwrap(),
+        [Duration::from_millis(10), Duration::from_millis(10)]
+    );
+}
+
+#[tokio::test]
+async fn retry_exhausted_test() {
+    let aggregate = EventSourcedAggregate::new(
+        InMemoryOrderEventRepository::new(vec![(created_event(), 0)], 2),
+        decider().map_error(&|()| AggregateError::DomainError("Decider error".to_string())),
+    );
+    let result = aggregate
+        .handle_with_retry(&update_command(), &RetryPolicy::new(2), &|_| ready(()))
+        .await;
+    assert!(matches!(result, Err(AggregateError::ConcurrencyError(_))));
+}
+
+#[tokio::test]
+async fn conflict_resolution_merge_test() {
+    let aggregate = EventSourcedAggregate::new(
+        InMemoryOrderEventRepository::new(vec![(created_event(), 0)], 1),
+        decider().map_error(&|()| AggregateError::DomainError("Decider error".to_string())),
+    );
+    let conflicts = Mutex::new(vec![]);
+    // The concurrent item updates commute, so my events are saved on top of theirs
+    let resolver = |mine: &[OrderEvent], theirs: &[OrderEvent]| {
+        conflicts.lock().unwrap().extend_from_slice(theirs);
+        Resolution::Merge(Vec::from(mine))
+    };
+    let result = aggregate
+        .handle_with_conflict_resolution(
+            &update_command(),
+            &resolver,
+            &RetryPolicy::new(3),
+            &|_| ready(()),
+        )
+        .await;
+    assert_eq!(
+        result.unwrap(),
+        [(
+            OrderEvent::Updated(OrderUpdatedEvent {
+                order_id: 1,
+                updated_items: vec!["Item 3".to_string()],
+            }),
+            2
+        )]
+    );
+    assert_eq!(
+        *conflicts.lock().unwrap(),
+        [OrderEvent::Updated(OrderUpdatedEvent {
+            order_id: 1,
+            updated_items: vec!["Concurrent item".to_string()],
+        })]
+    );
+}
+
+#[tokio::test]
+async fn conflict_resolution_reject_test() {
+    let aggregate = EventSourcedAggregate::new(
+        InMemoryOrderEventRepository::new(vec![(created_event(), 0)], 1),
+        decider().map_error(&|()| AggregateError::DomainError("Decider error".to_string())),
+    );
+    let resolver = |_: &[OrderEvent], _: &[OrderEvent]| Resolution::Reject;
+    let result = aggregate
+        .handle_with_conflict_resolution(
+            &update_command(),
+            &resolver,
+            &RetryPolicy::new(3),
+            &|_| ready(()),
+        )
+        .await;
+    assert!(matches!(result, Err(AggregateError::ConcurrencyError(_))));
+}
@@ -0,0 +1,282 @@
+use std::future::ready;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use fmodel_rust::aggregate::{EventRepository, EventRepositoryWithOutbox, EventSourcedAggregate};
+use fmodel_rust::decider::Decider;
+use fmodel_rust::outbox::{
+    Backoff, EventPublisher, EventPublishingAggregate, OutboxProcessor, OutboxRepository,
+};
+
+use crate::api::{CreateOrderCommand, OrderCommand, OrderCreatedEvent, OrderEvent, OrderState};
+use crate::application::AggregateError;
+
+mod api;
+mod application;
+
+/// A simple in-memory event repository with the outbox - infrastructure
+#[derive(Default)]
+struct InMemoryOrderEventRepository {
+    events: Mutex<Vec<(OrderEvent, i32)>>,
+    outbox: Arc<Mutex<Vec<(OrderEvent, i32)>>>,
+}
+
+impl EventRepository<OrderCommand, OrderEvent, i32, AggregateError>
+    for InMemoryOrderEventRepository
+{
+    async fn fetch_events(
+        &self,
+        _command: &OrderCommand,
+    ) -> Result<Vec<(OrderEvent, i32)>, AggregateError> {
+        Ok(self.events.lock().unwrap().clone())
+    }
+
+    async fn save(&self, events: &[OrderEvent]) -> Result<Vec<(OrderEvent, i32)>, AggregateError> {
+        let mut stored = self.events.lock().unwrap();
+        let saved = events
+            .iter()
+            .map(|event| (event.clone(), stored.len() as i32))
+            .collect::<Vec<_>>();
+        stored.extend_from_slice(&saved);
+        Ok(saved)
+    }
+
+    async fn version_provider(&self, _event: &OrderEvent) -> Result<Option<i32>, AggregateError> {
+        Ok(None)
+    }
+}
+
+impl EventRepositoryWithOutbox<OrderCommand, OrderEvent, i32, AggregateError>
+    for InMemoryOrderEventRepository
+{
+    async fn save_and_outbox(
+        &self,
+        events: &[OrderEvent],
+    ) -> Result<Vec<(OrderEvent, i32)>, AggregateError> {
+        // In real life, the events and the outbox entries are saved in the same database transaction
+        let saved = self.save(events).await?;
+        self.outbox.lock().unwrap().extend_from_slice(&saved);
+        Ok(saved)
+    }
+}
+
+/// A simple in-memory event publisher - infrastructure
+#[derive(Default)]
+struct InMemoryEventPublisher {
+    published: Mutex<Vec<(OrderEvent, i32)>>,
+}
+
+impl EventPublisher<OrderEvent, i32, AggregateError> for &InMemoryEventPublisher {
+    async fn publish(
+        &self,
+        events: &[(OrderEvent, i32)],
+    ) -> Result<Vec<(OrderEvent, i32)>, AggregateError> {
+        self.published.lock().unwrap().extend_from_slice(events);
+        Ok(Vec::from(events))
+    }
+}
+
+/// A simple in-memory outbox, sharing the entries with the event repository - infrastructure
+struct InMemoryOutboxRepository {
+    outbox: Arc<Mutex<Vec<(OrderEvent, i32)>>>,
+}
+
+impl OutboxRepository<OrderEvent, i32, AggregateError> for InMemoryOutboxRepository {
+    async fn fetch_pending(&self, limit: usize) -> Result<Vec<(OrderEvent, i32)>, AggregateError> {
+        Ok(self
+            .outbox
+            .lock()
+            .unwrap()
+            .iter()
+            .take(limit)
+            .cloned()
+            .collect())
+    }
+
+    async fn mark_dispatched(&self, events: &[(OrderEvent, i32)]) -> Result<(), AggregateError> {
+        self.outbox
+            .lock()
+            .unwrap()
+            .retain(|(_, version)| !events.iter().any(|(_, v)| v == version));
+        Ok(())
+    }
+}
+
+/// An event publisher that is failing the first `failures` attempts - infrastructure
+struct FlakyEventPublisher {
+    failures: Mutex<usize>,
+    published: Mutex<Vec<(OrderEvent, i32)>>,
+}
+
+impl FlakyEventPublisher {
+    fn new(failures: usize) -> Self {
+        FlakyEventPublisher {
+            failures: Mutex::new(failures),
+            published: Mutex::new(vec![]),
+        }
+    }
+}
+
+impl EventPublisher<OrderEvent, i32, AggregateError> for &FlakyEventPublisher {
+    async fn publish(
+        &self,
+        events: &[(OrderEvent, i32)],
+    ) -> Result<Vec<(OrderEvent, i32)>, AggregateError> {
+        let mut failures = self.failures.lock().unwrap();
+        if *failures > 0 {
+            *failures -= 1;
+            return Err(AggregateError::SaveEvents("Broker unavailable".to_string()));
+        }
+        self.published.lock().unwrap().extend_from_slice(events);
+        Ok(Vec::from(events))
+    }
+}
+
+fn decider<'a>() -> Decider<'a, OrderCommand, OrderState, OrderEvent> {
+    Decider {
+        decide: Box::new(|command, _state| match command {
+            OrderCommand::Create(cmd) => Ok(vec![OrderEvent::Created(OrderCreatedEvent {
+                order_id: cmd.order_id,
+                customer_name: cmd.customer_name.to_owned(),
+                items: cmd.items.to_owned(),
+            })]),
+            _ => Ok(vec![]),
+        }),
+        evolve: Box::new(|state, _event| state.clone()),
+        initial_state: Box::new(|| OrderState {
+            order_id: 0,
+            customer_name: "".to_string(),
+            items: Vec::new(),
+            is_cancelled: false,
+        }),
+    }
+}
+
+fn create_command() -> OrderCommand {
+    OrderCommand::Create(CreateOrderCommand {
+        order_id: 1,
+        customer_name: "John Doe".to_string(),
+        items: vec!["Item 1".to_string()],
+    })
+}
+
+fn order_created_event() -> OrderEvent {
+    OrderEvent::Created(OrderCreatedEvent {
+        order_id: 1,
+        customer_name: "John Doe".to_string(),
+        items: vec!["Item 1".to_string()],
+    })
+}
+
+#[tokio::test]
+async fn publishing_test() {
+    let publisher = InMemoryEventPublisher::default();
+    let aggregate = EventPublishingAggregate::new(
+        EventSourcedAggregate::new(
+            InMemoryOrderEventRepository::default(),
+            decider().map_error(&|()| AggregateError::DomainError("Decider error".to_string())),
+        ),
+        &publisher,
+    );
+
+    let result = aggregate.handle(&create_command()).await;
+    assert_eq!(result.unwrap(), [(order_created_event(), 0)]);
+    assert_eq!(
+        *publisher.published.lock().unwrap(),
+        [(order_created_event(), 0)]
+    );
+}
+
+#[tokio::test]
+async fn outbox_test() {
+    let repository = InMemoryOrderEventRepository::default();
+    let outbox = Arc::clone(&repository.outbox);
+    let aggregate = EventSourcedAggregate::new(
+        repository,
+        decider().map_error(&|()| AggregateError::DomainError("Decider error".to_string())),
+    );
+
+    let result = aggregate.handle_with_outbox(&create_command()).await;
+    assert_eq!(result.unwrap(), [(order_created_event(), 0)]);
+    assert_eq!(*outbox.lock().unwrap(), [(order_created_event(), 0)]);
+
+    // The plain `handle` is not recording the events in the outbox
+    let result = aggregate.handle(&create_command()).await;
+    assert_eq!(result.unwrap(), [(order_created_event(), 1)]);
+    assert_eq!(outbox.lock().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn outbox_processor_test() {
+    let repository = InMemoryOrderEventRepository::default();
+    let outbox = Arc::clone(&repository.outbox);
+    let aggregate = EventSourcedAggregate::new(
+        repository,
+        decider().map_error(&|()| AggregateError::DomainError("Decider error".to_string())),
+    );
+    for _ in 0..3 {
+        aggregate
+            .handle_with_outbox(&create_command())
+            .await
+            .unwrap();
+    }
+
+    let publisher = FlakyEventPublisher::new(2);
+    let processor = OutboxProcessor::new(
+        InMemoryOutboxRepository {
+            outbox: Arc::clone(&outbox),
+        },
+        &publisher,
+        2,
+        Backoff::new(3, Duration::from_millis(10), Duration::from_millis(15)),
+    );
+    let delays = Mutex::new(vec![]);
+    let sleep = |delay: Duration| {
+        delays.lock().unwrap().push(delay);
+        ready(())
+    };
+
+    // The first batch is published after two failed attempts
+    let result = processor.process_batch_with_retry(&sleep).await;
+    assert_eq!(result.unwrap(), 2);
+    assert_eq!(
+        *delays.lock().unwrap(),
+        [Duration::from_millis(10), Duration::from_millis(15)]
+    );
+    let result = processor.process_batch().await;
+    assert_eq!(result.unwrap(), 1);
+    let result = processor.process_batch().await;
+    assert_eq!(result.unwrap(), 0);
+    assert!(outbox.lock().unwrap().is_empty());
+    assert_eq!(
+        publisher
+            .published
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(_, version)| *version)
+            .collect::<Vec<_>>(),
+        [0, 1, 2]
+    );
+}
+
+#[tokio::test]
+async fn outbox_processor_run_test() {
+    let outbox = Arc::new(Mutex::new(vec![(order_created_event(), 0)]));
+    let publisher = FlakyEventPublisher::new(usize::MAX);
+    let processor = OutboxProcessor::new(
+        InMemoryOutboxRepository {
+            outbox: Arc::clone(&outbox),
+        },
+        &publisher,
+        10,
+        Backoff::default(),
+    );
+
+    // The processing loop stops once the batch could not be published within the backoff attempts
+    let error = processor
+        .run(&|_| ready(()), Duration::from_millis(10))
+        .await;
+    assert!(matches!(error, AggregateError::SaveEvents(_)));
+    assert_eq!(outbox.lock().unwrap().len(), 1);
+}
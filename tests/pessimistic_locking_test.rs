@@ -0,0 +1,152 @@
+use std::collections::HashSet;
+use std::sync::Mutex;
+
+use fmodel_rust::aggregate::{EventRepository, EventSourcedAggregate, LockRepository};
+use fmodel_rust::decider::Decider;
+use fmodel_rust::Identifier;
+
+use crate::api::{
+    CancelOrderCommand, CreateOrderCommand, OrderCommand, OrderCreatedEvent, OrderEvent, OrderState,
+};
+use crate::application::AggregateError;
+
+mod api;
+mod application;
+
+/// A simple in-memory event repository - infrastructure
+#[derive(Default)]
+struct InMemoryOrderEventRepository {
+    events: Mutex<Vec<(OrderEvent, i32)>>,
+}
+
+impl EventRepository<OrderCommand, OrderEvent, i32, AggregateError>
+    for InMemoryOrderEventRepository
+{
+    async fn fetch_events(
+        &self,
+        command: &OrderCommand,
+    ) -> Result<Vec<(OrderEvent, i32)>, AggregateError> {
+        Ok(self
+            .events
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(event, _)| event.identifier() == command.identifier())
+            .cloned()
+            .collect())
+    }
+
+    async fn save(&self, events: &[OrderEvent]) -> Result<Vec<(OrderEvent, i32)>, AggregateError> {
+        let mut stored = self.events.lock().unwrap();
+        let saved = events
+            .iter()
+            .map(|event| (event.clone(), stored.len() as i32))
+            .collect::<Vec<_>>();
+        stored.extend_from_slice(&saved);
+        Ok(saved)
+    }
+
+    async fn version_provider(&self, _event: &OrderEvent) -> Result<Option<i32>, AggregateError> {
+        Ok(None)
+    }
+}
+
+/// A simple in-memory lock repository, failing fast if the lock is already held - infrastructure
+/// In real life, the lock is acquired via the database (for example, `pg_advisory_lock`), waiting for the other process to release it.
+#[derive(Default)]
+struct InMemoryLockRepository {
+    locks: Mutex<HashSet<String>>,
+    log: Mutex<Vec<String>>,
+}
+
+impl LockRepository<OrderCommand, AggregateError> for InMemoryLockRepository {
+    async fn acquire_lock(&self, command: &OrderCommand) -> Result<(), AggregateError> {
+        if !self.locks.lock().unwrap().insert(command.identifier()) {
+            return Err(AggregateError::FetchEvents(format!(
+                "Order {} is locked",
+                command.identifier()
+            )));
+        }
+        self.log
+            .lock()
+            .unwrap()
+            .push(format!("acquire {}", command.identifier()));
+        Ok(())
+    }
+
+    async fn release_lock(&self, command: &OrderCommand) -> Result<(), AggregateError> {
+        self.locks.lock().unwrap().remove(&command.identifier());
+        self.log
+            .lock()
+            .unwrap()
+            .push(format!("release {}", command.identifier()));
+        Ok(())
+    }
+}
+
+/// Decider is rejecting the cancellation of the non-existing order
+fn decider<'a>() -> Decider<'a, OrderCommand, OrderState, OrderEvent, AggregateError> {
+    Decider {
+        decide: Box::new(|command, state| match command {
+            OrderCommand::Create(cmd) => Ok(vec![OrderEvent::Created(OrderCreatedEvent {
+                order_id: cmd.order_id,
+                customer_name: cmd.customer_name.to_owned(),
+                items: cmd.items.to_owned(),
+            })]),
+            OrderCommand::Cancel(cmd) if state.order_id != cmd.order_id => Err(
+                AggregateError::DomainError("Order does not exist".to_string()),
+            ),
+            _ => Ok(vec![]),
+        }),
+        evolve: Box::new(|state, event| {
+            let mut new_state = state.clone();
+            if let OrderEvent::Created(evt) = event {
+                new_state.order_id = evt.order_id;
+            }
+            new_state
+        }),
+        initial_state: Box::new(|| OrderState {
+            order_id: 0,
+            customer_name: "".to_string(),
+            items: Vec::new(),
+            is_cancelled: false,
+        }),
+    }
+}
+
+#[tokio::test]
+async fn test() {
+    let aggregate = EventSourcedAggregate::new(InMemoryOrderEventRepository::default(), decider());
+    let lock_repository = InMemoryLockRepository::default();
+
+    let result = aggregate
+        .handle_with_lock(
+            &OrderCommand::Create(CreateOrderCommand {
+                order_id: 1,
+                customer_name: "John Doe".to_string(),
+                items: vec!["Item 1".to_string()],
+            }),
+            &lock_repository,
+        )
+        .await;
+    assert_eq!(result.unwrap().len(), 1);
+
+    // The lock is released, even if the command is rejected
+    let cancel_command = OrderCommand::Cancel(CancelOrderCommand { order_id: 2 });
+    let result = aggregate
+        .handle_with_lock(&cancel_command, &lock_repository)
+        .await;
+    assert!(matches!(result, Err(AggregateError::DomainError(_))));
+    assert_eq!(
+        *lock_repository.log.lock().unwrap(),
+        ["acquire 1", "release 1", "acquire 2", "release 2"]
+    );
+
+    // The command is not handled if the lock could not be acquired
+    lock_repository.acquire_lock(&cancel_command).await.unwrap();
+    let result = aggregate
+        .handle_with_lock(&cancel_command, &lock_repository)
+        .await;
+    assert!(matches!(result, Err(AggregateError::FetchEvents(_))));
+    assert_eq!(lock_repository.log.lock().unwrap().len(), 5);
+}
@@ -0,0 +1,120 @@
+use fmodel_rust::process_manager::{ProcessComputation, ProcessManager};
+
+use crate::api::{
+    CreateShipmentCommand, OrderCreatedEvent, ShipmentCreatedEvent, UpdateOrderCommand,
+};
+use crate::application::{Command, Event};
+
+mod api;
+mod application;
+
+/// The state of the Order fulfillment process
+#[derive(Debug, Clone, PartialEq)]
+struct OrderFulfillmentState {
+    is_shipment_requested: bool,
+    is_shipped: bool,
+}
+
+fn process_manager<'a>() -> ProcessManager<'a, Event, OrderFulfillmentState, Command> {
+    ProcessManager {
+        react: Box::new(|state, event| match event {
+            Event::OrderCreated(evt) => {
+                if state.is_shipment_requested {
+                    Ok(vec![])
+                } else {
+                    Ok(vec![Command::ShipmentCreate(CreateShipmentCommand {
+                        shipment_id: evt.order_id,
+                        order_id: evt.order_id,
+                        customer_name: evt.customer_name.to_owned(),
+                        items: evt.items.to_owned(),
+                    })])
+                }
+            }
+            Event::ShipmentCreated(evt) => {
+                if state.is_shipped {
+                    Ok(vec![])
+                } else {
+                    Ok(vec![Command::OrderUpdate(UpdateOrderCommand {
+                        order_id: evt.order_id,
+                        new_items: evt.items.to_owned(),
+                    })])
+                }
+            }
+            Event::OrderUpdated(_) => Ok(vec![]),
+            Event::OrderCancelled(_) => Ok(vec![]),
+        }),
+        evolve: Box::new(|state, event| {
+            let mut new_state = state.clone();
+            match event {
+                Event::OrderCreated(_) => {
+                    new_state.is_shipment_requested = true;
+                }
+                Event::ShipmentCreated(_) => {
+                    new_state.is_shipped = true;
+                }
+                Event::OrderUpdated(_) => {}
+                Event::OrderCancelled(_) => {}
+            }
+            new_state
+        }),
+        initial_state: Box::new(|| OrderFulfillmentState {
+            is_shipment_requested: false,
+            is_shipped: false,
+        }),
+    }
+}
+
+#[test]
+fn test() {
+    let process_manager: ProcessManager<Event, OrderFulfillmentState, Command> = process_manager();
+    let order_created_event = Event::OrderCreated(OrderCreatedEvent {
+        order_id: 1,
+        customer_name: "John Doe".to_string(),
+        items: vec!["Item 1".to_string(), "Item 2".to_string()],
+    });
+    let shipment_created_event = Event::ShipmentCreated(ShipmentCreatedEvent {
+        shipment_id: 1,
+        order_id: 1,
+        customer_name: "John Doe".to_string(),
+        items: vec!["Item 1".to_string(), "Item 2".to_string()],
+    });
+
+    let result = process_manager.compute_new_state_and_actions(None, &order_created_event);
+    assert_eq!(
+        result,
+        Ok((
+            OrderFulfillmentState {
+                is_shipment_requested: true,
+                is_shipped: false,
+            },
+            vec![Command::ShipmentCreate(CreateShipmentCommand {
+                shipment_id: 1,
+                order_id: 1,
+                customer_name: "John Doe".to_string(),
+                items: vec!["Item 1".to_string(), "Item 2".to_string()],
+            })]
+        ))
+    );
+
+    // The process already requested the shipment, so the duplicated event is not producing new commands
+    let (state, _) = result.unwrap();
+    let result =
+        process_manager.compute_new_state_and_actions(Some(state.clone()), &order_created_event);
+    assert_eq!(result, Ok((state.clone(), vec![])));
+
+    let result =
+        process_manager.compute_new_state_and_actions(Some(state), &shipment_created_event);
+    assert_eq!(
+        result,
+        Ok((
+            OrderFulfillmentState {
+                is_shipment_requested: true,
+                is_shipped: true,
+            },
+            vec![Command::OrderUpdate(UpdateOrderCommand {
+                order_id: 1,
+                new_items: vec!["Item 1".to_string(), "Item 2".to_string()],
+            })]
+        ))
+    );
+}
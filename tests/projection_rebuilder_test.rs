@@ -0,0 +1,191 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use fmodel_rust::materialized_view::{
+    CheckpointRepository, MaterializedView, ProjectionRebuilder, ViewStateRepository,
+};
+use fmodel_rust::view::View;
+use fmodel_rust::Identifier;
+
+use crate::api::{
+    OrderCancelledEvent, OrderCreatedEvent, OrderEvent, OrderUpdatedEvent, OrderViewState,
+};
+use crate::application::MaterializedViewError;
+
+mod api;
+mod application;
+
+fn view<'a>() -> View<'a, OrderViewState, OrderEvent> {
+    View {
+        evolve: Box::new(|state, event| {
+            let mut new_state = state.clone();
+            match event {
+                OrderEvent::Created(evt) => {
+                    new_state.order_id = evt.order_id;
+                    new_state.customer_name = evt.customer_name.to_owned();
+                    new_state.items = evt.items.to_owned();
+                }
+                OrderEvent::Updated(evt) => {
+                    new_state.items = evt.updated_items.to_owned();
+                }
+                OrderEvent::Cancelled(_) => {
+                    new_state.is_cancelled = true;
+                }
+            }
+            new_state
+        }),
+        initial_state: Box::new(|| OrderViewState {
+            order_id: 0,
+            customer_name: "".to_string(),
+            items: Vec::new(),
+            is_cancelled: false,
+        }),
+    }
+}
+
+/// A simple in-memory view state repository - infrastructure
+#[derive(Default, Clone)]
+struct InMemoryViewOrderStateRepository {
+    states: Arc<Mutex<HashMap<u32, OrderViewState>>>,
+}
+
+impl ViewStateRepository<OrderEvent, OrderViewState, MaterializedViewError>
+    for InMemoryViewOrderStateRepository
+{
+    async fn fetch_state(
+        &self,
+        event: &OrderEvent,
+    ) -> Result<Option<OrderViewState>, MaterializedViewError> {
+        Ok(self
+            .states
+            .lock()
+            .unwrap()
+            .get(&event.identifier().parse::<u32>().unwrap())
+            .cloned())
+    }
+
+    async fn save(&self, state: &OrderViewState) -> Result<OrderViewState, MaterializedViewError> {
+        self.states
+            .lock()
+            .unwrap()
+            .insert(state.order_id, state.clone());
+        Ok(state.clone())
+    }
+}
+
+/// A simple in-memory checkpoint repository, recording all the saved checkpoints - infrastructure
+#[derive(Default, Clone)]
+struct InMemoryCheckpointRepository {
+    checkpoints: Arc<Mutex<Vec<u64>>>,
+}
+
+impl CheckpointRepository<u64, MaterializedViewError> for InMemoryCheckpointRepository {
+    async fn fetch_checkpoint(&self) -> Result<Option<u64>, MaterializedViewError> {
+        Ok(self.checkpoints.lock().unwrap().last().copied())
+    }
+
+    async fn save_checkpoint(&self, checkpoint: &u64) -> Result<(), MaterializedViewError> {
+        self.checkpoints.lock().unwrap().push(*checkpoint);
+        Ok(())
+    }
+}
+
+fn events() -> Vec<(OrderEvent, u64)> {
+    vec![
+        (
+            OrderEvent::Created(OrderCreatedEvent {
+                order_id: 1,
+                customer_name: "John Doe".to_string(),
+                items: vec!["Item 1".to_string()],
+            }),
+            0,
+        ),
+        (
+            OrderEvent::Created(OrderCreatedEvent {
+                order_id: 2,
+                customer_name: "John Doe".to_string(),
+                items: vec!["Item 1".to_string()],
+            }),
+            1,
+        ),
+        (
+            OrderEvent::Updated(OrderUpdatedEvent {
+                order_id: 1,
+                updated_items: vec!["Item 2".to_string()],
+            }),
+            2,
+        ),
+        (
+            OrderEvent::Cancelled(OrderCancelledEvent { order_id: 2 }),
+            3,
+        ),
+        (
+            OrderEvent::Cancelled(OrderCancelledEvent { order_id: 1 }),
+            4,
+        ),
+    ]
+}
+
+#[tokio::test]
+async fn test() {
+    let repository = InMemoryViewOrderStateRepository::default();
+    let checkpoints = InMemoryCheckpointRepository::default();
+    let rebuilder = ProjectionRebuilder::new(
+        MaterializedView::new(repository.clone(), view()),
+        checkpoints.clone(),
+        2,
+    );
+    // The first three events are replayed, and the checkpoint is saved after every two events and at the end
+    let result = rebuilder.rebuild(events().into_iter().take(3)).await;
+    assert_eq!(result.unwrap(), Some(2));
+    assert_eq!(*checkpoints.checkpoints.lock().unwrap(), [1, 2]);
+
+    // The replay is resumed after the last checkpoint
+    let result = rebuilder.rebuild(events()).await;
+    assert_eq!(result.unwrap(), Some(4));
+    assert_eq!(*checkpoints.checkpoints.lock().unwrap(), [1, 2, 4]);
+
+    // Nothing to replay, the checkpoint stays the same
+    let result = rebuilder.rebuild(events()).await;
+    assert_eq!(result.unwrap(), Some(4));
+    assert_eq!(*checkpoints.checkpoints.lock().unwrap(), [1, 2, 4]);
+
+    assert_eq!(
+        repository.states.lock().unwrap().get(&1).cloned(),
+        Some(OrderViewState {
+            order_id: 1,
+            customer_name: "John Doe".to_string(),
+            items: vec!["Item 2".to_string()],
+            is_cancelled: true,
+        })
+    );
+}
+
+#[tokio::test]
+async fn partitioned_test() {
+    let repository = InMemoryViewOrderStateRepository::default();
+    let checkpoints = InMemoryCheckpointRepository::default();
+    let rebuilder = ProjectionRebuilder::new(
+        MaterializedView::new(repository.clone(), view()),
+        checkpoints.clone(),
+        2,
+    );
+    // The orders are projected concurrently, and the checkpoint is saved once the whole batch is projected
+    let result = rebuilder.rebuild_partitioned(events(), 2).await;
+    assert_eq!(result.unwrap(), Some(4));
+    assert_eq!(*checkpoints.checkpoints.lock().unwrap(), [1, 3, 4]);
+
+    // Nothing to replay, the checkpoint stays the same
+    let result = rebuilder.rebuild_partitioned(events(), 2).await;
+    assert_eq!(result.unwrap(), Some(4));
+    assert_eq!(*checkpoints.checkpoints.lock().unwrap(), [1, 3, 4]);
+
+    // The order of the events is preserved per order
+    for order_id in [1, 2] {
+        assert!(repository.states.lock().unwrap()[&order_id].is_cancelled);
+    }
+    assert_eq!(
+        repository.states.lock().unwrap()[&1].items,
+        vec!["Item 2".to_string()]
+    );
+}
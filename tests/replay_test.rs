@@ -0,0 +1,179 @@
+use fmodel_rust::aggregate::EventRepository;
+use fmodel_rust::replay::{EventReplayer, EventRepositoryWithTimestamps, PointInTimeState};
+use fmodel_rust::view::View;
+
+use crate::api::{
+    CancelOrderCommand, OrderCancelledEvent, OrderCommand, OrderCreatedEvent, OrderEvent,
+    OrderUpdatedEvent, OrderViewState,
+};
+use crate::application::AggregateError;
+
+mod api;
+mod application;
+
+fn order_view<'a>() -> View<'a, OrderViewState, OrderEvent> {
+    View {
+        evolve: Box::new(|state, event| {
+            let mut new_state = state.clone();
+            match event {
+                OrderEvent::Created(evt) => {
+                    new_state.order_id = evt.order_id;
+                    new_state.customer_name = evt.customer_name.to_owned();
+                    new_state.items = evt.items.to_owned();
+                }
+                OrderEvent::Updated(evt) => {
+                    new_state.items = evt.updated_items.to_owned();
+                }
+                OrderEvent::Cancelled(_) => {
+                    new_state.is_cancelled = true;
+                }
+            }
+            new_state
+        }),
+        initial_state: Box::new(|| OrderViewState {
+            order_id: 0,
+            customer_name: "".to_string(),
+            items: Vec::new(),
+            is_cancelled: false,
+        }),
+    }
+}
+
+/// A simple event repository, holding the history of the order, with the versions and the timestamps - infrastructure
+struct OrderHistoryRepository {
+    events: Vec<(OrderEvent, i32, u128)>,
+}
+
+impl OrderHistoryRepository {
+    fn new() -> Self {
+        OrderHistoryRepository {
+            events: vec![
+                (
+                    OrderEvent::Created(OrderCreatedEvent {
+                        order_id: 1,
+                        customer_name: "John Doe".to_string(),
+                        items: vec!["Item 1".to_string()],
+                    }),
+                    1,
+                    100,
+                ),
+                (
+                    OrderEvent::Updated(OrderUpdatedEvent {
+                        order_id: 1,
+                        updated_items: vec!["Item 2".to_string()],
+                    }),
+                    2,
+                    200,
+                ),
+                (
+                    OrderEvent::Cancelled(OrderCancelledEvent { order_id: 1 }),
+                    3,
+                    300,
+                ),
+            ],
+        }
+    }
+}
+
+impl EventRepository<OrderCommand, OrderEvent, i32, AggregateError> for OrderHistoryRepository {
+    async fn fetch_events(
+        &self,
+        _command: &OrderCommand,
+    ) -> Result<Vec<(OrderEvent, i32)>, AggregateError> {
+        Ok(self
+            .events
+            .iter()
+            .map(|(event, version, _)| (event.clone(), *version))
+            .collect())
+    }
+
+    async fn save(&self, _events: &[OrderEvent]) -> Result<Vec<(OrderEvent, i32)>, AggregateError> {
+        Err(AggregateError::SaveEvents(
+            "The history is read-only".to_string(),
+        ))
+    }
+
+    async fn version_provider(&self, _event: &OrderEvent) -> Result<Option<i32>, AggregateError> {
+        Ok(self.events.last().map(|(_, version, _)| *version))
+    }
+}
+
+impl EventRepositoryWithTimestamps<OrderCommand, OrderEvent, i32, AggregateError>
+    for OrderHistoryRepository
+{
+    async fn fetch_events_with_timestamps(
+        &self,
+        _command: &OrderCommand,
+    ) -> Result<Vec<(OrderEvent, i32, u128)>, AggregateError> {
+        Ok(self.events.clone())
+    }
+}
+
+fn order_state(items: &[&str], is_cancelled: bool) -> OrderViewState {
+    OrderViewState {
+        order_id: 1,
+        customer_name: "John Doe".to_string(),
+        items: items.iter().map(|item| item.to_string()).collect(),
+        is_cancelled,
+    }
+}
+
+fn command() -> OrderCommand {
+    OrderCommand::Cancel(CancelOrderCommand { order_id: 1 })
+}
+
+#[tokio::test]
+async fn replay_until_version_test() {
+    let replayer = EventReplayer::new(OrderHistoryRepository::new(), order_view());
+
+    let state = replayer.replay_until_version(&command(), &2).await.unwrap();
+    assert_eq!(
+        state,
+        PointInTimeState {
+            state: order_state(&["Item 2"], false),
+            version: Some(2),
+        }
+    );
+
+    // No event is included before the first version
+    let state = replayer.replay_until_version(&command(), &0).await.unwrap();
+    assert_eq!(state.version, None);
+    assert_eq!(state.state.order_id, 0);
+}
+
+#[tokio::test]
+async fn replay_until_timestamp_test() {
+    let replayer = EventReplayer::new(OrderHistoryRepository::new(), order_view());
+
+    let state = replayer
+        .replay_until_timestamp(&command(), 150)
+        .await
+        .unwrap();
+    assert_eq!(
+        state,
+        PointInTimeState {
+            state: order_state(&["Item 1"], false),
+            version: Some(1),
+        }
+    );
+}
+
+#[tokio::test]
+async fn diff_test() {
+    let replayer = EventReplayer::new(OrderHistoryRepository::new(), order_view());
+
+    let diff = replayer.diff_until_version(&command(), &1).await.unwrap();
+    assert!(diff.is_changed());
+    assert_eq!(diff.past.state, order_state(&["Item 1"], false));
+    assert_eq!(diff.current.state, order_state(&["Item 2"], true));
+    assert_eq!(diff.current.version, Some(3));
+    assert_eq!(diff.events_after, 2);
+
+    let diff = replayer
+        .diff_until_timestamp(&command(), 300)
+        .await
+        .unwrap();
+    assert!(!diff.is_changed());
+    assert_eq!(diff.past, diff.current);
+    assert_eq!(diff.events_after, 0);
+}
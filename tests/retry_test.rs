@@ -0,0 +1,216 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use fmodel_rust::aggregate::{EventRepository, EventSourcedAggregate, RetryPolicy};
+use fmodel_rust::decider::Decider;
+use fmodel_rust::retry::{Retrying, TransientError};
+use fmodel_rust::saga::Saga;
+use fmodel_rust::saga_manager::{ActionPublisher, SagaManager};
+
+use crate::api::{
+    CreateOrderCommand, CreateShipmentCommand, OrderCommand, OrderCreatedEvent, OrderEvent,
+    OrderState, ShipmentCommand,
+};
+use crate::application::{AggregateError, SagaManagerError};
+
+mod api;
+mod application;
+
+impl TransientError for AggregateError {
+    fn is_transient(&self) -> bool {
+        matches!(
+            self,
+            AggregateError::FetchEvents(_) | AggregateError::SaveEvents(_)
+        )
+    }
+}
+
+impl TransientError for SagaManagerError {
+    fn is_transient(&self) -> bool {
+        matches!(self, SagaManagerError::PublishAction(_))
+    }
+}
+
+/// An event repository that is failing the first `failures` fetches - infrastructure
+struct FlakyOrderEventRepository {
+    failures: Mutex<usize>,
+}
+
+impl EventRepository<OrderCommand, OrderEvent, i32, AggregateError> for FlakyOrderEventRepository {
+    async fn fetch_events(
+        &self,
+        _command: &OrderCommand,
+    ) -> Result<Vec<(OrderEvent, i32)>, AggregateError> {
+        let mut failures = self.failures.lock().unwrap();
+        if *failures > 0 {
+            *failures -= 1;
+            return Err(AggregateError::FetchEvents("Connection lost".to_string()));
+        }
+        Ok(vec![])
+    }
+
+    async fn save(&self, events: &[OrderEvent]) -> Result<Vec<(OrderEvent, i32)>, AggregateError> {
+        Ok(events.iter().map(|event| (event.clone(), 1)).collect())
+    }
+
+    async fn version_provider(&self, _event: &OrderEvent) -> Result<Option<i32>, AggregateError> {
+        Ok(None)
+    }
+}
+
+/// An action publisher that is failing the first `failures` publications - infrastructure
+struct FlakyActionPublisher {
+    failures: Mutex<usize>,
+}
+
+impl ActionPublisher<ShipmentCommand, SagaManagerError> for FlakyActionPublisher {
+    async fn publish(
+        &self,
+        action: &[ShipmentCommand],
+    ) -> Result<Vec<ShipmentCommand>, SagaManagerError> {
+        let mut failures = self.failures.lock().unwrap();
+        if *failures > 0 {
+            *failures -= 1;
+            return Err(SagaManagerError::PublishAction(
+                "Broker unavailable".to_string(),
+            ));
+        }
+        Ok(Vec::from(action))
+    }
+}
+
+fn decider<'a>() -> Decider<'a, OrderCommand, OrderState, OrderEvent> {
+    Decider {
+        decide: Box::new(|command, _state| match command {
+            OrderCommand::Create(cmd) => Ok(vec![OrderEvent::Created(OrderCreatedEvent {
+                order_id: cmd.order_id,
+                customer_name: cmd.customer_name.to_owned(),
+                items: cmd.items.to_owned(),
+            })]),
+            _ => Ok(vec![]),
+        }),
+        evolve: Box::new(|state, _event| state.clone()),
+        initial_state: Box::new(|| OrderState {
+            order_id: 0,
+            customer_name: "".to_string(),
+            items: Vec::new(),
+            is_cancelled: false,
+        }),
+    }
+}
+
+fn saga<'a>() -> Saga<'a, OrderEvent, ShipmentCommand> {
+    Saga {
+        react: Box::new(|event| match event {
+            OrderEvent::Created(evt) => Ok(vec![ShipmentCommand::Create(CreateShipmentCommand {
+                shipment_id: evt.order_id,
+                order_id: evt.order_id,
+                customer_name: evt.customer_name.to_owned(),
+                items: evt.items.to_owned(),
+            })]),
+            _ => Ok(vec![]),
+        }),
+    }
+}
+
+fn command() -> OrderCommand {
+    OrderCommand::Create(CreateOrderCommand {
+        order_id: 1,
+        customer_name: "John Doe".to_string(),
+        items: vec!["Item 1".to_string()],
+    })
+}
+
+/// A sleep function that is recording the delays, instead of sleeping
+fn recording_sleep(
+    delays: Arc<Mutex<Vec<Duration>>>,
+) -> impl Fn(Duration) -> std::future::Ready<()> + Sync {
+    move |delay| {
+        delays.lock().unwrap().push(delay);
+        std::future::ready(())
+    }
+}
+
+#[tokio::test]
+async fn repository_test() {
+    let delays = Arc::new(Mutex::new(vec![]));
+    let aggregate = EventSourcedAggregate::new(
+        Retrying::new(
+            FlakyOrderEventRepository {
+                failures: Mutex::new(2),
+            },
+            RetryPolicy::fixed(3, Duration::from_millis(10)),
+            recording_sleep(delays.clone()),
+        ),
+        decider().map_error(&|()| AggregateError::DomainError("Decider error".to_string())),
+    );
+
+    let result = aggregate.handle(&command()).await;
+    assert_eq!(result.unwrap().len(), 1);
+    assert_eq!(
+        *delays.lock().unwrap(),
+        [Duration::from_millis(10), Duration::from_millis(10)]
+    );
+}
+
+#[tokio::test]
+async fn repository_exhausted_test() {
+    let aggregate = EventSourcedAggregate::new(
+        Retrying::new(
+            FlakyOrderEventRepository {
+                failures: Mutex::new(3),
+            },
+            RetryPolicy::new(3),
+            recording_sleep(Arc::new(Mutex::new(vec![]))),
+        ),
+        decider().map_error(&|()| AggregateError::DomainError("Decider error".to_string())),
+    );
+
+    let result = aggregate.handle(&command()).await;
+    assert!(matches!(result, Err(AggregateError::FetchEvents(_))));
+}
+
+#[tokio::test]
+async fn publisher_test() {
+    let delays = Arc::new(Mutex::new(vec![]));
+    let saga_manager = SagaManager::new(
+        Retrying::new(
+            FlakyActionPublisher {
+                failures: Mutex::new(2),
+            },
+            RetryPolicy::exponential(
+                3,
+                Duration::from_millis(10),
+                Duration::from_millis(15),
+                false,
+            ),
+            recording_sleep(delays.clone()),
+        ),
+        saga().map_error(&|()| SagaManagerError::DomainError("Saga error".to_string())),
+    );
+
+    let result = saga_manager
+        .handle(&OrderEvent::Created(OrderCreatedEvent {
+            order_id: 1,
+            customer_name: "John Doe".to_string(),
+            items: vec!["Item 1".to_string()],
+        }))
+        .await;
+    assert_eq!(result.unwrap().len(), 1);
+    assert_eq!(
+        *delays.lock().unwrap(),
+        [Duration::from_millis(10), Duration::from_millis(15)]
+    );
+}
+
+#[test]
+fn jitter_test() {
+    let retry_policy =
+        RetryPolicy::exponential(5, Duration::from_millis(10), Duration::from_secs(1), true);
+    for attempt in 1..=4 {
+        assert!(
+            retry_policy.delay(attempt) <= Duration::from_millis(10 * 2u64.pow(attempt as u32 - 1))
+        );
+    }
+    assert_eq!(RetryPolicy::new(3).delay(1), Duration::ZERO);
+}
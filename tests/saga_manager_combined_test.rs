@@ -13,20 +13,14 @@ mod application;
 fn order_saga<'a>() -> Saga<'a, OrderEvent, ShipmentCommand> {
     Saga {
         react: Box::new(|event| match event {
-            OrderEvent::Created(evt) => {
-                vec![ShipmentCommand::Create(CreateShipmentCommand {
-                    shipment_id: evt.order_id,
-                    order_id: evt.order_id,
-                    customer_name: evt.customer_name.to_owned(),
-                    items: evt.items.to_owned(),
-                })]
-            }
-            OrderEvent::Updated(_) => {
-                vec![]
-            }
-            OrderEvent::Cancelled(_) => {
-                vec![]
-            }
+            OrderEvent::Created(evt) => Ok(vec![ShipmentCommand::Create(CreateShipmentCommand {
+                shipment_id: evt.order_id,
+                order_id: evt.order_id,
+                customer_name: evt.customer_name.to_owned(),
+                items: evt.items.to_owned(),
+            })]),
+            OrderEvent::Updated(_) => Ok(vec![]),
+            OrderEvent::Cancelled(_) => Ok(vec![]),
         }),
     }
 }
@@ -34,12 +28,10 @@ fn order_saga<'a>() -> Saga<'a, OrderEvent, ShipmentCommand> {
 fn shipment_saga<'a>() -> Saga<'a, ShipmentEvent, OrderCommand> {
     Saga {
         react: Box::new(|event| match event {
-            ShipmentEvent::Created(evt) => {
-                vec![OrderCommand::Update(UpdateOrderCommand {
-                    order_id: evt.order_id,
-                    new_items: evt.items.to_owned(),
-                })]
-            }
+            ShipmentEvent::Created(evt) => Ok(vec![OrderCommand::Update(UpdateOrderCommand {
+                order_id: evt.order_id,
+                new_items: evt.items.to_owned(),
+            })]),
         }),
     }
 }
@@ -73,7 +65,8 @@ async fn test() {
         shipment_saga()
             .combine(order_saga())
             .map_action(&sum_to_command2)
-            .map_action_result(&event_from_sum2),
+            .map_action_result(&event_from_sum2)
+            .map_error(&|()| SagaManagerError::DomainError("Saga error".to_string())),
     );
     let result = saga_manager.handle(&order_created_event).await;
     assert!(result.is_ok());
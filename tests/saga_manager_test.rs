@@ -1,5 +1,13 @@
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+
+use fmodel_rust::dead_letter::{DeadLetter, DeadLetterRepository};
+use fmodel_rust::materialized_view::CheckpointRepository;
 use fmodel_rust::saga::Saga;
-use fmodel_rust::saga_manager::{ActionPublisher, SagaManager};
+use fmodel_rust::saga_manager::{
+    ActionPublisher, Interception, SagaInterceptor, SagaManager, SagaRunner, StepLimitExceeded,
+    TransactionalPublisher, UnpublishedActions,
+};
 
 use crate::api::{CreateShipmentCommand, OrderCreatedEvent, OrderEvent, ShipmentCommand};
 use crate::application::SagaManagerError;
@@ -10,20 +18,14 @@ mod application;
 fn saga<'a>() -> Saga<'a, OrderEvent, ShipmentCommand> {
     Saga {
         react: Box::new(|event| match event {
-            OrderEvent::Created(evt) => {
-                vec![ShipmentCommand::Create(CreateShipmentCommand {
-                    shipment_id: evt.order_id,
-                    order_id: evt.order_id,
-                    customer_name: evt.customer_name.to_owned(),
-                    items: evt.items.to_owned(),
-                })]
-            }
-            OrderEvent::Updated(_) => {
-                vec![]
-            }
-            OrderEvent::Cancelled(_) => {
-                vec![]
-            }
+            OrderEvent::Created(evt) => Ok(vec![ShipmentCommand::Create(CreateShipmentCommand {
+                shipment_id: evt.order_id,
+                order_id: evt.order_id,
+                customer_name: evt.customer_name.to_owned(),
+                items: evt.items.to_owned(),
+            })]),
+            OrderEvent::Updated(_) => Ok(vec![]),
+            OrderEvent::Cancelled(_) => Ok(vec![]),
         }),
     }
 }
@@ -56,7 +58,10 @@ async fn test() {
         items: vec!["Item 1".to_string(), "Item 2".to_string()],
     });
 
-    let saga_manager = SagaManager::new(SimpleActionPublisher::new(), saga);
+    let saga_manager = SagaManager::new(
+        SimpleActionPublisher::new(),
+        saga.map_error(&|()| SagaManagerError::DomainError("Saga error".to_string())),
+    );
     let result = saga_manager.handle(&order_created_event).await;
     assert!(result.is_ok());
     assert_eq!(
@@ -69,3 +74,489 @@ async fn test() {
         })]
     );
 }
+
+#[tokio::test]
+async fn saga_error_test() {
+    let saga: Saga<OrderEvent, ShipmentCommand> = Saga {
+        react: Box::new(|event| match event {
+            OrderEvent::Created(_) => Err(()),
+            _ => Ok(vec![]),
+        }),
+    };
+    let order_created_event = OrderEvent::Created(OrderCreatedEvent {
+        order_id: 1,
+        customer_name: "John Doe".to_string(),
+        items: vec!["Item 1".to_string(), "Item 2".to_string()],
+    });
+
+    let saga_manager = SagaManager::new(
+        SimpleActionPublisher::new(),
+        saga.map_error(&|()| SagaManagerError::DomainError("Saga error".to_string())),
+    );
+    // The error of the saga is propagated, and no action is published
+    let result = saga_manager.handle(&order_created_event).await;
+    assert!(matches!(result, Err(SagaManagerError::DomainError(error)) if error == "Saga error"));
+}
+
+#[cfg(feature = "send")]
+#[tokio::test]
+async fn shared_saga_test() {
+    // The `'static` saga is shared among many saga managers, running on different tasks
+    let saga: Arc<Saga<'static, OrderEvent, ShipmentCommand, SagaManagerError>> = Arc::new(Saga {
+        react: Box::new(|event| match event {
+            OrderEvent::Created(evt) => Ok(vec![ShipmentCommand::Create(CreateShipmentCommand {
+                shipment_id: evt.order_id,
+                order_id: evt.order_id,
+                customer_name: evt.customer_name.to_owned(),
+                items: evt.items.to_owned(),
+            })]),
+            OrderEvent::Updated(_) => Ok(vec![]),
+            OrderEvent::Cancelled(_) => Ok(vec![]),
+        }),
+    });
+
+    let handles = (1..=2)
+        .map(|order_id| {
+            let saga_manager = SagaManager::new(SimpleActionPublisher::new(), Arc::clone(&saga));
+            tokio::spawn(async move {
+                saga_manager
+                    .handle(&OrderEvent::Created(OrderCreatedEvent {
+                        order_id,
+                        customer_name: "John Doe".to_string(),
+                        items: vec!["Item 1".to_string()],
+                    }))
+                    .await
+            })
+        })
+        .collect::<Vec<_>>();
+
+    for (order_id, handle) in (1..=2).zip(handles) {
+        assert_eq!(
+            handle.await.unwrap().unwrap(),
+            vec![ShipmentCommand::Create(CreateShipmentCommand {
+                shipment_id: order_id,
+                order_id,
+                customer_name: "John Doe".to_string(),
+                items: vec!["Item 1".to_string()],
+            })]
+        );
+    }
+}
+
+/// Drops the events of the orders that are already reacted to
+#[derive(Default)]
+struct DeduplicatingInterceptor {
+    seen: Mutex<HashSet<u32>>,
+}
+
+impl SagaInterceptor<OrderEvent, ShipmentCommand, SagaManagerError> for DeduplicatingInterceptor {
+    async fn intercept_action_result(
+        &self,
+        action_result: &OrderEvent,
+    ) -> Result<Interception<OrderEvent>, SagaManagerError> {
+        let order_id = match action_result {
+            OrderEvent::Created(evt) => evt.order_id,
+            OrderEvent::Updated(evt) => evt.order_id,
+            OrderEvent::Cancelled(evt) => evt.order_id,
+        };
+        if self.seen.lock().unwrap().insert(order_id) {
+            Ok(Interception::Unchanged)
+        } else {
+            Ok(Interception::Dropped)
+        }
+    }
+
+    async fn intercept_actions(
+        &self,
+        actions: Vec<ShipmentCommand>,
+    ) -> Result<Vec<ShipmentCommand>, SagaManagerError> {
+        Ok(actions)
+    }
+}
+
+/// Enriches the shipment commands, by normalizing the customer name
+struct EnrichingInterceptor;
+
+impl SagaInterceptor<OrderEvent, ShipmentCommand, SagaManagerError> for EnrichingInterceptor {
+    async fn intercept_action_result(
+        &self,
+        _action_result: &OrderEvent,
+    ) -> Result<Interception<OrderEvent>, SagaManagerError> {
+        Ok(Interception::Unchanged)
+    }
+
+    async fn intercept_actions(
+        &self,
+        actions: Vec<ShipmentCommand>,
+    ) -> Result<Vec<ShipmentCommand>, SagaManagerError> {
+        Ok(actions
+            .into_iter()
+            .map(|action| match action {
+                ShipmentCommand::Create(cmd) => ShipmentCommand::Create(CreateShipmentCommand {
+                    customer_name: cmd.customer_name.to_uppercase(),
+                    ..cmd
+                }),
+            })
+            .collect())
+    }
+}
+
+#[tokio::test]
+async fn interceptor_test() {
+    let saga_manager = SagaManager::new(
+        SimpleActionPublisher::new(),
+        saga().map_error(&|()| SagaManagerError::DomainError("Saga error".to_string())),
+    )
+    .with_interceptor((DeduplicatingInterceptor::default(), EnrichingInterceptor));
+    let order_created_event = OrderEvent::Created(OrderCreatedEvent {
+        order_id: 1,
+        customer_name: "John Doe".to_string(),
+        items: vec!["Item 1".to_string()],
+    });
+
+    let result = saga_manager.handle(&order_created_event).await;
+    assert_eq!(
+        result.unwrap(),
+        vec![ShipmentCommand::Create(CreateShipmentCommand {
+            shipment_id: 1,
+            order_id: 1,
+            customer_name: "JOHN DOE".to_string(),
+            items: vec!["Item 1".to_string()],
+        })]
+    );
+    // The duplicated event is dropped, so nothing is published
+    let result = saga_manager.handle(&order_created_event).await;
+    assert_eq!(result.unwrap(), vec![]);
+}
+
+/// The action result that is not `Clone`
+struct OrderPlaced {
+    order_id: u32,
+}
+
+#[tokio::test]
+async fn non_clone_action_result_test() {
+    let saga: Saga<OrderPlaced, ShipmentCommand, SagaManagerError> = Saga {
+        react: Box::new(|event| {
+            Ok(vec![ShipmentCommand::Create(CreateShipmentCommand {
+                shipment_id: event.order_id,
+                order_id: event.order_id,
+                customer_name: "John Doe".to_string(),
+                items: vec![],
+            })])
+        }),
+    };
+    // The action result is not cloned by the default (no-op) interceptor
+    let saga_manager = SagaManager::new(SimpleActionPublisher::new(), saga);
+    let result = saga_manager.handle(&OrderPlaced { order_id: 1 }).await;
+    assert_eq!(result.unwrap().len(), 1);
+}
+
+impl From<StepLimitExceeded> for SagaManagerError {
+    fn from(error: StepLimitExceeded) -> Self {
+        SagaManagerError::DomainError(error.to_string())
+    }
+}
+
+#[tokio::test]
+async fn saga_runner_test() {
+    // The countdown choreography: every counted number is reacted to with the next (lower) one, until zero
+    let countdown: Saga<u32, u32, SagaManagerError> = Saga {
+        react: Box::new(|n| if *n > 0 { Ok(vec![n - 1]) } else { Ok(vec![]) }),
+    };
+    // The local handler counts the number, and reports it back
+    let runner = SagaRunner::new(countdown, |n: &u32| std::future::ready(Ok(vec![*n])), 10);
+    assert_eq!(runner.run(3).await.unwrap(), vec![2, 1, 0]);
+
+    // The cyclic choreography never settles
+    let ping_pong: Saga<u32, u32, SagaManagerError> = Saga {
+        react: Box::new(|n| Ok(vec![*n])),
+    };
+    let runner = SagaRunner::new(ping_pong, |n: &u32| std::future::ready(Ok(vec![*n])), 10);
+    assert!(matches!(
+        runner.run(1).await,
+        Err(SagaManagerError::DomainError(_))
+    ));
+}
+
+/// The action publisher that is failing while it is offline.
+struct OfflineActionPublisher {
+    offline: Arc<Mutex<bool>>,
+}
+
+impl ActionPublisher<ShipmentCommand, SagaManagerError> for OfflineActionPublisher {
+    async fn publish(
+        &self,
+        action: &[ShipmentCommand],
+    ) -> Result<Vec<ShipmentCommand>, SagaManagerError> {
+        if *self.offline.lock().unwrap() {
+            Err(SagaManagerError::PublishAction("Offline".to_string()))
+        } else {
+            Ok(Vec::from(action))
+        }
+    }
+}
+
+/// The parked actions of the order saga
+type OrderDeadLetter =
+    DeadLetter<UnpublishedActions<OrderEvent, ShipmentCommand>, SagaManagerError>;
+
+/// A simple in-memory dead letter repository - infrastructure
+#[derive(Default)]
+struct InMemoryDeadLetterRepository {
+    dead_letters: Mutex<Vec<OrderDeadLetter>>,
+}
+
+impl DeadLetterRepository<UnpublishedActions<OrderEvent, ShipmentCommand>, SagaManagerError>
+    for InMemoryDeadLetterRepository
+{
+    async fn park(&self, dead_letter: OrderDeadLetter) -> Result<(), SagaManagerError> {
+        self.dead_letters.lock().unwrap().push(dead_letter);
+        Ok(())
+    }
+
+    async fn fetch_dead_letters(
+        &self,
+        limit: usize,
+    ) -> Result<Vec<OrderDeadLetter>, SagaManagerError> {
+        Ok(self
+            .dead_letters
+            .lock()
+            .unwrap()
+            .iter()
+            .take(limit)
+            .cloned()
+            .collect())
+    }
+
+    async fn acknowledge(&self, dead_letter: &OrderDeadLetter) -> Result<(), SagaManagerError> {
+        let mut dead_letters = self.dead_letters.lock().unwrap();
+        if let Some(index) = dead_letters
+            .iter()
+            .position(|parked| parked.message == dead_letter.message)
+        {
+            dead_letters.remove(index);
+        }
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn dead_letter_test() {
+    let offline = Arc::new(Mutex::new(true));
+    let saga_manager = SagaManager::new(
+        OfflineActionPublisher {
+            offline: Arc::clone(&offline),
+        },
+        saga().map_error(&|()| SagaManagerError::DomainError("Saga error".to_string())),
+    );
+    let dead_letters = InMemoryDeadLetterRepository::default();
+    let order_created_event = OrderEvent::Created(OrderCreatedEvent {
+        order_id: 1,
+        customer_name: "John Doe".to_string(),
+        items: vec!["Item 1".to_string()],
+    });
+    let shipment_command = ShipmentCommand::Create(CreateShipmentCommand {
+        shipment_id: 1,
+        order_id: 1,
+        customer_name: "John Doe".to_string(),
+        items: vec!["Item 1".to_string()],
+    });
+
+    // The actions that fail to be published are parked, together with the action result and the error
+    let result = saga_manager
+        .handle_with_dead_letters(&order_created_event, &dead_letters)
+        .await;
+    assert!(matches!(result, Ok(None)));
+    {
+        let parked = dead_letters.dead_letters.lock().unwrap();
+        assert_eq!(parked.len(), 1);
+        assert_eq!(parked[0].message.action_result, order_created_event);
+        assert_eq!(parked[0].message.actions, vec![shipment_command.clone()]);
+        assert!(matches!(
+            parked[0].error,
+            SagaManagerError::PublishAction(_)
+        ));
+    }
+
+    // The failed replay is parked again
+    let replayed = saga_manager.replay_dead_letters(&dead_letters, 10).await;
+    assert!(replayed.unwrap().is_empty());
+    assert_eq!(dead_letters.dead_letters.lock().unwrap().len(), 1);
+
+    // The successful replay publishes the parked actions, and empties the dead letters
+    *offline.lock().unwrap() = false;
+    let replayed = saga_manager.replay_dead_letters(&dead_letters, 10).await;
+    assert_eq!(replayed.unwrap(), vec![shipment_command]);
+    assert!(dead_letters.dead_letters.lock().unwrap().is_empty());
+}
+
+/// The action publisher that is recording the published actions, and the maximum number of the concurrent publishing calls
+#[derive(Default)]
+struct ConcurrentActionPublisher {
+    published: Mutex<Vec<ShipmentCommand>>,
+    in_flight: Mutex<(usize, usize)>,
+}
+
+impl ActionPublisher<ShipmentCommand, SagaManagerError> for &ConcurrentActionPublisher {
+    async fn publish(
+        &self,
+        action: &[ShipmentCommand],
+    ) -> Result<Vec<ShipmentCommand>, SagaManagerError> {
+        {
+            let mut in_flight = self.in_flight.lock().unwrap();
+            in_flight.0 += 1;
+            in_flight.1 = in_flight.1.max(in_flight.0);
+        }
+        tokio::task::yield_now().await;
+        self.published.lock().unwrap().extend_from_slice(action);
+        self.in_flight.lock().unwrap().0 -= 1;
+        Ok(Vec::from(action))
+    }
+}
+
+#[tokio::test]
+async fn concurrent_publishing_test() {
+    let shipment = |shipment_id: u32, item: &str| {
+        ShipmentCommand::Create(CreateShipmentCommand {
+            shipment_id,
+            order_id: 1,
+            customer_name: "John Doe".to_string(),
+            items: vec![item.to_string()],
+        })
+    };
+    let saga: Saga<OrderEvent, ShipmentCommand, SagaManagerError> = Saga {
+        react: Box::new(move |_event| {
+            Ok(vec![
+                shipment(1, "Item 1"),
+                shipment(2, "Item 2"),
+                shipment(1, "Item 3"),
+                shipment(2, "Item 4"),
+            ])
+        }),
+    };
+    let publisher = ConcurrentActionPublisher::default();
+    let saga_manager = SagaManager::new(&publisher, saga).with_max_in_flight(2);
+
+    let result = saga_manager
+        .handle_concurrently(&OrderEvent::Created(OrderCreatedEvent {
+            order_id: 1,
+            customer_name: "John Doe".to_string(),
+            items: vec![],
+        }))
+        .await;
+    // The published actions are returned in the order they are computed
+    assert_eq!(
+        result
+            .unwrap()
+            .into_iter()
+            .map(|ShipmentCommand::Create(cmd)| cmd.items[0].clone())
+            .collect::<Vec<_>>(),
+        ["Item 1", "Item 2", "Item 3", "Item 4"]
+    );
+
+    // The targets are published concurrently, and the actions of the same target in order
+    assert_eq!(publisher.in_flight.lock().unwrap().1, 2);
+    let items_of = |shipment_id: u32| {
+        publisher
+            .published
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|ShipmentCommand::Create(cmd)| cmd)
+            .filter(|cmd| cmd.shipment_id == shipment_id)
+            .map(|cmd| cmd.items[0].clone())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(items_of(1), ["Item 1", "Item 3"]);
+    assert_eq!(items_of(2), ["Item 2", "Item 4"]);
+}
+
+/// The outbox and the checkpoint, that are stored in the same transaction.
+#[derive(Default)]
+struct Outbox {
+    published: Vec<ShipmentCommand>,
+    checkpoint: Option<u64>,
+}
+
+/// A simple in-memory transactional publisher, storing the actions into the outbox together with the checkpoint - infrastructure
+struct OutboxActionPublisher {
+    outbox: Arc<Mutex<Outbox>>,
+}
+
+impl ActionPublisher<ShipmentCommand, SagaManagerError> for OutboxActionPublisher {
+    async fn publish(
+        &self,
+        action: &[ShipmentCommand],
+    ) -> Result<Vec<ShipmentCommand>, SagaManagerError> {
+        self.outbox
+            .lock()
+            .unwrap()
+            .published
+            .extend_from_slice(action);
+        Ok(Vec::from(action))
+    }
+}
+
+impl CheckpointRepository<u64, SagaManagerError> for OutboxActionPublisher {
+    async fn fetch_checkpoint(&self) -> Result<Option<u64>, SagaManagerError> {
+        Ok(self.outbox.lock().unwrap().checkpoint)
+    }
+
+    async fn save_checkpoint(&self, checkpoint: &u64) -> Result<(), SagaManagerError> {
+        self.outbox.lock().unwrap().checkpoint = Some(*checkpoint);
+        Ok(())
+    }
+}
+
+impl TransactionalPublisher<ShipmentCommand, u64, SagaManagerError> for OutboxActionPublisher {
+    async fn publish_with_checkpoint(
+        &self,
+        action: &[ShipmentCommand],
+        checkpoint: &u64,
+    ) -> Result<Vec<ShipmentCommand>, SagaManagerError> {
+        let mut outbox = self.outbox.lock().unwrap();
+        outbox.published.extend_from_slice(action);
+        outbox.checkpoint = Some(*checkpoint);
+        Ok(Vec::from(action))
+    }
+}
+
+#[tokio::test]
+async fn transactional_test() {
+    let outbox = Arc::new(Mutex::new(Outbox::default()));
+    let saga_manager = SagaManager::new(
+        OutboxActionPublisher {
+            outbox: Arc::clone(&outbox),
+        },
+        saga().map_error(&|()| SagaManagerError::DomainError("Saga error".to_string())),
+    );
+    let order_created_event = |order_id| {
+        OrderEvent::Created(OrderCreatedEvent {
+            order_id,
+            customer_name: "John Doe".to_string(),
+            items: vec!["Item 1".to_string()],
+        })
+    };
+
+    let result = saga_manager
+        .handle_transactional(&order_created_event(1), &1)
+        .await;
+    assert_eq!(result.unwrap().len(), 1);
+    assert_eq!(outbox.lock().unwrap().checkpoint, Some(1));
+
+    // The action result that is delivered again (after the crash) is already handled
+    let result = saga_manager
+        .handle_transactional(&order_created_event(1), &1)
+        .await;
+    assert!(result.unwrap().is_empty());
+    assert_eq!(outbox.lock().unwrap().published.len(), 1);
+
+    let result = saga_manager
+        .handle_transactional(&order_created_event(2), &2)
+        .await;
+    assert_eq!(result.unwrap().len(), 1);
+    let outbox = outbox.lock().unwrap();
+    assert_eq!(outbox.published.len(), 2);
+    assert_eq!(outbox.checkpoint, Some(2));
+}
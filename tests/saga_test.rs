@@ -1,7 +1,12 @@
-use fmodel_rust::saga::{ActionComputation, Saga};
+use fmodel_rust::saga::{
+    ActionComputation, AsyncActionComputation, AsyncSaga, ConversionPolicy, MergeOrdering,
+    OwnedActionComputation, OwnedSaga, Saga, SagaRegistry,
+};
+use fmodel_rust::{Sum, Sum3, Variant};
 
 use crate::api::{
-    CreateShipmentCommand, OrderCommand, OrderCreatedEvent, OrderEvent, ShipmentCommand,
+    CancelOrderCommand, CreateShipmentCommand, OrderCancelledEvent, OrderCommand,
+    OrderCreatedEvent, OrderEvent, OrderUpdatedEvent, ShipmentCommand, ShipmentCreatedEvent,
     ShipmentEvent, UpdateOrderCommand,
 };
 use crate::application::{event_from_sum, sum_to_command, Command, Event};
@@ -12,20 +17,14 @@ mod application;
 fn order_saga<'a>() -> Saga<'a, OrderEvent, ShipmentCommand> {
     Saga {
         react: Box::new(|event| match event {
-            OrderEvent::Created(evt) => {
-                vec![ShipmentCommand::Create(CreateShipmentCommand {
-                    shipment_id: evt.order_id,
-                    order_id: evt.order_id,
-                    customer_name: evt.customer_name.to_owned(),
-                    items: evt.items.to_owned(),
-                })]
-            }
-            OrderEvent::Updated(_) => {
-                vec![]
-            }
-            OrderEvent::Cancelled(_) => {
-                vec![]
-            }
+            OrderEvent::Created(evt) => Ok(vec![ShipmentCommand::Create(CreateShipmentCommand {
+                shipment_id: evt.order_id,
+                order_id: evt.order_id,
+                customer_name: evt.customer_name.to_owned(),
+                items: evt.items.to_owned(),
+            })]),
+            OrderEvent::Updated(_) => Ok(vec![]),
+            OrderEvent::Cancelled(_) => Ok(vec![]),
         }),
     }
 }
@@ -33,23 +32,15 @@ fn order_saga<'a>() -> Saga<'a, OrderEvent, ShipmentCommand> {
 fn order_saga_2<'a>() -> Saga<'a, Event, ShipmentCommand> {
     Saga {
         react: Box::new(|event| match event {
-            Event::OrderCreated(evt) => {
-                vec![ShipmentCommand::Create(CreateShipmentCommand {
-                    shipment_id: evt.order_id,
-                    order_id: evt.order_id,
-                    customer_name: evt.customer_name.to_owned(),
-                    items: evt.items.to_owned(),
-                })]
-            }
-            Event::OrderUpdated(_) => {
-                vec![]
-            }
-            Event::OrderCancelled(_) => {
-                vec![]
-            }
-            Event::ShipmentCreated(_) => {
-                vec![]
-            }
+            Event::OrderCreated(evt) => Ok(vec![ShipmentCommand::Create(CreateShipmentCommand {
+                shipment_id: evt.order_id,
+                order_id: evt.order_id,
+                customer_name: evt.customer_name.to_owned(),
+                items: evt.items.to_owned(),
+            })]),
+            Event::OrderUpdated(_) => Ok(vec![]),
+            Event::OrderCancelled(_) => Ok(vec![]),
+            Event::ShipmentCreated(_) => Ok(vec![]),
         }),
     }
 }
@@ -57,12 +48,10 @@ fn order_saga_2<'a>() -> Saga<'a, Event, ShipmentCommand> {
 fn shipment_saga<'a>() -> Saga<'a, ShipmentEvent, OrderCommand> {
     Saga {
         react: Box::new(|event| match event {
-            ShipmentEvent::Created(evt) => {
-                vec![OrderCommand::Update(UpdateOrderCommand {
-                    order_id: evt.order_id,
-                    new_items: evt.items.to_owned(),
-                })]
-            }
+            ShipmentEvent::Created(evt) => Ok(vec![OrderCommand::Update(UpdateOrderCommand {
+                order_id: evt.order_id,
+                new_items: evt.items.to_owned(),
+            })]),
         }),
     }
 }
@@ -70,22 +59,14 @@ fn shipment_saga<'a>() -> Saga<'a, ShipmentEvent, OrderCommand> {
 fn shipment_saga_2<'a>() -> Saga<'a, Event, OrderCommand> {
     Saga {
         react: Box::new(|event| match event {
-            Event::ShipmentCreated(evt) => {
-                vec![OrderCommand::Update(UpdateOrderCommand {
-                    order_id: evt.order_id,
-                    new_items: evt.items.to_owned(),
-                })]
-            }
+            Event::ShipmentCreated(evt) => Ok(vec![OrderCommand::Update(UpdateOrderCommand {
+                order_id: evt.order_id,
+                new_items: evt.items.to_owned(),
+            })]),
 
-            Event::OrderCreated(_) => {
-                vec![]
-            }
-            Event::OrderUpdated(_) => {
-                vec![]
-            }
-            Event::OrderCancelled(_) => {
-                vec![]
-            }
+            Event::OrderCreated(_) => Ok(vec![]),
+            Event::OrderUpdated(_) => Ok(vec![]),
+            Event::OrderCancelled(_) => Ok(vec![]),
         }),
     }
 }
@@ -113,12 +94,12 @@ fn test() {
     let commands = order_saga.compute_new_actions(&order_created_event);
     assert_eq!(
         commands,
-        [ShipmentCommand::Create(CreateShipmentCommand {
+        Ok(vec![ShipmentCommand::Create(CreateShipmentCommand {
             shipment_id: 1,
             order_id: 1,
             customer_name: "John Doe".to_string(),
             items: vec!["Item 1".to_string(), "Item 2".to_string()],
-        })]
+        })])
     );
     let order_created_event2 = Event::OrderCreated(OrderCreatedEvent {
         order_id: 1,
@@ -129,22 +110,504 @@ fn test() {
     let combined_commands = combined_saga.compute_new_actions(&order_created_event2);
     assert_eq!(
         combined_commands,
-        [Command::ShipmentCreate(CreateShipmentCommand {
+        Ok(vec![Command::ShipmentCreate(CreateShipmentCommand {
             shipment_id: 1,
             order_id: 1,
             customer_name: "John Doe".to_string(),
             items: vec!["Item 1".to_string(), "Item 2".to_string()],
-        })]
+        })])
     );
 
     let merged_commands = merged_saga.compute_new_actions(&order_created_event2);
     assert_eq!(
         merged_commands,
-        [Command::ShipmentCreate(CreateShipmentCommand {
+        Ok(vec![Command::ShipmentCreate(CreateShipmentCommand {
+            shipment_id: 1,
+            order_id: 1,
+            customer_name: "John Doe".to_string(),
+            items: vec!["Item 1".to_string(), "Item 2".to_string()],
+        })])
+    );
+}
+
+#[test]
+fn combine3_test() {
+    let combined_saga: Saga<
+        Sum3<OrderEvent, ShipmentEvent, Event>,
+        Sum3<ShipmentCommand, OrderCommand, OrderCommand>,
+    > = order_saga().combine3(shipment_saga(), shipment_saga_2());
+
+    let order_created_event = Sum3::First(OrderEvent::Created(OrderCreatedEvent {
+        order_id: 1,
+        customer_name: "John Doe".to_string(),
+        items: vec!["Item 1".to_string(), "Item 2".to_string()],
+    }));
+    let commands = combined_saga.compute_new_actions(&order_created_event);
+    assert_eq!(
+        commands,
+        Ok(vec![Sum3::First(ShipmentCommand::Create(
+            CreateShipmentCommand {
+                shipment_id: 1,
+                order_id: 1,
+                customer_name: "John Doe".to_string(),
+                items: vec!["Item 1".to_string(), "Item 2".to_string()],
+            }
+        ))])
+    );
+
+    let order_created_event2 = Sum3::Third(Event::OrderCreated(OrderCreatedEvent {
+        order_id: 1,
+        customer_name: "John Doe".to_string(),
+        items: vec!["Item 1".to_string(), "Item 2".to_string()],
+    }));
+    let commands = combined_saga.compute_new_actions(&order_created_event2);
+    assert_eq!(commands, Ok(vec![]));
+}
+
+#[test]
+fn merge_same_test() {
+    let merged_saga: Saga<Event, ShipmentCommand> = order_saga_2().merge_same(order_saga_2());
+
+    let order_created_event = Event::OrderCreated(OrderCreatedEvent {
+        order_id: 1,
+        customer_name: "John Doe".to_string(),
+        items: vec!["Item 1".to_string(), "Item 2".to_string()],
+    });
+    let shipment_command = ShipmentCommand::Create(CreateShipmentCommand {
+        shipment_id: 1,
+        order_id: 1,
+        customer_name: "John Doe".to_string(),
+        items: vec!["Item 1".to_string(), "Item 2".to_string()],
+    });
+    let commands = merged_saga.compute_new_actions(&order_created_event);
+    assert_eq!(
+        commands,
+        Ok(vec![shipment_command.clone(), shipment_command])
+    );
+}
+
+#[test]
+fn merge_with_test() {
+    let saga = || Saga::<u32, u32> {
+        react: Box::new(|ar: &u32| Ok(vec![*ar, *ar + 1, *ar + 2])),
+    };
+    let saga2 = || Saga::<u32, u32> {
+        react: Box::new(|ar: &u32| Ok(vec![*ar * 10])),
+    };
+
+    let commands = saga()
+        .merge_with(saga2(), MergeOrdering::Sequential)
+        .compute_new_actions(&1);
+    assert_eq!(
+        commands,
+        Ok(vec![
+            Sum::Second(1),
+            Sum::Second(2),
+            Sum::Second(3),
+            Sum::First(10)
+        ])
+    );
+    let commands = saga()
+        .merge_with(saga2(), MergeOrdering::Reversed)
+        .compute_new_actions(&1);
+    assert_eq!(
+        commands,
+        Ok(vec![
+            Sum::First(10),
+            Sum::Second(1),
+            Sum::Second(2),
+            Sum::Second(3)
+        ])
+    );
+    let commands = saga()
+        .merge_with(saga2(), MergeOrdering::Interleave)
+        .compute_new_actions(&1);
+    assert_eq!(
+        commands,
+        Ok(vec![
+            Sum::Second(1),
+            Sum::First(10),
+            Sum::Second(2),
+            Sum::Second(3)
+        ])
+    );
+    assert_eq!(
+        saga().merge(saga2()).compute_new_actions(&1),
+        saga()
+            .merge_with(saga2(), MergeOrdering::default())
+            .compute_new_actions(&1)
+    );
+}
+
+impl Variant<OrderEvent> for OrderCreatedEvent {
+    fn from_variant(value: &OrderEvent) -> Option<&Self> {
+        match value {
+            OrderEvent::Created(evt) => Some(evt),
+            _ => None,
+        }
+    }
+}
+
+impl Variant<OrderEvent> for OrderCancelledEvent {
+    fn from_variant(value: &OrderEvent) -> Option<&Self> {
+        match value {
+            OrderEvent::Cancelled(evt) => Some(evt),
+            _ => None,
+        }
+    }
+}
+
+#[test]
+fn registry_test() {
+    let registry: SagaRegistry<OrderEvent, String, String> = SagaRegistry::new()
+        .register(Saga {
+            react: Box::new(|evt: &OrderCreatedEvent| Ok(vec![format!("ship {}", evt.order_id)])),
+        })
+        .register_all(Saga {
+            react: Box::new(|evt: &OrderEvent| match evt {
+                OrderEvent::Updated(_) => Err("Updates are not supported".to_string()),
+                _ => Ok(vec!["audit".to_string()]),
+            }),
+        })
+        .register(Saga {
+            react: Box::new(|evt: &OrderCancelledEvent| {
+                Ok(vec![format!("refund {}", evt.order_id)])
+            }),
+        })
+        .register(Saga {
+            react: Box::new(|evt: &OrderCreatedEvent| Ok(vec![format!("bill {}", evt.order_id)])),
+        });
+
+    let actions = registry.compute_new_actions(&OrderEvent::Created(OrderCreatedEvent {
+        order_id: 1,
+        customer_name: "John Doe".to_string(),
+        items: vec!["Item 1".to_string()],
+    }));
+    assert_eq!(
+        actions,
+        Ok(vec![
+            "ship 1".to_string(),
+            "audit".to_string(),
+            "bill 1".to_string()
+        ])
+    );
+
+    let saga = registry.build();
+    let actions = (saga.react)(&OrderEvent::Cancelled(OrderCancelledEvent { order_id: 1 }));
+    assert_eq!(
+        actions,
+        Ok(vec!["audit".to_string(), "refund 1".to_string()])
+    );
+    let actions = (saga.react)(&OrderEvent::Updated(OrderUpdatedEvent {
+        order_id: 1,
+        updated_items: vec![],
+    }));
+    assert_eq!(actions, Err("Updates are not supported".to_string()));
+}
+
+#[test]
+fn filter_test() {
+    let filtered_saga = order_saga().filter(&|event: &OrderEvent| match event {
+        OrderEvent::Created(evt) => evt.order_id != 2,
+        _ => true,
+    });
+
+    let commands = filtered_saga.compute_new_actions(&OrderEvent::Created(OrderCreatedEvent {
+        order_id: 1,
+        customer_name: "John Doe".to_string(),
+        items: vec!["Item 1".to_string(), "Item 2".to_string()],
+    }));
+    assert_eq!(
+        commands,
+        Ok(vec![ShipmentCommand::Create(CreateShipmentCommand {
+            shipment_id: 1,
+            order_id: 1,
+            customer_name: "John Doe".to_string(),
+            items: vec!["Item 1".to_string(), "Item 2".to_string()],
+        })])
+    );
+
+    let commands = filtered_saga.compute_new_actions(&OrderEvent::Created(OrderCreatedEvent {
+        order_id: 2,
+        customer_name: "John Doe".to_string(),
+        items: vec!["Item 1".to_string(), "Item 2".to_string()],
+    }));
+    assert_eq!(commands, Ok(vec![]));
+}
+
+/// Converts the external event into the order event, failing on the events of the other domains
+fn try_order_event(event: &Event) -> Result<OrderEvent, String> {
+    match event_from_sum(event) {
+        Sum::First(order_event) => Ok(order_event),
+        Sum::Second(_) => Err("Not an order event".to_string()),
+    }
+}
+
+/// Converts the shipment command into the command, failing on the shipments of the order 2
+fn try_command(command: &ShipmentCommand) -> Result<Command, String> {
+    match command {
+        ShipmentCommand::Create(c) if c.order_id == 2 => Err("Order 2 is blocked".to_string()),
+        ShipmentCommand::Create(c) => Ok(Command::ShipmentCreate(c.to_owned())),
+    }
+}
+
+#[test]
+fn try_map_test() {
+    let order_created_event = Event::OrderCreated(OrderCreatedEvent {
+        order_id: 1,
+        customer_name: "John Doe".to_string(),
+        items: vec!["Item 1".to_string(), "Item 2".to_string()],
+    });
+    let shipment_created_event = Event::ShipmentCreated(ShipmentCreatedEvent {
+        shipment_id: 1,
+        order_id: 1,
+        customer_name: "John Doe".to_string(),
+        items: vec!["Item 1".to_string(), "Item 2".to_string()],
+    });
+    let expected_command = ShipmentCommand::Create(CreateShipmentCommand {
+        shipment_id: 1,
+        order_id: 1,
+        customer_name: "John Doe".to_string(),
+        items: vec!["Item 1".to_string(), "Item 2".to_string()],
+    });
+
+    let skipping_saga: Saga<Event, ShipmentCommand, String> = order_saga()
+        .map_error(&|()| String::new())
+        .try_map_action_result(&try_order_event, ConversionPolicy::Skip);
+    assert_eq!(
+        skipping_saga.compute_new_actions(&order_created_event),
+        Ok(vec![expected_command.clone()])
+    );
+    assert_eq!(
+        skipping_saga.compute_new_actions(&shipment_created_event),
+        Ok(vec![])
+    );
+
+    let failing_saga: Saga<Event, ShipmentCommand, String> = order_saga()
+        .map_error(&|()| String::new())
+        .try_map_action_result(&try_order_event, ConversionPolicy::Fail);
+    assert_eq!(
+        failing_saga.compute_new_actions(&order_created_event),
+        Ok(vec![expected_command])
+    );
+    assert_eq!(
+        failing_saga.compute_new_actions(&shipment_created_event),
+        Err("Not an order event".to_string())
+    );
+
+    let order_2_created_event = OrderEvent::Created(OrderCreatedEvent {
+        order_id: 2,
+        customer_name: "John Doe".to_string(),
+        items: vec!["Item 1".to_string()],
+    });
+    let skipping_saga: Saga<OrderEvent, Command, String> = order_saga()
+        .map_error(&|()| String::new())
+        .try_map_action(&try_command, ConversionPolicy::Skip);
+    assert_eq!(
+        skipping_saga.compute_new_actions(&order_2_created_event),
+        Ok(vec![])
+    );
+    let failing_saga: Saga<OrderEvent, Command, String> = order_saga()
+        .map_error(&|()| String::new())
+        .try_map_action(&try_command, ConversionPolicy::Fail);
+    assert_eq!(
+        failing_saga.compute_new_actions(&order_2_created_event),
+        Err("Order 2 is blocked".to_string())
+    );
+}
+
+/// Simulates the remote shipment service, assigning the shipment id to the order
+async fn shipment_id(order_id: u32) -> u32 {
+    order_id + 100
+}
+
+fn async_order_saga<'a>() -> AsyncSaga<'a, OrderEvent, ShipmentCommand> {
+    AsyncSaga {
+        react: Box::new(|event| match event {
+            OrderEvent::Created(evt) => {
+                let evt = evt.clone();
+                Box::pin(async move {
+                    Ok(vec![ShipmentCommand::Create(CreateShipmentCommand {
+                        shipment_id: shipment_id(evt.order_id).await,
+                        order_id: evt.order_id,
+                        customer_name: evt.customer_name,
+                        items: evt.items,
+                    })])
+                })
+            }
+            OrderEvent::Updated(_) => Box::pin(async { Ok(vec![]) }),
+            OrderEvent::Cancelled(_) => Box::pin(async { Err(()) }),
+        }),
+    }
+}
+
+#[tokio::test]
+async fn async_saga_test() {
+    let order_created_event = OrderEvent::Created(OrderCreatedEvent {
+        order_id: 1,
+        customer_name: "John Doe".to_string(),
+        items: vec!["Item 1".to_string(), "Item 2".to_string()],
+    });
+
+    let commands = async_order_saga()
+        .compute_new_actions(&order_created_event)
+        .await;
+    assert_eq!(
+        commands,
+        Ok(vec![ShipmentCommand::Create(CreateShipmentCommand {
+            shipment_id: 101,
+            order_id: 1,
+            customer_name: "John Doe".to_string(),
+            items: vec!["Item 1".to_string(), "Item 2".to_string()],
+        })])
+    );
+
+    let saga = async_order_saga().map_error(&|()| "Saga error");
+    let commands = saga
+        .compute_new_actions(&OrderEvent::Cancelled(OrderCancelledEvent { order_id: 1 }))
+        .await;
+    assert_eq!(commands, Err("Saga error"));
+
+    // The synchronous saga can be lifted into the asynchronous one
+    let saga: AsyncSaga<OrderEvent, ShipmentCommand> = order_saga().into();
+    let commands = saga.compute_new_actions(&order_created_event).await;
+    assert_eq!(
+        commands,
+        Ok(vec![ShipmentCommand::Create(CreateShipmentCommand {
             shipment_id: 1,
             order_id: 1,
             customer_name: "John Doe".to_string(),
             items: vec!["Item 1".to_string(), "Item 2".to_string()],
-        })]
+        })])
+    );
+}
+
+fn owned_order_saga<'a>() -> OwnedSaga<'a, OrderEvent, ShipmentCommand> {
+    OwnedSaga {
+        react: Box::new(|event| match event {
+            // The customer name and the items are moved out of the event, not cloned
+            OrderEvent::Created(evt) => Ok(vec![ShipmentCommand::Create(CreateShipmentCommand {
+                shipment_id: evt.order_id,
+                order_id: evt.order_id,
+                customer_name: evt.customer_name,
+                items: evt.items,
+            })]),
+            OrderEvent::Updated(_) => Ok(vec![]),
+            OrderEvent::Cancelled(_) => Err(()),
+        }),
+    }
+}
+
+#[test]
+fn owned_saga_test() {
+    let order_created_event = OrderEvent::Created(OrderCreatedEvent {
+        order_id: 1,
+        customer_name: "John Doe".to_string(),
+        items: vec!["Item 1".to_string(), "Item 2".to_string()],
+    });
+    let expected = ShipmentCommand::Create(CreateShipmentCommand {
+        shipment_id: 1,
+        order_id: 1,
+        customer_name: "John Doe".to_string(),
+        items: vec!["Item 1".to_string(), "Item 2".to_string()],
+    });
+
+    let saga = owned_order_saga();
+    let commands = saga.compute_new_actions_owned(order_created_event.clone());
+    assert_eq!(commands, Ok(vec![expected.clone()]));
+
+    let saga = owned_order_saga().map_error(&|()| "Saga error");
+    let commands =
+        saga.compute_new_actions_owned(OrderEvent::Cancelled(OrderCancelledEvent { order_id: 1 }));
+    assert_eq!(commands, Err("Saga error"));
+
+    let saga = owned_order_saga()
+        .map_action_result(&|event: Event| match event {
+            Event::OrderCreated(evt) => OrderEvent::Created(evt),
+            Event::OrderUpdated(evt) => OrderEvent::Updated(evt),
+            Event::OrderCancelled(evt) => OrderEvent::Cancelled(evt),
+            _ => OrderEvent::Updated(OrderUpdatedEvent {
+                order_id: 0,
+                updated_items: vec![],
+            }),
+        })
+        .map_action(&|command: ShipmentCommand| vec![command]);
+    let commands = saga.compute_new_actions_owned(Event::OrderCreated(OrderCreatedEvent {
+        order_id: 1,
+        customer_name: "John Doe".to_string(),
+        items: vec!["Item 1".to_string(), "Item 2".to_string()],
+    }));
+    assert_eq!(commands, Ok(vec![vec![expected.clone()]]));
+
+    // The borrowing saga can be lifted into the owned one
+    let saga: OwnedSaga<OrderEvent, ShipmentCommand> = order_saga().into();
+    let commands = saga.compute_new_actions_owned(order_created_event.clone());
+    assert_eq!(commands, Ok(vec![expected.clone()]));
+
+    // ... or used directly with the owned action results
+    let commands = order_saga().compute_new_actions_owned(order_created_event);
+    assert_eq!(commands, Ok(vec![expected]));
+}
+
+/// The outcome of the order fulfillment, reporting the downstream failure of the shipment
+enum FulfillmentOutcome {
+    OrderCreated(OrderCreatedEvent),
+    ShipmentFailed(Command),
+}
+
+#[test]
+fn compensation_test() {
+    let saga: Saga<FulfillmentOutcome, Command> = Saga {
+        react: Box::new(|outcome| match outcome {
+            FulfillmentOutcome::OrderCreated(evt) => {
+                Ok(vec![Command::ShipmentCreate(CreateShipmentCommand {
+                    shipment_id: evt.order_id,
+                    order_id: evt.order_id,
+                    customer_name: evt.customer_name.to_owned(),
+                    items: evt.items.to_owned(),
+                })])
+            }
+            FulfillmentOutcome::ShipmentFailed(_) => Ok(vec![]),
+        }),
+    }
+    .with_compensation(
+        &|outcome| match outcome {
+            FulfillmentOutcome::ShipmentFailed(command) => Some(command.clone()),
+            FulfillmentOutcome::OrderCreated(_) => None,
+        },
+        &|command| match command {
+            Command::ShipmentCreate(cmd) => vec![Command::OrderCancel(CancelOrderCommand {
+                order_id: cmd.order_id,
+            })],
+            _ => vec![],
+        },
+    );
+
+    let shipment_command = Command::ShipmentCreate(CreateShipmentCommand {
+        shipment_id: 1,
+        order_id: 1,
+        customer_name: "John Doe".to_string(),
+        items: vec!["Item 1".to_string()],
+    });
+    let commands = saga.compute_new_actions(&FulfillmentOutcome::OrderCreated(OrderCreatedEvent {
+        order_id: 1,
+        customer_name: "John Doe".to_string(),
+        items: vec!["Item 1".to_string()],
+    }));
+    assert_eq!(commands, Ok(vec![shipment_command.clone()]));
+
+    // The shipment failed downstream, so the order is cancelled
+    let commands = saga.compute_new_actions(&FulfillmentOutcome::ShipmentFailed(shipment_command));
+    assert_eq!(
+        commands,
+        Ok(vec![Command::OrderCancel(CancelOrderCommand {
+            order_id: 1
+        })])
     );
+
+    // The action without the compensation is not compensated
+    let commands = saga.compute_new_actions(&FulfillmentOutcome::ShipmentFailed(
+        Command::OrderCancel(CancelOrderCommand { order_id: 1 }),
+    ));
+    assert_eq!(commands, Ok(vec![]));
 }
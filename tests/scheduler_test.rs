@@ -0,0 +1,216 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use fmodel_rust::saga::ActionComputation;
+use fmodel_rust::saga::Saga;
+use fmodel_rust::saga_manager::{ActionPublisher, SagaManager};
+use fmodel_rust::scheduler::{ScheduledAction, Scheduler, Scheduling, TimerFired};
+use fmodel_rust::Sum;
+
+use crate::api::{CancelOrderCommand, CreateShipmentCommand, OrderCreatedEvent, OrderEvent};
+use crate::application::{Command, SagaManagerError};
+
+mod api;
+mod application;
+
+/// The payment deadline of the order
+const PAYMENT_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+/// Creates the shipment immediately, and cancels the order if it is not paid in 30 minutes
+fn saga<'a>() -> Saga<'a, OrderEvent, ScheduledAction<Command>> {
+    Saga {
+        react: Box::new(|event| match event {
+            OrderEvent::Created(evt) => Ok(vec![
+                ScheduledAction::now(Command::ShipmentCreate(CreateShipmentCommand {
+                    shipment_id: evt.order_id,
+                    order_id: evt.order_id,
+                    customer_name: evt.customer_name.to_owned(),
+                    items: evt.items.to_owned(),
+                })),
+                ScheduledAction::after(
+                    Command::OrderCancel(CancelOrderCommand {
+                        order_id: evt.order_id,
+                    }),
+                    PAYMENT_TIMEOUT,
+                ),
+            ]),
+            _ => Ok(vec![]),
+        }),
+    }
+}
+
+/// Action publisher that is recording the published actions, and failing while it is offline - infrastructure
+#[derive(Default, Clone)]
+struct RecordingActionPublisher {
+    published: Arc<Mutex<Vec<Command>>>,
+    offline: Arc<Mutex<bool>>,
+}
+
+impl ActionPublisher<Command, SagaManagerError> for RecordingActionPublisher {
+    async fn publish(&self, action: &[Command]) -> Result<Vec<Command>, SagaManagerError> {
+        if *self.offline.lock().unwrap() {
+            return Err(SagaManagerError::PublishAction("Offline".to_string()));
+        }
+        self.published.lock().unwrap().extend_from_slice(action);
+        Ok(Vec::from(action))
+    }
+}
+
+/// In-memory scheduler with the manually advanced clock - infrastructure
+#[derive(Default, Clone)]
+struct InMemoryScheduler {
+    now: Arc<Mutex<Duration>>,
+    scheduled: Arc<Mutex<Vec<(Duration, Command)>>>,
+}
+
+impl InMemoryScheduler {
+    fn advance(&self, duration: Duration) {
+        *self.now.lock().unwrap() += duration;
+    }
+}
+
+impl Scheduler<Command, SagaManagerError> for InMemoryScheduler {
+    async fn schedule(
+        &self,
+        actions: &[ScheduledAction<Command>],
+    ) -> Result<Vec<ScheduledAction<Command>>, SagaManagerError> {
+        let now = *self.now.lock().unwrap();
+        self.scheduled.lock().unwrap().extend(
+            actions
+                .iter()
+                .map(|scheduled| (now + scheduled.delay, scheduled.action.clone())),
+        );
+        Ok(Vec::from(actions))
+    }
+
+    async fn fetch_due(&self) -> Result<Vec<Command>, SagaManagerError> {
+        let now = *self.now.lock().unwrap();
+        Ok(self
+            .scheduled
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(due_at, _)| *due_at <= now)
+            .map(|(_, action)| action.clone())
+            .collect())
+    }
+
+    async fn mark_dispatched(&self, actions: &[Command]) -> Result<(), SagaManagerError> {
+        self.scheduled
+            .lock()
+            .unwrap()
+            .retain(|(_, action)| !actions.contains(action));
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn test() {
+    let publisher = RecordingActionPublisher::default();
+    let scheduler = InMemoryScheduler::default();
+    let saga_manager = SagaManager::new(
+        Scheduling::new(publisher.clone(), scheduler.clone()),
+        saga().map_error(&|()| SagaManagerError::DomainError("Saga error".to_string())),
+    );
+    // The due actions are dispatched separately, for example, by the background task
+    let dispatcher = Scheduling::new(publisher.clone(), scheduler.clone());
+    let create_shipment = Command::ShipmentCreate(CreateShipmentCommand {
+        shipment_id: 1,
+        order_id: 1,
+        customer_name: "John Doe".to_string(),
+        items: vec!["Item 1".to_string()],
+    });
+    let cancel_order = Command::OrderCancel(CancelOrderCommand { order_id: 1 });
+
+    let result = saga_manager
+        .handle(&OrderEvent::Created(OrderCreatedEvent {
+            order_id: 1,
+            customer_name: "John Doe".to_string(),
+            items: vec!["Item 1".to_string()],
+        }))
+        .await;
+    assert_eq!(
+        result.unwrap(),
+        vec![
+            ScheduledAction::now(create_shipment.clone()),
+            ScheduledAction::after(cancel_order.clone(), PAYMENT_TIMEOUT),
+        ]
+    );
+    // The shipment is created immediately, the cancellation is not due yet
+    assert_eq!(
+        *publisher.published.lock().unwrap(),
+        std::slice::from_ref(&create_shipment)
+    );
+    assert_eq!(dispatcher.dispatch_due().await.unwrap(), vec![]);
+
+    // The cancellation is published once the deadline passes, and it is kept while the publishing fails
+    scheduler.advance(PAYMENT_TIMEOUT);
+    *publisher.offline.lock().unwrap() = true;
+    assert!(dispatcher.dispatch_due().await.is_err());
+    assert_eq!(scheduler.scheduled.lock().unwrap().len(), 1);
+    *publisher.offline.lock().unwrap() = false;
+    assert_eq!(
+        dispatcher.dispatch_due().await.unwrap(),
+        vec![cancel_order.clone()]
+    );
+    assert_eq!(dispatcher.dispatch_due().await.unwrap(), vec![]);
+    assert_eq!(
+        *publisher.published.lock().unwrap(),
+        [create_shipment, cancel_order]
+    );
+}
+
+#[test]
+fn deadline_test() {
+    // Creates the shipment immediately, and fires the payment timer of the order in 30 minutes
+    let saga: Saga<OrderEvent, Sum<Command, TimerFired<u32>>> = Saga {
+        react: Box::new(|event| match event {
+            OrderEvent::Created(evt) => Ok(vec![Sum::First(Command::ShipmentCreate(
+                CreateShipmentCommand {
+                    shipment_id: evt.order_id,
+                    order_id: evt.order_id,
+                    customer_name: evt.customer_name.to_owned(),
+                    items: evt.items.to_owned(),
+                },
+            ))]),
+            _ => Ok(vec![]),
+        }),
+    };
+    let saga = saga.with_deadline(PAYMENT_TIMEOUT, &|event| match event {
+        OrderEvent::Created(evt) => Some(Sum::Second(TimerFired::new(evt.order_id))),
+        _ => None,
+    });
+    // Cancels the order once the payment timer fired
+    let timeout_saga: Saga<TimerFired<u32>, Command> = Saga {
+        react: Box::new(|timer_fired| {
+            Ok(vec![Command::OrderCancel(CancelOrderCommand {
+                order_id: timer_fired.timer,
+            })])
+        }),
+    };
+
+    let actions = saga.compute_new_actions(&OrderEvent::Created(OrderCreatedEvent {
+        order_id: 1,
+        customer_name: "John Doe".to_string(),
+        items: vec!["Item 1".to_string()],
+    }));
+    assert_eq!(
+        actions,
+        Ok(vec![
+            ScheduledAction::now(Sum::First(Command::ShipmentCreate(CreateShipmentCommand {
+                shipment_id: 1,
+                order_id: 1,
+                customer_name: "John Doe".to_string(),
+                items: vec!["Item 1".to_string()],
+            }))),
+            ScheduledAction::after(Sum::Second(TimerFired::new(1)), PAYMENT_TIMEOUT),
+        ])
+    );
+
+    assert_eq!(
+        timeout_saga.compute_new_actions(&TimerFired::new(1)),
+        Ok(vec![Command::OrderCancel(CancelOrderCommand {
+            order_id: 1
+        })])
+    );
+}
@@ -0,0 +1,269 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+
+use fmodel_rust::aggregate::EventRepository;
+use fmodel_rust::envelope::EventEnvelope;
+use fmodel_rust::shredding::{
+    Cipher, CryptoShredder, EncryptingEventRepository, EncryptionKeyStore, PersonalData,
+    ERASED_PERSONAL_DATA,
+};
+use fmodel_rust::{EventName, Identifier};
+
+use crate::api::{
+    CancelOrderCommand, OrderCancelledEvent, OrderCommand, OrderCreatedEvent, OrderEvent,
+};
+use crate::application::AggregateError;
+
+mod api;
+mod application;
+
+impl EventName for OrderEvent {
+    fn event_name(&self) -> &'static str {
+        match self {
+            OrderEvent::Created(_) => "OrderCreated",
+            OrderEvent::Updated(_) => "OrderUpdated",
+            OrderEvent::Cancelled(_) => "OrderCancelled",
+        }
+    }
+}
+
+/// The customer name is the personal data of the order, and the order is the data subject
+impl PersonalData for OrderEvent {
+    fn data_subject(&self) -> Option<String> {
+        match self {
+            OrderEvent::Created(evt) => Some(evt.order_id.to_string()),
+            _ => None,
+        }
+    }
+
+    fn try_map_personal_data<Error, F>(self, mut f: F) -> Result<Self, Error>
+    where
+        F: FnMut(String) -> Result<String, Error>,
+    {
+        match self {
+            OrderEvent::Created(evt) => Ok(OrderEvent::Created(OrderCreatedEvent {
+                customer_name: f(evt.customer_name)?,
+                ..evt
+            })),
+            event => Ok(event),
+        }
+    }
+}
+
+/// A simple in-memory key store, with the tombstones of the deleted keys - infrastructure
+#[derive(Default, Clone)]
+struct InMemoryKeyStore {
+    keys: Arc<Mutex<HashMap<String, u8>>>,
+    tombstones: Arc<Mutex<HashSet<String>>>,
+}
+
+impl EncryptionKeyStore<u8, AggregateError> for InMemoryKeyStore {
+    async fn fetch_key(&self, subject: &str) -> Result<Option<u8>, AggregateError> {
+        Ok(self.keys.lock().unwrap().get(subject).copied())
+    }
+
+    async fn create_key(&self, subject: &str) -> Result<u8, AggregateError> {
+        let mut keys = self.keys.lock().unwrap();
+        let key = keys.len() as u8 + 1;
+        Ok(*keys.entry(subject.to_string()).or_insert(key))
+    }
+
+    async fn delete_key(&self, subject: &str) -> Result<(), AggregateError> {
+        self.keys.lock().unwrap().remove(subject);
+        self.tombstones.lock().unwrap().insert(subject.to_string());
+        Ok(())
+    }
+
+    async fn is_shredded(&self, subject: &str) -> Result<bool, AggregateError> {
+        Ok(self.tombstones.lock().unwrap().contains(subject))
+    }
+}
+
+/// A toy cipher, shifting the characters by the key - do not use it in production
+struct ShiftCipher;
+
+impl Cipher<u8, AggregateError> for ShiftCipher {
+    fn encrypt(&self, key: &u8, plaintext: &str) -> Result<String, AggregateError> {
+        Ok(plaintext
+            .chars()
+            .map(|c| char::from_u32(c as u32 + *key as u32).unwrap())
+            .collect())
+    }
+
+    fn decrypt(&self, key: &u8, ciphertext: &str) -> Result<String, AggregateError> {
+        ciphertext
+            .chars()
+            .map(|c| {
+                (c as u32)
+                    .checked_sub(*key as u32)
+                    .and_then(char::from_u32)
+                    .ok_or(AggregateError::FetchEvents(
+                        "Invalid ciphertext".to_string(),
+                    ))
+            })
+            .collect()
+    }
+}
+
+/// A simple in-memory event repository, storing the events as they are given - infrastructure
+#[derive(Default, Clone)]
+struct InMemoryOrderEventRepository {
+    events: Arc<Mutex<Vec<(OrderEvent, i32)>>>,
+}
+
+impl EventRepository<OrderCommand, OrderEvent, i32, AggregateError>
+    for InMemoryOrderEventRepository
+{
+    async fn fetch_events(
+        &self,
+        command: &OrderCommand,
+    ) -> Result<Vec<(OrderEvent, i32)>, AggregateError> {
+        Ok(self
+            .events
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(event, _)| event.identifier() == command.identifier())
+            .cloned()
+            .collect())
+    }
+
+    async fn save(&self, events: &[OrderEvent]) -> Result<Vec<(OrderEvent, i32)>, AggregateError> {
+        let mut stored = self.events.lock().unwrap();
+        let saved: Vec<(OrderEvent, i32)> = events
+            .iter()
+            .map(|event| (event.clone(), stored.len() as i32))
+            .collect();
+        stored.extend(saved.clone());
+        Ok(saved)
+    }
+
+    async fn version_provider(&self, event: &OrderEvent) -> Result<Option<i32>, AggregateError> {
+        Ok(self
+            .events
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(stored, _)| stored.identifier() == event.identifier())
+            .map(|(_, version)| *version)
+            .next_back())
+    }
+}
+
+#[tokio::test]
+async fn test() {
+    let key_store = InMemoryKeyStore::default();
+    let shredder = CryptoShredder::new(key_store.clone(), ShiftCipher);
+    let order_created_event = OrderEvent::Created(OrderCreatedEvent {
+        order_id: 1,
+        customer_name: "John Doe".to_string(),
+        items: vec!["Item 1".to_string()],
+    });
+
+    // The personal data is encrypted on save, with the key of the data subject
+    let encrypted = shredder
+        .encrypt_envelope(EventEnvelope::new(order_created_event.clone(), 0, ()))
+        .await
+        .unwrap();
+    assert_eq!(encrypted.event_type, "OrderCreated");
+    assert_eq!(key_store.keys.lock().unwrap().get("1"), Some(&1));
+    assert_eq!(
+        encrypted.event,
+        OrderEvent::Created(OrderCreatedEvent {
+            order_id: 1,
+            customer_name: "Kpio!Epf".to_string(),
+            items: vec!["Item 1".to_string()],
+        })
+    );
+
+    // The personal data is decrypted on load
+    let decrypted = shredder.decrypt_envelope(encrypted.clone()).await.unwrap();
+    assert_eq!(decrypted.event, order_created_event);
+
+    // The personal data is erased on load, once the key is shredded
+    shredder.shred("1").await.unwrap();
+    let erased_event = OrderEvent::Created(OrderCreatedEvent {
+        order_id: 1,
+        customer_name: ERASED_PERSONAL_DATA.to_string(),
+        items: vec!["Item 1".to_string()],
+    });
+    let erased = shredder.decrypt_envelope(encrypted.clone()).await.unwrap();
+    assert_eq!(erased.event, erased_event);
+
+    // The key of the shredded data subject is not created again, the new personal data is erased on save
+    let encrypted_again = shredder.encrypt(order_created_event.clone()).await.unwrap();
+    assert_eq!(encrypted_again, erased_event);
+    assert!(key_store.keys.lock().unwrap().is_empty());
+    let erased = shredder.decrypt_envelope(encrypted).await.unwrap();
+    assert_eq!(erased.event, erased_event);
+}
+
+#[tokio::test]
+async fn no_personal_data_test() {
+    let key_store = InMemoryKeyStore::default();
+    let shredder = CryptoShredder::new(key_store.clone(), ShiftCipher);
+    let order_cancelled_event = OrderEvent::Cancelled(OrderCancelledEvent { order_id: 1 });
+
+    // The events without the personal data are left as they are, and no key is created
+    let encrypted: Result<OrderEvent, AggregateError> =
+        shredder.encrypt(order_cancelled_event.clone()).await;
+    assert_eq!(encrypted.unwrap(), order_cancelled_event);
+    assert!(key_store.keys.lock().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn encrypting_event_repository_test() {
+    let event_repository = InMemoryOrderEventRepository::default();
+    let key_store = InMemoryKeyStore::default();
+    let repository = EncryptingEventRepository::new(
+        event_repository.clone(),
+        CryptoShredder::new(key_store.clone(), ShiftCipher),
+    );
+    let order_created_event = OrderEvent::Created(OrderCreatedEvent {
+        order_id: 1,
+        customer_name: "John Doe".to_string(),
+        items: vec!["Item 1".to_string()],
+    });
+    let command = OrderCommand::Cancel(CancelOrderCommand { order_id: 1 });
+
+    // The event store only sees the ciphertext, the saved events are returned decrypted
+    let saved = repository
+        .save(std::slice::from_ref(&order_created_event))
+        .await
+        .unwrap();
+    assert_eq!(saved, vec![(order_created_event.clone(), 0)]);
+    assert_eq!(
+        *event_repository.events.lock().unwrap(),
+        vec![(
+            OrderEvent::Created(OrderCreatedEvent {
+                order_id: 1,
+                customer_name: "Kpio!Epf".to_string(),
+                items: vec!["Item 1".to_string()],
+            }),
+            0
+        )]
+    );
+
+    // The fetched events are decrypted
+    assert_eq!(
+        repository.fetch_events(&command).await.unwrap(),
+        vec![(order_created_event, 0)]
+    );
+
+    // The fetched events are erased, once the key is shredded
+    CryptoShredder::new(key_store, ShiftCipher)
+        .shred("1")
+        .await
+        .unwrap();
+    assert_eq!(
+        repository.fetch_events(&command).await.unwrap(),
+        vec![(
+            OrderEvent::Created(OrderCreatedEvent {
+                order_id: 1,
+                customer_name: ERASED_PERSONAL_DATA.to_string(),
+                items: vec!["Item 1".to_string()],
+            }),
+            0
+        )]
+    );
+}
@@ -0,0 +1,425 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use fmodel_rust::aggregate::{
+    EventRepository, EventSourcedSnapshottingAggregate, EventTailRepository, SnapshotRepository,
+};
+use fmodel_rust::decider::Decider;
+use fmodel_rust::snapshot::{Snapshot, SnapshotSerializer};
+use fmodel_rust::upcaster::Upcaster;
+use fmodel_rust::{DeciderInfo, Identifier};
+
+use crate::api::{
+    CreateOrderCommand, OrderCommand, OrderCreatedEvent, OrderEvent, OrderState, OrderUpdatedEvent,
+    UpdateOrderCommand,
+};
+use crate::application::AggregateError;
+
+mod api;
+mod application;
+
+/// A simple in-memory event repository, that is able to fetch the tail of the stream - infrastructure
+struct InMemoryOrderEventRepository {
+    events: Mutex<Vec<(OrderEvent, i32)>>,
+}
+
+impl InMemoryOrderEventRepository {
+    fn new() -> Self {
+        InMemoryOrderEventRepository {
+            events: Mutex::new(vec![]),
+        }
+    }
+}
+
+impl EventRepository<OrderCommand, OrderEvent, i32, AggregateError>
+    for InMemoryOrderEventRepository
+{
+    async fn fetch_events(
+        &self,
+        command: &OrderCommand,
+    ) -> Result<Vec<(OrderEvent, i32)>, AggregateError> {
+        Ok(self
+            .events
+            .lock()
+            .unwrap()
+            .clone()
+            .into_iter()
+            .filter(|(event, _)| event.identifier() == command.identifier())
+            .collect())
+    }
+
+    async fn save(&self, events: &[OrderEvent]) -> Result<Vec<(OrderEvent, i32)>, AggregateError> {
+        let mut latest_version = self
+            .version_provider(events.first().unwrap())
+            .await?
+            .unwrap_or(-1);
+        let events = events
+            .iter()
+            .map(|event| {
+                latest_version += 1;
+                (event.clone(), latest_version)
+            })
+            .collect::<Vec<(OrderEvent, i32)>>();
+        self.events.lock().unwrap().extend_from_slice(&events);
+        Ok(events)
+    }
+
+    async fn version_provider(&self, event: &OrderEvent) -> Result<Option<i32>, AggregateError> {
+        Ok(self
+            .events
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(e, _)| e.identifier() == event.identifier())
+            .map(|(_, version)| *version)
+            .next_back())
+    }
+}
+
+impl EventTailRepository<OrderCommand, OrderEvent, i32, AggregateError>
+    for InMemoryOrderEventRepository
+{
+    async fn fetch_events_after(
+        &self,
+        command: &OrderCommand,
+        version: &i32,
+    ) -> Result<Vec<(OrderEvent, i32)>, AggregateError> {
+        Ok(self
+            .fetch_events(command)
+            .await?
+            .into_iter()
+            .filter(|(_, v)| v > version)
+            .collect())
+    }
+}
+
+/// A simple in-memory snapshot repository - infrastructure
+struct InMemoryOrderSnapshotRepository {
+    snapshots: Mutex<HashMap<u32, (OrderState, i32)>>,
+}
+
+impl InMemoryOrderSnapshotRepository {
+    fn new() -> Self {
+        InMemoryOrderSnapshotRepository {
+            snapshots: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl SnapshotRepository<OrderCommand, OrderState, i32, AggregateError>
+    for InMemoryOrderSnapshotRepository
+{
+    async fn fetch_snapshot(
+        &self,
+        command: &OrderCommand,
+    ) -> Result<Option<(OrderState, i32)>, AggregateError> {
+        Ok(self
+            .snapshots
+            .lock()
+            .unwrap()
+            .get(&command.identifier().parse::<u32>().unwrap())
+            .cloned())
+    }
+
+    async fn save_snapshot(
+        &self,
+        state: &OrderState,
+        version: &i32,
+    ) -> Result<(OrderState, i32), AggregateError> {
+        self.snapshots
+            .lock()
+            .unwrap()
+            .insert(state.order_id, (state.clone(), *version));
+        Ok((state.clone(), *version))
+    }
+}
+
+fn decider<'a>() -> Decider<'a, OrderCommand, OrderState, OrderEvent> {
+    Decider {
+        decide: Box::new(|command, _state| match command {
+            OrderCommand::Create(cmd) => Ok(vec![OrderEvent::Created(OrderCreatedEvent {
+                order_id: cmd.order_id,
+                customer_name: cmd.customer_name.to_owned(),
+                items: cmd.items.to_owned(),
+            })]),
+            OrderCommand::Update(cmd) => Ok(vec![OrderEvent::Updated(OrderUpdatedEvent {
+                order_id: cmd.order_id,
+                updated_items: cmd.new_items.to_owned(),
+            })]),
+            OrderCommand::Cancel(_) => Ok(vec![]),
+        }),
+        evolve: Box::new(|state, event| {
+            let mut new_state = state.clone();
+            match event {
+                OrderEvent::Created(evt) => {
+                    new_state.order_id = evt.order_id;
+                    new_state.customer_name = evt.customer_name.to_owned();
+                    new_state.items = evt.items.to_owned();
+                }
+                OrderEvent::Updated(evt) => {
+                    new_state.items = evt.updated_items.to_owned();
+                }
+                OrderEvent::Cancelled(_) => {
+                    new_state.is_cancelled = true;
+                }
+            }
+            new_state
+        }),
+        initial_state: Box::new(|| OrderState {
+            order_id: 0,
+            customer_name: "".to_string(),
+            items: Vec::new(),
+            is_cancelled: false,
+        }),
+    }
+}
+
+fn update_command(item: &str) -> OrderCommand {
+    OrderCommand::Update(UpdateOrderCommand {
+        order_id: 1,
+        new_items: vec![item.to_string()],
+    })
+}
+
+#[tokio::test]
+async fn test() {
+    let aggregate = EventSourcedSnapshottingAggregate::new(
+        InMemoryOrderEventRepository::new(),
+        InMemoryOrderSnapshotRepository::new(),
+        decider().map_error(&|()| AggregateError::DomainError("Decider error".to_string())),
+        2,
+    );
+    let command = OrderCommand::Create(CreateOrderCommand {
+        order_id: 1,
+        customer_name: "John Doe".to_string(),
+        items: vec!["Item 1".to_string(), "Item 2".to_string()],
+    });
+    let result = aggregate.handle(&command).await;
+    assert_eq!(
+        result.unwrap(),
+        [(
+            OrderEvent::Created(OrderCreatedEvent {
+                order_id: 1,
+                customer_name: "John Doe".to_string(),
+                items: vec!["Item 1".to_string(), "Item 2".to_string()],
+            }),
+            0
+        )]
+    );
+    // The threshold is not reached yet
+    assert_eq!(aggregate.fetch_snapshot(&command).await.unwrap(), None);
+
+    let result = aggregate.handle(&update_command("Item 3")).await;
+    assert_eq!(result.unwrap().first().unwrap().1, 1);
+    // The threshold is reached: two events since the (missing) snapshot
+    let expected_state = OrderState {
+        order_id: 1,
+        customer_name: "John Doe".to_string(),
+        items: vec!["Item 3".to_string()],
+        is_cancelled: false,
+    };
+    assert_eq!(
+        aggregate.fetch_snapshot(&command).await.unwrap(),
+        Some((expected_state, 1))
+    );
+
+    // Only the tail of the stream is replayed on top of the snapshot
+    let result = aggregate.handle(&update_command("Item 4")).await;
+    assert_eq!(result.unwrap().first().unwrap().1, 2);
+    let result = aggregate.handle(&update_command("Item 5")).await;
+    assert_eq!(result.unwrap().first().unwrap().1, 3);
+    let expected_state = OrderState {
+        order_id: 1,
+        customer_name: "John Doe".to_string(),
+        items: vec!["Item 5".to_string()],
+        is_cancelled: false,
+    };
+    assert_eq!(
+        aggregate.fetch_snapshot(&command).await.unwrap(),
+        Some((expected_state.clone(), 3))
+    );
+
+    // The current state is the snapshot with the tail of the stream replayed on top of it, and no command is handled
+    let result = aggregate.handle(&update_command("Item 6")).await;
+    assert_eq!(result.unwrap().first().unwrap().1, 4);
+    assert_eq!(
+        aggregate.fetch_current_state(&command).await.unwrap(),
+        OrderState {
+            items: vec!["Item 6".to_string()],
+            ..expected_state.clone()
+        }
+    );
+    assert_eq!(
+        aggregate.fetch_snapshot(&command).await.unwrap(),
+        Some((expected_state, 3))
+    );
+}
+
+/// The persisted (old) schema of the order state: the items were stored as the comma separated string
+#[derive(Debug, Clone, PartialEq)]
+enum StoredOrderState {
+    V1 { order_id: u32, items: String },
+    V2(OrderState),
+}
+
+/// A simple text serializer of the snapshots - infrastructure. Use `serde_json` or similar in real life.
+struct TextSnapshotSerializer;
+
+impl SnapshotSerializer<StoredOrderState, i32, AggregateError> for TextSnapshotSerializer {
+    fn serialize(
+        &self,
+        snapshot: &Snapshot<StoredOrderState, i32>,
+    ) -> Result<Vec<u8>, AggregateError> {
+        match &snapshot.state {
+            StoredOrderState::V1 { order_id, items } => Ok(format!(
+                "{};{};{};{};{}",
+                snapshot.decider_type, snapshot.schema_version, snapshot.version, order_id, items
+            )
+            .into_bytes()),
+            StoredOrderState::V2(_) => Err(AggregateError::SaveState(
+                "Only the schema version 1 is supported by the test serializer".to_string(),
+            )),
+        }
+    }
+
+    fn deserialize(&self, bytes: &[u8]) -> Result<Snapshot<StoredOrderState, i32>, AggregateError> {
+        let error = || AggregateError::FetchState("Malformed snapshot".to_string());
+        let text = String::from_utf8(bytes.to_vec()).map_err(|_| error())?;
+        let parts = text.split(';').collect::<Vec<&str>>();
+        let [decider_type, schema_version, version, order_id, items] = parts[..] else {
+            return Err(error());
+        };
+        Ok(Snapshot::new(
+            StoredOrderState::V1 {
+                order_id: order_id.parse().map_err(|_| error())?,
+                items: items.to_string(),
+            },
+            version.parse().map_err(|_| error())?,
+            decider_type,
+            schema_version.parse().map_err(|_| error())?,
+        ))
+    }
+}
+
+#[test]
+fn serialization_test() {
+    let serializer = TextSnapshotSerializer;
+    let snapshot = Snapshot::new(
+        StoredOrderState::V1 {
+            order_id: 1,
+            items: "Item 1,Item 2".to_string(),
+        },
+        2,
+        "Order",
+        1,
+    );
+    let bytes = serializer.serialize(&snapshot).unwrap();
+    let loaded = serializer.deserialize(&bytes).unwrap();
+    assert_eq!(loaded, snapshot);
+
+    // The state is migrated to the current schema on load
+    let upcaster: Upcaster<StoredOrderState, OrderState> = Upcaster::identity()
+        .and_then(|state: &StoredOrderState| match state {
+            StoredOrderState::V1 { order_id, items } => StoredOrderState::V2(OrderState {
+                order_id: *order_id,
+                customer_name: "unknown".to_string(),
+                items: items.split(',').map(str::to_string).collect(),
+                is_cancelled: false,
+            }),
+            state => state.clone(),
+        })
+        .and_then(|state: &StoredOrderState| match state {
+            StoredOrderState::V2(state) => state.clone(),
+            StoredOrderState::V1 { .. } => unreachable!(),
+        });
+    assert_eq!(
+        loaded.upcast(&upcaster, 2),
+        Snapshot::new(
+            OrderState {
+                order_id: 1,
+                customer_name: "unknown".to_string(),
+                items: vec!["Item 1".to_string(), "Item 2".to_string()],
+                is_cancelled: false,
+            },
+            2,
+            "Order",
+            2,
+        )
+    );
+}
+
+/// Describes the order decider - the version 2 is the current schema of the order state
+impl DeciderInfo for OrderCommand {
+    fn decider_name() -> &'static str {
+        "Order"
+    }
+
+    fn decider_version() -> u32 {
+        2
+    }
+
+    fn handled_commands() -> &'static [&'static str] {
+        &["Create", "Update", "Cancel"]
+    }
+}
+
+#[test]
+fn decider_info_test() {
+    let snapshot = Snapshot::for_decider::<OrderCommand>(
+        StoredOrderState::V1 {
+            order_id: 1,
+            items: "Item 1".to_string(),
+        },
+        1,
+    );
+    assert_eq!(snapshot.decider_type, "Order");
+    assert_eq!(snapshot.schema_version, 2);
+    assert!(snapshot.is_current::<OrderCommand>());
+    assert!(!Snapshot::new(0, 1, "Order", 1).is_current::<OrderCommand>());
+}
+
+/// A snapshot repository that is failing to save the snapshots - infrastructure
+struct FailingOrderSnapshotRepository;
+
+impl SnapshotRepository<OrderCommand, OrderState, i32, AggregateError>
+    for FailingOrderSnapshotRepository
+{
+    async fn fetch_snapshot(
+        &self,
+        _command: &OrderCommand,
+    ) -> Result<Option<(OrderState, i32)>, AggregateError> {
+        Ok(None)
+    }
+
+    async fn save_snapshot(
+        &self,
+        _state: &OrderState,
+        _version: &i32,
+    ) -> Result<(OrderState, i32), AggregateError> {
+        Err(AggregateError::SaveEvents(
+            "Snapshot store is down".to_string(),
+        ))
+    }
+}
+
+#[tokio::test]
+async fn snapshot_failure_test() {
+    let aggregate = EventSourcedSnapshottingAggregate::new(
+        InMemoryOrderEventRepository::new(),
+        FailingOrderSnapshotRepository,
+        decider().map_error(&|()| AggregateError::DomainError("Decider error".to_string())),
+        1,
+    );
+
+    // The events are saved, even though the snapshot fails to be saved
+    let result = aggregate
+        .handle(&OrderCommand::Create(CreateOrderCommand {
+            order_id: 1,
+            customer_name: "John Doe".to_string(),
+            items: vec!["Item 1".to_string()],
+        }))
+        .await;
+    assert_eq!(result.unwrap().first().unwrap().1, 0);
+    let result = aggregate.handle(&update_command("Item 2")).await;
+    assert_eq!(result.unwrap().first().unwrap().1, 1);
+}
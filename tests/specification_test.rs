@@ -0,0 +1,226 @@
+use fmodel_rust::decider::Decider;
+use fmodel_rust::saga::Saga;
+use fmodel_rust::specification::{
+    DeciderTestSpecification, SagaTestSpecification, ViewTestSpecification,
+};
+use fmodel_rust::view::View;
+
+use crate::api::{
+    CancelOrderCommand, CreateOrderCommand, CreateShipmentCommand, OrderCancelledEvent,
+    OrderCommand, OrderCreatedEvent, OrderEvent, OrderState, OrderUpdatedEvent, OrderViewState,
+    ShipmentCommand, UpdateOrderCommand,
+};
+
+mod api;
+mod application;
+
+/// Decider for the Order aggregate - Domain logic
+/// It is rejecting the commands that are targeting the non-existing order, with an error.
+fn order_decider<'a>() -> Decider<'a, OrderCommand, OrderState, OrderEvent> {
+    Decider {
+        decide: Box::new(|command, state| match command {
+            OrderCommand::Create(cmd) => Ok(vec![OrderEvent::Created(OrderCreatedEvent {
+                order_id: cmd.order_id,
+                customer_name: cmd.customer_name.to_owned(),
+                items: cmd.items.to_owned(),
+            })]),
+            OrderCommand::Update(cmd) => {
+                if state.order_id == cmd.order_id {
+                    Ok(vec![OrderEvent::Updated(OrderUpdatedEvent {
+                        order_id: cmd.order_id,
+                        updated_items: cmd.new_items.to_owned(),
+                    })])
+                } else {
+                    Err(())
+                }
+            }
+            OrderCommand::Cancel(cmd) => {
+                if state.order_id == cmd.order_id {
+                    Ok(vec![OrderEvent::Cancelled(OrderCancelledEvent {
+                        order_id: cmd.order_id,
+                    })])
+                } else {
+                    Err(())
+                }
+            }
+        }),
+        evolve: Box::new(|state, event| {
+            let mut new_state = state.clone();
+            match event {
+                OrderEvent::Created(evt) => {
+                    new_state.order_id = evt.order_id;
+                    new_state.customer_name = evt.customer_name.to_owned();
+                    new_state.items = evt.items.to_owned();
+                }
+                OrderEvent::Updated(evt) => {
+                    new_state.items = evt.updated_items.to_owned();
+                }
+                OrderEvent::Cancelled(_) => {
+                    new_state.is_cancelled = true;
+                }
+            }
+            new_state
+        }),
+        initial_state: Box::new(|| OrderState {
+            order_id: 0,
+            customer_name: "".to_string(),
+            items: Vec::new(),
+            is_cancelled: false,
+        }),
+    }
+}
+
+fn order_view<'a>() -> View<'a, OrderViewState, OrderEvent> {
+    View {
+        evolve: Box::new(|state, event| {
+            let mut new_state = state.clone();
+            match event {
+                OrderEvent::Created(evt) => {
+                    new_state.order_id = evt.order_id;
+                    new_state.customer_name = evt.customer_name.to_owned();
+                    new_state.items = evt.items.to_owned();
+                }
+                OrderEvent::Updated(evt) => {
+                    new_state.items = evt.updated_items.to_owned();
+                }
+                OrderEvent::Cancelled(_) => {
+                    new_state.is_cancelled = true;
+                }
+            }
+            new_state
+        }),
+        initial_state: Box::new(|| OrderViewState {
+            order_id: 0,
+            customer_name: "".to_string(),
+            items: Vec::new(),
+            is_cancelled: false,
+        }),
+    }
+}
+
+fn order_saga<'a>() -> Saga<'a, OrderEvent, ShipmentCommand> {
+    Saga {
+        react: Box::new(|event| match event {
+            OrderEvent::Created(evt) => Ok(vec![ShipmentCommand::Create(CreateShipmentCommand {
+                shipment_id: evt.order_id,
+                order_id: evt.order_id,
+                customer_name: evt.customer_name.to_owned(),
+                items: evt.items.to_owned(),
+            })]),
+            OrderEvent::Updated(_) => Ok(vec![]),
+            OrderEvent::Cancelled(_) => Err(()),
+        }),
+    }
+}
+
+#[test]
+fn decider_test() {
+    // Event-sourced
+    DeciderTestSpecification::default()
+        .for_decider(order_decider())
+        .given(vec![])
+        .when(OrderCommand::Create(CreateOrderCommand {
+            order_id: 1,
+            customer_name: "John Doe".to_string(),
+            items: vec!["Item 1".to_string(), "Item 2".to_string()],
+        }))
+        .then(vec![OrderEvent::Created(OrderCreatedEvent {
+            order_id: 1,
+            customer_name: "John Doe".to_string(),
+            items: vec!["Item 1".to_string(), "Item 2".to_string()],
+        })]);
+
+    DeciderTestSpecification::default()
+        .for_decider(order_decider())
+        .given(vec![OrderEvent::Created(OrderCreatedEvent {
+            order_id: 1,
+            customer_name: "John Doe".to_string(),
+            items: vec!["Item 1".to_string(), "Item 2".to_string()],
+        })])
+        .when(OrderCommand::Cancel(CancelOrderCommand { order_id: 1 }))
+        .then(vec![OrderEvent::Cancelled(OrderCancelledEvent {
+            order_id: 1,
+        })]);
+
+    DeciderTestSpecification::default()
+        .for_decider(order_decider())
+        .given(vec![])
+        .when(OrderCommand::Cancel(CancelOrderCommand { order_id: 1 }))
+        .then_error(());
+
+    // State-stored
+    DeciderTestSpecification::default()
+        .for_decider(order_decider())
+        .given_state(Some(OrderState {
+            order_id: 1,
+            customer_name: "John Doe".to_string(),
+            items: vec!["Item 1".to_string(), "Item 2".to_string()],
+            is_cancelled: false,
+        }))
+        .when(OrderCommand::Update(UpdateOrderCommand {
+            order_id: 1,
+            new_items: vec!["Item 3".to_string()],
+        }))
+        .then_state(OrderState {
+            order_id: 1,
+            customer_name: "John Doe".to_string(),
+            items: vec!["Item 3".to_string()],
+            is_cancelled: false,
+        });
+
+    DeciderTestSpecification::default()
+        .for_decider(order_decider())
+        .given_state(Some(OrderState {
+            order_id: 1,
+            customer_name: "John Doe".to_string(),
+            items: vec!["Item 1".to_string(), "Item 2".to_string()],
+            is_cancelled: false,
+        }))
+        .when(OrderCommand::Update(UpdateOrderCommand {
+            order_id: 2,
+            new_items: vec!["Item 3".to_string()],
+        }))
+        .then_error(());
+}
+
+#[test]
+fn view_test() {
+    ViewTestSpecification::default()
+        .for_view(order_view())
+        .given(vec![
+            OrderEvent::Created(OrderCreatedEvent {
+                order_id: 1,
+                customer_name: "John Doe".to_string(),
+                items: vec!["Item 1".to_string(), "Item 2".to_string()],
+            }),
+            OrderEvent::Cancelled(OrderCancelledEvent { order_id: 1 }),
+        ])
+        .then_state(OrderViewState {
+            order_id: 1,
+            customer_name: "John Doe".to_string(),
+            items: vec!["Item 1".to_string(), "Item 2".to_string()],
+            is_cancelled: true,
+        });
+}
+
+#[test]
+fn saga_test() {
+    SagaTestSpecification::default()
+        .for_saga(order_saga())
+        .when(OrderEvent::Created(OrderCreatedEvent {
+            order_id: 1,
+            customer_name: "John Doe".to_string(),
+            items: vec!["Item 1".to_string(), "Item 2".to_string()],
+        }))
+        .then(vec![ShipmentCommand::Create(CreateShipmentCommand {
+            shipment_id: 1,
+            order_id: 1,
+            customer_name: "John Doe".to_string(),
+            items: vec!["Item 1".to_string(), "Item 2".to_string()],
+        })]);
+
+    SagaTestSpecification::default()
+        .for_saga(order_saga())
+        .when(OrderEvent::Cancelled(OrderCancelledEvent { order_id: 1 }))
+        .then_error(());
+}
@@ -0,0 +1,286 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use fmodel_rust::envelope::EventEnvelope;
+use fmodel_rust::materialized_view::{CheckpointRepository, MaterializedView, ViewStateRepository};
+use fmodel_rust::saga::Saga;
+use fmodel_rust::saga_manager::{ActionPublisher, SagaManager};
+use fmodel_rust::subscription::{
+    EventSubscription, ProjectionTimeout, ProjectionWaiter, Subscriber,
+};
+use fmodel_rust::view::View;
+
+use crate::api::{
+    CreateShipmentCommand, OrderCancelledEvent, OrderCreatedEvent, OrderEvent, ShipmentCommand,
+};
+use crate::application::{MaterializedViewError, SagaManagerError};
+
+mod api;
+mod application;
+
+/// A simple in-memory event stream, polled in the batches of two events - infrastructure
+struct InMemoryEventSubscription {
+    events: Vec<EventEnvelope<OrderEvent, usize>>,
+}
+
+impl<Error> EventSubscription<OrderEvent, usize, (), usize, Error> for InMemoryEventSubscription {
+    async fn poll(
+        &self,
+        checkpoint: &Option<usize>,
+    ) -> Result<(Vec<EventEnvelope<OrderEvent, usize>>, Option<usize>), Error> {
+        let events = self
+            .events
+            .iter()
+            .filter(|envelope| checkpoint.is_none_or(|checkpoint| envelope.version > checkpoint))
+            .take(2)
+            .cloned()
+            .collect::<Vec<_>>();
+        let new_checkpoint = events
+            .last()
+            .map(|envelope| envelope.version)
+            .or(*checkpoint);
+        Ok((events, new_checkpoint))
+    }
+}
+
+/// A simple in-memory checkpoint repository - infrastructure
+#[derive(Default, Clone)]
+struct InMemoryCheckpointRepository {
+    checkpoint: Arc<Mutex<Option<usize>>>,
+}
+
+impl<Error> CheckpointRepository<usize, Error> for InMemoryCheckpointRepository {
+    async fn fetch_checkpoint(&self) -> Result<Option<usize>, Error> {
+        Ok(*self.checkpoint.lock().unwrap())
+    }
+
+    async fn save_checkpoint(&self, checkpoint: &usize) -> Result<(), Error> {
+        *self.checkpoint.lock().unwrap() = Some(*checkpoint);
+        Ok(())
+    }
+}
+
+/// A simple in-memory view state repository, storing the number of the active orders - infrastructure
+#[derive(Default, Clone)]
+struct InMemoryOrderCountRepository {
+    count: Arc<Mutex<i32>>,
+}
+
+impl ViewStateRepository<OrderEvent, i32, MaterializedViewError> for InMemoryOrderCountRepository {
+    async fn fetch_state(&self, _event: &OrderEvent) -> Result<Option<i32>, MaterializedViewError> {
+        Ok(Some(*self.count.lock().unwrap()))
+    }
+
+    async fn save(&self, state: &i32) -> Result<i32, MaterializedViewError> {
+        *self.count.lock().unwrap() = *state;
+        Ok(*state)
+    }
+}
+
+/// Action publisher that is recording the published actions - infrastructure
+#[derive(Default, Clone)]
+struct RecordingActionPublisher {
+    published: Arc<Mutex<Vec<ShipmentCommand>>>,
+}
+
+impl ActionPublisher<ShipmentCommand, SagaManagerError> for RecordingActionPublisher {
+    async fn publish(
+        &self,
+        action: &[ShipmentCommand],
+    ) -> Result<Vec<ShipmentCommand>, SagaManagerError> {
+        self.published.lock().unwrap().extend_from_slice(action);
+        Ok(Vec::from(action))
+    }
+}
+
+fn view<'a>() -> View<'a, i32, OrderEvent> {
+    View {
+        evolve: Box::new(|state, event| match event {
+            OrderEvent::Created(_) => state + 1,
+            OrderEvent::Cancelled(_) => state - 1,
+            OrderEvent::Updated(_) => *state,
+        }),
+        initial_state: Box::new(|| 0),
+    }
+}
+
+fn saga<'a>() -> Saga<'a, OrderEvent, ShipmentCommand> {
+    Saga {
+        react: Box::new(|event| match event {
+            OrderEvent::Created(evt) => Ok(vec![ShipmentCommand::Create(CreateShipmentCommand {
+                shipment_id: evt.order_id,
+                order_id: evt.order_id,
+                customer_name: evt.customer_name.to_owned(),
+                items: evt.items.to_owned(),
+            })]),
+            _ => Ok(vec![]),
+        }),
+    }
+}
+
+fn envelope(event: OrderEvent, version: usize) -> EventEnvelope<OrderEvent, usize> {
+    EventEnvelope {
+        event,
+        event_type: "OrderEvent".to_string(),
+        version,
+        timestamp: 0,
+        metadata: (),
+    }
+}
+
+fn subscription() -> InMemoryEventSubscription {
+    let created = |order_id| {
+        OrderEvent::Created(OrderCreatedEvent {
+            order_id,
+            customer_name: "John Doe".to_string(),
+            items: vec!["Item 1".to_string()],
+        })
+    };
+    InMemoryEventSubscription {
+        events: vec![
+            envelope(created(1), 1),
+            envelope(created(2), 2),
+            envelope(
+                OrderEvent::Cancelled(OrderCancelledEvent { order_id: 1 }),
+                3,
+            ),
+        ],
+    }
+}
+
+#[tokio::test]
+async fn view_test() {
+    let view_repository = InMemoryOrderCountRepository::default();
+    let checkpoint_repository = InMemoryCheckpointRepository::default();
+    let subscriber = Subscriber::new(
+        subscription(),
+        MaterializedView::new(view_repository.clone(), view()),
+        checkpoint_repository.clone(),
+    );
+
+    assert_eq!(subscriber.poll_once().await.unwrap(), 2);
+    assert_eq!(*checkpoint_repository.checkpoint.lock().unwrap(), Some(2));
+    assert_eq!(*view_repository.count.lock().unwrap(), 2);
+
+    assert_eq!(subscriber.poll_once().await.unwrap(), 1);
+    assert_eq!(*checkpoint_repository.checkpoint.lock().unwrap(), Some(3));
+    assert_eq!(*view_repository.count.lock().unwrap(), 1);
+
+    // The subscription is caught up
+    assert_eq!(subscriber.poll_once().await.unwrap(), 0);
+    assert_eq!(*checkpoint_repository.checkpoint.lock().unwrap(), Some(3));
+}
+
+#[tokio::test]
+async fn saga_test() {
+    let publisher = RecordingActionPublisher::default();
+    let checkpoint_repository = InMemoryCheckpointRepository::default();
+    // The subscription is resumed from the saved checkpoint
+    *checkpoint_repository.checkpoint.lock().unwrap() = Some(1);
+    let subscriber = Subscriber::new(
+        subscription(),
+        SagaManager::new(
+            publisher.clone(),
+            saga().map_error(&|()| SagaManagerError::DomainError("Saga error".to_string())),
+        ),
+        checkpoint_repository.clone(),
+    );
+
+    assert_eq!(subscriber.poll_once().await.unwrap(), 2);
+    assert_eq!(*checkpoint_repository.checkpoint.lock().unwrap(), Some(3));
+    assert_eq!(
+        *publisher.published.lock().unwrap(),
+        [ShipmentCommand::Create(CreateShipmentCommand {
+            shipment_id: 2,
+            order_id: 2,
+            customer_name: "John Doe".to_string(),
+            items: vec!["Item 1".to_string()],
+        })]
+    );
+}
+
+#[tokio::test]
+async fn partitioned_test() {
+    let view_repository = InMemoryOrderCountRepository::default();
+    let checkpoint_repository = InMemoryCheckpointRepository::default();
+    let subscriber = Subscriber::new(
+        subscription(),
+        MaterializedView::new(view_repository.clone(), view()),
+        checkpoint_repository.clone(),
+    );
+
+    assert_eq!(subscriber.poll_once_partitioned(2).await.unwrap(), 2);
+    assert_eq!(subscriber.poll_once_partitioned(2).await.unwrap(), 1);
+    assert_eq!(subscriber.poll_once_partitioned(2).await.unwrap(), 0);
+    assert_eq!(*checkpoint_repository.checkpoint.lock().unwrap(), Some(3));
+    assert_eq!(*view_repository.count.lock().unwrap(), 1);
+}
+
+#[tokio::test]
+async fn catch_up_test() {
+    let view_repository = InMemoryOrderCountRepository::default();
+    let subscriber = Subscriber::new(
+        subscription(),
+        MaterializedView::new(view_repository.clone(), view()),
+        InMemoryCheckpointRepository::default(),
+    );
+
+    // The subscriber is catching up, until all the stored events are handled
+    assert!(!subscriber.is_live());
+    assert_eq!(subscriber.poll_once().await.unwrap(), 2);
+    assert!(!subscriber.is_live());
+    assert_eq!(subscriber.catch_up().await.unwrap(), 1);
+    assert!(subscriber.is_live());
+    assert_eq!(*view_repository.count.lock().unwrap(), 1);
+}
+
+impl From<ProjectionTimeout> for MaterializedViewError {
+    fn from(error: ProjectionTimeout) -> Self {
+        MaterializedViewError::FetchState(error.to_string())
+    }
+}
+
+#[tokio::test]
+async fn wait_for_projection_test() {
+    let checkpoint_repository = InMemoryCheckpointRepository::default();
+    let subscriber = Subscriber::new(
+        subscription(),
+        MaterializedView::new(InMemoryOrderCountRepository::default(), view()),
+        checkpoint_repository.clone(),
+    );
+    // The subscriber is projecting the next batch while the waiter sleeps
+    let waiter = ProjectionWaiter::new(
+        checkpoint_repository,
+        |_| async {
+            let _: Result<usize, MaterializedViewError> = subscriber.poll_once().await;
+        },
+        Duration::from_millis(10),
+    );
+
+    // The write at the position 3 is projected by the second batch
+    let result: Result<(), MaterializedViewError> = waiter
+        .wait_for_projection(&3, Duration::from_millis(100))
+        .await;
+    assert!(result.is_ok());
+
+    // The write at the position 4 is never projected
+    let result: Result<(), MaterializedViewError> = waiter
+        .wait_for_projection(&4, Duration::from_millis(100))
+        .await;
+    assert!(matches!(result, Err(MaterializedViewError::FetchState(_))));
+}
+
+#[tokio::test]
+async fn wait_for_projection_with_zero_poll_interval_test() {
+    let waiter = ProjectionWaiter::new(
+        InMemoryCheckpointRepository::default(),
+        |_| async {},
+        Duration::ZERO,
+    );
+
+    // The waiter is polling without the delay, but it still times out
+    let result: Result<(), MaterializedViewError> = waiter
+        .wait_for_projection(&1, Duration::from_millis(10))
+        .await;
+    assert!(matches!(result, Err(MaterializedViewError::FetchState(_))));
+}
@@ -0,0 +1,24 @@
+use fmodel_rust::Sum;
+
+mod api;
+mod application;
+
+#[test]
+fn test() {
+    let first: Sum<u32, String> = Sum::First(1);
+    let second: Sum<u32, String> = Sum::Second("one".to_string());
+
+    assert_eq!(first.clone().map_first(|a| a + 1), Sum::First(2));
+    assert_eq!(second.clone().map_first(|a| a + 1), second);
+    assert_eq!(second.clone().map_second(|b| b.len()), Sum::Second(3));
+    assert_eq!(first.clone().swap(), Sum::<String, u32>::Second(1));
+
+    assert_eq!(first.as_first(), Some(&1));
+    assert_eq!(first.as_second(), None);
+    assert_eq!(first.clone().into_first(), Some(1));
+    assert_eq!(second.clone().into_second(), Some("one".to_string()));
+    assert_eq!(Sum::<u32, u32>::Second(2).into_inner(), 2);
+
+    assert_eq!(Result::from(first.clone()), Ok::<u32, String>(1));
+    assert_eq!(Sum::from(Err::<u32, String>("one".to_string())), second);
+}
@@ -0,0 +1,239 @@
+use std::sync::Mutex;
+
+use fmodel_rust::aggregate::{EventRepository, EventSourcedAggregate};
+use fmodel_rust::decider::Decider;
+use fmodel_rust::upcaster::{Upcaster, UpcastingEventRepository};
+use fmodel_rust::view::{View, ViewStateComputation};
+use fmodel_rust::Identifier;
+
+use crate::api::{
+    OrderCommand, OrderCreatedEvent, OrderEvent, OrderState, OrderUpdatedEvent, OrderViewState,
+    UpdateOrderCommand,
+};
+use crate::application::AggregateError;
+
+mod api;
+mod application;
+
+/// A simple in-memory event repository - infrastructure
+struct InMemoryOrderEventRepository {
+    events: Mutex<Vec<(OrderEvent, i32)>>,
+}
+
+impl InMemoryOrderEventRepository {
+    fn new(events: Vec<(OrderEvent, i32)>) -> Self {
+        InMemoryOrderEventRepository {
+            events: Mutex::new(events),
+        }
+    }
+}
+
+impl EventRepository<OrderCommand, OrderEvent, i32, AggregateError>
+    for InMemoryOrderEventRepository
+{
+    async fn fetch_events(
+        &self,
+        command: &OrderCommand,
+    ) -> Result<Vec<(OrderEvent, i32)>, AggregateError> {
+        Ok(self
+            .events
+            .lock()
+            .unwrap()
+            .clone()
+            .into_iter()
+            .filter(|(event, _)| event.identifier() == command.identifier())
+            .collect())
+    }
+
+    async fn save(&self, events: &[OrderEvent]) -> Result<Vec<(OrderEvent, i32)>, AggregateError> {
+        let mut latest_version = self
+            .version_provider(events.first().unwrap())
+            .await?
+            .unwrap_or(-1);
+        let events = events
+            .iter()
+            .map(|event| {
+                latest_version += 1;
+                (event.clone(), latest_version)
+            })
+            .collect::<Vec<(OrderEvent, i32)>>();
+        self.events.lock().unwrap().extend_from_slice(&events);
+        Ok(events)
+    }
+
+    async fn version_provider(&self, event: &OrderEvent) -> Result<Option<i32>, AggregateError> {
+        Ok(self
+            .events
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(e, _)| e.identifier() == event.identifier())
+            .map(|(_, version)| *version)
+            .next_back())
+    }
+}
+
+/// The legacy, persisted event schema - it did not capture the customer name
+#[derive(Debug, Clone, PartialEq)]
+enum LegacyOrderEvent {
+    Created { order_id: u32, items: Vec<String> },
+}
+
+/// Decider is rejecting the update of the orders without the customer name
+fn decider<'a>() -> Decider<'a, OrderCommand, OrderState, OrderEvent> {
+    Decider {
+        decide: Box::new(|command, state| match command {
+            OrderCommand::Update(cmd) if !state.customer_name.is_empty() => {
+                Ok(vec![OrderEvent::Updated(OrderUpdatedEvent {
+                    order_id: cmd.order_id,
+                    updated_items: cmd.new_items.to_owned(),
+                })])
+            }
+            _ => Err(()),
+        }),
+        evolve: Box::new(|state, event| {
+            let mut new_state = state.clone();
+            match event {
+                OrderEvent::Created(evt) => {
+                    new_state.order_id = evt.order_id;
+                    new_state.customer_name = evt.customer_name.to_owned();
+                    new_state.items = evt.items.to_owned();
+                }
+                OrderEvent::Updated(evt) => {
+                    new_state.items = evt.updated_items.to_owned();
+                }
+                OrderEvent::Cancelled(_) => {
+                    new_state.is_cancelled = true;
+                }
+            }
+            new_state
+        }),
+        initial_state: Box::new(|| OrderState {
+            order_id: 0,
+            customer_name: "".to_string(),
+            items: Vec::new(),
+            is_cancelled: false,
+        }),
+    }
+}
+
+fn view<'a>() -> View<'a, OrderViewState, OrderEvent> {
+    View {
+        evolve: Box::new(|state, event| {
+            let mut new_state = state.clone();
+            match event {
+                OrderEvent::Created(evt) => {
+                    new_state.order_id = evt.order_id;
+                    new_state.customer_name = evt.customer_name.to_owned();
+                    new_state.items = evt.items.to_owned();
+                }
+                OrderEvent::Updated(evt) => {
+                    new_state.items = evt.updated_items.to_owned();
+                }
+                OrderEvent::Cancelled(_) => {
+                    new_state.is_cancelled = true;
+                }
+            }
+            new_state
+        }),
+        initial_state: Box::new(|| OrderViewState {
+            order_id: 0,
+            customer_name: "".to_string(),
+            items: Vec::new(),
+            is_cancelled: false,
+        }),
+    }
+}
+
+/// Raw upcaster - fills in the customer name of the old events
+fn customer_name_upcaster<'a>() -> Upcaster<'a, OrderEvent> {
+    Upcaster::identity().and_then(|event: &OrderEvent| match event {
+        OrderEvent::Created(evt) if evt.customer_name.is_empty() => {
+            OrderEvent::Created(OrderCreatedEvent {
+                customer_name: "Unknown".to_string(),
+                ..evt.clone()
+            })
+        }
+        event => event.clone(),
+    })
+}
+
+#[tokio::test]
+async fn aggregate_test() {
+    let legacy_event = OrderEvent::Created(OrderCreatedEvent {
+        order_id: 1,
+        customer_name: "".to_string(),
+        items: vec!["Item 1".to_string()],
+    });
+    let command = OrderCommand::Update(UpdateOrderCommand {
+        order_id: 1,
+        new_items: vec!["Item 2".to_string()],
+    });
+
+    let aggregate = EventSourcedAggregate::new(
+        InMemoryOrderEventRepository::new(vec![(legacy_event.clone(), 0)]),
+        decider().map_error(&|()| AggregateError::DomainError("Decider error".to_string())),
+    );
+    assert!(aggregate.handle(&command).await.is_err());
+
+    let aggregate = EventSourcedAggregate::new(
+        UpcastingEventRepository::new(
+            InMemoryOrderEventRepository::new(vec![(legacy_event, 0)]),
+            customer_name_upcaster(),
+        ),
+        decider().map_error(&|()| AggregateError::DomainError("Decider error".to_string())),
+    );
+    assert_eq!(
+        aggregate.fetch_events(&command).await.unwrap(),
+        [(
+            OrderEvent::Created(OrderCreatedEvent {
+                order_id: 1,
+                customer_name: "Unknown".to_string(),
+                items: vec!["Item 1".to_string()],
+            }),
+            0
+        )]
+    );
+    assert_eq!(
+        aggregate.handle(&command).await.unwrap(),
+        [(
+            OrderEvent::Updated(OrderUpdatedEvent {
+                order_id: 1,
+                updated_items: vec!["Item 2".to_string()],
+            }),
+            1
+        )]
+    );
+}
+
+#[test]
+fn view_test() {
+    // Typed upcaster - migrates the legacy event type to the current one, and then applies the raw upcaster
+    let customer_name_upcaster = customer_name_upcaster();
+    let upcaster = Upcaster::new(|event: &LegacyOrderEvent| match event {
+        LegacyOrderEvent::Created { order_id, items } => OrderEvent::Created(OrderCreatedEvent {
+            order_id: *order_id,
+            customer_name: "".to_string(),
+            items: items.to_owned(),
+        }),
+    })
+    .and_then(move |event: &OrderEvent| customer_name_upcaster.upcast(event));
+
+    let legacy_view = view().upcast(upcaster);
+    let new_state = legacy_view.compute_new_state(
+        None,
+        &[&LegacyOrderEvent::Created {
+            order_id: 1,
+            items: vec!["Item 1".to_string()],
+        }],
+    );
+    assert_eq!(
+        new_state,
+        OrderViewState {
+            order_id: 1,
+            customer_name: "Unknown".to_string(),
+            items: vec!["Item 1".to_string()],
+            is_cancelled: false,
+        }
+    );
+}
@@ -1,5 +1,5 @@
 use api::OrderView2State;
-use fmodel_rust::view::{View, ViewStateComputation};
+use fmodel_rust::view::{FoldingView, View, ViewStateComputation};
 
 use crate::api::{
     OrderCancelledEvent, OrderCreatedEvent, OrderEvent, OrderUpdatedEvent, OrderViewState,
@@ -226,3 +226,38 @@ fn test() {
         }
     );
 }
+
+#[test]
+fn folding_view_test() {
+    let order_created_event = OrderEvent::Created(OrderCreatedEvent {
+        order_id: 1,
+        customer_name: "John Doe".to_string(),
+        items: vec!["Item 1".to_string(), "Item 2".to_string()],
+    });
+    let order_cancelled_event = OrderEvent::Cancelled(OrderCancelledEvent { order_id: 1 });
+    let events = [&order_created_event, &order_cancelled_event];
+
+    let folding_view = FoldingView {
+        evolve_mut: Box::new(
+            |state: &mut OrderViewState, event: &OrderEvent| match event {
+                OrderEvent::Created(evt) => {
+                    state.order_id = evt.order_id;
+                    state.customer_name = evt.customer_name.to_owned();
+                    state.items = evt.items.to_owned();
+                }
+                OrderEvent::Updated(evt) => {
+                    state.items = evt.updated_items.to_owned();
+                }
+                OrderEvent::Cancelled(_) => {
+                    state.is_cancelled = true;
+                }
+            },
+        ),
+        initial_state: order_view().initial_state,
+    };
+    let expected = order_view().compute_new_state(None, &events);
+    assert_eq!(folding_view.compute_new_state(None, &events), expected);
+
+    let converted: FoldingView<OrderViewState, OrderEvent> = order_view().into();
+    assert_eq!(converted.compute_new_state(None, &events), expected);
+}
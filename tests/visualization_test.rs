@@ -0,0 +1,192 @@
+use fmodel_rust::choreography::Choreography;
+use fmodel_rust::saga::Saga;
+use fmodel_rust::visualization::{ApplicationDiagram, Component, ComponentKind};
+use fmodel_rust::{DeciderInfo, SagaInfo, ViewInfo};
+
+use crate::api::{
+    CreateShipmentCommand, OrderCommand, OrderCreatedEvent, OrderEvent, ShipmentCommand,
+};
+
+mod api;
+mod application;
+
+impl DeciderInfo for OrderCommand {
+    fn decider_name() -> &'static str {
+        "Order"
+    }
+    fn decider_version() -> u32 {
+        1
+    }
+    fn handled_commands() -> &'static [&'static str] {
+        &["CreateOrder", "CancelOrder"]
+    }
+}
+
+impl ViewInfo for OrderEvent {
+    fn view_name() -> &'static str {
+        "Order View"
+    }
+    fn view_version() -> u32 {
+        2
+    }
+    fn handled_events() -> &'static [&'static str] {
+        &["OrderCreated"]
+    }
+}
+
+impl SagaInfo for OrderCreatedEvent {
+    fn saga_name() -> &'static str {
+        "Order Saga"
+    }
+    fn saga_version() -> u32 {
+        1
+    }
+    fn handled_action_results() -> &'static [&'static str] {
+        &["OrderCreated"]
+    }
+}
+
+fn diagram() -> ApplicationDiagram {
+    ApplicationDiagram::new()
+        .decider::<OrderCommand>()
+        .view::<OrderEvent>()
+        .saga::<OrderCreatedEvent>()
+}
+
+#[test]
+fn components_test() {
+    let kinds: Vec<(ComponentKind, &str)> = diagram()
+        .components()
+        .iter()
+        .map(|component| (component.kind, component.name))
+        .collect();
+    assert_eq!(
+        kinds,
+        [
+            (ComponentKind::Decider, "Order"),
+            (ComponentKind::View, "Order View"),
+            (ComponentKind::Saga, "Order Saga"),
+        ]
+    );
+}
+
+#[test]
+fn mermaid_class_diagram_test() {
+    assert_eq!(
+        diagram().to_mermaid_class_diagram(),
+        "classDiagram
+    class decider_Order {
+        <<decider>>
+        version 1
+        CreateOrder
+        CancelOrder
+    }
+    class view_Order_View {
+        <<view>>
+        version 2
+        OrderCreated
+    }
+    class saga_Order_Saga {
+        <<saga>>
+        version 1
+        OrderCreated
+    }
+"
+    );
+}
+
+#[test]
+fn mermaid_flowchart_test() {
+    let saga: Saga<OrderEvent, ShipmentCommand> = Saga {
+        react: Box::new(|event| match event {
+            OrderEvent::Created(evt) => Ok(vec![ShipmentCommand::Create(CreateShipmentCommand {
+                shipment_id: evt.order_id,
+                order_id: evt.order_id,
+                customer_name: evt.customer_name.to_owned(),
+                items: evt.items.to_owned(),
+            })]),
+            _ => Ok(vec![]),
+        }),
+    };
+    let choreography = Choreography::new().record(
+        &saga,
+        &[OrderEvent::Created(OrderCreatedEvent {
+            order_id: 1,
+            customer_name: "John Doe".to_string(),
+            items: vec!["Item 1".to_string()],
+        })],
+        &|_| "OrderCreated".to_string(),
+        &|_| "CreateShipment".to_string(),
+    );
+
+    assert_eq!(
+        diagram().choreography(choreography).to_mermaid_flowchart(),
+        "flowchart LR
+    decider_Order[\"Order v1\"]
+    view_Order_View[\"Order View v2\"]
+    saga_Order_Saga[\"Order Saga v1\"]
+    message_CreateOrder([\"CreateOrder\"])
+    message_CancelOrder([\"CancelOrder\"])
+    message_OrderCreated([\"OrderCreated\"])
+    message_CreateShipment([\"CreateShipment\"])
+    message_CreateOrder --> decider_Order
+    message_CancelOrder --> decider_Order
+    message_OrderCreated --> view_Order_View
+    message_OrderCreated --> saga_Order_Saga
+    message_OrderCreated -.-> message_CreateShipment
+"
+    );
+}
+
+#[test]
+fn plantuml_test() {
+    assert_eq!(
+        diagram().to_plantuml(),
+        "@startuml
+class \"Order\" as decider_Order <<decider>> {
+  version 1
+  CreateOrder
+  CancelOrder
+}
+class \"Order View\" as view_Order_View <<view>> {
+  version 2
+  OrderCreated
+}
+class \"Order Saga\" as saga_Order_Saga <<saga>> {
+  version 1
+  OrderCreated
+}
+@enduml
+"
+    );
+}
+
+#[test]
+fn quoted_names_test() {
+    let diagram = ApplicationDiagram::new().component(Component {
+        kind: ComponentKind::Decider,
+        name: "Order \"Legacy\"",
+        version: 1,
+        handles: &["Create \"Order\""],
+    });
+
+    // The double quotes are escaped the way each renderer supports
+    assert_eq!(
+        diagram.to_mermaid_flowchart(),
+        "flowchart LR
+    decider_Order__Legacy_[\"Order #quot;Legacy#quot; v1\"]
+    message_Create__Order_([\"Create #quot;Order#quot;\"])
+    message_Create__Order_ --> decider_Order__Legacy_
+"
+    );
+    assert_eq!(
+        diagram.to_plantuml(),
+        "@startuml
+class \"Order &#34;Legacy&#34;\" as decider_Order__Legacy_ <<decider>> {
+  version 1
+  Create \"Order\"
+}
+@enduml
+"
+    );
+}